@@ -12,11 +12,7 @@ Usage:
 
 #![deny(warnings)]
 #[cfg(feature = "cairo-native")]
-use {
-    cairo_native::cache::{AotProgramCache, JitProgramCache, ProgramCache},
-    starknet_in_rust::utils::get_native_context,
-    tracing::info,
-};
+use tracing::info;
 
 use cairo_vm::Felt252;
 use lazy_static::lazy_static;
@@ -26,6 +22,7 @@ use starknet_in_rust::{
         block_context::StarknetChainId,
         constants::{TRANSACTION_VERSION, VALIDATE_ENTRY_POINT_SELECTOR},
     },
+    execution::backend::{ExecutionBackend, ExecutionConfig},
     hash_utils::calculate_contract_address,
     services::api::contract_classes::{
         compiled_class::CompiledClass, deprecated_contract_class::ContractClass,
@@ -41,9 +38,6 @@ use starknet_in_rust::{
 };
 use std::{hint::black_box, sync::Arc};
 
-#[cfg(feature = "cairo-native")]
-use std::{cell::RefCell, rc::Rc};
-
 lazy_static! {
     // include_str! doesn't seem to work in CI
     static ref CONTRACT_CLASS: ContractClass = ContractClass::from_path(
@@ -72,7 +66,7 @@ fn scope<T>(f: impl FnOnce() -> T) -> T {
 // FnOnce calls for each test, that are merged in the flamegraph.
 fn main() {
     #[cfg(feature = "cairo-native")]
-    let program_cache = {
+    let execution_config = {
         let mut jit_run: bool = true;
         let args: Vec<String> = std::env::args().collect();
         if args.len() < 2 {
@@ -91,30 +85,19 @@ fn main() {
                 }
             }
         }
-        let cache = if jit_run {
-            ProgramCache::from(JitProgramCache::new(get_native_context()))
+        ExecutionConfig::new(if jit_run {
+            ExecutionBackend::NativeJit
         } else {
-            ProgramCache::from(AotProgramCache::new(get_native_context()))
-        };
-        Rc::new(RefCell::new(cache))
+            ExecutionBackend::NativeAot
+        })
     };
+    #[cfg(not(feature = "cairo-native"))]
+    let execution_config = ExecutionConfig::new(ExecutionBackend::Vm);
 
-    deploy_account(
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
-    );
-    declare(
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
-    );
-    deploy(
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
-    );
-    invoke(
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
-    );
+    deploy_account(&execution_config);
+    declare(&execution_config);
+    deploy(&execution_config);
+    invoke(&execution_config);
 
     // The black_box ensures there's no tail-call optimization.
     // If not, the flamegraph ends up less nice.
@@ -122,9 +105,7 @@ fn main() {
 }
 
 #[inline(never)]
-pub fn deploy_account(
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
-) {
+pub fn deploy_account(execution_config: &ExecutionConfig) {
     const RUNS: usize = 500;
 
     let state_reader = Arc::new(InMemoryStateReader::default());
@@ -159,21 +140,14 @@ pub fn deploy_account(
                 StarknetChainId::TestNet.to_felt(),
             )
             .unwrap();
-            internal_deploy_account.execute(
-                &mut state_copy,
-                block_context,
-                #[cfg(feature = "cairo-native")]
-                Some(program_cache.clone()),
-            )
+            internal_deploy_account.execute(&mut state_copy, block_context, execution_config)
         })
         .unwrap();
     }
 }
 
 #[inline(never)]
-pub fn declare(
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
-) {
+pub fn declare(execution_config: &ExecutionConfig) {
     const RUNS: usize = 5;
 
     let state_reader = Arc::new(InMemoryStateReader::default());
@@ -201,21 +175,14 @@ pub fn declare(
             )
             .expect("couldn't create transaction");
 
-            declare_tx.execute(
-                &mut cloned_state,
-                block_context,
-                #[cfg(feature = "cairo-native")]
-                Some(program_cache.clone()),
-            )
+            declare_tx.execute(&mut cloned_state, block_context, execution_config)
         })
         .unwrap();
     }
 }
 
 #[inline(never)]
-pub fn deploy(
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
-) {
+pub fn deploy(execution_config: &ExecutionConfig) {
     const RUNS: usize = 8;
 
     let state_reader = Arc::new(InMemoryStateReader::default());
@@ -250,21 +217,14 @@ pub fn deploy(
                 0.into(),
             )
             .unwrap();
-            internal_deploy.execute(
-                &mut state_copy,
-                block_context,
-                #[cfg(feature = "cairo-native")]
-                Some(program_cache.clone()),
-            )
+            internal_deploy.execute(&mut state_copy, block_context, execution_config)
         })
         .unwrap();
     }
 }
 
 #[inline(never)]
-pub fn invoke(
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
-) {
+pub fn invoke(execution_config: &ExecutionConfig) {
     const RUNS: usize = 100;
 
     let state_reader = Arc::new(InMemoryStateReader::default());
@@ -297,12 +257,7 @@ pub fn invoke(
     .unwrap();
 
     let _deploy_exec_info = deploy
-        .execute(
-            &mut state,
-            block_context,
-            #[cfg(feature = "cairo-native")]
-            Some(program_cache.clone()),
-        )
+        .execute(&mut state, block_context, execution_config)
         .unwrap();
 
     for _ in 0..RUNS {
@@ -324,13 +279,7 @@ pub fn invoke(
                 Some(Felt252::ZERO),
             )
             .unwrap();
-            internal_invoke.execute(
-                &mut state_copy,
-                block_context,
-                2_000_000,
-                #[cfg(feature = "cairo-native")]
-                Some(program_cache.clone()),
-            )
+            internal_invoke.execute(&mut state_copy, block_context, 2_000_000, execution_config)
         })
         .unwrap();
     }
@@ -20,6 +20,7 @@ use starknet_in_rust::{
         block_context::{BlockContext, StarknetChainId},
         constants::EXECUTE_ENTRY_POINT_SELECTOR,
     },
+    execution::backend::{ExecutionBackend, ExecutionConfig},
     state::{
         cached_state::CachedState, contract_class_cache::ContractClassCache, state_api::StateReader,
     },
@@ -29,14 +30,6 @@ use std::time::{Duration, Instant};
 use tracing::{debug, info};
 use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
-#[cfg(feature = "cairo-native")]
-use {
-    cairo_native::cache::{AotProgramCache, JitProgramCache, ProgramCache},
-    starknet_in_rust::transaction::ClassHash,
-    starknet_in_rust::utils::get_native_context,
-    std::{cell::RefCell, rc::Rc},
-};
-
 const WARMUP_TIME: Duration = Duration::from_secs(3);
 const BENCHMARK_TIME: Duration = Duration::from_secs(5);
 
@@ -78,46 +71,29 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut state = utils::default_state()?;
     #[cfg(feature = "cairo-native")]
-    let cache = if jit_run {
-        ProgramCache::from(JitProgramCache::new(get_native_context()))
+    let execution_config = ExecutionConfig::new(if jit_run {
+        ExecutionBackend::NativeJit
     } else {
-        ProgramCache::from(AotProgramCache::new(get_native_context()))
-    };
-    #[cfg(feature = "cairo-native")]
-    let program_cache = Rc::new(RefCell::new(cache));
+        ExecutionBackend::NativeAot
+    });
+    #[cfg(not(feature = "cairo-native"))]
+    let execution_config = ExecutionConfig::new(ExecutionBackend::Vm);
 
     // Declare ERC20, YASFactory, YASPool and YASRouter contracts.
     info!("Declaring the ERC20 contract.");
-    let erc20_class_hash = declare_erc20(
-        &mut state,
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
-    )?;
+    let erc20_class_hash = declare_erc20(&mut state, &execution_config)?;
     info!("Declaring the YASFactory contract.");
-    let yas_factory_class_hash = declare_yas_factory(
-        &mut state,
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
-    )?;
+    let yas_factory_class_hash = declare_yas_factory(&mut state, &execution_config)?;
     info!("Declaring the YASRouter contract.");
-    let yas_router_class_hash = declare_yas_router(
-        &mut state,
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
-    )?;
+    let yas_router_class_hash = declare_yas_router(&mut state, &execution_config)?;
     info!("Declaring the YASPool contract.");
-    let yas_pool_class_hash = declare_yas_pool(
-        &mut state,
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
-    )?;
+    let yas_pool_class_hash = declare_yas_pool(&mut state, &execution_config)?;
 
     // Deploy two ERC20 contracts.
     info!("Deploying TYAS0 token on ERC20.");
     let yas0_token_address = deploy_erc20(
         &mut state,
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
+        &execution_config,
         &erc20_class_hash,
         "TYAS0",
         "$YAS0",
@@ -127,8 +103,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Deploying TYAS1 token on ERC20.");
     let yas1_token_address = deploy_erc20(
         &mut state,
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
+        &execution_config,
         &erc20_class_hash,
         "TYAS1",
         "$YAS1",
@@ -140,8 +115,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Deploying YASFactory contract.");
     let yas_factory_address = deploy_yas_factory(
         &mut state,
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
+        &execution_config,
         &yas_factory_class_hash,
         *OWNER_ADDRESS,
         yas_pool_class_hash,
@@ -149,19 +123,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     // Deploy YASRouter contract.
     info!("Deploying YASRouter contract.");
-    let yas_router_address = deploy_yas_router(
-        &mut state,
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
-        &yas_router_class_hash,
-    )?;
+    let yas_router_address =
+        deploy_yas_router(&mut state, &execution_config, &yas_router_class_hash)?;
 
     // Deploy YASPool contract.
     info!("Deploying YASPool contract.");
     let yas_pool_address = deploy_yas_pool(
         &mut state,
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
+        &execution_config,
         &yas_pool_class_hash,
         yas_factory_address,
         yas0_token_address,
@@ -174,8 +143,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Initializing pool.");
     initialize_pool(
         &mut state,
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
+        &execution_config,
         &yas_pool_address,
         (79_228_162_514_264_337_593_543_950_336, 0),
         false,
@@ -185,8 +153,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "TYAS0 balance: {}",
         balance_of(
             &mut state,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
+            &execution_config,
             &yas0_token_address,
             *OWNER_ADDRESS
         )?
@@ -195,8 +162,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "TYAS1 balance: {}",
         balance_of(
             &mut state,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
+            &execution_config,
             &yas1_token_address,
             *OWNER_ADDRESS
         )?
@@ -206,16 +172,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Approving tokens.");
     approve_max(
         &mut state,
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
+        &execution_config,
         &ACCOUNT_ADDRESS,
         yas0_token_address,
         yas_router_address,
     )?;
     approve_max(
         &mut state,
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
+        &execution_config,
         &ACCOUNT_ADDRESS,
         yas1_token_address,
         yas_router_address,
@@ -225,8 +189,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "TYAS0 balance: {}",
         balance_of(
             &mut state,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
+            &execution_config,
             &yas0_token_address,
             *OWNER_ADDRESS
         )?
@@ -235,8 +198,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "TYAS1 balance: {}",
         balance_of(
             &mut state,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
+            &execution_config,
             &yas1_token_address,
             *OWNER_ADDRESS
         )?
@@ -246,8 +208,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Minting tokens.");
     mint(
         &mut state,
-        #[cfg(feature = "cairo-native")]
-        program_cache.clone(),
+        &execution_config,
         &ACCOUNT_ADDRESS,
         yas_router_address,
         yas_pool_address,
@@ -261,8 +222,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "TYAS0 balance: {}",
         balance_of(
             &mut state,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
+            &execution_config,
             &yas0_token_address,
             *OWNER_ADDRESS
         )?
@@ -271,8 +231,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "TYAS1 balance: {}",
         balance_of(
             &mut state,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
+            &execution_config,
             &yas1_token_address,
             *OWNER_ADDRESS
         )?
@@ -288,8 +247,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         let t0 = Instant::now();
         swap(
             &mut state,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
+            &execution_config,
             &ACCOUNT_ADDRESS,
             yas_router_address,
             yas_pool_address,
@@ -332,8 +290,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "TYAS0 balance: {}",
         balance_of(
             &mut state,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
+            &execution_config,
             &yas0_token_address,
             *OWNER_ADDRESS
         )?
@@ -342,8 +299,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "TYAS1 balance: {}",
         balance_of(
             &mut state,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
+            &execution_config,
             &yas1_token_address,
             *OWNER_ADDRESS
         )?
@@ -354,7 +310,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
 fn declare_erc20<S, C>(
     state: &mut CachedState<S, C>,
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
+    execution_config: &ExecutionConfig,
 ) -> Result<Felt252, Box<dyn std::error::Error>>
 where
     S: StateReader,
@@ -377,12 +333,7 @@ where
         vec![],
         nonce,
     )?
-    .execute(
-        state,
-        &BlockContext::default(),
-        #[cfg(feature = "cairo-native")]
-        Some(program_cache),
-    )?;
+    .execute(state, &BlockContext::default(), execution_config)?;
 
     // Ensure the execution was successful.
     let validate_info = tx_execution_info.validate_info.unwrap();
@@ -395,7 +346,7 @@ where
 
 fn declare_yas_factory<S, C>(
     state: &mut CachedState<S, C>,
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
+    execution_config: &ExecutionConfig,
 ) -> Result<Felt252, Box<dyn std::error::Error>>
 where
     S: StateReader,
@@ -418,12 +369,7 @@ where
         vec![],
         nonce,
     )?
-    .execute(
-        state,
-        &BlockContext::default(),
-        #[cfg(feature = "cairo-native")]
-        Some(program_cache),
-    )?;
+    .execute(state, &BlockContext::default(), execution_config)?;
 
     // Ensure the execution was successful.
     let validate_info = tx_execution_info.validate_info.unwrap();
@@ -436,7 +382,7 @@ where
 
 fn declare_yas_router<S, C>(
     state: &mut CachedState<S, C>,
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
+    execution_config: &ExecutionConfig,
 ) -> Result<Felt252, Box<dyn std::error::Error>>
 where
     S: StateReader,
@@ -459,12 +405,7 @@ where
         vec![],
         nonce,
     )?
-    .execute(
-        state,
-        &BlockContext::default(),
-        #[cfg(feature = "cairo-native")]
-        Some(program_cache),
-    )?;
+    .execute(state, &BlockContext::default(), execution_config)?;
 
     // Ensure the execution was successful.
     let validate_info = tx_execution_info.validate_info.unwrap();
@@ -477,7 +418,7 @@ where
 
 fn declare_yas_pool<S, C>(
     state: &mut CachedState<S, C>,
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
+    execution_config: &ExecutionConfig,
 ) -> Result<Felt252, Box<dyn std::error::Error>>
 where
     S: StateReader,
@@ -500,12 +441,7 @@ where
         vec![],
         nonce,
     )?
-    .execute(
-        state,
-        &BlockContext::default(),
-        #[cfg(feature = "cairo-native")]
-        Some(program_cache),
-    )?;
+    .execute(state, &BlockContext::default(), execution_config)?;
 
     // Ensure the execution was successful.
     let validate_info = tx_execution_info.validate_info.unwrap();
@@ -518,7 +454,7 @@ where
 
 fn deploy_erc20<S, C>(
     state: &mut CachedState<S, C>,
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
+    execution_config: &ExecutionConfig,
     erc20_class_hash: &Felt252,
     name: &str,
     symbol: &str,
@@ -555,8 +491,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        #[cfg(feature = "cairo-native")]
-        Some(program_cache),
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
@@ -570,7 +505,7 @@ where
 
 fn deploy_yas_factory<S, C>(
     state: &mut CachedState<S, C>,
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
+    execution_config: &ExecutionConfig,
     yas_factory_class_hash: &Felt252,
     owner_address: Felt252,
     pool_class_hash: Felt252,
@@ -602,8 +537,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        #[cfg(feature = "cairo-native")]
-        Some(program_cache),
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
@@ -617,7 +551,7 @@ where
 
 fn deploy_yas_router<S, C>(
     state: &mut CachedState<S, C>,
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
+    execution_config: &ExecutionConfig,
     yas_router_class_hash: &Felt252,
 ) -> Result<Felt252, Box<dyn std::error::Error>>
 where
@@ -641,8 +575,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        #[cfg(feature = "cairo-native")]
-        Some(program_cache),
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
@@ -657,7 +590,7 @@ where
 #[allow(clippy::too_many_arguments)]
 fn deploy_yas_pool<S, C>(
     state: &mut CachedState<S, C>,
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
+    execution_config: &ExecutionConfig,
     yas_pool_class_hash: &Felt252,
     yas_factory_address: Felt252,
     yas0_token_address: Felt252,
@@ -696,8 +629,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        #[cfg(feature = "cairo-native")]
-        Some(program_cache),
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
@@ -711,7 +643,7 @@ where
 
 fn initialize_pool<S, C>(
     state: &mut CachedState<S, C>,
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
+    execution_config: &ExecutionConfig,
     yas_pool_address: &Felt252,
     price_sqrt: (u128, u128),
     sign: bool,
@@ -747,8 +679,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        #[cfg(feature = "cairo-native")]
-        Some(program_cache),
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
@@ -762,7 +693,7 @@ where
 
 fn approve_max<S, C>(
     state: &mut CachedState<S, C>,
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
+    execution_config: &ExecutionConfig,
     account_address: &Felt252,
     token_address: Felt252,
     wallet_address: Felt252,
@@ -796,8 +727,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        #[cfg(feature = "cairo-native")]
-        Some(program_cache),
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
@@ -812,7 +742,7 @@ where
 #[allow(clippy::too_many_arguments)]
 fn mint<S, C>(
     state: &mut CachedState<S, C>,
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
+    execution_config: &ExecutionConfig,
     account_address: &Felt252,
     yas_router_address: Felt252,
     yas_pool_address: Felt252,
@@ -854,8 +784,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        #[cfg(feature = "cairo-native")]
-        Some(program_cache),
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
@@ -870,7 +799,7 @@ where
 #[allow(clippy::too_many_arguments)]
 fn swap<S, C>(
     state: &mut CachedState<S, C>,
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
+    execution_config: &ExecutionConfig,
     account_address: &Felt252,
     yas_router_address: Felt252,
     yas_pool_address: Felt252,
@@ -914,8 +843,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        #[cfg(feature = "cairo-native")]
-        Some(program_cache),
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
@@ -929,7 +857,7 @@ where
 
 fn balance_of<S, C>(
     state: &mut CachedState<S, C>,
-    #[cfg(feature = "cairo-native")] program_cache: Rc<RefCell<ProgramCache<ClassHash>>>,
+    execution_config: &ExecutionConfig,
     token_address: &Felt252,
     wallet_address: Felt252,
 ) -> Result<Felt252, Box<dyn std::error::Error>>
@@ -962,8 +890,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        #[cfg(feature = "cairo-native")]
-        Some(program_cache),
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
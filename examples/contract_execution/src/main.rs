@@ -13,6 +13,7 @@ use cairo_vm::Felt252;
 use starknet_in_rust::{
     core::contract_address::{compute_casm_class_hash, compute_deprecated_class_hash},
     definitions::block_context::BlockContext,
+    execution::backend::ExecutionConfig,
     services::api::contract_classes::{
         compiled_class::CompiledClass, deprecated_contract_class::ContractClass,
     },
@@ -133,12 +134,7 @@ fn test_contract(
     .unwrap();
 
     let account_contract_address = internal_deploy
-        .execute(
-            &mut state,
-            &block_context,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &block_context, &ExecutionConfig::default())
         .expect("Account Deploy Failed")
         .call_info
         .unwrap()
@@ -174,12 +170,7 @@ fn test_contract(
     .expect("couldn't create declare transaction");
 
     declare_tx
-        .execute(
-            &mut state,
-            &block_context,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &block_context, &ExecutionConfig::default())
         .expect("could not declare the contract class");
 
     //* ----------------------------------------------------------
@@ -199,13 +190,7 @@ fn test_contract(
     .unwrap();
 
     let contract_address = deploy
-        .execute(
-            &mut state,
-            &block_context,
-            0,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &block_context, 0, &ExecutionConfig::default())
         .expect("could not deploy contract")
         .call_info
         .unwrap()
@@ -248,13 +233,7 @@ fn test_contract(
     .unwrap();
 
     let tx_exec_info = invoke_tx
-        .execute(
-            &mut state,
-            &block_context,
-            0,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &block_context, 0, &ExecutionConfig::default())
         .unwrap();
 
     //* --------------------------------------------
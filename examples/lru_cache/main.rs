@@ -4,6 +4,7 @@ use cairo_vm::Felt252;
 use lru::LruCache;
 use starknet_in_rust::{
     definitions::{block_context::BlockContext, constants::TRANSACTION_VERSION},
+    execution::backend::ExecutionConfig,
     services::api::contract_classes::{
         compiled_class::CompiledClass, deprecated_contract_class::ContractClass,
     },
@@ -65,12 +66,7 @@ fn run_contract(
     .unwrap();
 
     declare_tx
-        .execute(
-            &mut state,
-            &block_context,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &block_context, &ExecutionConfig::default())
         .unwrap();
 
     let deploy_tx = Deploy::new(
@@ -83,12 +79,7 @@ fn run_contract(
     .unwrap();
 
     deploy_tx
-        .execute(
-            &mut state,
-            &block_context,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &block_context, &ExecutionConfig::default())
         .unwrap();
 
     let entry_point_selector =
@@ -107,13 +98,7 @@ fn run_contract(
     .unwrap();
 
     let invoke_tx_execution_info = invoke_tx
-        .execute(
-            &mut state,
-            &block_context,
-            0,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &block_context, 0, &ExecutionConfig::default())
         .unwrap();
 
     // Store the local cache changes into the shared cache. This updates the shared cache with all
@@ -10,7 +10,8 @@ use starknet_in_rust::EntryPointType;
 use starknet_in_rust::{
     definitions::{block_context::BlockContext, constants::TRANSACTION_VERSION},
     execution::{
-        execution_entry_point::ExecutionEntryPoint, CallInfo, CallType, TransactionExecutionContext,
+        backend::ExecutionConfig, execution_entry_point::ExecutionEntryPoint, CallInfo, CallType,
+        TransactionExecutionContext,
     },
     services::api::contract_classes::{
         compiled_class::CompiledClass, deprecated_contract_class::ContractClass,
@@ -194,8 +195,7 @@ fn main() {
                     &mut tx_execution_context,
                     false,
                     block_context.invoke_tx_max_n_steps(),
-                    #[cfg(feature = "cairo-native")]
-                    None,
+                    &ExecutionConfig::default(),
                 )
                 .unwrap();
             assert_eq!(call_info.unwrap(), expected_call_info);
@@ -16,7 +16,7 @@ use starknet_api::{
     stark_felt,
     transaction::{Transaction, TransactionHash},
 };
-use starknet_in_rust::execution::TransactionExecutionInfo;
+use starknet_in_rust::execution::{backend::ExecutionConfig, TransactionExecutionInfo};
 #[cfg(feature = "benchmark")]
 use starknet_in_rust::{
     definitions::block_context::GasPrices,
@@ -36,6 +36,13 @@ use std::{collections::HashMap, sync::Arc, time::Instant};
 struct ReplayCLI {
     #[command(subcommand)]
     subcommand: ReplayExecute,
+    #[cfg(feature = "cairo-native")]
+    #[arg(
+        long,
+        global = true,
+        help = "Also execute natively run calls on the VM, reporting where both disagree."
+    )]
+    differential: bool,
 }
 
 #[derive(Subcommand, Debug)]
@@ -75,6 +82,13 @@ Caches all rpc data before the benchmark runs to provide accurate results"
 
 fn main() {
     let cli = ReplayCLI::parse();
+    let execution_config = ExecutionConfig::default();
+    #[cfg(feature = "cairo-native")]
+    let execution_config = if cli.differential {
+        execution_config.with_differential()
+    } else {
+        execution_config
+    };
 
     match cli.subcommand {
         ReplayExecute::Tx {
@@ -83,7 +97,7 @@ fn main() {
             block_number,
             silent,
         } => {
-            show_execution_data(tx_hash, &chain, block_number, silent);
+            show_execution_data(tx_hash, &chain, block_number, silent, &execution_config);
         }
         ReplayExecute::Block {
             block_number,
@@ -97,7 +111,7 @@ fn main() {
                 .expect("Unable to fetch the transaction hashes.");
 
             for tx_hash in transaction_hashes {
-                show_execution_data(tx_hash, &chain, block_number.0, silent);
+                show_execution_data(tx_hash, &chain, block_number.0, silent, &execution_config);
             }
         }
         ReplayExecute::BlockRange {
@@ -114,7 +128,7 @@ fn main() {
                     .expect("Unable to fetch the transaction hashes.");
 
                 for tx_hash in transaction_hashes {
-                    show_execution_data(tx_hash, &chain, block_number.0, silent);
+                    show_execution_data(tx_hash, &chain, block_number.0, silent, &execution_config);
                 }
             }
         }
@@ -184,6 +198,7 @@ fn main() {
                         false,
                         true,
                         &mut state,
+                        &execution_config,
                     );
                 }
                 // Add the txs from the current block to the transactions cache
@@ -230,6 +245,7 @@ fn main() {
                             false,
                             true,
                             state,
+                            &execution_config,
                         );
                     }
                 }
@@ -256,7 +272,13 @@ fn parse_network(network: &str) -> RpcChain {
     }
 }
 
-fn show_execution_data(tx_hash: String, chain: &str, block_number: u64, silent: Option<bool>) {
+fn show_execution_data(
+    tx_hash: String,
+    chain: &str,
+    block_number: u64,
+    silent: Option<bool>,
+    execution_config: &ExecutionConfig,
+) {
     let rpc_chain = parse_network(chain);
     if silent.is_none() || !silent.unwrap() {
         println!("Executing transaction with hash: {}", tx_hash);
@@ -265,14 +287,29 @@ fn show_execution_data(tx_hash: String, chain: &str, block_number: u64, silent:
     }
     let previous_block_number = BlockNumber(block_number - 1);
 
-    let (tx_info, _trace, receipt) =
-        match execute_tx_configurable(&tx_hash, rpc_chain, previous_block_number, false, true) {
-            Ok(x) => x,
-            Err(error_reason) => {
-                println!("Error: {}", error_reason);
-                return;
-            }
-        };
+    let execution_result = execute_tx_configurable(
+        &tx_hash,
+        rpc_chain,
+        previous_block_number,
+        false,
+        true,
+        execution_config,
+    );
+
+    // Divergences are always shown, since finding them is the point of the differential mode.
+    if let Some(report) = execution_config.differential_report() {
+        for divergence in report.take_divergences() {
+            println!("[DIFF] Transaction {}: {}", tx_hash, divergence);
+        }
+    }
+
+    let (tx_info, _trace, receipt) = match execution_result {
+        Ok(x) => x,
+        Err(error_reason) => {
+            println!("Error: {}", error_reason);
+            return;
+        }
+    };
     let TransactionExecutionInfo {
         revert_error,
         actual_fee,
@@ -0,0 +1,576 @@
+//! Fork mode: running a local chain on top of a remote block.
+//!
+//! [`ForkStateReader`] reads the state of a remote block pinned by number, keeping every response
+//! in memory and, optionally, in a JSON file so that later runs forking the same block don't need
+//! to hit the RPC endpoint again. [`Fork`] layers a writable [`CachedState`] over it, executing
+//! transactions and producing local blocks on top of the forked one.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use cairo_vm::Felt252;
+use serde::{Deserialize, Serialize};
+use starknet::core::types::ContractClass as SNContractClass;
+use starknet_api::{
+    block::BlockNumber,
+    core::{ClassHash as SNClassHash, ContractAddress, PatriciaKey},
+    hash::{StarkFelt, StarkHash},
+    state::StorageKey,
+};
+use starknet_in_rust::{
+    core::errors::state_errors::StateError,
+    definitions::{
+        block_context::{
+            BlockContext, FeeTokenAddresses, GasPrices, StarknetChainId, StarknetOsConfig,
+        },
+        constants::{
+            DEFAULT_CAIRO_RESOURCE_FEE_WEIGHTS, DEFAULT_CONTRACT_STORAGE_COMMITMENT_TREE_HEIGHT,
+            DEFAULT_GLOBAL_STATE_COMMITMENT_TREE_HEIGHT, DEFAULT_INVOKE_TX_MAX_N_STEPS,
+            DEFAULT_VALIDATE_MAX_N_STEPS, STORED_BLOCK_HASH_BUFFER,
+        },
+    },
+    execution::{backend::ExecutionConfig, TransactionExecutionInfo},
+    services::api::contract_classes::compiled_class::CompiledClass,
+    state::{
+        block_hash::pre_process_block_from_reader, cached_state::CachedState,
+        contract_class_cache::PermanentContractClassCache, state_api::StateReader,
+        state_cache::StorageEntry, BlockInfo,
+    },
+    transaction::{error::TransactionError, Address, ClassHash, Transaction},
+};
+
+use crate::{
+    rpc_state::{BlockValue, RpcBlockInfo, RpcChain, RpcState},
+    rpc_state_errors::RpcStateError,
+};
+
+// --------------------
+// Fork cache structure
+// --------------------
+
+/// Remote responses for a forked block, as persisted to disk.
+///
+/// Keys are hex strings since JSON objects can only be keyed by strings.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ForkCache {
+    block: Option<ForkBlock>,
+    class_hashes: HashMap<String, StarkHash>,
+    nonces: HashMap<String, StarkFelt>,
+    storage: HashMap<String, StarkFelt>,
+    block_hashes: HashMap<u64, StarkHash>,
+    contract_classes: HashMap<String, SNContractClass>,
+    /// Whether there are responses that haven't been written to disk yet.
+    #[serde(skip)]
+    dirty: bool,
+}
+
+/// The header of the forked block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ForkBlock {
+    number: u64,
+    timestamp: u64,
+    sequencer_address: StarkFelt,
+    eth_l1_gas_price: u128,
+    strk_l1_gas_price: u128,
+}
+
+// --------------------
+// Fork state reader structure
+// --------------------
+
+/// A [`StateReader`] reading the state of a remote block through [`RpcState`], caching every
+/// response.
+///
+/// The cache is written to `{cache_dir}/{chain}_{block_number}.json` by [`ForkStateReader::save`]
+/// and when the reader is dropped, and loaded back when a reader for the same block is created.
+/// Unlike [`RpcStateReader`](crate::RpcStateReader), requests that fail because the endpoint
+/// can't be reached are reported as errors instead of being read as zero, so that they never
+/// end up in the cache.
+#[derive(Debug)]
+pub struct ForkStateReader {
+    rpc_state: RpcState,
+    block: ForkBlock,
+    cache: Mutex<ForkCache>,
+    cache_path: Option<PathBuf>,
+}
+
+impl ForkStateReader {
+    /// Creates a reader for `rpc_state`'s block, pinning it by number if it was given by tag or
+    /// hash.
+    ///
+    /// When `cache_dir` is given, responses cached by previous runs on the same block are loaded
+    /// from it.
+    pub fn new(mut rpc_state: RpcState, cache_dir: Option<PathBuf>) -> Result<Self, RpcStateError> {
+        let block_number = match rpc_state.block {
+            BlockValue::Number(block_number) => block_number,
+            BlockValue::Tag(_) | BlockValue::Hash(_) => {
+                let RpcBlockInfo { block_number, .. } = rpc_state.get_block_info()?;
+                rpc_state.block = BlockValue::Number(block_number);
+                block_number
+            }
+        };
+
+        let cache_path =
+            cache_dir.map(|dir| dir.join(format!("{}_{}.json", rpc_state.chain, block_number.0)));
+        let mut cache: ForkCache = match &cache_path {
+            Some(path) if path.exists() => serde_json::from_slice(&fs::read(path)?)?,
+            _ => ForkCache::default(),
+        };
+
+        let block = match cache.block.clone() {
+            Some(block) => block,
+            None => {
+                let RpcBlockInfo {
+                    block_timestamp,
+                    sequencer_address,
+                    ..
+                } = rpc_state.get_block_info()?;
+                let gas_price = rpc_state.get_gas_price(block_number.0)?;
+                let block = ForkBlock {
+                    number: block_number.0,
+                    timestamp: block_timestamp.0,
+                    sequencer_address: *sequencer_address.0.key(),
+                    eth_l1_gas_price: gas_price.eth_l1_gas_price,
+                    strk_l1_gas_price: gas_price.strk_l1_gas_price,
+                };
+                cache.block = Some(block.clone());
+                cache.dirty = true;
+                block
+            }
+        };
+
+        Ok(Self {
+            rpc_state,
+            block,
+            cache: Mutex::new(cache),
+            cache_path,
+        })
+    }
+
+    /// Returns the chain the state is read from.
+    pub fn chain(&self) -> RpcChain {
+        self.rpc_state.chain
+    }
+
+    /// Returns the number of the forked block.
+    pub fn block_number(&self) -> u64 {
+        self.block.number
+    }
+
+    /// Returns the info of the forked block.
+    pub fn block_info(&self) -> BlockInfo {
+        BlockInfo {
+            block_number: self.block.number,
+            block_timestamp: self.block.timestamp,
+            gas_price: GasPrices::new(self.block.eth_l1_gas_price, self.block.strk_l1_gas_price),
+            sequencer_address: Address(Felt252::from_bytes_be_slice(
+                self.block.sequencer_address.bytes(),
+            )),
+        }
+    }
+
+    /// Returns the hash of a block up to the forked one, or `None` for later blocks.
+    pub fn block_hash(&self, block_number: u64) -> Result<Option<StarkHash>, RpcStateError> {
+        if block_number > self.block.number {
+            return Ok(None);
+        }
+        if let Some(hash) = self.cache().block_hashes.get(&block_number) {
+            return Ok(Some(*hash));
+        }
+
+        let hash = self.rpc_state.get_block_hash(BlockNumber(block_number))?;
+        let mut cache = self.cache();
+        cache.block_hashes.insert(block_number, hash);
+        cache.dirty = true;
+        Ok(Some(hash))
+    }
+
+    /// Writes the cached responses to disk, if a cache directory was given and there are new
+    /// ones.
+    pub fn save(&self) -> Result<(), RpcStateError> {
+        let Some(path) = &self.cache_path else {
+            return Ok(());
+        };
+        let mut cache = self.cache();
+        if !cache.dirty {
+            return Ok(());
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_vec(&*cache)?)?;
+        cache.dirty = false;
+        Ok(())
+    }
+
+    fn cache(&self) -> MutexGuard<'_, ForkCache> {
+        // The cache is only ever updated by single inserts, so it's still consistent even if a
+        // thread panicked while holding the lock.
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the cached value for `key`, or requests it with `fetch` and caches it.
+    fn cached<T: Clone>(
+        &self,
+        entries: impl Fn(&mut ForkCache) -> &mut HashMap<String, T>,
+        key: String,
+        fetch: impl FnOnce(&RpcState) -> Result<T, RpcStateError>,
+    ) -> Result<T, StateError> {
+        if let Some(value) = entries(&mut *self.cache()).get(&key) {
+            return Ok(value.clone());
+        }
+
+        // The lock isn't held during the request so that other readers aren't blocked by it.
+        let value =
+            fetch(&self.rpc_state).map_err(|err| StateError::CustomError(err.to_string()))?;
+        let mut cache = self.cache();
+        entries(&mut *cache).insert(key, value.clone());
+        cache.dirty = true;
+        Ok(value)
+    }
+}
+
+impl Drop for ForkStateReader {
+    fn drop(&mut self) {
+        // There's no way of reporting the error here, and the cache can always be rebuilt from
+        // the endpoint.
+        let _ = self.save();
+    }
+}
+
+impl StateReader for ForkStateReader {
+    fn get_contract_class(&self, class_hash: &ClassHash) -> Result<CompiledClass, StateError> {
+        let hash = SNClassHash(to_stark_hash(&class_hash.0)?);
+        let contract_class = self.cached(
+            |cache| &mut cache.contract_classes,
+            hash.0.to_string(),
+            |rpc_state| rpc_state.try_get_contract_class(&hash),
+        )?;
+        let contract_class = contract_class.ok_or(StateError::MissingCasmClass(*class_hash))?;
+        Ok(CompiledClass::from(contract_class))
+    }
+
+    fn get_class_hash_at(&self, contract_address: &Address) -> Result<ClassHash, StateError> {
+        let address = to_contract_address(contract_address)?;
+        let hash = self.cached(
+            |cache| &mut cache.class_hashes,
+            address.0.key().to_string(),
+            |rpc_state| Ok(rpc_state.try_get_class_hash_at(&address)?.0),
+        )?;
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(hash.bytes());
+        Ok(ClassHash(bytes))
+    }
+
+    fn get_nonce_at(&self, contract_address: &Address) -> Result<Felt252, StateError> {
+        let address = to_contract_address(contract_address)?;
+        let nonce = self.cached(
+            |cache| &mut cache.nonces,
+            address.0.key().to_string(),
+            |rpc_state| rpc_state.try_get_nonce_at(&address),
+        )?;
+        Ok(Felt252::from_bytes_be_slice(nonce.bytes()))
+    }
+
+    fn get_storage_at(&self, storage_entry: &StorageEntry) -> Result<Felt252, StateError> {
+        let (contract_address, key) = storage_entry;
+        let address = to_contract_address(contract_address)?;
+        let key = StorageKey(
+            PatriciaKey::try_from(to_stark_hash(key)?)
+                .map_err(|err| StateError::CustomError(err.to_string()))?,
+        );
+        let value = self.cached(
+            |cache| &mut cache.storage,
+            format!("{}/{}", address.0.key(), key.0.key()),
+            |rpc_state| rpc_state.try_get_storage_at(&address, &key),
+        )?;
+        Ok(Felt252::from_bytes_be_slice(value.bytes()))
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: &ClassHash) -> Result<ClassHash, StateError> {
+        Ok(*class_hash)
+    }
+
+    fn get_block_hash(&self, block_number: u64) -> Result<Option<Felt252>, StateError> {
+        let block_hash = self
+            .block_hash(block_number)
+            .map_err(|err| StateError::CustomError(err.to_string()))?;
+        Ok(block_hash.map(|hash| Felt252::from_bytes_be_slice(hash.bytes())))
+    }
+}
+
+fn to_stark_hash(bytes: &[u8; 32]) -> Result<StarkHash, StateError> {
+    StarkHash::new(*bytes).map_err(|err| StateError::CustomError(err.to_string()))
+}
+
+fn to_contract_address(address: &Address) -> Result<ContractAddress, StateError> {
+    PatriciaKey::try_from(to_stark_hash(&address.0.to_bytes_be())?)
+        .map(ContractAddress)
+        .map_err(|err| StateError::CustomError(err.to_string()))
+}
+
+// --------------------
+// Fork structure
+// --------------------
+
+/// A local chain running on top of a remote block.
+///
+/// Local changes are kept in a [`CachedState`] over a [`ForkStateReader`], so keys that weren't
+/// written locally are read from the forked block. Local blocks start right after the forked one.
+pub struct Fork {
+    state: CachedState<ForkStateReader, PermanentContractClassCache>,
+    block_context: BlockContext,
+}
+
+impl Fork {
+    /// Forks the block of `rpc_state`, caching the remote responses in `cache_dir` if given.
+    ///
+    /// Fees are paid with the tokens at `fee_token_address`, which should be those deployed in
+    /// the forked chain.
+    pub fn new(
+        rpc_state: RpcState,
+        fee_token_address: FeeTokenAddresses,
+        cache_dir: Option<PathBuf>,
+    ) -> Result<Self, RpcStateError> {
+        let reader = ForkStateReader::new(rpc_state, cache_dir)?;
+
+        let chain_id: StarknetChainId = reader.chain().into();
+        let mut block_info = reader.block_info();
+        block_info.block_number += 1;
+        let starknet_os_config = StarknetOsConfig::new(
+            chain_id.to_felt(),
+            fee_token_address,
+            block_info.gas_price.clone(),
+        );
+        let block_context = BlockContext::new(
+            starknet_os_config,
+            DEFAULT_CONTRACT_STORAGE_COMMITMENT_TREE_HEIGHT,
+            DEFAULT_GLOBAL_STATE_COMMITMENT_TREE_HEIGHT,
+            DEFAULT_CAIRO_RESOURCE_FEE_WEIGHTS.clone(),
+            DEFAULT_INVOKE_TX_MAX_N_STEPS,
+            DEFAULT_VALIDATE_MAX_N_STEPS,
+            block_info,
+            Default::default(),
+            true,
+        );
+
+        let mut fork = Self {
+            state: CachedState::new(reader.into(), PermanentContractClassCache::default().into()),
+            block_context,
+        };
+        fork.pre_process_block()?;
+        Ok(fork)
+    }
+
+    /// Returns the number of the forked block.
+    pub fn fork_block_number(&self) -> u64 {
+        self.state.state_reader.block_number()
+    }
+
+    pub fn state(&self) -> &CachedState<ForkStateReader, PermanentContractClassCache> {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut CachedState<ForkStateReader, PermanentContractClassCache> {
+        &mut self.state
+    }
+
+    pub fn block_context(&self) -> &BlockContext {
+        &self.block_context
+    }
+
+    pub fn block_context_mut(&mut self) -> &mut BlockContext {
+        &mut self.block_context
+    }
+
+    /// Executes `tx` in the current local block.
+    pub fn execute(
+        &mut self,
+        tx: &Transaction,
+    ) -> Result<TransactionExecutionInfo, TransactionError> {
+        let execution_info = tx.execute(
+            &mut self.state,
+            &self.block_context,
+            u128::MAX,
+            &ExecutionConfig::default(),
+        )?;
+
+        Ok(execution_info)
+    }
+
+    /// Starts a new local block with the given timestamp, returning its number.
+    ///
+    /// The hashes of local blocks aren't known, so the `get_block_hash` syscall only finds
+    /// those of the forked block and its predecessors.
+    pub fn advance_block(&mut self, block_timestamp: u64) -> Result<u64, RpcStateError> {
+        let block_info = self.block_context.block_info_mut();
+        block_info.block_number += 1;
+        block_info.block_timestamp = block_timestamp;
+
+        self.pre_process_block()?;
+        Ok(self.block_context.block_info().block_number)
+    }
+
+    /// Writes the cached remote responses to disk.
+    pub fn save_cache(&self) -> Result<(), RpcStateError> {
+        self.state.state_reader.save()
+    }
+
+    /// Splits the fork into its state and block context, e.g. to serve them from a devnet.
+    pub fn into_parts(
+        self,
+    ) -> (
+        CachedState<ForkStateReader, PermanentContractClassCache>,
+        BlockContext,
+    ) {
+        (self.state, self.block_context)
+    }
+
+    /// Writes the hash of the block `STORED_BLOCK_HASH_BUFFER` blocks before the current one, as
+    /// the OS does at the start of each block.
+    ///
+    /// Nothing is written once that block is a local one, as the hashes of local blocks aren't
+    /// known.
+    fn pre_process_block(&mut self) -> Result<(), RpcStateError> {
+        let block_number = self.block_context.block_info().block_number;
+        if block_number > self.fork_block_number() + STORED_BLOCK_HASH_BUFFER {
+            return Ok(());
+        }
+        let reader = self.state.state_reader.clone();
+        pre_process_block_from_reader(&mut self.state, &*reader, block_number)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions_sorted::assert_eq;
+    use starknet_api::stark_felt;
+    use starknet_in_rust::{definitions::constants::BLOCK_HASH_CONTRACT_ADDRESS, state::state_api};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    /// Creates a fork of mainnet block 100 whose responses are all cached in a fresh directory,
+    /// using an endpoint that can't be reached.
+    fn cached_fork() -> (Fork, PathBuf) {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let cache_dir = std::env::temp_dir().join(format!("rpc_state_reader_fork_{nanos}"));
+
+        let cache = ForkCache {
+            block: Some(ForkBlock {
+                number: 100,
+                timestamp: 1_700_000_000,
+                sequencer_address: stark_felt!("0x1234"),
+                eth_l1_gas_price: 10,
+                strk_l1_gas_price: 20,
+            }),
+            nonces: HashMap::from([(stark_felt!("0x101").to_string(), stark_felt!("0x3"))]),
+            storage: HashMap::from([(
+                format!("{}/{}", stark_felt!("0x101"), stark_felt!("0x5")),
+                stark_felt!("0x2a"),
+            )]),
+            block_hashes: HashMap::from([(91, stark_felt!("0x91")), (92, stark_felt!("0x92"))]),
+            ..Default::default()
+        };
+        fs::create_dir_all(&cache_dir).unwrap();
+        fs::write(
+            cache_dir.join("starknet-mainnet_100.json"),
+            serde_json::to_vec(&cache).unwrap(),
+        )
+        .unwrap();
+
+        let rpc_state = RpcState::new(
+            RpcChain::MainNet,
+            BlockNumber(100).into(),
+            "http://127.0.0.1:1",
+        );
+        (
+            Fork::new(
+                rpc_state,
+                FeeTokenAddresses::new(Address(Felt252::from(0x10)), Address(Felt252::from(0x20))),
+                Some(cache_dir.clone()),
+            )
+            .unwrap(),
+            cache_dir,
+        )
+    }
+
+    #[test]
+    fn fork_reads_cached_remote_state_under_local_changes() {
+        let (mut fork, cache_dir) = cached_fork();
+        let address = Address(Felt252::from(0x101));
+        let entry = (address.clone(), Felt252::from(5).to_bytes_be());
+
+        assert_eq!(fork.fork_block_number(), 100);
+        assert_eq!(
+            fork.block_context()
+                .starknet_os_config()
+                .fee_token_address()
+                .strk_fee_token_address,
+            Address(Felt252::from(0x20))
+        );
+        assert_eq!(fork.block_context().block_info().block_number, 101);
+        assert_eq!(
+            fork.block_context().block_info().block_timestamp,
+            1_700_000_000
+        );
+        assert_eq!(
+            fork.state_mut().get_nonce_at(&address).unwrap(),
+            Felt252::from(3)
+        );
+        assert_eq!(
+            fork.state_mut().get_storage_at(&entry).unwrap(),
+            Felt252::from(42)
+        );
+        let old_block_hash_entry = (
+            BLOCK_HASH_CONTRACT_ADDRESS.clone(),
+            Felt252::from(91).to_bytes_be(),
+        );
+        assert_eq!(
+            fork.state_mut()
+                .get_storage_at(&old_block_hash_entry)
+                .unwrap(),
+            Felt252::from(0x91)
+        );
+
+        // Local writes shadow the remote state.
+        state_api::State::set_storage_at(fork.state_mut(), &entry, Felt252::from(7));
+        assert_eq!(
+            fork.state_mut().get_storage_at(&entry).unwrap(),
+            Felt252::from(7)
+        );
+
+        assert_eq!(fork.advance_block(1_700_000_010).unwrap(), 102);
+        assert_eq!(
+            fork.block_context().block_info().block_timestamp,
+            1_700_000_010
+        );
+        let old_block_hash_entry = (
+            BLOCK_HASH_CONTRACT_ADDRESS.clone(),
+            Felt252::from(92).to_bytes_be(),
+        );
+        assert_eq!(
+            fork.state_mut()
+                .get_storage_at(&old_block_hash_entry)
+                .unwrap(),
+            Felt252::from(0x92)
+        );
+        assert_eq!(fork.state().state_reader.get_block_hash(101).unwrap(), None);
+
+        // Uncached keys can't be read as the endpoint is unreachable, and aren't cached as zero.
+        let uncached_entry = (address, Felt252::from(6).to_bytes_be());
+        assert!(fork.state_mut().get_storage_at(&uncached_entry).is_err());
+        assert!(fork.state_mut().get_storage_at(&uncached_entry).is_err());
+
+        drop(fork);
+        fs::remove_dir_all(cache_dir).unwrap();
+    }
+}
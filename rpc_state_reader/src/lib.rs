@@ -2,9 +2,11 @@ pub mod rpc_state;
 pub mod rpc_state_errors;
 pub mod utils;
 
-// only export the sir_state_reader module when the starknet_in_rust feature
+// only export the sir_state_reader and fork modules when the starknet_in_rust feature
 // is enabled.
 #[cfg(feature = "starknet_in_rust")]
+pub mod fork;
+#[cfg(feature = "starknet_in_rust")]
 mod sir_state_reader;
 #[cfg(feature = "starknet_in_rust")]
 pub use sir_state_reader::{
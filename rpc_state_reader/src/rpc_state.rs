@@ -146,6 +146,11 @@ pub struct RpcBlockInfo {
     pub transactions: Vec<SNTransaction>,
 }
 
+/// Code of the JSON-RPC error answered for contracts that aren't deployed.
+const CONTRACT_NOT_FOUND: i64 = 20;
+/// Code of the JSON-RPC error answered for classes that aren't declared.
+const CLASS_HASH_NOT_FOUND: i64 = 28;
+
 /// A RPC response.
 #[derive(Debug, Deserialize, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub struct RpcResponse<T> {
@@ -356,6 +361,25 @@ impl RpcState {
         Ok(self.rpc_call::<RpcResponse<T>>(method, params)?.result)
     }
 
+    /// Calls `method`, returning `None` instead of failing when the node answers that the
+    /// contract or class doesn't exist. Any other error answered by the node is returned.
+    fn rpc_call_result_or_none<T: for<'a> Deserialize<'a>>(
+        &self,
+        method: &str,
+        params: &serde_json::Value,
+    ) -> Result<Option<T>, RpcStateError> {
+        let response: serde_json::Value = self.rpc_call(method, params)?;
+        match response.get("error") {
+            Some(error) => match error.get("code").and_then(serde_json::Value::as_i64) {
+                Some(CONTRACT_NOT_FOUND | CLASS_HASH_NOT_FOUND) => Ok(None),
+                _ => Err(RpcStateError::RpcCall(error.to_string())),
+            },
+            None => Ok(Some(
+                Self::deserialize_call::<RpcResponse<T>>(response)?.result,
+            )),
+        }
+    }
+
     fn rpc_call<T: for<'a> Deserialize<'a>>(
         &self,
         method: &str,
@@ -554,6 +578,19 @@ impl RpcState {
         ClassHash(hash)
     }
 
+    /// Requests the hash of the given block.
+    pub fn get_block_hash(&self, block_number: BlockNumber) -> Result<StarkHash, RpcStateError> {
+        let block: serde_json::Value = self.rpc_call_result(
+            "starknet_getBlockWithTxHashes",
+            &json!([BlockValue::Number(block_number).to_value()?]),
+        )?;
+
+        block
+            .get("block_hash")
+            .and_then(|block_hash| serde_json::from_value(block_hash.clone()).ok())
+            .ok_or_else(|| RpcStateError::RpcObjectHasNoField("block".into(), "block_hash".into()))
+    }
+
     pub fn get_nonce_at(&self, contract_address: &ContractAddress) -> StarkFelt {
         self.block
             .to_value()
@@ -589,6 +626,62 @@ impl RpcState {
             .unwrap_or_default()
     }
 
+    /// Like [`RpcState::get_contract_class`], but only returns `None` when the node answers with
+    /// an error (e.g. an undeclared class), failing when the node can't be reached.
+    pub fn try_get_contract_class(
+        &self,
+        class_hash: &ClassHash,
+    ) -> Result<Option<SNContractClass>, RpcStateError> {
+        self.rpc_call_result_or_none(
+            "starknet_getClass",
+            &json!([self.block.to_value()?, class_hash.0.to_string()]),
+        )
+    }
+
+    /// Like [`RpcState::get_class_hash_at`], but only defaults to zero when the node answers with
+    /// an error (e.g. an undeployed contract), failing when the node can't be reached.
+    pub fn try_get_class_hash_at(
+        &self,
+        contract_address: &ContractAddress,
+    ) -> Result<ClassHash, RpcStateError> {
+        let hash: Option<StarkHash> = self.rpc_call_result_or_none(
+            "starknet_getClassHashAt",
+            &json!([self.block.to_value()?, contract_address.0.key().to_string()]),
+        )?;
+        Ok(ClassHash(hash.unwrap_or_default()))
+    }
+
+    /// Like [`RpcState::get_nonce_at`], but only defaults to zero when the node answers with an
+    /// error (e.g. an undeployed contract), failing when the node can't be reached.
+    pub fn try_get_nonce_at(
+        &self,
+        contract_address: &ContractAddress,
+    ) -> Result<StarkFelt, RpcStateError> {
+        let nonce: Option<StarkFelt> = self.rpc_call_result_or_none(
+            "starknet_getNonce",
+            &json!([self.block.to_value()?, contract_address.0.key().to_string()]),
+        )?;
+        Ok(nonce.unwrap_or_default())
+    }
+
+    /// Like [`RpcState::get_storage_at`], but only defaults to zero when the node answers with an
+    /// error (e.g. an undeployed contract), failing when the node can't be reached.
+    pub fn try_get_storage_at(
+        &self,
+        contract_address: &ContractAddress,
+        key: &StorageKey,
+    ) -> Result<StarkFelt, RpcStateError> {
+        let value: Option<StarkFelt> = self.rpc_call_result_or_none(
+            "starknet_getStorageAt",
+            &json!([
+                contract_address.0.key().to_string(),
+                key.0.key().to_string(),
+                self.block.to_value()?
+            ]),
+        )?;
+        Ok(value.unwrap_or_default())
+    }
+
     /// Requests the given transaction to the Feeder Gateway API.
     pub fn get_transaction_receipt(
         &self,
@@ -1,3 +1,4 @@
+use starknet_in_rust::core::errors::state_errors::StateError;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -24,4 +25,6 @@ pub enum RpcStateError {
     MissingRpcResponseField(String),
     #[error("Wrong type for response field '{0}'")]
     RpcResponseWrongType(String),
+    #[error(transparent)]
+    State(#[from] StateError),
 }
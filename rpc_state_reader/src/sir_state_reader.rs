@@ -18,7 +18,7 @@ use starknet_in_rust::{
             DEFAULT_VALIDATE_MAX_N_STEPS,
         },
     },
-    execution::TransactionExecutionInfo,
+    execution::{backend::ExecutionConfig, TransactionExecutionInfo},
     services::api::contract_classes::compiled_class::CompiledClass,
     state::{
         cached_state::CachedState,
@@ -98,6 +98,14 @@ impl StateReader for RpcStateReader {
     fn get_compiled_class_hash(&self, class_hash: &ClassHash) -> Result<ClassHash, StateError> {
         Ok(*class_hash)
     }
+
+    fn get_block_hash(&self, block_number: u64) -> Result<Option<Felt252>, StateError> {
+        let block_hash = self
+            .0
+            .get_block_hash(BlockNumber(block_number))
+            .map_err(|err| StateError::CustomError(err.to_string()))?;
+        Ok(Some(Felt252::from_bytes_be_slice(block_hash.bytes())))
+    }
 }
 
 pub fn execute_tx_configurable(
@@ -106,6 +114,7 @@ pub fn execute_tx_configurable(
     block_number: BlockNumber,
     skip_validate: bool,
     skip_nonce_check: bool,
+    execution_config: &ExecutionConfig,
 ) -> Result<
     (
         TransactionExecutionInfo,
@@ -143,6 +152,7 @@ pub fn execute_tx_configurable(
         skip_validate,
         skip_nonce_check,
         &mut state,
+        execution_config,
     )?;
     let trace = state
         .state_reader
@@ -165,6 +175,7 @@ pub fn execute_tx_configurable_with_state(
     skip_validate: bool,
     skip_nonce_check: bool,
     state: &mut CachedState<RpcStateReader, PermanentContractClassCache>,
+    execution_config: &ExecutionConfig,
 ) -> Result<TransactionExecutionInfo, TransactionError> {
     let fee_token_address = FeeTokenAddresses::new(
         Address(
@@ -252,10 +263,7 @@ pub fn execute_tx_configurable_with_state(
         true,
     );
 
-    #[cfg(not(feature = "cairo-native"))]
-    let sir_execution = tx.execute(state, &block_context, u128::MAX)?;
-    #[cfg(feature = "cairo-native")]
-    let sir_execution = tx.execute(state, &block_context, u128::MAX, None)?;
+    let sir_execution = tx.execute(state, &block_context, u128::MAX, execution_config)?;
 
     Ok(sir_execution)
 }
@@ -272,7 +280,14 @@ pub fn execute_tx(
     ),
     TransactionError,
 > {
-    execute_tx_configurable(tx_hash, network, block_number, false, false)
+    execute_tx_configurable(
+        tx_hash,
+        network,
+        block_number,
+        false,
+        false,
+        &ExecutionConfig::default(),
+    )
 }
 
 pub fn execute_tx_without_validate(
@@ -287,7 +302,14 @@ pub fn execute_tx_without_validate(
     ),
     TransactionError,
 > {
-    execute_tx_configurable(tx_hash, network, block_number, true, true)
+    execute_tx_configurable(
+        tx_hash,
+        network,
+        block_number,
+        true,
+        true,
+        &ExecutionConfig::default(),
+    )
 }
 
 pub fn get_transaction_hashes(
@@ -317,8 +317,15 @@ fn starknet_in_rust_test_case_declare_tx(hash: &str, block_number: u64, chain: R
     RpcChain::MainNet
 )]
 fn starknet_in_rust_test_case_tx_skip_nonce_check(hash: &str, block_number: u64, chain: RpcChain) {
-    let (tx_info, trace, receipt) =
-        execute_tx_configurable(hash, chain, BlockNumber(block_number), false, true).unwrap();
+    let (tx_info, trace, receipt) = execute_tx_configurable(
+        hash,
+        chain,
+        BlockNumber(block_number),
+        false,
+        true,
+        &Default::default(),
+    )
+    .unwrap();
 
     let TransactionExecutionInfo {
         call_info,
@@ -0,0 +1,35 @@
+use thiserror::Error;
+
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum AbiError {
+    #[error("Failed to parse ABI: {0}")]
+    Parse(String),
+    #[error("Contract class has no ABI")]
+    MissingAbi,
+    #[error("Function {0} not found in ABI")]
+    FunctionNotFound(String),
+    #[error("Unknown ABI type {0}")]
+    UnknownType(String),
+    #[error("Function {function} expects {expected} arguments, got {actual}")]
+    WrongArgumentCount {
+        function: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("Value {value} doesn't match ABI type {ty}")]
+    TypeMismatch { ty: String, value: String },
+    #[error("Value {value} is out of range for ABI type {ty}")]
+    OutOfRange { ty: String, value: String },
+    #[error("Missing member {member} of struct {ty}")]
+    MissingMember { ty: String, member: String },
+    #[error("Unknown variant {variant} of enum {ty}")]
+    UnknownVariant { ty: String, variant: String },
+    #[error("Not enough data to decode ABI type {0}")]
+    NotEnoughData(String),
+    #[error("Found {0} felts left after decoding")]
+    TrailingData(usize),
+    #[error("No event of the ABI matches keys {0:?}")]
+    UnknownEvent(Vec<String>),
+    #[error("Invalid ByteArray encoding")]
+    InvalidByteArray,
+}
@@ -0,0 +1,446 @@
+use super::{error::AbiError, fold_array_lengths, Abi, AbiParam, AbiValue};
+use crate::utils::calculate_sn_keccak;
+use cairo_vm::Felt252;
+use serde::Deserialize;
+
+// --------------------
+// AbiEvent structure
+// --------------------
+
+/// Where an event field is serialized, as declared by Cairo 1 event attributes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventFieldKind {
+    /// A `#[key]` member, serialized into the event keys.
+    Key,
+    /// A member serialized into the event data.
+    Data,
+    /// An enum variant whose selector is appended to the keys before its payload.
+    Nested,
+    /// A `#[flat]` enum variant, whose payload is serialized without a selector.
+    Flat,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AbiEventField {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub kind: EventFieldKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AbiEvent {
+    /// A Cairo 0 event. Its first key is the selector of its name.
+    Cairo0(Vec<AbiEventField>),
+    /// A Cairo 1 event struct.
+    Struct(Vec<AbiEventField>),
+    /// A Cairo 1 event enum, such as the `Event` enum of a contract.
+    Enum(Vec<AbiEventField>),
+}
+
+/// Event entry as found in the JSON of both Cairo 0 and Cairo 1 ABIs.
+#[derive(Debug, Deserialize)]
+pub(super) struct RawEvent {
+    name: String,
+    kind: Option<String>,
+    #[serde(default)]
+    members: Vec<AbiEventField>,
+    #[serde(default)]
+    variants: Vec<AbiEventField>,
+    #[serde(default)]
+    keys: Vec<AbiParam>,
+    #[serde(default)]
+    data: Vec<AbiParam>,
+}
+
+impl RawEvent {
+    pub(super) fn into_abi_event(self) -> (String, AbiEvent) {
+        let event = match self.kind.as_deref() {
+            Some("struct") => AbiEvent::Struct(self.members),
+            Some("enum") => AbiEvent::Enum(self.variants),
+            _ => {
+                let with_kind = |params: Vec<AbiParam>, kind| {
+                    fold_array_lengths(params)
+                        .into_iter()
+                        .map(move |AbiParam { name, ty }| AbiEventField { name, ty, kind })
+                };
+                AbiEvent::Cairo0(
+                    with_kind(self.keys, EventFieldKind::Key)
+                        .chain(with_kind(self.data, EventFieldKind::Data))
+                        .collect(),
+                )
+            }
+        };
+
+        (self.name, event)
+    }
+}
+
+// --------------------
+// DecodedEvent structure
+// --------------------
+
+/// An event decoded with the ABI of the contract that emitted it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedEvent {
+    /// Name of the event: the type of the Cairo 1 event struct, or the Cairo 0 event name.
+    pub name: String,
+    /// Variants of the event enums leading to the event, starting from the contract's event
+    /// enum. Empty for Cairo 0 events.
+    pub path: Vec<String>,
+    /// Event members, by name, in declaration order.
+    pub fields: Vec<(String, AbiValue)>,
+}
+
+impl DecodedEvent {
+    pub fn field(&self, name: &str) -> Option<&AbiValue> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value)
+    }
+}
+
+impl Abi {
+    pub fn event(&self, name: &str) -> Option<&AbiEvent> {
+        self.events.get(name)
+    }
+
+    /// Decodes an event emitted by a contract with this ABI.
+    ///
+    /// Cairo 0 events are identified by their first key. Cairo 1 events are decoded starting from
+    /// the contract's event enum: each nested variant consumes its selector from the keys, while
+    /// flat variants are tried in order until one of them matches.
+    pub fn decode_event(
+        &self,
+        keys: &[Felt252],
+        data: &[Felt252],
+    ) -> Result<DecodedEvent, AbiError> {
+        if let Some((selector, event_keys)) = keys.split_first() {
+            let cairo0_event = self.events.iter().find(|(name, event)| {
+                matches!(event, AbiEvent::Cairo0(_)) && event_selector(name) == *selector
+            });
+            if let Some((name, _)) = cairo0_event {
+                return self.decode_complete_event(name, event_keys, data);
+            }
+        }
+
+        self.root_events()
+            .into_iter()
+            .find_map(|name| self.decode_complete_event(name, keys, data).ok())
+            .ok_or_else(|| {
+                AbiError::UnknownEvent(keys.iter().map(|key| format!("{key:#x}")).collect())
+            })
+    }
+
+    /// Returns the names of the Cairo 1 event enums that aren't a variant of another event enum.
+    fn root_events(&self) -> Vec<&str> {
+        let is_variant = |name: &str| {
+            self.events.values().any(|event| match event {
+                AbiEvent::Enum(variants) => variants.iter().any(|variant| variant.ty == name),
+                _ => false,
+            })
+        };
+
+        let mut roots: Vec<&str> = self
+            .events
+            .iter()
+            .filter(|(name, event)| {
+                matches!(event, AbiEvent::Enum(_)) && !is_variant(name.as_str())
+            })
+            .map(|(name, _)| name.as_str())
+            .collect();
+        roots.sort_unstable();
+        roots
+    }
+
+    fn decode_complete_event(
+        &self,
+        name: &str,
+        keys: &[Felt252],
+        data: &[Felt252],
+    ) -> Result<DecodedEvent, AbiError> {
+        let (mut keys, mut data) = (keys, data);
+        let event = self.decode_event_from(name, &mut keys, &mut data, &mut Vec::new())?;
+
+        if !keys.is_empty() || !data.is_empty() {
+            return Err(AbiError::TrailingData(keys.len() + data.len()));
+        }
+        Ok(event)
+    }
+
+    fn decode_event_from<'a>(
+        &self,
+        name: &str,
+        keys: &mut &'a [Felt252],
+        data: &mut &'a [Felt252],
+        path: &mut Vec<String>,
+    ) -> Result<DecodedEvent, AbiError> {
+        let unknown_event = |keys: &[Felt252]| {
+            AbiError::UnknownEvent(keys.iter().map(|key| format!("{key:#x}")).collect())
+        };
+
+        match self.events.get(name) {
+            Some(AbiEvent::Cairo0(members)) | Some(AbiEvent::Struct(members)) => {
+                let mut fields = Vec::with_capacity(members.len());
+                for member in members {
+                    let source = match member.kind {
+                        EventFieldKind::Key => &mut *keys,
+                        _ => &mut *data,
+                    };
+                    fields.push((member.name.clone(), self.decode_from(&member.ty, source)?));
+                }
+
+                Ok(DecodedEvent {
+                    name: name.to_string(),
+                    path: path.clone(),
+                    fields,
+                })
+            }
+            Some(AbiEvent::Enum(variants)) => {
+                if let Some((selector, variant_keys)) = keys.split_first() {
+                    let nested = variants.iter().find(|variant| {
+                        variant.kind == EventFieldKind::Nested
+                            && event_selector(&variant.name) == *selector
+                    });
+                    if let Some(variant) = nested {
+                        *keys = variant_keys;
+                        path.push(variant.name.clone());
+                        return self.decode_event_from(&variant.ty, keys, data, path);
+                    }
+                }
+
+                for variant in variants
+                    .iter()
+                    .filter(|variant| variant.kind == EventFieldKind::Flat)
+                {
+                    let (mut flat_keys, mut flat_data) = (*keys, *data);
+                    // A failed attempt may have gone through nested variants before failing.
+                    let depth = path.len();
+                    path.push(variant.name.clone());
+                    if let Ok(event) =
+                        self.decode_event_from(&variant.ty, &mut flat_keys, &mut flat_data, path)
+                    {
+                        *keys = flat_keys;
+                        *data = flat_data;
+                        return Ok(event);
+                    }
+                    path.truncate(depth);
+                }
+
+                Err(unknown_event(*keys))
+            }
+            None => Err(AbiError::UnknownType(name.to_string())),
+        }
+    }
+}
+
+/// Returns the selector of an event or event variant, which is also its first key.
+fn event_selector(name: &str) -> Felt252 {
+    Felt252::from_bytes_be(&calculate_sn_keccak(name.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_bigint::BigUint;
+    use pretty_assertions_sorted::assert_eq;
+
+    const CAIRO1_ABI: &str = r#"[
+        {
+            "type": "event",
+            "name": "token::Transfer",
+            "kind": "struct",
+            "members": [
+                { "name": "from", "type": "core::starknet::contract_address::ContractAddress", "kind": "key" },
+                { "name": "to", "type": "core::starknet::contract_address::ContractAddress", "kind": "key" },
+                { "name": "value", "type": "core::integer::u256", "kind": "data" }
+            ]
+        },
+        {
+            "type": "event",
+            "name": "ownable::OwnershipTransferred",
+            "kind": "struct",
+            "members": [
+                { "name": "new_owner", "type": "core::felt252", "kind": "data" }
+            ]
+        },
+        {
+            "type": "event",
+            "name": "ownable::Event",
+            "kind": "enum",
+            "variants": [
+                { "name": "OwnershipTransferred", "type": "ownable::OwnershipTransferred", "kind": "nested" }
+            ]
+        },
+        {
+            "type": "event",
+            "name": "token::Event",
+            "kind": "enum",
+            "variants": [
+                { "name": "Transfer", "type": "token::Transfer", "kind": "nested" },
+                { "name": "OwnableEvent", "type": "ownable::Event", "kind": "flat" }
+            ]
+        }
+    ]"#;
+
+    #[test]
+    fn decode_nested_event() {
+        let abi = Abi::from_json(CAIRO1_ABI).unwrap();
+        let keys = vec![event_selector("Transfer"), Felt252::ONE, Felt252::TWO];
+        let data = vec![Felt252::from(100), Felt252::ZERO];
+
+        let event = abi.decode_event(&keys, &data).unwrap();
+        assert_eq!(
+            event,
+            DecodedEvent {
+                name: "token::Transfer".to_string(),
+                path: vec!["Transfer".to_string()],
+                fields: vec![
+                    ("from".to_string(), AbiValue::Felt(Felt252::ONE)),
+                    ("to".to_string(), AbiValue::Felt(Felt252::TWO)),
+                    ("value".to_string(), AbiValue::U256(BigUint::from(100u8))),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn decode_flat_event() {
+        let abi = Abi::from_json(CAIRO1_ABI).unwrap();
+        let keys = vec![event_selector("OwnershipTransferred")];
+        let data = vec![Felt252::from(7)];
+
+        let event = abi.decode_event(&keys, &data).unwrap();
+        assert_eq!(event.name, "ownable::OwnershipTransferred");
+        assert_eq!(
+            event.path,
+            vec![
+                "OwnableEvent".to_string(),
+                "OwnershipTransferred".to_string()
+            ]
+        );
+        assert_eq!(
+            event.field("new_owner"),
+            Some(&AbiValue::Felt(Felt252::from(7)))
+        );
+    }
+
+    #[test]
+    fn decode_second_flat_event() {
+        // The first flat variant has a nested variant with the same selector, which fails to
+        // decode the event because it expects more data.
+        let abi = Abi::from_json(
+            r#"[
+                {
+                    "type": "event",
+                    "name": "ownable::OwnershipTransferred",
+                    "kind": "struct",
+                    "members": [
+                        { "name": "previous_owner", "type": "core::felt252", "kind": "data" },
+                        { "name": "new_owner", "type": "core::felt252", "kind": "data" }
+                    ]
+                },
+                {
+                    "type": "event",
+                    "name": "ownable::Event",
+                    "kind": "enum",
+                    "variants": [
+                        { "name": "OwnershipTransferred", "type": "ownable::OwnershipTransferred", "kind": "nested" }
+                    ]
+                },
+                {
+                    "type": "event",
+                    "name": "legacy_ownable::OwnershipTransferred",
+                    "kind": "struct",
+                    "members": [
+                        { "name": "new_owner", "type": "core::felt252", "kind": "data" }
+                    ]
+                },
+                {
+                    "type": "event",
+                    "name": "legacy_ownable::Event",
+                    "kind": "enum",
+                    "variants": [
+                        { "name": "OwnershipTransferred", "type": "legacy_ownable::OwnershipTransferred", "kind": "nested" }
+                    ]
+                },
+                {
+                    "type": "event",
+                    "name": "token::Event",
+                    "kind": "enum",
+                    "variants": [
+                        { "name": "OwnableEvent", "type": "ownable::Event", "kind": "flat" },
+                        { "name": "LegacyOwnableEvent", "type": "legacy_ownable::Event", "kind": "flat" }
+                    ]
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        let event = abi
+            .decode_event(
+                &[event_selector("OwnershipTransferred")],
+                &[Felt252::from(7)],
+            )
+            .unwrap();
+        assert_eq!(event.name, "legacy_ownable::OwnershipTransferred");
+        assert_eq!(
+            event.path,
+            vec![
+                "LegacyOwnableEvent".to_string(),
+                "OwnershipTransferred".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_unknown_event() {
+        let abi = Abi::from_json(CAIRO1_ABI).unwrap();
+
+        assert_matches!(
+            abi.decode_event(&[event_selector("Approval")], &[]),
+            Err(AbiError::UnknownEvent(_))
+        );
+        // Transfer with missing data.
+        assert_matches!(
+            abi.decode_event(
+                &[event_selector("Transfer"), Felt252::ONE, Felt252::TWO],
+                &[]
+            ),
+            Err(AbiError::UnknownEvent(_))
+        );
+    }
+
+    #[test]
+    fn decode_cairo0_event() {
+        let abi = Abi::from_json(
+            r#"[
+                {
+                    "data": [
+                        { "name": "values_len", "type": "felt" },
+                        { "name": "values", "type": "felt*" }
+                    ],
+                    "keys": [],
+                    "name": "values_stored",
+                    "type": "event"
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        let event = abi
+            .decode_event(
+                &[event_selector("values_stored")],
+                &[Felt252::ONE, Felt252::from(3)],
+            )
+            .unwrap();
+        assert_eq!(event.name, "values_stored");
+        assert!(event.path.is_empty());
+        assert_eq!(
+            event.field("values"),
+            Some(&AbiValue::Array(vec![AbiValue::Felt(Felt252::from(3))]))
+        );
+    }
+}
@@ -0,0 +1,986 @@
+//! Typed access to contract ABIs.
+//!
+//! An [`Abi`] is built from either a Cairo 0 ABI or a Cairo 1 (Sierra) ABI and is used to resolve
+//! entry points by name, to encode typed arguments into calldata and to decode return data or
+//! event data back into typed [`AbiValue`]s.
+
+pub mod error;
+pub mod events;
+
+use self::{
+    error::AbiError,
+    events::{AbiEvent, RawEvent},
+};
+use crate::{
+    services::api::contract_classes::deprecated_contract_class::{
+        AbiType, ContractClass, EntryPointType,
+    },
+    utils::{byte_array_serialized_len, calculate_sn_keccak, decode_byte_array, encode_byte_array},
+};
+use cairo_lang_starknet::{abi::Contract, contract_class::ContractClass as SierraContractClass};
+use cairo_vm::Felt252;
+use getset::{CopyGetters, Getters};
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Types serialized as a single felt, other than the builtin integer types.
+const FELT_TYPES: [&str; 8] = [
+    "felt",
+    "core::felt252",
+    "core::bytes_31::bytes31",
+    "core::starknet::contract_address::ContractAddress",
+    "core::starknet::class_hash::ClassHash",
+    "core::starknet::eth_address::EthAddress",
+    "core::starknet::storage_access::StorageAddress",
+    "core::starknet::storage_access::StorageBaseAddress",
+];
+
+const U256_TYPES: [&str; 2] = ["core::integer::u256", "Uint256"];
+const BOOL_TYPE: &str = "core::bool";
+const BYTE_ARRAY_TYPE: &str = "core::byte_array::ByteArray";
+const ARRAY_PREFIXES: [&str; 2] = ["core::array::Array::<", "core::array::Span::<"];
+
+// --------------------
+// AbiValue structure
+// --------------------
+
+/// A typed value of a contract's ABI.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AbiValue {
+    /// Any type serialized as a single felt: `felt252`, integers other than `u256`, addresses,
+    /// class hashes, etc.
+    Felt(Felt252),
+    Bool(bool),
+    U256(BigUint),
+    ByteArray(String),
+    /// An `Array<T>`, a `Span<T>` or a Cairo 0 pointer with its length.
+    Array(Vec<AbiValue>),
+    /// A tuple. The unit type is an empty tuple.
+    Tuple(Vec<AbiValue>),
+    /// Struct members, by name, in declaration order.
+    Struct(Vec<(String, AbiValue)>),
+    /// Enum variant name and its payload.
+    Enum(String, Box<AbiValue>),
+}
+
+impl AbiValue {
+    /// Returns the unit value, used as the payload of enum variants without data.
+    pub const fn unit() -> Self {
+        AbiValue::Tuple(Vec::new())
+    }
+
+    /// Returns the value of the struct member `name`, if this is a struct.
+    pub fn member(&self, name: &str) -> Option<&AbiValue> {
+        match self {
+            AbiValue::Struct(members) => members
+                .iter()
+                .find(|(member, _)| member == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+impl From<Felt252> for AbiValue {
+    fn from(value: Felt252) -> Self {
+        AbiValue::Felt(value)
+    }
+}
+
+impl From<bool> for AbiValue {
+    fn from(value: bool) -> Self {
+        AbiValue::Bool(value)
+    }
+}
+
+impl From<BigUint> for AbiValue {
+    fn from(value: BigUint) -> Self {
+        AbiValue::U256(value)
+    }
+}
+
+impl From<&str> for AbiValue {
+    fn from(value: &str) -> Self {
+        AbiValue::ByteArray(value.to_string())
+    }
+}
+
+impl<T: Into<AbiValue>> From<Vec<T>> for AbiValue {
+    fn from(values: Vec<T>) -> Self {
+        AbiValue::Array(values.into_iter().map(Into::into).collect())
+    }
+}
+
+// --------------------
+// AbiFunction structure
+// --------------------
+
+/// A named and typed function parameter, struct member or enum variant.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct AbiParam {
+    #[serde(default)]
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// A function of the ABI. For Cairo 0 functions, the `<name>_len` parameter preceding a pointer
+/// parameter is folded into it, so arrays are handled the same way for both Cairo versions.
+#[derive(Clone, Debug, PartialEq, Eq, Getters, CopyGetters)]
+pub struct AbiFunction {
+    #[getset(get = "pub")]
+    name: String,
+    #[getset(get = "pub")]
+    selector: Felt252,
+    #[getset(get_copy = "pub")]
+    entry_point_type: EntryPointType,
+    #[getset(get = "pub")]
+    inputs: Vec<AbiParam>,
+    #[getset(get = "pub")]
+    outputs: Vec<AbiParam>,
+}
+
+// --------------------
+// Raw ABI entries
+// --------------------
+
+/// ABI entry as found in the JSON of both Cairo 0 and Cairo 1 ABIs.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum RawAbiEntry {
+    Function(RawFunction),
+    Constructor(RawFunction),
+    L1Handler(RawFunction),
+    Struct(RawStruct),
+    Enum(RawEnum),
+    Interface(RawInterface),
+    Event(RawEvent),
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawFunction {
+    name: String,
+    #[serde(default)]
+    inputs: Vec<AbiParam>,
+    #[serde(default)]
+    outputs: Vec<AbiParam>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawStruct {
+    name: String,
+    members: Vec<AbiParam>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawEnum {
+    name: String,
+    variants: Vec<AbiParam>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawInterface {
+    items: Vec<RawAbiEntry>,
+}
+
+// --------------------
+// Abi structure
+// --------------------
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Abi {
+    functions: HashMap<String, AbiFunction>,
+    structs: HashMap<String, Vec<AbiParam>>,
+    enums: HashMap<String, Vec<AbiParam>>,
+    events: HashMap<String, AbiEvent>,
+}
+
+impl Abi {
+    /// Parses an ABI from its JSON representation, as found in Cairo 0 compiled classes and in
+    /// Sierra classes.
+    pub fn from_json(json: &str) -> Result<Self, AbiError> {
+        let entries: Vec<RawAbiEntry> =
+            serde_json::from_str(json).map_err(|e| AbiError::Parse(e.to_string()))?;
+        Ok(Self::from_entries(entries))
+    }
+
+    pub fn from_deprecated_abi(abi: &AbiType) -> Result<Self, AbiError> {
+        Self::from_serializable(abi)
+    }
+
+    pub fn from_sierra_abi(abi: &Contract) -> Result<Self, AbiError> {
+        Self::from_serializable(abi)
+    }
+
+    pub fn from_deprecated_class(contract_class: &ContractClass) -> Result<Self, AbiError> {
+        Self::from_deprecated_abi(contract_class.abi().as_ref().ok_or(AbiError::MissingAbi)?)
+    }
+
+    pub fn from_sierra_class(contract_class: &SierraContractClass) -> Result<Self, AbiError> {
+        Self::from_sierra_abi(contract_class.abi.as_ref().ok_or(AbiError::MissingAbi)?)
+    }
+
+    fn from_serializable(abi: &impl serde::Serialize) -> Result<Self, AbiError> {
+        let value = serde_json::to_value(abi).map_err(|e| AbiError::Parse(e.to_string()))?;
+        let entries: Vec<RawAbiEntry> =
+            serde_json::from_value(value).map_err(|e| AbiError::Parse(e.to_string()))?;
+        Ok(Self::from_entries(entries))
+    }
+
+    fn from_entries(entries: Vec<RawAbiEntry>) -> Self {
+        let mut abi = Abi::default();
+        for entry in entries {
+            abi.add_entry(entry);
+        }
+        abi
+    }
+
+    fn add_entry(&mut self, entry: RawAbiEntry) {
+        let (function, entry_point_type) = match entry {
+            RawAbiEntry::Function(function) => (function, EntryPointType::External),
+            RawAbiEntry::Constructor(function) => (function, EntryPointType::Constructor),
+            RawAbiEntry::L1Handler(function) => (function, EntryPointType::L1Handler),
+            RawAbiEntry::Struct(RawStruct { name, members }) => {
+                self.structs.insert(name, members);
+                return;
+            }
+            RawAbiEntry::Enum(RawEnum { name, variants }) => {
+                self.enums.insert(name, variants);
+                return;
+            }
+            RawAbiEntry::Interface(RawInterface { items }) => {
+                for item in items {
+                    self.add_entry(item);
+                }
+                return;
+            }
+            RawAbiEntry::Event(event) => {
+                let (name, event) = event.into_abi_event();
+                self.events.insert(name, event);
+                return;
+            }
+            RawAbiEntry::Other => return,
+        };
+
+        let selector = Felt252::from_bytes_be(&calculate_sn_keccak(function.name.as_bytes()));
+        self.functions.insert(
+            function.name.clone(),
+            AbiFunction {
+                name: function.name,
+                selector,
+                entry_point_type,
+                inputs: fold_array_lengths(function.inputs),
+                outputs: fold_array_lengths(function.outputs),
+            },
+        );
+    }
+
+    // --------------------
+    // Entry points
+    // --------------------
+
+    pub fn functions(&self) -> impl Iterator<Item = &AbiFunction> {
+        self.functions.values()
+    }
+
+    pub fn function(&self, name: &str) -> Result<&AbiFunction, AbiError> {
+        self.functions
+            .get(name)
+            .ok_or_else(|| AbiError::FunctionNotFound(name.to_string()))
+    }
+
+    /// Resolves the selector of the function `name`.
+    pub fn selector(&self, name: &str) -> Result<Felt252, AbiError> {
+        self.function(name).map(|function| function.selector)
+    }
+
+    /// Returns the function whose selector is `selector`.
+    pub fn function_by_selector(&self, selector: &Felt252) -> Option<&AbiFunction> {
+        self.functions
+            .values()
+            .find(|function| function.selector == *selector)
+    }
+
+    // --------------------
+    // Encoding
+    // --------------------
+
+    /// Encodes the arguments of the function `name` into calldata.
+    pub fn encode_calldata(&self, name: &str, args: &[AbiValue]) -> Result<Vec<Felt252>, AbiError> {
+        let function = self.function(name)?;
+        if function.inputs.len() != args.len() {
+            return Err(AbiError::WrongArgumentCount {
+                function: name.to_string(),
+                expected: function.inputs.len(),
+                actual: args.len(),
+            });
+        }
+
+        let mut calldata = Vec::new();
+        for (param, arg) in function.inputs.iter().zip(args) {
+            self.encode_into(&param.ty, arg, &mut calldata)?;
+        }
+        Ok(calldata)
+    }
+
+    /// Encodes a single value of type `ty`.
+    pub fn encode_value(&self, ty: &str, value: &AbiValue) -> Result<Vec<Felt252>, AbiError> {
+        let mut encoded = Vec::new();
+        self.encode_into(ty, value, &mut encoded)?;
+        Ok(encoded)
+    }
+
+    fn encode_into(
+        &self,
+        ty: &str,
+        value: &AbiValue,
+        out: &mut Vec<Felt252>,
+    ) -> Result<(), AbiError> {
+        let ty = ty.trim().trim_start_matches('@');
+        let mismatch = || AbiError::TypeMismatch {
+            ty: ty.to_string(),
+            value: format!("{value:?}"),
+        };
+
+        match (self.resolve(ty)?, value) {
+            (AbiTypeKind::Felt, AbiValue::Felt(felt)) => {
+                if let Some(bits) = unsigned_bits(ty) {
+                    if felt.to_biguint().bits() > bits {
+                        return Err(AbiError::OutOfRange {
+                            ty: ty.to_string(),
+                            value: format!("{felt:#x}"),
+                        });
+                    }
+                }
+                out.push(*felt);
+            }
+            (AbiTypeKind::Bool, AbiValue::Bool(value)) => out.push(Felt252::from(*value as u8)),
+            (AbiTypeKind::U256, AbiValue::U256(value)) => encode_u256(ty, value, out)?,
+            (AbiTypeKind::U256, AbiValue::Felt(felt)) => encode_u256(ty, &felt.to_biguint(), out)?,
+            (AbiTypeKind::ByteArray, AbiValue::ByteArray(string)) => {
+                encode_byte_array(string.as_bytes(), out)
+            }
+            (AbiTypeKind::Array(inner), AbiValue::Array(values)) => {
+                out.push(Felt252::from(values.len()));
+                for value in values {
+                    self.encode_into(inner, value, out)?;
+                }
+            }
+            (AbiTypeKind::Tuple(types), AbiValue::Tuple(values)) => {
+                if types.len() != values.len() {
+                    return Err(mismatch());
+                }
+                for (ty, value) in types.iter().zip(values) {
+                    self.encode_into(ty, value, out)?;
+                }
+            }
+            (AbiTypeKind::Struct(members), AbiValue::Struct(_)) => {
+                for member in members {
+                    let value =
+                        value
+                            .member(&member.name)
+                            .ok_or_else(|| AbiError::MissingMember {
+                                ty: ty.to_string(),
+                                member: member.name.clone(),
+                            })?;
+                    self.encode_into(&member.ty, value, out)?;
+                }
+            }
+            (AbiTypeKind::Enum(variants), AbiValue::Enum(variant, payload)) => {
+                let (index, variant) = variants
+                    .iter()
+                    .enumerate()
+                    .find(|(_, v)| v.name == *variant)
+                    .ok_or_else(|| AbiError::UnknownVariant {
+                        ty: ty.to_string(),
+                        variant: variant.clone(),
+                    })?;
+                out.push(Felt252::from(index));
+                self.encode_into(&variant.ty, payload, out)?;
+            }
+            _ => return Err(mismatch()),
+        }
+
+        Ok(())
+    }
+
+    // --------------------
+    // Decoding
+    // --------------------
+
+    /// Decodes the return data of the function `name` into its outputs.
+    pub fn decode_retdata(
+        &self,
+        name: &str,
+        retdata: &[Felt252],
+    ) -> Result<Vec<AbiValue>, AbiError> {
+        let types: Vec<&str> = self
+            .function(name)?
+            .outputs
+            .iter()
+            .map(|output| output.ty.as_str())
+            .collect();
+        self.decode_values(&types, retdata)
+    }
+
+    /// Decodes `data` as the consecutive serialization of values of the given types, such as the
+    /// data of an event. All of `data` must be consumed.
+    pub fn decode_values(
+        &self,
+        types: &[&str],
+        data: &[Felt252],
+    ) -> Result<Vec<AbiValue>, AbiError> {
+        let mut data = data;
+        let values = types
+            .iter()
+            .map(|ty| self.decode_from(ty, &mut data))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if !data.is_empty() {
+            return Err(AbiError::TrailingData(data.len()));
+        }
+        Ok(values)
+    }
+
+    /// Decodes a value of type `ty` from the start of `data`, advancing it past the decoded
+    /// felts.
+    pub fn decode_from(&self, ty: &str, data: &mut &[Felt252]) -> Result<AbiValue, AbiError> {
+        let ty = ty.trim().trim_start_matches('@');
+        let mut next_felt = || match data.split_first() {
+            Some((felt, rest)) => {
+                *data = rest;
+                Ok(*felt)
+            }
+            None => Err(AbiError::NotEnoughData(ty.to_string())),
+        };
+
+        let value = match self.resolve(ty)? {
+            AbiTypeKind::Felt => AbiValue::Felt(next_felt()?),
+            AbiTypeKind::Bool => {
+                let felt = next_felt()?;
+                if felt == Felt252::ZERO {
+                    AbiValue::Bool(false)
+                } else if felt == Felt252::ONE {
+                    AbiValue::Bool(true)
+                } else {
+                    return Err(AbiError::TypeMismatch {
+                        ty: ty.to_string(),
+                        value: format!("{felt:#x}"),
+                    });
+                }
+            }
+            AbiTypeKind::U256 => {
+                let low = next_felt()?.to_biguint();
+                let high = next_felt()?.to_biguint();
+                AbiValue::U256((high << 128) + low)
+            }
+            AbiTypeKind::ByteArray => {
+                let n_full_words = data
+                    .first()
+                    .ok_or_else(|| AbiError::NotEnoughData(ty.to_string()))?;
+                let len =
+                    byte_array_serialized_len(n_full_words).ok_or(AbiError::InvalidByteArray)?;
+                if data.len() < len {
+                    return Err(AbiError::NotEnoughData(ty.to_string()));
+                }
+                let (serialized, rest) = data.split_at(len);
+                *data = rest;
+
+                AbiValue::ByteArray(
+                    decode_byte_array(serialized).ok_or(AbiError::InvalidByteArray)?,
+                )
+            }
+            AbiTypeKind::Array(inner) => {
+                let len = next_felt()?;
+                let len = len.to_usize().ok_or_else(|| AbiError::OutOfRange {
+                    ty: ty.to_string(),
+                    value: format!("{len:#x}"),
+                })?;
+                AbiValue::Array(
+                    (0..len)
+                        .map(|_| self.decode_from(inner, data))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            AbiTypeKind::Tuple(types) => AbiValue::Tuple(
+                types
+                    .iter()
+                    .map(|ty| self.decode_from(ty, data))
+                    .collect::<Result<_, _>>()?,
+            ),
+            AbiTypeKind::Struct(members) => AbiValue::Struct(
+                members
+                    .iter()
+                    .map(|member| Ok((member.name.clone(), self.decode_from(&member.ty, data)?)))
+                    .collect::<Result<_, AbiError>>()?,
+            ),
+            AbiTypeKind::Enum(variants) => {
+                let index = next_felt()?;
+                let variant = index
+                    .to_usize()
+                    .and_then(|index| variants.get(index))
+                    .ok_or_else(|| AbiError::UnknownVariant {
+                        ty: ty.to_string(),
+                        variant: format!("{index:#x}"),
+                    })?;
+                AbiValue::Enum(
+                    variant.name.clone(),
+                    Box::new(self.decode_from(&variant.ty, data)?),
+                )
+            }
+        };
+
+        Ok(value)
+    }
+
+    // --------------------
+    // Type resolution
+    // --------------------
+
+    fn resolve<'a>(&'a self, ty: &'a str) -> Result<AbiTypeKind<'a>, AbiError> {
+        if FELT_TYPES.contains(&ty) || unsigned_bits(ty).is_some() || is_signed_integer(ty) {
+            return Ok(AbiTypeKind::Felt);
+        }
+        if U256_TYPES.contains(&ty) {
+            return Ok(AbiTypeKind::U256);
+        }
+        if ty == BOOL_TYPE {
+            return Ok(AbiTypeKind::Bool);
+        }
+        if ty == BYTE_ARRAY_TYPE {
+            return Ok(AbiTypeKind::ByteArray);
+        }
+        if let Some(inner) = ty.strip_suffix('*') {
+            return Ok(AbiTypeKind::Array(inner));
+        }
+        if let Some(inner) = ARRAY_PREFIXES
+            .iter()
+            .find_map(|prefix| ty.strip_prefix(prefix)?.strip_suffix('>'))
+        {
+            return Ok(AbiTypeKind::Array(inner));
+        }
+        if let Some(inner) = ty.strip_prefix('(').and_then(|ty| ty.strip_suffix(')')) {
+            return Ok(AbiTypeKind::Tuple(split_tuple(inner)));
+        }
+        if let Some(members) = self.structs.get(ty) {
+            return Ok(AbiTypeKind::Struct(members));
+        }
+        if let Some(variants) = self.enums.get(ty) {
+            return Ok(AbiTypeKind::Enum(variants));
+        }
+
+        Err(AbiError::UnknownType(ty.to_string()))
+    }
+}
+
+/// How a type is serialized.
+enum AbiTypeKind<'a> {
+    Felt,
+    Bool,
+    U256,
+    ByteArray,
+    Array(&'a str),
+    Tuple(Vec<&'a str>),
+    Struct(&'a [AbiParam]),
+    Enum(&'a [AbiParam]),
+}
+
+// --------------------
+// Helpers
+// --------------------
+
+/// Removes the `<name>_len` parameters that Cairo 0 places before each `<name>` pointer
+/// parameter, as the length is serialized as part of the array.
+pub(crate) fn fold_array_lengths(params: Vec<AbiParam>) -> Vec<AbiParam> {
+    let is_length_of = |param: &AbiParam, next: Option<&AbiParam>| {
+        next.map_or(false, |next| {
+            next.ty.ends_with('*') && param.name == format!("{}_len", next.name)
+        })
+    };
+
+    params
+        .iter()
+        .enumerate()
+        .filter(|(i, param)| !is_length_of(param, params.get(i + 1)))
+        .map(|(_, param)| param.clone())
+        .collect()
+}
+
+/// Returns the bit width of the unsigned integer type `ty`, if it is one (other than `u256`).
+fn unsigned_bits(ty: &str) -> Option<u64> {
+    match ty {
+        "core::integer::u8" => Some(8),
+        "core::integer::u16" => Some(16),
+        "core::integer::u32" | "core::integer::usize" => Some(32),
+        "core::integer::u64" => Some(64),
+        "core::integer::u128" => Some(128),
+        _ => None,
+    }
+}
+
+fn is_signed_integer(ty: &str) -> bool {
+    matches!(
+        ty,
+        "core::integer::i8"
+            | "core::integer::i16"
+            | "core::integer::i32"
+            | "core::integer::i64"
+            | "core::integer::i128"
+    )
+}
+
+/// Splits the members of a tuple type at its top-level commas. Cairo 0 named tuple members
+/// (`name: type`) are stripped of their name.
+fn split_tuple(inner: &str) -> Vec<&str> {
+    let mut members = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in inner.char_indices() {
+        match c {
+            '(' | '<' => depth += 1,
+            ')' | '>' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                members.push(&inner[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    members.push(&inner[start..]);
+
+    members
+        .into_iter()
+        .map(|member| match member.split_once(": ") {
+            Some((_, ty)) => ty.trim(),
+            None => member.trim(),
+        })
+        .filter(|member| !member.is_empty())
+        .collect()
+}
+
+fn encode_u256(ty: &str, value: &BigUint, out: &mut Vec<Felt252>) -> Result<(), AbiError> {
+    if value.bits() > 256 {
+        return Err(AbiError::OutOfRange {
+            ty: ty.to_string(),
+            value: value.to_string(),
+        });
+    }
+    let mask = (BigUint::one() << 128) - BigUint::one();
+    let low = (value & &mask).to_u128().unwrap_or_default();
+    let high = (value >> 128).to_u128().unwrap_or_default();
+    out.push(Felt252::from(low));
+    out.push(Felt252::from(high));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions_sorted::assert_eq;
+
+    const CAIRO1_ABI: &str = r#"[
+        {
+            "type": "impl",
+            "name": "TokenImpl",
+            "interface_name": "token::IToken"
+        },
+        {
+            "type": "struct",
+            "name": "core::integer::u256",
+            "members": [
+                { "name": "low", "type": "core::integer::u128" },
+                { "name": "high", "type": "core::integer::u128" }
+            ]
+        },
+        {
+            "type": "struct",
+            "name": "token::Position",
+            "members": [
+                { "name": "x", "type": "core::integer::u32" },
+                { "name": "y", "type": "core::integer::u32" }
+            ]
+        },
+        {
+            "type": "enum",
+            "name": "core::option::Option::<token::Position>",
+            "variants": [
+                { "name": "Some", "type": "token::Position" },
+                { "name": "None", "type": "()" }
+            ]
+        },
+        {
+            "type": "interface",
+            "name": "token::IToken",
+            "items": [
+                {
+                    "type": "function",
+                    "name": "transfer",
+                    "inputs": [
+                        { "name": "recipient", "type": "core::starknet::contract_address::ContractAddress" },
+                        { "name": "amount", "type": "core::integer::u256" }
+                    ],
+                    "outputs": [{ "type": "core::bool" }],
+                    "state_mutability": "external"
+                },
+                {
+                    "type": "function",
+                    "name": "describe",
+                    "inputs": [
+                        { "name": "positions", "type": "core::array::Span::<token::Position>" },
+                        { "name": "last", "type": "core::option::Option::<token::Position>" }
+                    ],
+                    "outputs": [{ "type": "(core::byte_array::ByteArray, core::felt252)" }],
+                    "state_mutability": "view"
+                }
+            ]
+        },
+        {
+            "type": "constructor",
+            "name": "constructor",
+            "inputs": [{ "name": "name", "type": "core::byte_array::ByteArray" }]
+        }
+    ]"#;
+
+    const CAIRO0_ABI: &str = r#"[
+        {
+            "members": [
+                { "name": "low", "offset": 0, "type": "felt" },
+                { "name": "high", "offset": 1, "type": "felt" }
+            ],
+            "name": "Uint256",
+            "size": 2,
+            "type": "struct"
+        },
+        {
+            "inputs": [
+                { "name": "values_len", "type": "felt" },
+                { "name": "values", "type": "felt*" },
+                { "name": "amount", "type": "Uint256" }
+            ],
+            "name": "store",
+            "outputs": [
+                { "name": "res_len", "type": "felt" },
+                { "name": "res", "type": "felt*" }
+            ],
+            "type": "function"
+        },
+        {
+            "inputs": [{ "name": "from_address", "type": "felt" }],
+            "name": "on_message",
+            "outputs": [],
+            "type": "l1_handler"
+        }
+    ]"#;
+
+    #[test]
+    fn resolve_entry_points_by_name() {
+        let abi = Abi::from_json(CAIRO1_ABI).unwrap();
+
+        let transfer = abi.function("transfer").unwrap();
+        assert_eq!(
+            transfer.selector(),
+            &Felt252::from_bytes_be(&calculate_sn_keccak(b"transfer"))
+        );
+        assert_eq!(transfer.entry_point_type(), EntryPointType::External);
+        assert_eq!(
+            abi.function("constructor").unwrap().entry_point_type(),
+            EntryPointType::Constructor
+        );
+        assert_eq!(
+            abi.function_by_selector(transfer.selector())
+                .unwrap()
+                .name(),
+            "transfer"
+        );
+        assert_eq!(
+            abi.selector("mint"),
+            Err(AbiError::FunctionNotFound("mint".to_string()))
+        );
+    }
+
+    #[test]
+    fn encode_cairo1_calldata() {
+        let abi = Abi::from_json(CAIRO1_ABI).unwrap();
+
+        let amount = (BigUint::one() << 128) + BigUint::from(5u8);
+        let calldata = abi
+            .encode_calldata("transfer", &[Felt252::from(0x1234).into(), amount.into()])
+            .unwrap();
+        assert_eq!(
+            calldata,
+            vec![Felt252::from(0x1234), Felt252::from(5), Felt252::ONE]
+        );
+
+        let position = |x: u32, y: u32| {
+            AbiValue::Struct(vec![
+                ("x".to_string(), Felt252::from(x).into()),
+                ("y".to_string(), Felt252::from(y).into()),
+            ])
+        };
+        let calldata = abi
+            .encode_calldata(
+                "describe",
+                &[
+                    AbiValue::Array(vec![position(1, 2), position(3, 4)]),
+                    AbiValue::Enum("None".to_string(), Box::new(AbiValue::unit())),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            calldata,
+            [2, 1, 2, 3, 4, 1]
+                .into_iter()
+                .map(Felt252::from)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn encode_rejects_invalid_values() {
+        let abi = Abi::from_json(CAIRO1_ABI).unwrap();
+
+        assert_matches!(
+            abi.encode_calldata("transfer", &[Felt252::ONE.into()]),
+            Err(AbiError::WrongArgumentCount {
+                expected: 2,
+                actual: 1,
+                ..
+            })
+        );
+        assert_matches!(
+            abi.encode_value("core::integer::u32", &Felt252::from(u64::MAX).into()),
+            Err(AbiError::OutOfRange { .. })
+        );
+        assert_matches!(
+            abi.encode_value("core::bool", &Felt252::ONE.into()),
+            Err(AbiError::TypeMismatch { .. })
+        );
+        assert_matches!(
+            abi.encode_value(
+                "core::option::Option::<token::Position>",
+                &AbiValue::Enum("Nope".to_string(), Box::new(AbiValue::unit()))
+            ),
+            Err(AbiError::UnknownVariant { .. })
+        );
+        assert_matches!(
+            abi.encode_value("token::Unknown", &Felt252::ONE.into()),
+            Err(AbiError::UnknownType(_))
+        );
+    }
+
+    #[test]
+    fn byte_array_round_trip() {
+        let abi = Abi::from_json(CAIRO1_ABI).unwrap();
+        let string = "This string is longer than a single ByteArray word";
+
+        let encoded = abi.encode_value(BYTE_ARRAY_TYPE, &string.into()).unwrap();
+        assert_eq!(encoded.len(), 4);
+        assert_eq!(encoded[0], Felt252::ONE);
+        assert_eq!(encoded[3], Felt252::from(string.len() - 31));
+
+        let mut data = encoded.as_slice();
+        assert_eq!(
+            abi.decode_from(BYTE_ARRAY_TYPE, &mut data),
+            Ok(AbiValue::ByteArray(string.to_string()))
+        );
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn decode_byte_array_with_overflowing_length() {
+        let abi = Abi::from_json(CAIRO1_ABI).unwrap();
+
+        let mut data: &[Felt252] = &[Felt252::from(u64::MAX), Felt252::ZERO];
+        assert_eq!(
+            abi.decode_from(BYTE_ARRAY_TYPE, &mut data),
+            Err(AbiError::InvalidByteArray)
+        );
+        let mut data: &[Felt252] = &[Felt252::ONE, Felt252::ZERO];
+        assert_eq!(
+            abi.decode_from(BYTE_ARRAY_TYPE, &mut data),
+            Err(AbiError::NotEnoughData(BYTE_ARRAY_TYPE.to_string()))
+        );
+    }
+
+    #[test]
+    fn decode_cairo1_retdata() {
+        let abi = Abi::from_json(CAIRO1_ABI).unwrap();
+
+        let mut retdata = abi.encode_value(BYTE_ARRAY_TYPE, &"ok".into()).unwrap();
+        retdata.push(Felt252::from(7));
+        assert_eq!(
+            abi.decode_retdata("describe", &retdata),
+            Ok(vec![AbiValue::Tuple(vec![
+                AbiValue::ByteArray("ok".to_string()),
+                AbiValue::Felt(Felt252::from(7)),
+            ])])
+        );
+
+        assert_eq!(
+            abi.decode_retdata("transfer", &[Felt252::ONE]),
+            Ok(vec![AbiValue::Bool(true)])
+        );
+        assert_eq!(
+            abi.decode_retdata("transfer", &[Felt252::ONE, Felt252::ONE]),
+            Err(AbiError::TrailingData(1))
+        );
+        assert_matches!(
+            abi.decode_retdata("describe", &[Felt252::ZERO]),
+            Err(AbiError::NotEnoughData(_))
+        );
+    }
+
+    #[test]
+    fn encode_and_decode_cairo0_arrays() {
+        let abi = Abi::from_json(CAIRO0_ABI).unwrap();
+
+        let store = abi.function("store").unwrap();
+        assert_eq!(store.inputs().len(), 2);
+        assert_eq!(
+            abi.function("on_message").unwrap().entry_point_type(),
+            EntryPointType::L1Handler
+        );
+
+        let calldata = abi
+            .encode_calldata(
+                "store",
+                &[
+                    vec![Felt252::from(3), Felt252::from(4)].into(),
+                    AbiValue::U256(BigUint::from(10u8)),
+                ],
+            )
+            .unwrap();
+        assert_eq!(
+            calldata,
+            [2, 3, 4, 10, 0]
+                .into_iter()
+                .map(Felt252::from)
+                .collect::<Vec<_>>()
+        );
+
+        assert_eq!(
+            abi.decode_retdata("store", &[Felt252::ONE, Felt252::from(9)]),
+            Ok(vec![vec![Felt252::from(9)].into()])
+        );
+    }
+
+    #[test]
+    fn split_named_tuples() {
+        assert_eq!(
+            split_tuple("a: felt, b: (felt, felt)"),
+            vec!["felt", "(felt, felt)"]
+        );
+        assert_eq!(
+            split_tuple("core::array::Array::<(core::felt252, core::bool)>, core::felt252"),
+            vec![
+                "core::array::Array::<(core::felt252, core::bool)>",
+                "core::felt252"
+            ]
+        );
+        assert!(split_tuple("").is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 use lazy_static::lazy_static;
 use starknet_in_rust::{
     definitions::{block_context::BlockContext, constants::TRANSACTION_VERSION},
+    execution::backend::ExecutionConfig,
     services::api::contract_classes::compiled_class::CompiledClass,
     services::api::contract_classes::deprecated_contract_class::ContractClass,
     state::{cached_state::CachedState, in_memory_state_reader::InMemoryStateReader},
@@ -55,13 +56,7 @@ fn main() {
             .unwrap();
         let tx = Transaction::Deploy(deploy);
 
-        tx.execute(
-            &mut state,
-            &block_context,
-            0,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
-        .unwrap();
+        tx.execute(&mut state, &block_context, 0, &ExecutionConfig::default())
+            .unwrap();
     }
 }
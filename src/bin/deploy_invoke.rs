@@ -3,6 +3,7 @@ use lazy_static::lazy_static;
 
 use starknet_in_rust::{
     definitions::{block_context::BlockContext, constants::TRANSACTION_VERSION},
+    execution::backend::ExecutionConfig,
     services::api::contract_classes::{
         compiled_class::CompiledClass, deprecated_contract_class::ContractClass,
     },
@@ -72,13 +73,7 @@ fn main() {
     let deploy_tx = Transaction::Deploy(deploy);
 
     let _tx_exec_info = deploy_tx
-        .execute(
-            &mut state,
-            &block_context,
-            0,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &block_context, 0, &ExecutionConfig::default())
         .unwrap();
 
     let signature = Vec::new();
@@ -106,14 +101,8 @@ fn main() {
         .unwrap();
 
         let tx = Transaction::InvokeFunction(invoke_first);
-        tx.execute(
-            &mut state,
-            &block_context,
-            0,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
-        .unwrap();
+        tx.execute(&mut state, &block_context, 0, &ExecutionConfig::default())
+            .unwrap();
 
         let invoke_second = InvokeFunction::new(
             contract_address.clone(),
@@ -129,13 +118,7 @@ fn main() {
 
         let tx = Transaction::InvokeFunction(invoke_second);
         let tx_exec_info = tx
-            .execute(
-                &mut state,
-                &block_context,
-                0,
-                #[cfg(feature = "cairo-native")]
-                None,
-            )
+            .execute(&mut state, &block_context, 0, &ExecutionConfig::default())
             .unwrap();
 
         assert_eq!(
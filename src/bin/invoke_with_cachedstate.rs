@@ -5,6 +5,7 @@ use starknet_in_rust::{
     definitions::{
         block_context::{BlockContext, StarknetChainId, StarknetOsConfig},
         constants::TRANSACTION_VERSION,
+        execution::backend::ExecutionConfig,
     },
     services::api::contract_classes::{
         compiled_class::CompiledClass, deprecated_contract_class::ContractClass,
@@ -68,8 +69,7 @@ fn main() {
             &mut cached_state,
             &block_context,
             0,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &ExecutionConfig::default(),
         )
         .unwrap();
 
@@ -88,8 +88,7 @@ fn main() {
             &mut cached_state,
             &block_context,
             0,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &ExecutionConfig::default(),
         )
         .unwrap();
 
@@ -7,10 +7,6 @@
 // $ native_bench <n_executions> native <fibo|fact>
 // where fibo executes a fibonacci function and fact a factorial n times.
 
-use cairo_native::cache::AotProgramCache;
-use cairo_native::cache::JitProgramCache;
-use cairo_native::cache::ProgramCache;
-use cairo_native::context::NativeContext;
 use cairo_vm::Felt252;
 use lazy_static::lazy_static;
 use starknet_in_rust::definitions::block_context::BlockContext;
@@ -21,20 +17,19 @@ use starknet_in_rust::state::contract_class_cache::PermanentContractClassCache;
 use starknet_in_rust::state::state_api::State;
 use starknet_in_rust::transaction::DeployAccount;
 use starknet_in_rust::utils::calculate_sn_keccak;
-use starknet_in_rust::utils::get_native_context;
 use starknet_in_rust::CasmContractClass;
 use starknet_in_rust::EntryPointType;
 use starknet_in_rust::{
     definitions::constants::TRANSACTION_VERSION,
     execution::{
-        execution_entry_point::ExecutionEntryPoint, CallInfo, CallType, TransactionExecutionContext,
+        backend::{ExecutionBackend, ExecutionConfig},
+        execution_entry_point::ExecutionEntryPoint,
+        CallInfo, CallType, TransactionExecutionContext,
     },
     state::cached_state::CachedState,
     state::{in_memory_state_reader::InMemoryStateReader, ExecutionResourcesManager},
     transaction::{Address, ClassHash},
 };
-use std::cell::RefCell;
-use std::rc::Rc;
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -57,6 +52,16 @@ impl FromStr for BenchType {
     }
 }
 
+impl BenchType {
+    fn execution_config(&self) -> ExecutionConfig {
+        ExecutionConfig::new(match self {
+            Self::VM => ExecutionBackend::Vm,
+            Self::Jit => ExecutionBackend::NativeJit,
+            Self::Aot => ExecutionBackend::NativeAot,
+        })
+    }
+}
+
 pub fn main() {
     let args: Vec<String> = std::env::args().collect();
 
@@ -146,12 +151,7 @@ fn bench_fibo(executions: usize, bench_type: BenchType) {
     /* f0, f1, N */
     let mut calldata = [1.into(), 1.into(), 2000000.into()];
 
-    let native_ctx = NativeContext::new();
-    let program_cache = Rc::new(RefCell::new(match bench_type {
-        BenchType::Aot => ProgramCache::Aot(AotProgramCache::new(&native_ctx)),
-        BenchType::Jit => ProgramCache::Jit(JitProgramCache::new(&native_ctx)),
-        BenchType::VM => ProgramCache::Jit(JitProgramCache::new(&native_ctx)),
-    }));
+    let execution_config = bench_type.execution_config();
 
     let constructor_selector: Felt252 = (&constructor_selector).into();
 
@@ -165,7 +165,7 @@ fn bench_fibo(executions: usize, bench_type: BenchType) {
             &calldata,
             EntryPointType::External,
             &CASM_CLASS_HASH,
-            program_cache.clone(),
+            &execution_config,
         );
 
         _ = std::hint::black_box(result);
@@ -234,12 +234,7 @@ fn bench_fact(executions: usize, bench_type: BenchType) {
     /* N */
     let mut calldata = [2000000.into()];
 
-    let native_ctx = NativeContext::new();
-    let program_cache = Rc::new(RefCell::new(match bench_type {
-        BenchType::Aot => ProgramCache::Aot(AotProgramCache::new(&native_ctx)),
-        BenchType::Jit => ProgramCache::Jit(JitProgramCache::new(&native_ctx)),
-        _ => unreachable!(),
-    }));
+    let execution_config = bench_type.execution_config();
 
     let constructor_selector: Felt252 = (&constructor_selector).into();
 
@@ -253,7 +248,7 @@ fn bench_fact(executions: usize, bench_type: BenchType) {
             &calldata,
             EntryPointType::External,
             &CASM_CLASS_HASH,
-            program_cache.clone(),
+            &execution_config,
         );
 
         _ = std::hint::black_box(result);
@@ -291,11 +286,7 @@ fn bench_erc20(executions: usize, bench_type: BenchType) {
         static ref ERC20_DEPLOYMENT_CALLER_ADDRESS: Address = Address(0000.into());
     }
 
-    let program_cache = Rc::new(RefCell::new(match bench_type {
-        BenchType::Aot => ProgramCache::Aot(AotProgramCache::new(get_native_context())),
-        BenchType::Jit => ProgramCache::Jit(JitProgramCache::new(get_native_context())),
-        BenchType::VM => ProgramCache::Jit(JitProgramCache::new(get_native_context())),
-    }));
+    let execution_config = bench_type.execution_config();
 
     // read the ERC20 contract class
     let erc20_casm_class = include_bytes!("../../starknet_programs/cairo2/erc20.casm");
@@ -378,7 +369,7 @@ fn bench_erc20(executions: usize, bench_type: BenchType) {
                     &mut tx_execution_context,
                     false,
                     block_context.invoke_tx_max_n_steps(),
-                    Some(program_cache.clone()),
+                    &execution_config,
                 )
                 .unwrap();
 
@@ -459,7 +450,7 @@ fn bench_erc20(executions: usize, bench_type: BenchType) {
                     &mut tx_execution_context,
                     false,
                     block_context.invoke_tx_max_n_steps(),
-                    Some(program_cache.clone()),
+                    &execution_config,
                 )
                 .unwrap();
 
@@ -527,7 +518,7 @@ fn bench_erc20(executions: usize, bench_type: BenchType) {
     // this will create the account and after that,
     // we can extract its address.
     let account1_address = account1_deploy_tx
-        .execute(&mut state, &Default::default(), Some(program_cache.clone()))
+        .execute(&mut state, &Default::default(), &execution_config)
         .expect("failed to execute the deployment of account 1")
         .validate_info
         .expect("validate_info missing")
@@ -557,7 +548,7 @@ fn bench_erc20(executions: usize, bench_type: BenchType) {
 
     // execute the deploy_account transaction and retrieve the deployed account address.
     let _account2_address = account2_deploy_tx
-        .execute(&mut state, &Default::default(), Some(program_cache.clone()))
+        .execute(&mut state, &Default::default(), &execution_config)
         .expect("failed to execute the deployment of account 2")
         .validate_info
         .expect("validate_info missing")
@@ -578,7 +569,7 @@ fn bench_erc20(executions: usize, bench_type: BenchType) {
             &calldata.clone(),
             EntryPointType::External,
             &ERC20_CLASS_HASH,
-            program_cache.clone(),
+            &execution_config,
         );
 
         _ = std::hint::black_box(result);
@@ -595,7 +586,7 @@ fn execute(
     calldata: &[Felt252],
     entrypoint_type: EntryPointType,
     class_hash: &ClassHash,
-    program_cache: Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+    execution_config: &ExecutionConfig,
 ) -> CallInfo {
     let exec_entry_point = ExecutionEntryPoint::new(
         (*callee_address).clone(),
@@ -629,7 +620,7 @@ fn execute(
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            Some(program_cache),
+            execution_config,
         )
         .unwrap()
         .call_info
@@ -0,0 +1,299 @@
+//! Cheat codes for testing.
+//!
+//! These functions write straight into a [`CachedState`] or [`BlockContext`], bypassing the
+//! transactions that would normally be needed to get there, in the spirit of Foundry's cheat
+//! codes. An [`Impersonator`] lets transactions from chosen accounts through without running their
+//! `__validate__` entry point, so they can be sent without knowing the account's keys.
+
+use crate::{
+    core::errors::state_errors::StateError,
+    definitions::block_context::{BlockContext, FeeType},
+    state::{
+        cached_state::CachedState,
+        contract_class_cache::ContractClassCache,
+        state_api::{State, StateReader},
+    },
+    transaction::{Address, ClassHash, Transaction},
+    utils::{get_erc20_balance_var_addresses, get_storage_var_address},
+};
+use cairo_vm::Felt252;
+use std::collections::HashSet;
+
+/// Sets the balance of `address` in the fee token for `fee_type`.
+///
+/// The total supply of the token isn't updated.
+pub fn set_balance<T: StateReader, C: ContractClassCache>(
+    state: &mut CachedState<T, C>,
+    block_context: &BlockContext,
+    fee_type: &FeeType,
+    address: &Address,
+    amount: Felt252,
+) -> Result<(), StateError> {
+    let fee_token_address = block_context.get_fee_token_address_by_fee_type(fee_type);
+    let (low_key, high_key) = get_erc20_balance_var_addresses(address)?;
+    let amount = amount.to_bytes_be();
+
+    state.set_storage_at(
+        &(fee_token_address.clone(), low_key),
+        Felt252::from_bytes_be_slice(&amount[16..]),
+    );
+    state.set_storage_at(
+        &(fee_token_address.clone(), high_key),
+        Felt252::from_bytes_be_slice(&amount[..16]),
+    );
+    Ok(())
+}
+
+/// Returns the balance of `address` in the fee token for `fee_type`.
+pub fn get_balance<T: StateReader, C: ContractClassCache>(
+    state: &CachedState<T, C>,
+    block_context: &BlockContext,
+    fee_type: &FeeType,
+    address: &Address,
+) -> Result<Felt252, StateError> {
+    let fee_token_address = block_context.get_fee_token_address_by_fee_type(fee_type);
+    let (low_key, high_key) = get_erc20_balance_var_addresses(address)?;
+
+    let low = StateReader::get_storage_at(state, &(fee_token_address.clone(), low_key))?;
+    let high = StateReader::get_storage_at(state, &(fee_token_address.clone(), high_key))?;
+    Ok(high * (Felt252::from(u128::MAX) + Felt252::ONE) + low)
+}
+
+/// Sets the storage variable `var_name` of `contract_address` to `value`.
+///
+/// For mappings, `keys` holds the keys of the entry to write, the same way as in
+/// [`get_storage_var_address`]. Values taking more than one slot have to be written slot by slot.
+pub fn set_storage_var<T: StateReader, C: ContractClassCache>(
+    state: &mut CachedState<T, C>,
+    contract_address: &Address,
+    var_name: &str,
+    keys: &[Felt252],
+    value: Felt252,
+) -> Result<(), StateError> {
+    let address = get_storage_var_address(var_name, keys)?;
+    state.set_storage_at(&(contract_address.clone(), address.to_bytes_be()), value);
+    Ok(())
+}
+
+/// Deploys the already declared class `class_hash` at `address`, without running its
+/// constructor.
+pub fn deploy_at<T: StateReader, C: ContractClassCache>(
+    state: &mut CachedState<T, C>,
+    address: Address,
+    class_hash: ClassHash,
+) -> Result<(), StateError> {
+    // Make sure the class exists, as deploying it would otherwise only fail when it's called.
+    StateReader::get_contract_class(state, &class_hash)?;
+    state.deploy_contract(address, class_hash)
+}
+
+/// Sets the nonce of `address`.
+pub fn set_nonce<T: StateReader, C: ContractClassCache>(
+    state: &mut CachedState<T, C>,
+    address: Address,
+    nonce: Felt252,
+) {
+    state.cache_mut().nonce_writes.insert(address, nonce);
+}
+
+// --------------------
+// BlockOverrides structure
+// --------------------
+
+/// Overrides for the info of the current block.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockOverrides {
+    pub block_number: Option<u64>,
+    pub block_timestamp: Option<u64>,
+    pub sequencer_address: Option<Address>,
+}
+
+impl BlockOverrides {
+    /// Writes the overridden fields into the block info of `block_context`.
+    pub fn apply(&self, block_context: &mut BlockContext) {
+        let block_info = block_context.block_info_mut();
+        if let Some(block_number) = self.block_number {
+            block_info.block_number = block_number;
+        }
+        if let Some(block_timestamp) = self.block_timestamp {
+            block_info.block_timestamp = block_timestamp;
+        }
+        if let Some(sequencer_address) = &self.sequencer_address {
+            block_info.sequencer_address = sequencer_address.clone();
+        }
+    }
+}
+
+// --------------------
+// Impersonator structure
+// --------------------
+
+/// Keeps track of the accounts being impersonated.
+#[derive(Clone, Debug, Default)]
+pub struct Impersonator {
+    accounts: HashSet<Address>,
+}
+
+impl Impersonator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts impersonating `account`.
+    pub fn impersonate(&mut self, account: Address) {
+        self.accounts.insert(account);
+    }
+
+    /// Stops impersonating `account`, returning whether it was impersonated.
+    pub fn stop_impersonating(&mut self, account: &Address) -> bool {
+        self.accounts.remove(account)
+    }
+
+    pub fn is_impersonated(&self, account: &Address) -> bool {
+        self.accounts.contains(account)
+    }
+
+    /// Returns `tx` set up to skip `__validate__` if it's sent by an impersonated account.
+    ///
+    /// Deploy and L1 handler transactions aren't sent by an account, so they are never changed.
+    pub fn prepare(&self, tx: Transaction) -> Transaction {
+        let sent_by_account = !matches!(tx, Transaction::Deploy(_) | Transaction::L1Handler(_));
+        if sent_by_account && self.is_impersonated(&tx.contract_address()) {
+            tx.create_for_simulation(true, false, false, false, false)
+        } else {
+            tx
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        definitions::block_context::StarknetChainId,
+        transaction::{InvokeFunction, VersionSpecificAccountTxFields},
+        utils::{
+            calculate_sn_keccak,
+            test_utils::{
+                create_account_tx_test_state, TEST_ACCOUNT_CONTRACT_ADDRESS, TEST_CONTRACT_ADDRESS,
+            },
+        },
+    };
+    use pretty_assertions_sorted::assert_eq;
+
+    #[test]
+    fn set_balance_storage_and_nonce() {
+        let (block_context, mut state) = create_account_tx_test_state().unwrap();
+        let account = TEST_ACCOUNT_CONTRACT_ADDRESS.clone();
+        let amount = Felt252::from(u128::MAX) * Felt252::from(3) + Felt252::from(7);
+
+        set_balance(&mut state, &block_context, &FeeType::Strk, &account, amount).unwrap();
+        assert_eq!(
+            get_balance(&state, &block_context, &FeeType::Strk, &account).unwrap(),
+            amount
+        );
+
+        set_storage_var(
+            &mut state,
+            &TEST_CONTRACT_ADDRESS,
+            "balances",
+            &[account.0],
+            42.into(),
+        )
+        .unwrap();
+        let key = get_storage_var_address("balances", &[account.0]).unwrap();
+        assert_eq!(
+            StateReader::get_storage_at(
+                &state,
+                &(TEST_CONTRACT_ADDRESS.clone(), key.to_bytes_be())
+            )
+            .unwrap(),
+            Felt252::from(42)
+        );
+
+        set_nonce(&mut state, account.clone(), 5.into());
+        assert_eq!(
+            StateReader::get_nonce_at(&state, &account).unwrap(),
+            Felt252::from(5)
+        );
+    }
+
+    #[test]
+    fn deploy_at_fixed_address() {
+        let (_, mut state) = create_account_tx_test_state().unwrap();
+        let class_hash = StateReader::get_class_hash_at(&state, &TEST_CONTRACT_ADDRESS).unwrap();
+        let address = Address(0x1234.into());
+
+        deploy_at(&mut state, address.clone(), class_hash).unwrap();
+        assert_eq!(
+            StateReader::get_class_hash_at(&state, &address).unwrap(),
+            class_hash
+        );
+        assert!(deploy_at(&mut state, Address(0x4321.into()), ClassHash([9; 32])).is_err());
+    }
+
+    #[test]
+    fn override_block_info() {
+        let (mut block_context, _) = create_account_tx_test_state().unwrap();
+        let sequencer_address = block_context.block_info().sequencer_address.clone();
+
+        BlockOverrides {
+            block_number: Some(100),
+            block_timestamp: Some(1_700_000_000),
+            ..Default::default()
+        }
+        .apply(&mut block_context);
+        assert_eq!(block_context.block_info().block_number, 100);
+        assert_eq!(block_context.block_info().block_timestamp, 1_700_000_000);
+        assert_eq!(
+            block_context.block_info().sequencer_address,
+            sequencer_address
+        );
+    }
+
+    #[test]
+    fn impersonated_accounts_skip_validation() {
+        let (block_context, mut state) = create_account_tx_test_state().unwrap();
+        let invoke = |nonce: u64| {
+            let calldata = vec![
+                TEST_CONTRACT_ADDRESS.0,
+                Felt252::from_bytes_be(&calculate_sn_keccak(b"fib")),
+                3.into(),
+                1.into(),
+                1.into(),
+                10.into(),
+            ];
+            Transaction::InvokeFunction(
+                InvokeFunction::new(
+                    TEST_ACCOUNT_CONTRACT_ADDRESS.clone(),
+                    Felt252::from_bytes_be(&calculate_sn_keccak(b"__execute__")),
+                    VersionSpecificAccountTxFields::new_deprecated(0),
+                    Felt252::ONE,
+                    calldata,
+                    vec![],
+                    StarknetChainId::TestNet.to_felt(),
+                    Some(nonce.into()),
+                )
+                .unwrap(),
+            )
+        };
+        let mut impersonator = Impersonator::new();
+
+        let execution_info = impersonator
+            .prepare(invoke(0))
+            .execute(&mut state, &block_context, u128::MAX, &Default::default())
+            .unwrap();
+        assert!(execution_info.validate_info.is_some());
+
+        impersonator.impersonate(TEST_ACCOUNT_CONTRACT_ADDRESS.clone());
+        let execution_info = impersonator
+            .prepare(invoke(1))
+            .execute(&mut state, &block_context, u128::MAX, &Default::default())
+            .unwrap();
+        assert!(execution_info.validate_info.is_none());
+        assert!(execution_info.call_info.is_some());
+
+        assert!(impersonator.stop_impersonating(&TEST_ACCOUNT_CONTRACT_ADDRESS));
+        assert!(!impersonator.is_impersonated(&TEST_ACCOUNT_CONTRACT_ADDRESS));
+    }
+}
@@ -0,0 +1,12 @@
+use crate::transaction::ClassHash;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum AotCacheError {
+    #[error("Failed to compile class {0} with Cairo Native: {1}")]
+    Compilation(ClassHash, String),
+    #[error("Failed to load the compiled class {0}: {1}")]
+    Load(ClassHash, String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "cairo-native")]
+pub mod aot_cache_errors;
 pub mod contract_address_errors;
 pub mod hash_errors;
 pub mod state_errors;
@@ -1,8 +1,9 @@
 use crate::{
     services::api::contract_class_errors::ContractClassError,
-    state::state_cache::StorageEntry,
+    state::{cached_state::SnapshotId, state_cache::StorageEntry},
     transaction::{Address, ClassHash},
 };
+use cairo_vm::Felt252;
 use starknet::core::types::FromByteArrayError;
 use thiserror::Error;
 
@@ -50,4 +51,20 @@ pub enum StateError {
     ByteArray(#[from] FromByteArrayError),
     #[error("Failed to read contract class cache")]
     FailedToReadContractClassCache,
+    #[error("The hash of block {old_block_number} is required to process block {block_number}")]
+    MissingOldBlockHash {
+        old_block_number: u64,
+        block_number: u64,
+    },
+    #[error(
+        "The hash of block {old_block_number} can't be written when processing block {block_number}"
+    )]
+    UnexpectedOldBlockHash {
+        old_block_number: u64,
+        block_number: u64,
+    },
+    #[error("Snapshot {0:?} doesn't exist or was already reverted")]
+    InvalidSnapshot(SnapshotId),
+    #[error("Storage value {0} can't be read as a {1}")]
+    InvalidStorageValue(Felt252, &'static str),
 }
@@ -5,15 +5,17 @@
 use crate::{state::BlockInfo, transaction::Address};
 use cairo_vm::Felt252;
 use core::fmt;
-use getset::{CopyGetters, Getters, MutGetters};
+use getset::{CopyGetters, Getters, MutGetters, Setters};
 use starknet_api::block::Block;
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use super::constants::{
     DEFAULT_CAIRO_RESOURCE_FEE_WEIGHTS, DEFAULT_CONTRACT_STORAGE_COMMITMENT_TREE_HEIGHT,
     DEFAULT_GLOBAL_STATE_COMMITMENT_TREE_HEIGHT, DEFAULT_INVOKE_TX_MAX_N_STEPS,
-    DEFAULT_SEQUENCER_ADDRESS, DEFAULT_STARKNET_OS_CONFIG, DEFAULT_VALIDATE_MAX_N_STEPS,
+    DEFAULT_SEQUENCER_ADDRESS, DEFAULT_STARKNET_OS_CONFIG, DEFAULT_STARKNET_VERSION,
+    DEFAULT_VALIDATE_MAX_N_STEPS,
 };
+use super::syscall_costs::SyscallCosts;
 
 /// Unique identifier of a Starknet chain.
 #[derive(Debug, Clone, Copy)]
@@ -63,6 +65,37 @@ impl StarknetChainId {
     }
 }
 
+/// Version of the Starknet protocol, which decides the syscalls available to contracts and their
+/// default [`SyscallCosts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StarknetVersion {
+    V0_13_0,
+    V0_13_1,
+    /// Adds the `sha256_process_block` syscall.
+    V0_13_2,
+    V0_13_3,
+    /// Adds the `get_class_hash_at` syscall.
+    V0_13_4,
+}
+
+impl fmt::Display for StarknetVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StarknetVersion::V0_13_0 => write!(f, "0.13.0"),
+            StarknetVersion::V0_13_1 => write!(f, "0.13.1"),
+            StarknetVersion::V0_13_2 => write!(f, "0.13.2"),
+            StarknetVersion::V0_13_3 => write!(f, "0.13.3"),
+            StarknetVersion::V0_13_4 => write!(f, "0.13.4"),
+        }
+    }
+}
+
+impl Default for StarknetVersion {
+    fn default() -> Self {
+        DEFAULT_STARKNET_VERSION
+    }
+}
+
 #[derive(Debug, Clone, Getters, MutGetters)]
 /// Starknet OS configuration.
 pub struct StarknetOsConfig {
@@ -155,7 +188,7 @@ impl Default for StarknetOsConfig {
 }
 
 /// Starknet block context.
-#[derive(Clone, Debug, CopyGetters, Getters, MutGetters)]
+#[derive(Clone, Debug, CopyGetters, Getters, MutGetters, Setters)]
 pub struct BlockContext {
     #[getset(get = "pub", get_mut = "pub")]
     pub(crate) starknet_os_config: StarknetOsConfig,
@@ -175,6 +208,11 @@ pub struct BlockContext {
     #[getset(get = "pub", get_mut = "pub")]
     pub(crate) blocks: HashMap<u64, Block>,
     pub(crate) enforce_l1_handler_fee: bool,
+    /// Version of the protocol the block is executed with.
+    #[getset(get_copy = "pub", set = "pub")]
+    pub(crate) starknet_version: StarknetVersion,
+    /// Replaces the default syscall costs of the Starknet version when set.
+    pub(crate) syscall_costs: Option<Arc<SyscallCosts>>,
 }
 
 impl BlockContext {
@@ -214,9 +252,24 @@ impl BlockContext {
             block_info,
             blocks,
             enforce_l1_handler_fee,
+            starknet_version: DEFAULT_STARKNET_VERSION,
+            syscall_costs: None,
         }
     }
 
+    /// Returns the costs of the syscalls, which are the default ones of the block's Starknet
+    /// version unless they've been replaced with [`set_syscall_costs`](Self::set_syscall_costs).
+    pub fn syscall_costs(&self) -> &SyscallCosts {
+        self.syscall_costs
+            .as_deref()
+            .unwrap_or_else(|| SyscallCosts::for_version(self.starknet_version))
+    }
+
+    pub fn set_syscall_costs(&mut self, syscall_costs: SyscallCosts) -> &mut Self {
+        self.syscall_costs = Some(Arc::new(syscall_costs));
+        self
+    }
+
     pub fn get_gas_price_by_fee_type(&self, fee_type: &FeeType) -> u128 {
         self.starknet_os_config.gas_price.get_by_fee_type(fee_type)
     }
@@ -241,6 +294,8 @@ impl Default for BlockContext {
             block_info: BlockInfo::empty(DEFAULT_SEQUENCER_ADDRESS.clone()),
             blocks: HashMap::default(),
             enforce_l1_handler_fee: true,
+            starknet_version: DEFAULT_STARKNET_VERSION,
+            syscall_costs: None,
         }
     }
 }
@@ -1,4 +1,4 @@
-use super::block_context::{StarknetChainId, StarknetOsConfig};
+use super::block_context::{StarknetChainId, StarknetOsConfig, StarknetVersion};
 use crate::transaction::Address;
 use cairo_vm::Felt252;
 use lazy_static::lazy_static;
@@ -63,12 +63,21 @@ pub const DEFAULT_CONTRACT_STORAGE_COMMITMENT_TREE_HEIGHT: u64 = 251;
 pub const DEFAULT_GLOBAL_STATE_COMMITMENT_TREE_HEIGHT: u64 = 251;
 pub const DEFAULT_INVOKE_TX_MAX_N_STEPS: u64 = 1000000;
 pub const DEFAULT_VALIDATE_MAX_N_STEPS: u64 = 1000000;
+pub const DEFAULT_STARKNET_VERSION: StarknetVersion = StarknetVersion::V0_13_1;
 
 // Gas Cost.
 // From cairo_programs/constants.cairo.
 pub const STEP_GAS_COST: u128 = 100;
 pub const INITIAL_GAS_COST: u128 = 10_u128.pow(8) * STEP_GAS_COST;
 
+// Gas charged for each builtin instance used by sierra code.
+// From token_gas_cost in cairo-lang-runner, where range checks are part of the constant cost.
+pub const RANGE_CHECK_GAS_COST: u128 = 70;
+pub const PEDERSEN_GAS_COST: u128 = 4130;
+pub const BITWISE_GAS_COST: u128 = 594;
+pub const EC_OP_GAS_COST: u128 = 4166;
+pub const POSEIDON_GAS_COST: u128 = 500;
+
 lazy_static! {
     /// Value generated from `get_selector_from_name('constructor')`.
     pub static ref CONSTRUCTOR_ENTRY_POINT_SELECTOR: Felt252 =
@@ -102,6 +111,11 @@ lazy_static! {
     pub static ref BLOCK_HASH_CONTRACT_ADDRESS: Address = Address(1.into());
 }
 
+/// Number of blocks between a block and the most recent block whose hash it can access. The hash
+/// of block `n - STORED_BLOCK_HASH_BUFFER` is written to the block hash contract when block `n`
+/// starts.
+pub const STORED_BLOCK_HASH_BUFFER: u64 = 10;
+
 // Indentation for transactions meant to query and not addressed to the OS.
 lazy_static! {
     static ref QUERY_VERSION_BASE: Felt252 =
@@ -1,3 +1,4 @@
 pub mod block_context;
 pub mod constants;
+pub mod syscall_costs;
 pub mod transaction_type;
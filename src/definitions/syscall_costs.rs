@@ -0,0 +1,308 @@
+//! # Syscall costs
+//!
+//! Gas charged by the syscalls and resources used by the OS to execute them, for each version of
+//! the Starknet protocol. The default costs of each version can be replaced by costs loaded from
+//! JSON, so that they can be updated without changing the code.
+
+use super::{
+    block_context::StarknetVersion,
+    constants::{BITWISE_GAS_COST, RANGE_CHECK_GAS_COST, STEP_GAS_COST},
+};
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, io::Read};
+
+lazy_static! {
+    // Taken from starkware/starknet/constants.py in cairo-lang, and the OS resources from
+    // blockifier.
+    static ref SYSCALL_COSTS_V0_13_0: SyscallCosts = SyscallCosts::v0_13_0();
+    static ref SYSCALL_COSTS_V0_13_2: SyscallCosts = SyscallCosts::v0_13_2();
+    static ref SYSCALL_COSTS_V0_13_4: SyscallCosts = SyscallCosts::v0_13_4();
+}
+
+/// Cost of a single syscall.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyscallCost {
+    /// Gas charged by the syscall, including the syscall base gas. The syscalls of deprecated
+    /// contracts don't charge gas.
+    #[serde(default)]
+    pub gas: Option<u128>,
+    /// Steps run by the OS to execute the syscall.
+    pub n_steps: usize,
+    #[serde(default)]
+    pub n_memory_holes: usize,
+    /// Builtins used by the OS to execute the syscall.
+    #[serde(default)]
+    pub builtin_instance_counter: HashMap<String, usize>,
+}
+
+impl SyscallCost {
+    fn new(gas: Option<u128>, n_steps: usize, builtins: &[(&str, usize)]) -> Self {
+        Self {
+            gas,
+            n_steps,
+            n_memory_holes: 0,
+            builtin_instance_counter: builtins
+                .iter()
+                .map(|(builtin, count)| (builtin.to_string(), *count))
+                .collect(),
+        }
+    }
+
+    fn with_memory_holes(mut self, n_memory_holes: usize) -> Self {
+        self.n_memory_holes = n_memory_holes;
+        self
+    }
+
+    /// Returns the resources used by the OS to execute the syscall.
+    pub fn os_resources(&self) -> ExecutionResources {
+        ExecutionResources {
+            n_steps: self.n_steps,
+            n_memory_holes: self.n_memory_holes,
+            builtin_instance_counter: self.builtin_instance_counter.clone(),
+        }
+    }
+}
+
+// --------------------
+// SyscallCosts structure
+// --------------------
+
+/// Costs of the syscalls of a version of the Starknet protocol, by syscall name.
+///
+/// The same table is used to charge gas by the syscall handlers of the VM and Cairo Native, and
+/// to account the resources used by the OS for the transactions, so that both stay in sync.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyscallCosts {
+    /// Gas charged for every syscall before it's executed, which is included in the gas of each
+    /// syscall.
+    pub syscall_base_gas: u128,
+    /// Gas charged for each block of input of the keccak syscall.
+    pub keccak_round_gas: u128,
+    pub syscalls: HashMap<String, SyscallCost>,
+}
+
+impl SyscallCosts {
+    /// Returns the default costs of the syscalls in the Starknet `version`.
+    pub fn for_version(version: StarknetVersion) -> &'static SyscallCosts {
+        match version {
+            StarknetVersion::V0_13_0 | StarknetVersion::V0_13_1 => &SYSCALL_COSTS_V0_13_0,
+            StarknetVersion::V0_13_2 | StarknetVersion::V0_13_3 => &SYSCALL_COSTS_V0_13_2,
+            StarknetVersion::V0_13_4 => &SYSCALL_COSTS_V0_13_4,
+        }
+    }
+
+    /// Reads the costs from JSON, in the format they're serialized to.
+    pub fn from_reader(reader: impl Read) -> Result<Self, serde_json::Error> {
+        serde_json::from_reader(reader)
+    }
+
+    pub fn get(&self, syscall_name: &str) -> Option<&SyscallCost> {
+        self.syscalls.get(syscall_name)
+    }
+
+    /// Returns the gas charged by the syscall handlers for `syscall_name`, which excludes the
+    /// syscall base gas.
+    pub fn required_gas(&self, syscall_name: &str) -> Option<u128> {
+        self.get(syscall_name)?
+            .gas
+            .map(|gas| gas.saturating_sub(self.syscall_base_gas))
+    }
+
+    fn v0_13_0() -> Self {
+        let syscall_base_gas = 100 * STEP_GAS_COST;
+        let entry_point_gas = 600 * STEP_GAS_COST;
+        let call_contract_gas = syscall_base_gas + 10 * STEP_GAS_COST + entry_point_gas;
+        let storage_gas = syscall_base_gas + 50 * STEP_GAS_COST;
+        let range_check = "range_check_builtin";
+
+        let syscalls = [
+            (
+                "call_contract",
+                SyscallCost::new(Some(call_contract_gas), 760, &[(range_check, 20)])
+                    .with_memory_holes(4),
+            ),
+            (
+                "delegate_call",
+                SyscallCost::new(None, 713, &[(range_check, 19)]),
+            ),
+            (
+                "delegate_l1_handler",
+                SyscallCost::new(None, 692, &[(range_check, 15)]),
+            ),
+            (
+                "deploy",
+                SyscallCost::new(
+                    Some(syscall_base_gas + 200 * STEP_GAS_COST + entry_point_gas),
+                    1010,
+                    &[(range_check, 19), ("pedersen_builtin", 7)],
+                )
+                .with_memory_holes(13),
+            ),
+            (
+                "emit_event",
+                SyscallCost::new(
+                    Some(syscall_base_gas + 10 * STEP_GAS_COST),
+                    61,
+                    &[(range_check, 1)],
+                ),
+            ),
+            (
+                "get_block_hash",
+                SyscallCost::new(Some(storage_gas), 74, &[(range_check, 2)]),
+            ),
+            ("get_block_number", SyscallCost::new(None, 40, &[])),
+            ("get_block_timestamp", SyscallCost::new(Some(0), 38, &[])),
+            (
+                "get_caller_address",
+                SyscallCost::new(None, 62, &[(range_check, 1)]),
+            ),
+            (
+                "get_contract_address",
+                SyscallCost::new(None, 62, &[(range_check, 1)]),
+            ),
+            (
+                "get_execution_info",
+                SyscallCost::new(
+                    Some(syscall_base_gas + 10 * STEP_GAS_COST),
+                    62,
+                    &[(range_check, 1)],
+                ),
+            ),
+            ("get_sequencer_address", SyscallCost::new(None, 34, &[])),
+            (
+                "get_tx_info",
+                SyscallCost::new(None, 62, &[(range_check, 1)]),
+            ),
+            ("get_tx_signature", SyscallCost::new(None, 44, &[])),
+            // The OS resources of keccak aren't known.
+            ("keccak", SyscallCost::new(Some(0), 0, &[])),
+            (
+                "library_call",
+                SyscallCost::new(Some(call_contract_gas), 751, &[(range_check, 20)])
+                    .with_memory_holes(4),
+            ),
+            (
+                "library_call_l1_handler",
+                SyscallCost::new(None, 659, &[(range_check, 15)]),
+            ),
+            (
+                "replace_class",
+                SyscallCost::new(Some(storage_gas), 98, &[(range_check, 1)]),
+            ),
+            (
+                "send_message_to_l1",
+                SyscallCost::new(Some(storage_gas), 139, &[(range_check, 1)]),
+            ),
+            (
+                "storage_read",
+                SyscallCost::new(Some(storage_gas), 87, &[(range_check, 1)]),
+            ),
+            (
+                "storage_write",
+                SyscallCost::new(Some(storage_gas), 89, &[(range_check, 1)]),
+            ),
+        ];
+
+        Self {
+            syscall_base_gas,
+            keccak_round_gas: 180000,
+            syscalls: syscalls
+                .into_iter()
+                .map(|(name, cost)| (name.to_string(), cost))
+                .collect(),
+        }
+    }
+
+    /// Adds the `sha256_process_block` syscall.
+    fn v0_13_2() -> Self {
+        let mut costs = Self::v0_13_0();
+        costs.syscalls.insert(
+            "sha256_process_block".to_string(),
+            SyscallCost::new(
+                Some(
+                    costs.syscall_base_gas
+                        + 1852 * STEP_GAS_COST
+                        + 65 * RANGE_CHECK_GAS_COST
+                        + 1115 * BITWISE_GAS_COST,
+                ),
+                1855,
+                &[("range_check_builtin", 65), ("bitwise_builtin", 1115)],
+            ),
+        );
+        costs
+    }
+
+    /// Adds the `get_class_hash_at` syscall.
+    fn v0_13_4() -> Self {
+        let mut costs = Self::v0_13_2();
+        costs.syscalls.insert(
+            "get_class_hash_at".to_string(),
+            SyscallCost::new(
+                Some(costs.syscall_base_gas),
+                89,
+                &[("range_check_builtin", 1)],
+            ),
+        );
+        costs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions_sorted::assert_eq;
+
+    #[test]
+    fn syscalls_of_each_version() {
+        let costs = SyscallCosts::for_version(StarknetVersion::V0_13_1);
+        assert_eq!(costs.required_gas("storage_read"), Some(5_000));
+        assert_eq!(costs.required_gas("get_block_number"), None);
+        assert_eq!(costs.get("sha256_process_block"), None);
+
+        let costs = SyscallCosts::for_version(StarknetVersion::V0_13_3);
+        assert_eq!(
+            costs.required_gas("sha256_process_block"),
+            Some(185_200 + 65 * 70 + 1115 * 594)
+        );
+        assert_eq!(costs.get("get_class_hash_at"), None);
+
+        let costs = SyscallCosts::for_version(StarknetVersion::V0_13_4);
+        assert_eq!(costs.required_gas("get_class_hash_at"), Some(0));
+    }
+
+    #[test]
+    fn load_costs_from_json() {
+        let costs = SyscallCosts::from_reader(
+            r#"{
+                "syscall_base_gas": 100,
+                "keccak_round_gas": 1000,
+                "syscalls": {
+                    "storage_read": { "gas": 150, "n_steps": 87, "builtin_instance_counter": { "range_check_builtin": 1 } },
+                    "get_tx_info": { "n_steps": 62 }
+                }
+            }"#
+            .as_bytes(),
+        )
+        .unwrap();
+
+        assert_eq!(costs.required_gas("storage_read"), Some(50));
+        assert_eq!(costs.required_gas("get_tx_info"), None);
+        assert_eq!(
+            costs.get("storage_read").unwrap().os_resources(),
+            ExecutionResources {
+                n_steps: 87,
+                n_memory_holes: 0,
+                builtin_instance_counter: HashMap::from([("range_check_builtin".to_string(), 1)]),
+            }
+        );
+
+        let serialized =
+            serde_json::to_string(SyscallCosts::for_version(StarknetVersion::V0_13_4)).unwrap();
+        assert_eq!(
+            &SyscallCosts::from_reader(serialized.as_bytes()).unwrap(),
+            SyscallCosts::for_version(StarknetVersion::V0_13_4)
+        );
+    }
+}
@@ -0,0 +1,18 @@
+use crate::{
+    core::errors::{hash_errors::HashError, state_errors::StateError},
+    transaction::error::TransactionError,
+};
+use cairo_vm::Felt252;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DevnetError {
+    #[error("Transaction {0:#x} was already added")]
+    DuplicateTransaction(Felt252),
+    #[error(transparent)]
+    Transaction(#[from] TransactionError),
+    #[error(transparent)]
+    State(#[from] StateError),
+    #[error(transparent)]
+    Hash(#[from] HashError),
+}
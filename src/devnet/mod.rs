@@ -0,0 +1,487 @@
+//! Local devnet node.
+//!
+//! A [`Devnet`] executes transactions over a [`CachedState`] as they are added, groups them into
+//! blocks, keeps their receipts and writes block hashes into the block hash contract the same way
+//! the OS does. The [`rpc`] module serves a subset of the JSON-RPC API on top of it, as plain
+//! function calls: the devnet has no server or clock of its own, the embedding application drives
+//! it by forwarding requests and calling [`Devnet::tick`].
+//!
+//! Block hashes are computed from the block header fields and transaction hashes only, so they
+//! don't match the hashes Starknet would compute for the same blocks.
+
+pub mod error;
+pub mod rpc;
+
+use self::error::DevnetError;
+use crate::{
+    definitions::{
+        block_context::{BlockContext, FeeType},
+        constants::{INITIAL_GAS_COST, STORED_BLOCK_HASH_BUFFER},
+    },
+    execute_transaction,
+    execution::{backend::ExecutionConfig, receipt::TransactionReceipt},
+    hash_utils::compute_hash_on_elements,
+    state::{
+        block_hash::{old_block_hash_from_reader, pre_process_block, BlockNumberHashPair},
+        cached_state::CachedState,
+        contract_class_cache::ContractClassCache,
+        state_api::{State, StateReader},
+        StateDiff,
+    },
+    transaction::{Address, Transaction},
+};
+use cairo_vm::Felt252;
+use std::{collections::HashMap, mem};
+
+// --------------------
+// DevnetConfig structure
+// --------------------
+
+/// When the devnet closes the pending block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlockProduction {
+    /// Once the pending block holds this number of transactions.
+    TransactionCount(usize),
+    /// Every given number of seconds, as measured by [`Devnet::tick`].
+    Interval(u64),
+    /// Only when [`Devnet::produce_block`] is called.
+    OnDemand,
+}
+
+impl Default for BlockProduction {
+    fn default() -> Self {
+        Self::TransactionCount(1)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DevnetConfig {
+    pub block_production: BlockProduction,
+    /// The gas supplied to execute each transaction.
+    pub remaining_gas: u128,
+}
+
+impl Default for DevnetConfig {
+    fn default() -> Self {
+        Self {
+            block_production: BlockProduction::default(),
+            remaining_gas: INITIAL_GAS_COST,
+        }
+    }
+}
+
+// --------------------
+// DevnetBlock structure
+// --------------------
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DevnetBlock {
+    pub number: u64,
+    pub hash: Felt252,
+    pub parent_hash: Felt252,
+    pub timestamp: u64,
+    pub sequencer_address: Address,
+    pub transaction_hashes: Vec<Felt252>,
+}
+
+// --------------------
+// Devnet structure
+// --------------------
+
+pub struct Devnet<S: StateReader, C: ContractClassCache> {
+    config: DevnetConfig,
+    state: CachedState<S, C>,
+    /// The context of the pending block.
+    block_context: BlockContext,
+    blocks: Vec<DevnetBlock>,
+    pending_transactions: Vec<Felt252>,
+    receipts: HashMap<Felt252, (TransactionReceipt, FeeType)>,
+    /// Time of the last [`Devnet::tick`], which becomes the timestamp of the next block.
+    time: u64,
+}
+
+impl<S: StateReader, C: ContractClassCache> Devnet<S, C> {
+    /// Creates a devnet whose pending block is described by `block_context`.
+    pub fn new(
+        state: CachedState<S, C>,
+        block_context: BlockContext,
+        config: DevnetConfig,
+    ) -> Self {
+        let time = block_context.block_info.block_timestamp;
+        Self {
+            config,
+            state,
+            block_context,
+            blocks: Vec::new(),
+            pending_transactions: Vec::new(),
+            receipts: HashMap::new(),
+            time,
+        }
+    }
+
+    pub fn config(&self) -> &DevnetConfig {
+        &self.config
+    }
+
+    /// Returns the state, including the changes of the pending block.
+    pub fn state(&self) -> &CachedState<S, C> {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut CachedState<S, C> {
+        &mut self.state
+    }
+
+    /// Returns the context of the pending block.
+    pub fn block_context(&self) -> &BlockContext {
+        &self.block_context
+    }
+
+    /// Returns the blocks produced by the devnet, in order.
+    pub fn blocks(&self) -> &[DevnetBlock] {
+        &self.blocks
+    }
+
+    pub fn latest_block(&self) -> Option<&DevnetBlock> {
+        self.blocks.last()
+    }
+
+    pub fn block_by_number(&self, block_number: u64) -> Option<&DevnetBlock> {
+        let first_block_number = self.blocks.first()?.number;
+        let index = block_number.checked_sub(first_block_number)?;
+        self.blocks.get(usize::try_from(index).ok()?)
+    }
+
+    pub fn block_by_hash(&self, block_hash: &Felt252) -> Option<&DevnetBlock> {
+        self.blocks.iter().find(|block| block.hash == *block_hash)
+    }
+
+    /// Returns the hashes of the transactions of the pending block.
+    pub fn pending_transactions(&self) -> &[Felt252] {
+        &self.pending_transactions
+    }
+
+    pub fn receipt(&self, transaction_hash: &Felt252) -> Option<&TransactionReceipt> {
+        self.receipts
+            .get(transaction_hash)
+            .map(|(receipt, _)| receipt)
+    }
+
+    /// Executes a transaction in the pending block and stores its receipt, producing a block
+    /// afterwards if the pending block is full.
+    ///
+    /// Transactions failing before their execution, such as transactions with an invalid nonce
+    /// or failing validation, are rejected without changing the state. Reverted transactions are
+    /// included.
+    pub fn add_transaction(&mut self, transaction: Transaction) -> Result<Felt252, DevnetError> {
+        let transaction_hash = transaction.hash_value();
+        if self.receipts.contains_key(&transaction_hash) {
+            return Err(DevnetError::DuplicateTransaction(transaction_hash));
+        }
+
+        // Transactions update the nonce before checking the balance and running `__validate__`,
+        // so they run on a transactional state that is only applied once they're accepted.
+        let fee_type = match &transaction {
+            Transaction::InvokeFunction(tx) => tx.account_tx_fields().fee_type(),
+            Transaction::Declare(tx) => tx.account_tx_fields.fee_type(),
+            Transaction::DeployAccount(tx) => tx.account_tx_fields().fee_type(),
+            Transaction::DeclareDeprecated(_)
+            | Transaction::Deploy(_)
+            | Transaction::L1Handler(_) => FeeType::Eth,
+        };
+        let mut transactional_state = self.state.create_transactional()?;
+        let execution_info = execute_transaction(
+            transaction,
+            &mut transactional_state,
+            self.block_context.clone(),
+            self.config.remaining_gas,
+            &ExecutionConfig::default(),
+        )?;
+        self.state
+            .apply_state_update(&StateDiff::from_cached_state(transactional_state.cache())?)?;
+        let receipt = TransactionReceipt::new(
+            transaction_hash,
+            self.block_context.block_info.block_number,
+            execution_info,
+        )?;
+        self.receipts.insert(transaction_hash, (receipt, fee_type));
+        self.pending_transactions.push(transaction_hash);
+
+        if let BlockProduction::TransactionCount(count) = self.config.block_production {
+            if self.pending_transactions.len() >= count {
+                self.produce_block()?;
+            }
+        }
+
+        Ok(transaction_hash)
+    }
+
+    /// Closes the pending block, even if it's empty, and returns its hash.
+    ///
+    /// The next block starts at the time of the last [`Devnet::tick`], and the hash of the block
+    /// `STORED_BLOCK_HASH_BUFFER` blocks before it is written into the block hash contract. That
+    /// hash is taken from the blocks produced by the devnet or, for the blocks before them, from
+    /// the state reader, and no block is produced if neither knows it.
+    pub fn produce_block(&mut self) -> Result<Felt252, DevnetError> {
+        let block_info = &self.block_context.block_info;
+        let parent_hash = self
+            .latest_block()
+            .map_or(Felt252::ZERO, |block| block.hash);
+        let mut elements = vec![
+            Felt252::from(block_info.block_number),
+            parent_hash,
+            Felt252::from(block_info.block_timestamp),
+            block_info.sequencer_address.0,
+        ];
+        elements.extend(self.pending_transactions.iter().copied());
+        let hash = compute_hash_on_elements(&elements)?;
+
+        // Start the next block before closing this one, so that nothing changes if it can't.
+        let next_block_number = block_info.block_number + 1;
+        let old_block = match self
+            .blocks
+            .iter()
+            .find(|block| block.number + STORED_BLOCK_HASH_BUFFER == next_block_number)
+        {
+            Some(block) => Some(BlockNumberHashPair {
+                number: block.number,
+                hash: block.hash,
+            }),
+            None => old_block_hash_from_reader(&self.state, next_block_number)?,
+        };
+        pre_process_block(&mut self.state, old_block, next_block_number)?;
+
+        let block_info = &self.block_context.block_info;
+        self.blocks.push(DevnetBlock {
+            number: block_info.block_number,
+            hash,
+            parent_hash,
+            timestamp: block_info.block_timestamp,
+            sequencer_address: block_info.sequencer_address.clone(),
+            transaction_hashes: mem::take(&mut self.pending_transactions),
+        });
+
+        let block_info = &mut self.block_context.block_info;
+        block_info.block_number += 1;
+        block_info.block_timestamp = block_info.block_timestamp.max(self.time);
+
+        Ok(hash)
+    }
+
+    /// Advances the time of the devnet to `now`, producing a block if the pending block has been
+    /// open for longer than the [`BlockProduction::Interval`]. Returns the hash of the produced
+    /// block, if any.
+    pub fn tick(&mut self, now: u64) -> Result<Option<Felt252>, DevnetError> {
+        self.time = self.time.max(now);
+
+        match self.config.block_production {
+            BlockProduction::Interval(interval)
+                if self.time >= self.block_context.block_info.block_timestamp + interval =>
+            {
+                self.produce_block().map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn fee_type(&self, transaction_hash: &Felt252) -> Option<FeeType> {
+        self.receipts
+            .get(transaction_hash)
+            .map(|(_, fee_type)| *fee_type)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{
+        core::errors::state_errors::StateError,
+        definitions::{block_context::StarknetChainId, constants::BLOCK_HASH_CONTRACT_ADDRESS},
+        state::{
+            contract_class_cache::PermanentContractClassCache,
+            in_memory_state_reader::InMemoryStateReader,
+        },
+        transaction::{error::TransactionError, InvokeFunction, VersionSpecificAccountTxFields},
+        utils::{
+            calculate_sn_keccak,
+            test_utils::{
+                create_account_tx_test_state, TEST_ACCOUNT_CONTRACT_ADDRESS, TEST_CONTRACT_ADDRESS,
+            },
+        },
+    };
+    use pretty_assertions_sorted::assert_eq;
+    use std::sync::Arc;
+
+    pub(crate) type TestDevnet = Devnet<InMemoryStateReader, PermanentContractClassCache>;
+
+    pub(crate) fn test_devnet(block_production: BlockProduction) -> TestDevnet {
+        let (block_context, state) = create_account_tx_test_state().unwrap();
+        Devnet::new(
+            state,
+            block_context,
+            DevnetConfig {
+                block_production,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Returns an invoke from the test account calling `fib(1, 1, 10)` on the test contract.
+    pub(crate) fn invoke_fib(nonce: u64) -> Transaction {
+        invoke_fib_with_max_fee(nonce, 0)
+    }
+
+    fn invoke_fib_with_max_fee(nonce: u64, max_fee: u128) -> Transaction {
+        let calldata = vec![
+            TEST_CONTRACT_ADDRESS.0,
+            Felt252::from_bytes_be(&calculate_sn_keccak(b"fib")),
+            3.into(),
+            1.into(),
+            1.into(),
+            10.into(),
+        ];
+        Transaction::InvokeFunction(
+            InvokeFunction::new(
+                TEST_ACCOUNT_CONTRACT_ADDRESS.clone(),
+                Felt252::from_bytes_be(&calculate_sn_keccak(b"__execute__")),
+                VersionSpecificAccountTxFields::new_deprecated(max_fee),
+                Felt252::ONE,
+                calldata,
+                vec![],
+                StarknetChainId::TestNet.to_felt(),
+                Some(nonce.into()),
+            )
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn produce_blocks_by_transaction_count() {
+        let mut devnet = test_devnet(BlockProduction::TransactionCount(2));
+
+        let first = devnet.add_transaction(invoke_fib(0)).unwrap();
+        assert!(devnet.blocks().is_empty());
+        assert_eq!(devnet.pending_transactions(), &[first]);
+
+        let second = devnet.add_transaction(invoke_fib(1)).unwrap();
+        assert_eq!(devnet.blocks().len(), 1);
+        assert_eq!(devnet.blocks()[0].transaction_hashes, vec![first, second]);
+        assert_eq!(devnet.receipt(&second).unwrap().block_number, 0);
+        assert!(devnet.pending_transactions().is_empty());
+        assert_eq!(devnet.block_context().block_info.block_number, 1);
+
+        // Rejected transactions aren't included.
+        assert_matches!(
+            devnet.add_transaction(invoke_fib(5)),
+            Err(DevnetError::Transaction(_))
+        );
+        assert_matches!(
+            devnet.add_transaction(invoke_fib(0)),
+            Err(DevnetError::DuplicateTransaction(_))
+        );
+        assert!(devnet.pending_transactions().is_empty());
+    }
+
+    #[test]
+    fn rejected_transaction_leaves_nonce_unchanged() {
+        let mut devnet = test_devnet(BlockProduction::OnDemand);
+        let nonce = |devnet: &TestDevnet| {
+            devnet
+                .state()
+                .get_nonce_at(&TEST_ACCOUNT_CONTRACT_ADDRESS)
+                .unwrap()
+        };
+        let initial_nonce = nonce(&devnet);
+
+        // The account can't afford the max fee, which is checked after the nonce is bumped.
+        assert_matches!(
+            devnet.add_transaction(invoke_fib_with_max_fee(0, u128::MAX)),
+            Err(DevnetError::Transaction(
+                TransactionError::MaxFeeExceedsBalance(_, _, _)
+            ))
+        );
+        assert_eq!(nonce(&devnet), initial_nonce);
+
+        // The same nonce can still be used.
+        devnet.add_transaction(invoke_fib(0)).unwrap();
+        assert_eq!(nonce(&devnet), initial_nonce + Felt252::ONE);
+    }
+
+    #[test]
+    fn produce_blocks_by_interval() {
+        let mut devnet = test_devnet(BlockProduction::Interval(10));
+
+        devnet.add_transaction(invoke_fib(0)).unwrap();
+        assert_eq!(devnet.tick(5).unwrap(), None);
+        let hash = devnet.tick(10).unwrap().unwrap();
+
+        assert_eq!(devnet.latest_block().unwrap().hash, hash);
+        assert_eq!(devnet.latest_block().unwrap().timestamp, 0);
+        assert_eq!(devnet.block_context().block_info.block_timestamp, 10);
+        assert_eq!(devnet.block_by_hash(&hash).unwrap().number, 0);
+    }
+
+    #[test]
+    fn produce_block_writes_old_block_hashes() {
+        let mut devnet = test_devnet(BlockProduction::OnDemand);
+
+        let hashes: Vec<Felt252> = (0..=STORED_BLOCK_HASH_BUFFER)
+            .map(|_| devnet.produce_block().unwrap())
+            .collect();
+        assert_eq!(devnet.blocks()[1].parent_hash, hashes[0]);
+
+        // Block 11 is pending, so the hash of block 1 has been written.
+        let stored_hash = |block_number: u64| {
+            devnet
+                .state()
+                .get_storage_at(&(
+                    BLOCK_HASH_CONTRACT_ADDRESS.clone(),
+                    Felt252::from(block_number).to_bytes_be(),
+                ))
+                .unwrap()
+        };
+        assert_eq!(stored_hash(0), hashes[0]);
+        assert_eq!(stored_hash(1), hashes[1]);
+        assert_eq!(stored_hash(2), Felt252::ZERO);
+    }
+
+    #[test]
+    fn produce_block_reads_older_block_hashes_from_the_state() {
+        let devnet_at_block_20 = |block_hashes: &[(u64, Felt252)]| {
+            let (mut block_context, mut state) = create_account_tx_test_state().unwrap();
+            block_context.block_info_mut().block_number = 20;
+            let mut state_reader = (*state.state_reader).clone();
+            state_reader
+                .block_hashes_mut()
+                .extend(block_hashes.iter().copied());
+            state.state_reader = Arc::new(state_reader);
+            Devnet::new(state, block_context, DevnetConfig::default())
+        };
+
+        let mut devnet = devnet_at_block_20(&[]);
+
+        // Block 21 needs the hash of block 11, which the devnet didn't produce.
+        assert_matches!(
+            devnet.produce_block(),
+            Err(DevnetError::State(StateError::MissingOldBlockHash {
+                old_block_number: 11,
+                block_number: 21,
+            }))
+        );
+        assert!(devnet.blocks().is_empty());
+        assert_eq!(devnet.block_context().block_info.block_number, 20);
+
+        let mut devnet = devnet_at_block_20(&[(11, 0x11.into())]);
+        devnet.produce_block().unwrap();
+        assert_eq!(
+            devnet
+                .state()
+                .get_storage_at(&(
+                    BLOCK_HASH_CONTRACT_ADDRESS.clone(),
+                    Felt252::from(11).to_bytes_be(),
+                ))
+                .unwrap(),
+            Felt252::from(0x11)
+        );
+    }
+}
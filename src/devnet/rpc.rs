@@ -0,0 +1,607 @@
+//! JSON-RPC handlers of the devnet.
+//!
+//! Requests are served from the state of the pending block, which is also the state of the latest
+//! block once it's closed: block ids must refer to the pending or latest block, as the devnet
+//! doesn't keep the state of older blocks.
+
+use super::{error::DevnetError, Devnet};
+use crate::{
+    call_contract,
+    core::errors::state_errors::StateError,
+    definitions::{block_context::FeeType, transaction_type::TransactionType},
+    estimate_fee,
+    execution::{backend::ExecutionConfig, receipt::TransactionReceipt},
+    state::{contract_class_cache::ContractClassCache, state_api::StateReader},
+    transaction::{error::TransactionError, Address, Transaction},
+};
+use cairo_vm::Felt252;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+// --------------------
+// RpcError structure
+// --------------------
+
+/// A JSON-RPC error, with the codes of the Starknet JSON-RPC specification.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    fn new(code: i64, message: &str) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+            data: None,
+        }
+    }
+
+    fn with_data(code: i64, message: &str, data: impl ToString) -> Self {
+        Self {
+            data: Some(Value::String(data.to_string())),
+            ..Self::new(code, message)
+        }
+    }
+
+    pub fn invalid_request() -> Self {
+        Self::new(-32600, "Invalid request")
+    }
+
+    pub fn method_not_found() -> Self {
+        Self::new(-32601, "Method not found")
+    }
+
+    pub fn invalid_params(data: impl ToString) -> Self {
+        Self::with_data(-32602, "Invalid params", data)
+    }
+
+    pub fn contract_not_found() -> Self {
+        Self::new(20, "Contract not found")
+    }
+
+    pub fn block_not_found() -> Self {
+        Self::new(24, "Block not found")
+    }
+
+    pub fn transaction_hash_not_found() -> Self {
+        Self::new(29, "Transaction hash not found")
+    }
+
+    pub fn contract_error(data: impl ToString) -> Self {
+        Self::with_data(40, "Contract error", data)
+    }
+
+    pub fn transaction_execution_error(data: impl ToString) -> Self {
+        Self::with_data(41, "Transaction execution error", data)
+    }
+
+    pub fn unexpected_error(data: impl ToString) -> Self {
+        Self::with_data(63, "An unexpected error occurred", data)
+    }
+}
+
+impl From<DevnetError> for RpcError {
+    fn from(error: DevnetError) -> Self {
+        match error {
+            DevnetError::DuplicateTransaction(_) => Self::new(
+                59,
+                "A transaction with the same hash already exists in the mempool",
+            ),
+            DevnetError::Transaction(TransactionError::InvalidTransactionNonce(_, _)) => {
+                Self::new(52, "Invalid transaction nonce")
+            }
+            DevnetError::Transaction(
+                TransactionError::MaxFeeTooLow(_, _)
+                | TransactionError::MaxL1GasAmountTooLow(_, _)
+                | TransactionError::MaxL1GasPriceTooLow(_, _),
+            ) => Self::new(53, "Max fee is smaller than the minimal transaction cost"),
+            DevnetError::Transaction(TransactionError::MaxFeeExceedsBalance(_, _, _)) => Self::new(
+                54,
+                "Account balance is smaller than the transaction's max_fee",
+            ),
+            DevnetError::Transaction(error) if is_validation_failure(&error) => {
+                Self::with_data(55, "Account validation failed", error)
+            }
+            DevnetError::Transaction(TransactionError::ClassAlreadyDeclared(_)) => {
+                Self::new(51, "Class already declared")
+            }
+            DevnetError::Transaction(TransactionError::SierraCompileError(error)) => {
+                Self::with_data(56, "Compilation failed", error)
+            }
+            DevnetError::Transaction(
+                TransactionError::ContractBytecodeTooLarge(_, _)
+                | TransactionError::ContractClassTooLarge(_, _),
+            ) => Self::new(57, "Contract class size is too large"),
+            DevnetError::Transaction(
+                TransactionError::InvalidCompiledClassHash(_, _)
+                | TransactionError::NotEqualClassHash,
+            ) => Self::new(60, "Compiled class hash mismatch"),
+            DevnetError::Transaction(
+                TransactionError::UnsupportedTxVersion(_, _, _)
+                | TransactionError::UnsupportedVersion(_),
+            ) => Self::new(61, "The transaction version is not supported"),
+            DevnetError::Transaction(
+                TransactionError::InvalidSierraVersion
+                | TransactionError::UnsupportedSierraVersion(_, _, _),
+            ) => Self::new(62, "The contract class version is not supported"),
+            error => Self::unexpected_error(error),
+        }
+    }
+}
+
+/// Returns whether a transaction was rejected because its validation failed.
+///
+/// Only `__validate__` and the constructors of deployed accounts run Cairo code whose failure
+/// rejects a transaction, as the failures of `__execute__` revert it instead.
+fn is_validation_failure(error: &TransactionError) -> bool {
+    matches!(
+        error,
+        TransactionError::WrongValidateRetdata
+            | TransactionError::UnauthorizedActionOnValidate
+            | TransactionError::InvalidContractCall
+            | TransactionError::EntryPointNotFound(_)
+            | TransactionError::Syscall(_)
+            | TransactionError::Vm(_)
+            | TransactionError::CairoRunner(_)
+            | TransactionError::Runner(_)
+            | TransactionError::MemoryException(_)
+            | TransactionError::TraceException(_)
+    )
+}
+
+impl From<StateError> for RpcError {
+    fn from(error: StateError) -> Self {
+        Self::unexpected_error(error)
+    }
+}
+
+// --------------------
+// Request handling
+// --------------------
+
+impl<S: StateReader, C: ContractClassCache> Devnet<S, C> {
+    /// Handles a JSON-RPC 2.0 request, returning its response.
+    pub fn handle_json_rpc(&mut self, request: &Value) -> Value {
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let result = match request.get("method").and_then(Value::as_str) {
+            Some(method) => self.handle_rpc_call(
+                method,
+                request.get("params").cloned().unwrap_or(Value::Null),
+            ),
+            None => Err(RpcError::invalid_request()),
+        };
+
+        match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+        }
+    }
+
+    /// Handles a call to a JSON-RPC method, with its parameters given either by position or by
+    /// name.
+    pub fn handle_rpc_call(&mut self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "starknet_call" => {
+                let [request, block_id] = params_by_name(params, ["request", "block_id"])?;
+                self.check_block_id(&block_id)?;
+                self.call(&request)
+            }
+            "starknet_estimateFee" => {
+                let [request, block_id] = params_by_name(params, ["request", "block_id"])?;
+                self.check_block_id(&block_id)?;
+                self.estimate_fee(request)
+            }
+            "starknet_addInvokeTransaction" => {
+                let [invoke_transaction] = params_by_name(params, ["invoke_transaction"])?;
+                self.add_invoke_transaction(invoke_transaction)
+            }
+            "starknet_getTransactionReceipt" => {
+                let [transaction_hash] = params_by_name(params, ["transaction_hash"])?;
+                self.get_transaction_receipt(&felt_param(&transaction_hash)?)
+            }
+            "starknet_getStorageAt" => {
+                let [contract_address, key, block_id] =
+                    params_by_name(params, ["contract_address", "key", "block_id"])?;
+                self.check_block_id(&block_id)?;
+                let contract_address = self.deployed_contract(&contract_address)?;
+                let key = felt_param(&key)?.to_bytes_be();
+                let value = self.state.get_storage_at(&(contract_address, key))?;
+                Ok(felt_to_json(&value))
+            }
+            "starknet_getNonce" => {
+                let [block_id, contract_address] =
+                    params_by_name(params, ["block_id", "contract_address"])?;
+                self.check_block_id(&block_id)?;
+                let contract_address = self.deployed_contract(&contract_address)?;
+                let nonce = self.state.get_nonce_at(&contract_address)?;
+                Ok(felt_to_json(&nonce))
+            }
+            "starknet_getClassHashAt" => {
+                let [block_id, contract_address] =
+                    params_by_name(params, ["block_id", "contract_address"])?;
+                self.check_block_id(&block_id)?;
+                let contract_address = self.deployed_contract(&contract_address)?;
+                let class_hash = self.state.get_class_hash_at(&contract_address)?;
+                Ok(felt_to_json(&Felt252::from_bytes_be(&class_hash.0)))
+            }
+            _ => Err(RpcError::method_not_found()),
+        }
+    }
+
+    fn call(&mut self, request: &Value) -> Result<Value, RpcError> {
+        let contract_address = self.deployed_contract(&request["contract_address"])?;
+        let entry_point_selector = felt_param(&request["entry_point_selector"])?;
+        let calldata = felts_param(&request["calldata"])?;
+
+        let mut state = self.state.create_transactional()?;
+        let retdata = call_contract(
+            contract_address.0,
+            entry_point_selector,
+            calldata,
+            &mut state,
+            self.block_context.clone(),
+            Address(Felt252::ZERO),
+            &ExecutionConfig::default(),
+        )
+        .map_err(RpcError::contract_error)?;
+
+        Ok(Value::Array(retdata.iter().map(felt_to_json).collect()))
+    }
+
+    fn estimate_fee(&mut self, request: Value) -> Result<Value, RpcError> {
+        let Value::Array(request) = request else {
+            return Err(RpcError::invalid_params("request must be an array"));
+        };
+        let chain_id = self.block_context.starknet_os_config.chain_id;
+        let transactions = request
+            .into_iter()
+            .map(|transaction| Transaction::from_broadcasted_json(transaction, chain_id))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(RpcError::invalid_params)?;
+        let fee_types: Vec<FeeType> = transactions.iter().map(super::fee_type).collect();
+
+        let estimations = estimate_fee(
+            &transactions,
+            self.state.create_transactional()?,
+            &self.block_context,
+            &ExecutionConfig::default(),
+        )
+        .map_err(RpcError::transaction_execution_error)?;
+
+        Ok(estimations
+            .into_iter()
+            .zip(fee_types)
+            .map(|((overall_fee, gas_consumed), fee_type)| {
+                let gas_price = self.block_context.get_gas_price_by_fee_type(&fee_type);
+                json!({
+                    "gas_consumed": format!("{gas_consumed:#x}"),
+                    "gas_price": format!("{gas_price:#x}"),
+                    "overall_fee": format!("{overall_fee:#x}"),
+                    "unit": fee_unit(fee_type),
+                })
+            })
+            .collect())
+    }
+
+    fn add_invoke_transaction(&mut self, invoke_transaction: Value) -> Result<Value, RpcError> {
+        let chain_id = self.block_context.starknet_os_config.chain_id;
+        let transaction = Transaction::from_broadcasted_json(invoke_transaction, chain_id)
+            .map_err(RpcError::invalid_params)?;
+        if !matches!(transaction, Transaction::InvokeFunction(_)) {
+            return Err(RpcError::invalid_params("not an invoke transaction"));
+        }
+
+        let transaction_hash = self.add_transaction(transaction)?;
+        Ok(json!({ "transaction_hash": felt_to_json(&transaction_hash) }))
+    }
+
+    fn get_transaction_receipt(&self, transaction_hash: &Felt252) -> Result<Value, RpcError> {
+        let (receipt, fee_type) = self
+            .receipt(transaction_hash)
+            .zip(self.fee_type(transaction_hash))
+            .ok_or_else(RpcError::transaction_hash_not_found)?;
+        receipt_to_json(
+            receipt,
+            fee_type,
+            self.block_by_number(receipt.block_number),
+        )
+    }
+
+    /// Checks that a block id refers to the pending or the latest block.
+    fn check_block_id(&self, block_id: &Value) -> Result<(), RpcError> {
+        let latest_block = self.latest_block();
+        let known = match block_id {
+            Value::String(tag) => tag == "latest" || tag == "pending",
+            Value::Object(id) => match (id.get("block_number"), id.get("block_hash")) {
+                (Some(block_number), None) => {
+                    let block_number = block_number
+                        .as_u64()
+                        .ok_or_else(|| RpcError::invalid_params("invalid block_number"))?;
+                    latest_block.map_or(false, |block| block.number == block_number)
+                        || self.block_context.block_info.block_number == block_number
+                }
+                (None, Some(block_hash)) => {
+                    let block_hash = felt_param(block_hash)?;
+                    latest_block.map_or(false, |block| block.hash == block_hash)
+                }
+                _ => return Err(RpcError::invalid_params("invalid block_id")),
+            },
+            _ => return Err(RpcError::invalid_params("invalid block_id")),
+        };
+
+        if known {
+            Ok(())
+        } else {
+            Err(RpcError::block_not_found())
+        }
+    }
+
+    /// Parses a contract address, checking that a contract is deployed at it.
+    fn deployed_contract(&self, contract_address: &Value) -> Result<Address, RpcError> {
+        let contract_address = Address(felt_param(contract_address)?);
+        if self.state.get_class_hash_at(&contract_address)?.0 == [0; 32] {
+            return Err(RpcError::contract_not_found());
+        }
+        Ok(contract_address)
+    }
+}
+
+fn receipt_to_json(
+    receipt: &TransactionReceipt,
+    fee_type: FeeType,
+    block: Option<&super::DevnetBlock>,
+) -> Result<Value, RpcError> {
+    let execution_info = &receipt.execution_info;
+    let messages_sent = execution_info
+        .get_sorted_l2_to_l1_messages()
+        .map_err(RpcError::unexpected_error)?
+        .into_iter()
+        .map(|message| {
+            json!({
+                "from_address": felt_to_json(&message.from_address.0),
+                "to_address": felt_to_json(&message.to_address.0),
+                "payload": message.payload.iter().map(felt_to_json).collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let events = receipt
+        .events
+        .iter()
+        .map(|event| {
+            json!({
+                "from_address": felt_to_json(&event.from_address.0),
+                "keys": event.keys.iter().map(felt_to_json).collect::<Vec<_>>(),
+                "data": event.data.iter().map(felt_to_json).collect::<Vec<_>>(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let execution_status = if execution_info.revert_error.is_some() {
+        "REVERTED"
+    } else {
+        "SUCCEEDED"
+    };
+
+    let mut json = json!({
+        "transaction_hash": felt_to_json(&receipt.transaction_hash),
+        "actual_fee": {
+            "amount": format!("{:#x}", execution_info.actual_fee),
+            "unit": fee_unit(fee_type),
+        },
+        "execution_status": execution_status,
+        "finality_status": "ACCEPTED_ON_L2",
+        "block_number": receipt.block_number,
+        "messages_sent": messages_sent,
+        "events": events,
+    });
+    if let Some(block) = block {
+        json["block_hash"] = felt_to_json(&block.hash);
+    }
+    if let Some(tx_type) = execution_info.tx_type.as_ref().and_then(tx_type_name) {
+        json["type"] = Value::from(tx_type);
+    }
+    if let Some(revert_error) = &execution_info.revert_error {
+        json["revert_reason"] = Value::from(revert_error.to_string());
+    }
+
+    Ok(json)
+}
+
+/// Returns the parameters named `names`, given either as an array in the same order or as an
+/// object. Missing parameters are null.
+fn params_by_name<const N: usize>(params: Value, names: [&str; N]) -> Result<[Value; N], RpcError> {
+    match params {
+        Value::Array(params) if params.len() <= N => {
+            let mut params = params.into_iter();
+            Ok(std::array::from_fn(|_| params.next().unwrap_or_default()))
+        }
+        Value::Object(mut params) => Ok(names.map(|name| params.remove(name).unwrap_or_default())),
+        _ => Err(RpcError::invalid_params("wrong number of parameters")),
+    }
+}
+
+fn felt_param(value: &Value) -> Result<Felt252, RpcError> {
+    value
+        .as_str()
+        .and_then(|felt| Felt252::from_hex(felt).ok())
+        .ok_or_else(|| RpcError::invalid_params(format!("invalid felt {value}")))
+}
+
+fn felts_param(value: &Value) -> Result<Vec<Felt252>, RpcError> {
+    value
+        .as_array()
+        .ok_or_else(|| RpcError::invalid_params(format!("invalid felt array {value}")))?
+        .iter()
+        .map(felt_param)
+        .collect()
+}
+
+fn felt_to_json(felt: &Felt252) -> Value {
+    Value::String(format!("{felt:#x}"))
+}
+
+fn fee_unit(fee_type: FeeType) -> &'static str {
+    match fee_type {
+        FeeType::Eth => "WEI",
+        FeeType::Strk => "FRI",
+    }
+}
+
+fn tx_type_name(tx_type: &TransactionType) -> Option<&'static str> {
+    match tx_type {
+        TransactionType::Declare => Some("DECLARE"),
+        TransactionType::Deploy => Some("DEPLOY"),
+        TransactionType::DeployAccount => Some("DEPLOY_ACCOUNT"),
+        TransactionType::InvokeFunction => Some("INVOKE"),
+        TransactionType::L1Handler => Some("L1_HANDLER"),
+        TransactionType::InitializeBlockInfo => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        devnet::{
+            tests::{invoke_fib, test_devnet},
+            BlockProduction,
+        },
+        transaction::ClassHash,
+        utils::{
+            calculate_sn_keccak,
+            test_utils::{TEST_ACCOUNT_CONTRACT_ADDRESS, TEST_CONTRACT_ADDRESS},
+        },
+    };
+    use pretty_assertions_sorted::assert_eq;
+
+    #[test]
+    fn call_and_read_state() {
+        let mut devnet = test_devnet(BlockProduction::default());
+        devnet.add_transaction(invoke_fib(0)).unwrap();
+        let account = felt_to_json(&TEST_ACCOUNT_CONTRACT_ADDRESS.0);
+
+        let response = devnet.handle_json_rpc(&json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "starknet_call",
+            "params": {
+                "request": {
+                    "contract_address": felt_to_json(&TEST_CONTRACT_ADDRESS.0),
+                    "entry_point_selector": felt_to_json(&Felt252::from_bytes_be(&calculate_sn_keccak(b"fib"))),
+                    "calldata": ["0x1", "0x1", "0xa"],
+                },
+                "block_id": "latest",
+            },
+        }));
+        assert_eq!(
+            response,
+            json!({ "jsonrpc": "2.0", "id": 1, "result": ["0x90"] })
+        );
+
+        assert_eq!(
+            devnet.handle_rpc_call("starknet_getNonce", json!(["latest", account])),
+            Ok(json!("0x1"))
+        );
+        assert_eq!(
+            devnet.handle_rpc_call(
+                "starknet_getNonce",
+                json!({ "block_id": { "block_number": 0 }, "contract_address": account })
+            ),
+            Ok(json!("0x1"))
+        );
+        assert_eq!(
+            devnet.handle_rpc_call("starknet_getNonce", json!([{ "block_number": 5 }, account])),
+            Err(RpcError::block_not_found())
+        );
+        assert_eq!(
+            devnet.handle_rpc_call("starknet_getClassHashAt", json!(["pending", "0x1234"])),
+            Err(RpcError::contract_not_found())
+        );
+        assert_eq!(
+            devnet.handle_rpc_call("starknet_getBlockWithTxs", json!([])),
+            Err(RpcError::method_not_found())
+        );
+    }
+
+    #[test]
+    fn add_invoke_transaction_and_get_receipt() {
+        let mut devnet = test_devnet(BlockProduction::default());
+        let invoke = json!({
+            "type": "INVOKE",
+            "version": "0x1",
+            "sender_address": felt_to_json(&TEST_ACCOUNT_CONTRACT_ADDRESS.0),
+            "calldata": [
+                felt_to_json(&TEST_CONTRACT_ADDRESS.0),
+                felt_to_json(&Felt252::from_bytes_be(&calculate_sn_keccak(b"fib"))),
+                "0x3", "0x1", "0x1", "0xa",
+            ],
+            "max_fee": "0x0",
+            "signature": [],
+            "nonce": "0x0",
+        });
+
+        let estimation = devnet
+            .handle_rpc_call("starknet_estimateFee", json!([[invoke.clone()], "latest"]))
+            .unwrap();
+        assert_eq!(estimation[0]["unit"], json!("WEI"));
+
+        let result = devnet
+            .handle_rpc_call("starknet_addInvokeTransaction", json!([invoke.clone()]))
+            .unwrap();
+        let transaction_hash = result["transaction_hash"].clone();
+        assert_eq!(
+            devnet
+                .handle_rpc_call("starknet_addInvokeTransaction", json!([invoke]))
+                .unwrap_err()
+                .code,
+            59
+        );
+
+        let receipt = devnet
+            .handle_rpc_call(
+                "starknet_getTransactionReceipt",
+                json!({ "transaction_hash": transaction_hash }),
+            )
+            .unwrap();
+        assert_eq!(receipt["execution_status"], json!("SUCCEEDED"));
+        assert_eq!(receipt["block_number"], json!(0));
+        assert_eq!(
+            receipt["block_hash"],
+            felt_to_json(&devnet.latest_block().unwrap().hash)
+        );
+        assert_eq!(receipt["type"], json!("INVOKE"));
+
+        assert_eq!(
+            devnet.handle_rpc_call("starknet_getTransactionReceipt", json!(["0x1234"])),
+            Err(RpcError::transaction_hash_not_found())
+        );
+    }
+    #[test]
+    fn transaction_errors_by_kind() {
+        let code = |error: TransactionError| RpcError::from(DevnetError::Transaction(error)).code;
+
+        assert_eq!(code(TransactionError::WrongValidateRetdata), 55);
+        assert_eq!(code(TransactionError::EntryPointNotFound(Felt252::ONE)), 55);
+        assert_eq!(
+            code(TransactionError::ClassAlreadyDeclared(ClassHash([0; 32]))),
+            51
+        );
+        assert_eq!(code(TransactionError::ContractClassTooLarge(2, 1)), 57);
+        assert_eq!(code(TransactionError::NotEqualClassHash), 60);
+        assert_eq!(
+            code(TransactionError::UnsupportedVersion("0x4".to_string())),
+            61
+        );
+        assert_eq!(code(TransactionError::InvalidSierraVersion), 62);
+        assert_eq!(
+            code(TransactionError::FeeTransferError(Box::new(
+                TransactionError::NotEqualClassHash
+            ))),
+            63
+        );
+    }
+}
@@ -0,0 +1,306 @@
+//! On-disk cache of the classes compiled ahead of time by Cairo Native.
+//!
+//! Compiling a class to a shared library takes much longer than running it, so an [`AotDiskCache`]
+//! keeps the compiled libraries in a directory, where they survive restarts. Each library is
+//! named after the class hash, the compiler version and the optimization level it was compiled
+//! with, and is stored next to a checksum which is checked before loading it. The checksum is
+//! stored after the library, so a library without a checksum is one still being stored.
+
+use crate::{
+    core::errors::aot_cache_errors::AotCacheError, transaction::ClassHash,
+    utils::get_native_context,
+};
+use cairo_lang_sierra::{
+    extensions::core::{CoreLibfunc, CoreType},
+    program::Program as SierraProgram,
+    program_registry::ProgramRegistry,
+};
+use cairo_native::{
+    executor::AotNativeExecutor, metadata::gas::GasMetadata, module_to_object,
+    object_to_shared_lib, OptLevel,
+};
+use libloading::Library;
+use sha3::{Digest, Keccak256};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread::JoinHandle,
+};
+
+/// Version of the compiler producing the cached libraries.
+///
+/// Libraries compiled by another version of Cairo Native may not be compatible, so this must be
+/// updated along with the `cairo-native` dependency.
+pub const NATIVE_COMPILER_VERSION: &str = "cairo-native-0.1.0-baf57d2";
+
+const CHECKSUM_EXTENSION: &str = "keccak";
+
+static NEXT_TEMP_FILE_ID: AtomicUsize = AtomicUsize::new(0);
+
+// --------------------
+// AotDiskCache structure
+// --------------------
+
+/// A directory of classes compiled ahead of time by Cairo Native.
+///
+/// The cache only touches the file system, so it can be cloned and shared between threads and
+/// processes freely.
+#[derive(Clone, Debug)]
+pub struct AotDiskCache {
+    dir: PathBuf,
+    opt_level: OptLevel,
+}
+
+impl AotDiskCache {
+    /// Opens the cache stored in `dir`, creating the directory if needed. Classes are compiled
+    /// with `opt_level`.
+    pub fn new(dir: impl Into<PathBuf>, opt_level: OptLevel) -> Result<Self, AotCacheError> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir, opt_level })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn opt_level(&self) -> OptLevel {
+        self.opt_level
+    }
+
+    /// Returns the path of the compiled library of `class_hash`.
+    pub fn library_path(&self, class_hash: &ClassHash) -> PathBuf {
+        let opt_level = match self.opt_level {
+            OptLevel::None => 0,
+            OptLevel::Less => 1,
+            OptLevel::Default => 2,
+            OptLevel::Aggressive => 3,
+        };
+        self.dir.join(format!(
+            "{}-{NATIVE_COMPILER_VERSION}-O{opt_level}.{}",
+            hex::encode(class_hash.0),
+            std::env::consts::DLL_EXTENSION,
+        ))
+    }
+
+    /// Returns whether `class_hash` has been compiled, without checking its integrity.
+    pub fn contains(&self, class_hash: &ClassHash) -> bool {
+        self.library_path(class_hash).is_file()
+    }
+
+    /// Loads the compiled `program` of `class_hash`.
+    ///
+    /// Returns `None` if the class hasn't been compiled or is still being stored, or if its
+    /// library doesn't match its checksum, in which case the library is removed so that it's
+    /// compiled again.
+    pub fn get(
+        &self,
+        class_hash: &ClassHash,
+        program: &SierraProgram,
+    ) -> Result<Option<AotNativeExecutor>, AotCacheError> {
+        let library_path = self.library_path(class_hash);
+        if !library_path.is_file() {
+            return Ok(None);
+        }
+
+        let checksum = match fs::read_to_string(library_path.with_extension(CHECKSUM_EXTENSION)) {
+            Ok(checksum) => checksum,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        if checksum.trim() != hex::encode(Keccak256::digest(fs::read(&library_path)?)) {
+            tracing::warn!("Removing the corrupted compiled class {class_hash}");
+            self.remove(class_hash)?;
+            return Ok(None);
+        }
+
+        load_executor(class_hash, &library_path, program).map(Some)
+    }
+
+    /// Loads the compiled `program` of `class_hash`, compiling and storing it if needed.
+    pub fn get_or_compile(
+        &self,
+        class_hash: &ClassHash,
+        program: &SierraProgram,
+    ) -> Result<AotNativeExecutor, AotCacheError> {
+        if let Some(executor) = self.get(class_hash, program)? {
+            return Ok(executor);
+        }
+
+        let library_path = self.compile_and_insert(class_hash, program)?;
+        load_executor(class_hash, &library_path, program)
+    }
+
+    /// Compiles `program` and stores it as the library of `class_hash`, replacing the previous one.
+    ///
+    /// Returns the path of the library.
+    pub fn compile_and_insert(
+        &self,
+        class_hash: &ClassHash,
+        program: &SierraProgram,
+    ) -> Result<PathBuf, AotCacheError> {
+        let compilation_error = |e| AotCacheError::Compilation(*class_hash, e);
+
+        let native_module = get_native_context()
+            .compile(program)
+            .map_err(|e| compilation_error(format!("{e:?}")))?;
+        let object = module_to_object(native_module.module(), self.opt_level)
+            .map_err(|e| compilation_error(format!("{e:?}")))?;
+
+        // The library and its checksum are written to temporary files first, so that other
+        // threads and processes never see them half written. The checksum is moved last, so that
+        // it never matches a library other than the one it was computed from.
+        let library_path = self.library_path(class_hash);
+        let temp_path = |extension: &str| {
+            library_path.with_extension(format!(
+                "{}-{}.{extension}.tmp",
+                std::process::id(),
+                NEXT_TEMP_FILE_ID.fetch_add(1, Ordering::Relaxed)
+            ))
+        };
+        let (temp_library_path, temp_checksum_path) = (
+            temp_path(std::env::consts::DLL_EXTENSION),
+            temp_path(CHECKSUM_EXTENSION),
+        );
+        object_to_shared_lib(&object, &temp_library_path)
+            .map_err(|e| compilation_error(format!("{e:?}")))?;
+
+        let checksum = hex::encode(Keccak256::digest(fs::read(&temp_library_path)?));
+        fs::write(&temp_checksum_path, checksum)?;
+        fs::rename(&temp_library_path, &library_path)?;
+        fs::rename(
+            &temp_checksum_path,
+            library_path.with_extension(CHECKSUM_EXTENSION),
+        )?;
+
+        Ok(library_path)
+    }
+
+    /// Removes the compiled library of `class_hash`, if any.
+    pub fn remove(&self, class_hash: &ClassHash) -> Result<(), AotCacheError> {
+        let library_path = self.library_path(class_hash);
+        for path in [
+            library_path.with_extension(CHECKSUM_EXTENSION),
+            library_path,
+        ] {
+            match fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Compiles the `classes` missing from the cache in a background thread.
+    ///
+    /// The returned handle yields the classes which failed to compile.
+    pub fn precompile(
+        &self,
+        classes: Vec<(ClassHash, SierraProgram)>,
+    ) -> JoinHandle<Vec<(ClassHash, AotCacheError)>> {
+        let cache = self.clone();
+        std::thread::spawn(move || {
+            classes
+                .into_iter()
+                .filter(|(class_hash, _)| !cache.contains(class_hash))
+                .filter_map(|(class_hash, program)| {
+                    tracing::debug!("Precompiling class {class_hash}");
+                    cache
+                        .compile_and_insert(&class_hash, &program)
+                        .err()
+                        .map(|e| (class_hash, e))
+                })
+                .collect()
+        })
+    }
+}
+
+fn load_executor(
+    class_hash: &ClassHash,
+    library_path: &Path,
+    program: &SierraProgram,
+) -> Result<AotNativeExecutor, AotCacheError> {
+    let load_error = |e| AotCacheError::Load(*class_hash, e);
+
+    let library = unsafe { Library::new(library_path) }.map_err(|e| load_error(e.to_string()))?;
+    let registry = ProgramRegistry::<CoreType, CoreLibfunc>::new(program)
+        .map_err(|e| load_error(e.to_string()))?;
+    let gas_metadata = GasMetadata::new(program, Some(Default::default()))
+        .map_err(|e| load_error(format!("{e:?}")))?;
+
+    Ok(AotNativeExecutor::new(library, registry, gas_metadata))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cairo_lang_starknet::contract_class::ContractClass as SierraContractClass;
+    use pretty_assertions_sorted::assert_eq;
+    use std::{fs::File, io::BufReader};
+
+    #[test]
+    fn corrupted_libraries_are_removed() {
+        let dir = std::env::temp_dir().join(format!("aot-cache-test-{}", std::process::id()));
+        let cache = AotDiskCache::new(&dir, OptLevel::Default).unwrap();
+        let class_hash = ClassHash([1; 32]);
+        let program = SierraProgram {
+            type_declarations: Vec::new(),
+            libfunc_declarations: Vec::new(),
+            statements: Vec::new(),
+            funcs: Vec::new(),
+        };
+
+        let library_path = cache.library_path(&class_hash);
+        assert_eq!(library_path.parent(), Some(dir.as_path()));
+        assert!(cache.get(&class_hash, &program).unwrap().is_none());
+
+        // A library without a checksum is still being stored.
+        fs::write(&library_path, b"library").unwrap();
+        assert!(cache.get(&class_hash, &program).unwrap().is_none());
+        assert!(cache.contains(&class_hash));
+
+        fs::write(library_path.with_extension(CHECKSUM_EXTENSION), "0x0").unwrap();
+
+        assert!(cache.get(&class_hash, &program).unwrap().is_none());
+        assert!(!cache.contains(&class_hash));
+        assert!(!library_path.with_extension(CHECKSUM_EXTENSION).exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn compile_store_and_reload_class() {
+        let dir =
+            std::env::temp_dir().join(format!("aot-cache-reload-test-{}", std::process::id()));
+        let cache = AotDiskCache::new(&dir, OptLevel::Default).unwrap();
+        let class_hash = ClassHash([2; 32]);
+        let sierra_class: SierraContractClass = serde_json::from_reader(BufReader::new(
+            File::open("starknet_programs/cairo2/fibonacci.sierra").unwrap(),
+        ))
+        .unwrap();
+        let program = sierra_class.extract_sierra_program().unwrap();
+
+        assert!(cache.get_or_compile(&class_hash, &program).is_ok());
+        let library_path = cache.library_path(&class_hash);
+        assert!(cache.contains(&class_hash));
+        assert_eq!(
+            fs::read_to_string(library_path.with_extension(CHECKSUM_EXTENSION)).unwrap(),
+            hex::encode(Keccak256::digest(fs::read(&library_path).unwrap()))
+        );
+        // Only the library and its checksum are left.
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+        // A new cache in the same directory loads the class without compiling it again.
+        let modified = fs::metadata(&library_path).unwrap().modified().unwrap();
+        let cache = AotDiskCache::new(&dir, OptLevel::Default).unwrap();
+        assert!(cache.get(&class_hash, &program).unwrap().is_some());
+        assert!(cache.get_or_compile(&class_hash, &program).is_ok());
+        assert_eq!(
+            fs::metadata(&library_path).unwrap().modified().unwrap(),
+            modified
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,187 @@
+//! Execution backends.
+//!
+//! Classes with a sierra program can either run on the Cairo VM, through their CASM, or on Cairo
+//! Native, which compiles the sierra program to machine code just in time or ahead of time. The
+//! backend is chosen through an [`ExecutionConfig`], which is passed down to every entry point
+//! execution, including the ones started by syscalls, so that nested calls run on the same backend
+//! as the transaction that started them. Which classes actually run natively is further decided by
+//! the config's [`ExecutionPolicy`].
+
+#[cfg(feature = "cairo-native")]
+use crate::execution::program_cache::{
+    NativeCompilation, NativeProgramCache, DEFAULT_PROGRAM_CACHE_CAPACITY,
+};
+use crate::execution::{
+    differential::DifferentialReport,
+    policy::{BackendMetrics, ExecutionPolicy},
+};
+use std::sync::Arc;
+
+/// The engine running classes with a sierra program.
+///
+/// Deprecated (Cairo 0) classes always run on the VM. The native backends are only available
+/// with the `cairo-native` feature, executing a class with them fails otherwise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExecutionBackend {
+    /// Runs the CASM of classes on the Cairo VM.
+    Vm,
+    /// Runs the sierra program of classes with Cairo Native, compiling it just in time.
+    NativeJit,
+    /// Runs the sierra program of classes with Cairo Native, compiling it ahead of time.
+    NativeAot,
+}
+
+impl ExecutionBackend {
+    pub fn is_native(&self) -> bool {
+        matches!(self, Self::NativeJit | Self::NativeAot)
+    }
+}
+
+impl Default for ExecutionBackend {
+    /// Cairo Native's JIT when the `cairo-native` feature is enabled, the VM otherwise.
+    fn default() -> Self {
+        if cfg!(feature = "cairo-native") {
+            Self::NativeJit
+        } else {
+            Self::Vm
+        }
+    }
+}
+
+// --------------------
+// ExecutionConfig structure
+// --------------------
+
+/// Configuration shared by all the entry points executed as part of a call or transaction.
+///
+/// The config is `Send + Sync`, so a single one (and its program cache) can be shared by the
+/// threads executing transactions.
+#[derive(Clone)]
+pub struct ExecutionConfig {
+    backend: ExecutionBackend,
+    policy: Arc<ExecutionPolicy>,
+    metrics: Arc<BackendMetrics>,
+    /// Set in differential mode, where native calls also run on the VM.
+    differential: Option<Arc<DifferentialReport>>,
+    /// Programs compiled by Cairo Native, set for the native backends.
+    #[cfg(feature = "cairo-native")]
+    program_cache: Option<NativeProgramCache>,
+}
+
+impl ExecutionConfig {
+    /// Creates a config for `backend`, with an empty program cache of
+    /// [`DEFAULT_PROGRAM_CACHE_CAPACITY`] classes for the native ones.
+    pub fn new(backend: ExecutionBackend) -> Self {
+        Self {
+            backend,
+            policy: Arc::default(),
+            metrics: Arc::default(),
+            differential: None,
+            #[cfg(feature = "cairo-native")]
+            program_cache: match backend {
+                ExecutionBackend::Vm => None,
+                ExecutionBackend::NativeJit => Some(NativeProgramCache::new(
+                    NativeCompilation::Jit,
+                    DEFAULT_PROGRAM_CACHE_CAPACITY,
+                )),
+                ExecutionBackend::NativeAot => Some(NativeProgramCache::new(
+                    NativeCompilation::Aot,
+                    DEFAULT_PROGRAM_CACHE_CAPACITY,
+                )),
+            },
+        }
+    }
+
+    /// Creates a config running classes with Cairo Native and reusing the programs of
+    /// `program_cache`, whose compilation decides between the JIT and AOT backends.
+    #[cfg(feature = "cairo-native")]
+    pub fn with_program_cache(program_cache: NativeProgramCache) -> Self {
+        let backend = match program_cache.compilation() {
+            NativeCompilation::Jit => ExecutionBackend::NativeJit,
+            NativeCompilation::Aot => ExecutionBackend::NativeAot,
+        };
+        Self {
+            backend,
+            policy: Arc::default(),
+            metrics: Arc::default(),
+            differential: None,
+            program_cache: Some(program_cache),
+        }
+    }
+
+    /// Decides with `policy` which classes run natively.
+    pub fn with_policy(mut self, policy: ExecutionPolicy) -> Self {
+        self.policy = Arc::new(policy);
+        self
+    }
+
+    /// Enables the differential mode, where every call running natively also runs on the VM, and
+    /// the divergences between both runs are collected in the config's
+    /// [`differential_report`](Self::differential_report).
+    ///
+    /// The VM run is the one applied to the state, so the transactions executed behave as if they
+    /// only ran on the VM.
+    pub fn with_differential(mut self) -> Self {
+        self.differential = Some(Arc::default());
+        self
+    }
+
+    pub fn backend(&self) -> ExecutionBackend {
+        self.backend
+    }
+
+    pub fn policy(&self) -> &ExecutionPolicy {
+        &self.policy
+    }
+
+    /// Returns the divergences found in differential mode, or `None` if it isn't enabled.
+    pub fn differential_report(&self) -> Option<&DifferentialReport> {
+        self.differential.as_deref()
+    }
+
+    /// Returns the config for the native run of a call in differential mode. Its inner calls
+    /// aren't compared on their own, since the outer call already compares their effects.
+    pub(crate) fn without_differential(&self) -> Self {
+        Self {
+            differential: None,
+            ..self.clone()
+        }
+    }
+
+    /// Returns the calls executed by each backend, counted across all the clones of the config.
+    pub fn metrics(&self) -> &BackendMetrics {
+        &self.metrics
+    }
+
+    #[cfg(feature = "cairo-native")]
+    pub fn program_cache(&self) -> Option<&NativeProgramCache> {
+        self.program_cache.as_ref()
+    }
+}
+
+impl Default for ExecutionConfig {
+    fn default() -> Self {
+        Self::new(ExecutionBackend::default())
+    }
+}
+
+impl std::fmt::Debug for ExecutionConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExecutionConfig")
+            .field("backend", &self.backend)
+            .field("policy", &self.policy)
+            .field("differential", &self.differential.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn execution_config_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ExecutionConfig>();
+    }
+}
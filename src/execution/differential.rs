@@ -0,0 +1,191 @@
+//! Differential execution of Cairo 1 calls.
+//!
+//! In differential mode, each call which would run natively runs on the VM as well, and the
+//! results of both runs are compared. The VM run is the one applied to the state, while the native
+//! one runs on a copy of it, so enabling the mode never changes the outcome of a transaction. The
+//! calls on which the backends disagree are collected in a [`DifferentialReport`].
+
+use crate::{
+    execution::CallInfo,
+    state::state_cache::StorageEntry,
+    transaction::{error::TransactionError, Address, ClassHash},
+};
+use cairo_vm::Felt252;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+// --------------------
+// Divergence structure
+// --------------------
+
+/// A call on which Cairo Native and the VM disagree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Divergence {
+    pub contract_address: Address,
+    pub class_hash: ClassHash,
+    pub entry_point_selector: Felt252,
+    pub calldata: Vec<Felt252>,
+    /// Description of each difference between the VM and the native runs.
+    pub differences: Vec<String>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Cairo Native diverged from the VM calling selector {:#x} of contract {} (class {}) with calldata {:?}:",
+            self.entry_point_selector, self.contract_address, self.class_hash, self.calldata,
+        )?;
+        for difference in &self.differences {
+            write!(f, "\n  - {difference}")?;
+        }
+        Ok(())
+    }
+}
+
+// --------------------
+// DifferentialReport structure
+// --------------------
+
+/// The divergences found by the calls executed in differential mode.
+#[derive(Debug, Default)]
+pub struct DifferentialReport {
+    compared_calls: AtomicUsize,
+    divergences: Mutex<Vec<Divergence>>,
+}
+
+impl DifferentialReport {
+    /// Returns the number of calls run on both backends.
+    pub fn compared_calls(&self) -> usize {
+        self.compared_calls.load(Ordering::Relaxed)
+    }
+
+    pub fn divergences(&self) -> Vec<Divergence> {
+        self.divergences.lock().unwrap().clone()
+    }
+
+    /// Removes and returns the divergences found so far.
+    pub fn take_divergences(&self) -> Vec<Divergence> {
+        std::mem::take(&mut *self.divergences.lock().unwrap())
+    }
+
+    pub(crate) fn add_comparison(&self, divergence: Option<Divergence>) {
+        self.compared_calls.fetch_add(1, Ordering::Relaxed);
+        if let Some(divergence) = divergence {
+            tracing::error!("{divergence}");
+            self.divergences.lock().unwrap().push(divergence);
+        }
+    }
+}
+
+/// Compares the results of running a call on the VM and natively, along with the storage writes
+/// of the states they ran on.
+///
+/// Failed calls are only compared on whether they failed, since the backends report errors
+/// differently.
+pub(crate) fn compare_executions(
+    vm: (
+        &Result<CallInfo, TransactionError>,
+        &HashMap<StorageEntry, Felt252>,
+    ),
+    native: (
+        &Result<CallInfo, TransactionError>,
+        &HashMap<StorageEntry, Felt252>,
+    ),
+) -> Vec<String> {
+    let (vm_call_info, native_call_info) = match (vm.0, native.0) {
+        (Ok(vm_call_info), Ok(native_call_info)) => (vm_call_info, native_call_info),
+        (Err(_), Err(_)) => return Vec::new(),
+        (Ok(_), Err(e)) => return vec![format!("the VM succeeded but Native failed: {e}")],
+        (Err(e), Ok(_)) => return vec![format!("Native succeeded but the VM failed: {e}")],
+    };
+
+    let mut differences = Vec::new();
+    let mut compare = |name: &str, vm_value: &dyn fmt::Debug, native_value: &dyn fmt::Debug| {
+        let (vm_value, native_value) = (format!("{vm_value:?}"), format!("{native_value:?}"));
+        if vm_value != native_value {
+            differences.push(format!("{name}: VM {vm_value}, Native {native_value}"));
+        }
+    };
+    compare("retdata", &vm_call_info.retdata, &native_call_info.retdata);
+    compare(
+        "failure flag",
+        &vm_call_info.failure_flag,
+        &native_call_info.failure_flag,
+    );
+    compare("events", &vm_call_info.events, &native_call_info.events);
+    compare(
+        "L2 to L1 messages",
+        &vm_call_info.l2_to_l1_messages,
+        &native_call_info.l2_to_l1_messages,
+    );
+    compare(
+        "gas consumed",
+        &vm_call_info.gas_consumed,
+        &native_call_info.gas_consumed,
+    );
+
+    let (vm_storage_writes, native_storage_writes) = (vm.1, native.1);
+    let mut storage_entries: Vec<_> = vm_storage_writes
+        .keys()
+        .chain(native_storage_writes.keys())
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .filter(|entry| vm_storage_writes.get(*entry) != native_storage_writes.get(*entry))
+        .collect();
+    storage_entries.sort_by(|a, b| a.0 .0.cmp(&b.0 .0).then(a.1.cmp(&b.1)));
+    for entry in storage_entries {
+        compare(
+            &format!(
+                "storage write of contract {} at key {:#x}",
+                entry.0,
+                Felt252::from_bytes_be(&entry.1)
+            ),
+            &vm_storage_writes.get(entry),
+            &native_storage_writes.get(entry),
+        );
+    }
+
+    differences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions_sorted::assert_eq;
+
+    #[test]
+    fn compare_vm_and_native_executions() {
+        let call_info = CallInfo {
+            retdata: vec![1.into()],
+            gas_consumed: 100,
+            ..Default::default()
+        };
+        let storage_writes = HashMap::from([((Address(1.into()), [2; 32]), Felt252::from(3))]);
+
+        let vm = Ok(call_info.clone());
+        assert!(compare_executions((&vm, &storage_writes), (&vm, &storage_writes)).is_empty());
+
+        let native = Ok(CallInfo {
+            gas_consumed: 110,
+            ..call_info
+        });
+        let differences = compare_executions((&vm, &storage_writes), (&native, &HashMap::new()));
+        assert_eq!(differences.len(), 2);
+        assert_eq!(differences[0], "gas consumed: VM 100, Native 110");
+        assert!(differences[1].starts_with("storage write of contract "));
+        assert!(differences[1].ends_with(", Native None"));
+
+        let native = Err(TransactionError::MissingCompiledClass);
+        assert_eq!(
+            compare_executions((&vm, &storage_writes), (&native, &storage_writes)).len(),
+            1
+        );
+    }
+}
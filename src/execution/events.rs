@@ -0,0 +1,240 @@
+use super::{receipt::TransactionReceipt, Event};
+use crate::{
+    abi::{error::AbiError, events::DecodedEvent, Abi},
+    transaction::Address,
+};
+use cairo_vm::Felt252;
+
+// --------------------
+// EmittedEvent structure
+// --------------------
+
+/// An event along with the transaction and block it was emitted in, as returned by
+/// `starknet_getEvents`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EmittedEvent {
+    pub from_address: Address,
+    pub keys: Vec<Felt252>,
+    pub data: Vec<Felt252>,
+    pub block_number: u64,
+    pub transaction_hash: Felt252,
+}
+
+impl EmittedEvent {
+    /// Decodes the event with the ABI of the contract that emitted it.
+    pub fn decode(&self, abi: &Abi) -> Result<DecodedEvent, AbiError> {
+        abi.decode_event(&self.keys, &self.data)
+    }
+}
+
+// --------------------
+// EventFilter structure
+// --------------------
+
+/// Selects events the same way `starknet_getEvents` does.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventFilter {
+    /// First block to include, if any.
+    pub from_block: Option<u64>,
+    /// Last block to include, if any.
+    pub to_block: Option<u64>,
+    /// Only include events emitted by this contract, if any.
+    pub address: Option<Address>,
+    /// Accepted values of each key, by position. An empty list accepts any value, and keys past
+    /// the end of the filter aren't checked.
+    pub keys: Vec<Vec<Felt252>>,
+}
+
+/// A page of the events matching a filter.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventsChunk {
+    pub events: Vec<EmittedEvent>,
+    /// Token to pass to [`EventFilter::filter_chunk`] to get the next page, if there are more
+    /// matching events.
+    pub continuation_token: Option<usize>,
+}
+
+impl EventFilter {
+    pub fn matches(&self, block_number: u64, event: &Event) -> bool {
+        let from_address = self
+            .address
+            .as_ref()
+            .map_or(true, |address| *address == event.from_address);
+        let keys = self.keys.iter().enumerate().all(|(i, accepted)| {
+            accepted.is_empty()
+                || event
+                    .keys
+                    .get(i)
+                    .map_or(false, |key| accepted.contains(key))
+        });
+
+        self.in_block_range(block_number) && from_address && keys
+    }
+
+    /// Returns the events of `receipts` matching the filter, in the order of the receipts.
+    pub fn filter<'a>(
+        &self,
+        receipts: impl IntoIterator<Item = &'a TransactionReceipt>,
+    ) -> Vec<EmittedEvent> {
+        receipts
+            .into_iter()
+            .filter(|receipt| self.in_block_range(receipt.block_number))
+            .flat_map(|receipt| self.matching_events(receipt))
+            .collect()
+    }
+
+    /// Returns at most `chunk_size` events matching the filter, starting at the position given by
+    /// `continuation_token`, or from the first matching event if there is none.
+    pub fn filter_chunk<'a>(
+        &self,
+        receipts: impl IntoIterator<Item = &'a TransactionReceipt>,
+        chunk_size: usize,
+        continuation_token: Option<usize>,
+    ) -> EventsChunk {
+        let start = continuation_token.unwrap_or_default();
+        let mut events: Vec<EmittedEvent> = receipts
+            .into_iter()
+            .filter(|receipt| self.in_block_range(receipt.block_number))
+            .flat_map(|receipt| self.matching_events(receipt))
+            .skip(start)
+            .take(chunk_size + 1)
+            .collect();
+
+        let continuation_token = if events.len() > chunk_size {
+            events.truncate(chunk_size);
+            Some(start + chunk_size)
+        } else {
+            None
+        };
+
+        EventsChunk {
+            events,
+            continuation_token,
+        }
+    }
+
+    fn in_block_range(&self, block_number: u64) -> bool {
+        self.from_block.map_or(true, |from| block_number >= from)
+            && self.to_block.map_or(true, |to| block_number <= to)
+    }
+
+    fn matching_events<'a>(
+        &'a self,
+        receipt: &'a TransactionReceipt,
+    ) -> impl Iterator<Item = EmittedEvent> + 'a {
+        receipt
+            .events
+            .iter()
+            .filter(move |event| self.matches(receipt.block_number, event))
+            .map(move |event| EmittedEvent {
+                from_address: event.from_address.clone(),
+                keys: event.keys.clone(),
+                data: event.data.clone(),
+                block_number: receipt.block_number,
+                transaction_hash: receipt.transaction_hash,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::execution::TransactionExecutionInfo;
+    use pretty_assertions_sorted::assert_eq;
+
+    fn receipt(block_number: u64, transaction_hash: u64, events: Vec<Event>) -> TransactionReceipt {
+        TransactionReceipt {
+            transaction_hash: transaction_hash.into(),
+            block_number,
+            execution_info: TransactionExecutionInfo::default(),
+            events,
+        }
+    }
+
+    fn event(from_address: u64, keys: &[u64]) -> Event {
+        Event {
+            from_address: Address(from_address.into()),
+            keys: keys.iter().copied().map(Felt252::from).collect(),
+            data: vec![],
+        }
+    }
+
+    fn receipts() -> Vec<TransactionReceipt> {
+        vec![
+            receipt(1, 10, vec![event(1, &[100, 5]), event(2, &[100, 6])]),
+            receipt(2, 20, vec![event(1, &[200])]),
+            receipt(3, 30, vec![event(1, &[100, 7]), event(1, &[100])]),
+        ]
+    }
+
+    fn transaction_hashes(events: &[EmittedEvent]) -> Vec<(u64, Felt252)> {
+        events
+            .iter()
+            .map(|event| (event.block_number, event.transaction_hash))
+            .collect()
+    }
+
+    #[test]
+    fn filter_by_address_and_block_range() {
+        let filter = EventFilter {
+            from_block: Some(2),
+            to_block: Some(3),
+            address: Some(Address(1.into())),
+            keys: vec![],
+        };
+
+        let events = filter.filter(&receipts());
+        assert_eq!(
+            transaction_hashes(&events),
+            vec![
+                (2, Felt252::from(20)),
+                (3, Felt252::from(30)),
+                (3, Felt252::from(30))
+            ]
+        );
+    }
+
+    #[test]
+    fn filter_by_keys() {
+        let filter = EventFilter {
+            keys: vec![vec![100.into()], vec![5.into(), 7.into()]],
+            ..Default::default()
+        };
+
+        let events = filter.filter(&receipts());
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| event.keys.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                vec![Felt252::from(100), Felt252::from(5)],
+                vec![Felt252::from(100), Felt252::from(7)],
+            ]
+        );
+
+        // An empty position accepts any key, but the key must exist.
+        let filter = EventFilter {
+            keys: vec![vec![], vec![6.into(), 7.into()]],
+            ..Default::default()
+        };
+        assert_eq!(filter.filter(&receipts()).len(), 2);
+    }
+
+    #[test]
+    fn filter_in_chunks() {
+        let filter = EventFilter::default();
+        let receipts = receipts();
+
+        let first = filter.filter_chunk(&receipts, 3, None);
+        assert_eq!(first.events.len(), 3);
+        assert_eq!(first.continuation_token, Some(3));
+
+        let second = filter.filter_chunk(&receipts, 3, first.continuation_token);
+        assert_eq!(
+            transaction_hashes(&second.events),
+            vec![(3, Felt252::from(30)), (3, Felt252::from(30))]
+        );
+        assert_eq!(second.continuation_token, None);
+    }
+}
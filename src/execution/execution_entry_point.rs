@@ -1,5 +1,9 @@
 use super::{
-    CallInfo, CallResult, CallType, OrderedEvent, OrderedL2ToL1Message, TransactionExecutionContext,
+    backend::{ExecutionBackend, ExecutionConfig},
+    differential::{compare_executions, Divergence},
+    revert::{ErrorFrame, RevertError},
+    CallInfo, CallResult, CallType, OrderedEvent, OrderedL2ToL1Message,
+    TransactionExecutionContext,
 };
 use crate::{
     definitions::{block_context::BlockContext, constants::DEFAULT_ENTRY_POINT_SELECTOR},
@@ -13,7 +17,7 @@ use crate::{
         contract_class_cache::ContractClassCache,
         contract_storage_state::ContractStorageState,
         state_api::{State, StateReader},
-        ExecutionResourcesManager,
+        ExecutionResourcesManager, StateDiff,
     },
     syscalls::{
         business_logic_syscall_handler::BusinessLogicSyscallHandler,
@@ -43,18 +47,12 @@ use cairo_vm::{
 };
 use std::sync::Arc;
 #[cfg(feature = "cairo-native")]
-use {
-    crate::state::StateDiff,
-    cairo_native::cache::{JitProgramCache, ProgramCache},
-    cairo_native::OptLevel,
-    std::{cell::RefCell, rc::Rc},
-    tracing::debug,
-};
+use tracing::debug;
 
 #[derive(Debug, Default, PartialEq)]
 pub struct ExecutionResult {
     pub call_info: Option<CallInfo>,
-    pub revert_error: Option<String>,
+    pub revert_error: Option<RevertError>,
     pub n_reverted_steps: usize,
 }
 
@@ -111,9 +109,7 @@ impl ExecutionEntryPoint {
         tx_execution_context: &mut TransactionExecutionContext,
         support_reverted: bool,
         max_steps: u64,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<ExecutionResult, TransactionError>
     where
         T: StateReader,
@@ -125,81 +121,111 @@ impl ExecutionEntryPoint {
             .get_contract_class(&class_hash)
             .map_err(|_| TransactionError::MissingCompiledClass)?;
 
+        let runs_natively = match &contract_class {
+            CompiledClass::Casm {
+                sierra: Some(sierra_program_and_entrypoints),
+                ..
+            } => {
+                execution_config.backend().is_native()
+                    && execution_config
+                        .policy()
+                        .runs_natively(&class_hash, &sierra_program_and_entrypoints.0)
+            }
+            _ => false,
+        };
+
         #[cfg(feature = "cairo-native")]
         debug!(
             "Executing entry point using {}",
-            match &contract_class {
-                CompiledClass::Casm {
-                    sierra: Some(_), ..
-                } => "Cairo Native's JIT",
-                _ => "the VM",
+            if runs_natively {
+                "Cairo Native"
+            } else {
+                "the VM"
             }
         );
 
-        match contract_class {
+        // Reverted executions must leave no trace in the state, so they run on top of a
+        // transactional state which is only applied when the execution succeeds.
+        let mut transactional_state = if support_reverted {
+            Some(state.create_transactional()?)
+        } else {
+            None
+        };
+        let execution_state = transactional_state.as_mut().unwrap_or(state);
+        let previous_cairo_usage = resources_manager.cairo_usage.clone();
+
+        let result = match contract_class {
             CompiledClass::Deprecated(contract_class) => {
-                let call_info = self._execute_version0_class(
-                    state,
+                execution_config.metrics().add_call(ExecutionBackend::Vm);
+                self._execute_version0_class(
+                    execution_state,
                     resources_manager,
                     block_context,
                     tx_execution_context,
                     contract_class,
                     class_hash,
-                )?;
-                Ok(ExecutionResult {
-                    call_info: Some(call_info),
-                    revert_error: None,
-                    n_reverted_steps: 0,
-                })
+                    execution_config,
+                )
             }
-            #[cfg(feature = "cairo-native")]
             CompiledClass::Casm {
+                casm: contract_class,
                 sierra: Some(sierra_program_and_entrypoints),
-                ..
-            } => {
-                let mut transactional_state = state.create_transactional()?;
-
-                let program_cache = program_cache.unwrap_or_else(|| {
-                    Rc::new(RefCell::new(ProgramCache::Jit(JitProgramCache::new(
-                        crate::utils::get_native_context(),
-                    ))))
-                });
-
+            } if runs_natively && execution_config.differential_report().is_some() => {
+                execution_config.metrics().add_call(ExecutionBackend::Vm);
+                self.differential_execute(
+                    execution_state,
+                    resources_manager,
+                    block_context,
+                    tx_execution_context,
+                    contract_class,
+                    sierra_program_and_entrypoints,
+                    class_hash,
+                    support_reverted,
+                    execution_config,
+                )
+            }
+            CompiledClass::Casm {
+                casm: contract_class,
+                sierra: Some(sierra_program_and_entrypoints),
+            } if runs_natively => {
                 match self.native_execute(
-                    &mut transactional_state,
+                    execution_state,
+                    resources_manager,
                     sierra_program_and_entrypoints,
                     tx_execution_context,
                     block_context,
                     &class_hash,
-                    program_cache,
+                    execution_config,
                 ) {
-                    Ok(call_info) => {
-                        state.apply_state_update(&StateDiff::from_cached_state(
-                            transactional_state.cache(),
-                        )?)?;
-
-                        Ok(ExecutionResult {
-                            call_info: Some(call_info),
-                            revert_error: None,
-                            n_reverted_steps: 0,
-                        })
+                    // The class is compiled before anything is executed, so the call can start
+                    // over on the VM.
+                    Err(TransactionError::NativeCompilation(_, message))
+                        if execution_config.policy().fallback_to_vm() =>
+                    {
+                        tracing::warn!(
+                            "Running class {class_hash} on the VM after failing to compile it: {message}"
+                        );
+                        execution_config
+                            .policy()
+                            .record_compilation_failure(class_hash);
+                        execution_config.metrics().add_fallback();
+                        execution_config.metrics().add_call(ExecutionBackend::Vm);
+                        self._execute(
+                            execution_state,
+                            resources_manager,
+                            block_context,
+                            tx_execution_context,
+                            contract_class,
+                            class_hash,
+                            support_reverted,
+                            execution_config,
+                        )
                     }
-                    Err(e) => {
-                        if !support_reverted {
-                            state.apply_state_update(&StateDiff::from_cached_state(
-                                transactional_state.cache(),
-                            )?)?;
-
-                            return Err(e);
-                        }
-
-                        let n_reverted_steps =
-                            (max_steps as usize) - resources_manager.cairo_usage.n_steps;
-                        Ok(ExecutionResult {
-                            call_info: None,
-                            revert_error: Some(e.to_string()),
-                            n_reverted_steps,
-                        })
+                    result => {
+                        execution_config
+                            .metrics()
+                            .add_call(execution_config.backend());
+                        result
                     }
                 }
             }
@@ -207,36 +233,177 @@ impl ExecutionEntryPoint {
                 casm: contract_class,
                 ..
             } => {
-                match self._execute(
-                    state,
+                execution_config.metrics().add_call(ExecutionBackend::Vm);
+                self._execute(
+                    execution_state,
                     resources_manager,
                     block_context,
                     tx_execution_context,
                     contract_class,
                     class_hash,
                     support_reverted,
-                ) {
-                    Ok(call_info) => Ok(ExecutionResult {
-                        call_info: Some(call_info),
-                        revert_error: None,
-                        n_reverted_steps: 0,
-                    }),
-                    Err(e) => {
-                        if !support_reverted {
-                            return Err(e);
-                        }
-
-                        let n_reverted_steps =
-                            (max_steps as usize) - resources_manager.cairo_usage.n_steps;
-                        Ok(ExecutionResult {
-                            call_info: None,
-                            revert_error: Some(e.to_string()),
-                            n_reverted_steps,
-                        })
-                    }
+                    execution_config,
+                )
+            }
+        };
+
+        match result {
+            Ok(call_info) if support_reverted && call_info.failure_flag => {
+                // A panicking entry point reverts the execution: its state changes are
+                // discarded, but the resources it used are still charged.
+                let revert_error = RevertError::from_panic(
+                    self.error_frame(class_hash, None),
+                    call_info.retdata.clone(),
+                );
+
+                Ok(ExecutionResult {
+                    call_info: Some(call_info),
+                    revert_error: Some(revert_error),
+                    n_reverted_steps: 0,
+                })
+            }
+            Ok(call_info) => {
+                if let Some(transactional_state) = transactional_state {
+                    state.apply_state_update(&StateDiff::from_cached_state(
+                        transactional_state.cache(),
+                    )?)?;
                 }
+
+                Ok(ExecutionResult {
+                    call_info: Some(call_info),
+                    revert_error: None,
+                    n_reverted_steps: 0,
+                })
+            }
+            Err(e) => {
+                let revert_error = self.build_revert_error(class_hash, &e, tx_execution_context);
+                if !support_reverted {
+                    // Keep the error around so that the calling frame can extend its call stack.
+                    tx_execution_context.revert_error = Some(revert_error);
+                    return Err(e);
+                }
+
+                // Steps consumed up to the failure point are charged through
+                // `n_reverted_steps`, while the builtins used remain accounted in the
+                // resources manager.
+                let n_reverted_steps = resources_manager
+                    .cairo_usage
+                    .n_steps
+                    .saturating_sub(previous_cairo_usage.n_steps)
+                    .min(max_steps as usize);
+                resources_manager.cairo_usage.n_steps = previous_cairo_usage.n_steps;
+
+                Ok(ExecutionResult {
+                    call_info: None,
+                    revert_error: Some(revert_error),
+                    n_reverted_steps,
+                })
+            }
+        }
+    }
+
+    /// Runs the call both on the VM and natively, reporting the divergences between both runs in
+    /// the config's differential report. The native run happens on a copy of the state and is
+    /// discarded, only the VM run is applied.
+    ///
+    /// A class failing to compile is handled as outside of the differential mode: the failure is
+    /// recorded by the policy and the call isn't compared, or it fails without the VM fallback.
+    #[allow(clippy::too_many_arguments)]
+    fn differential_execute<S: StateReader, C: ContractClassCache>(
+        &self,
+        state: &mut CachedState<S, C>,
+        resources_manager: &mut ExecutionResourcesManager,
+        block_context: &BlockContext,
+        tx_execution_context: &mut TransactionExecutionContext,
+        contract_class: Arc<CasmContractClass>,
+        sierra_program_and_entrypoints: Arc<(SierraProgram, ContractEntryPoints)>,
+        class_hash: ClassHash,
+        support_reverted: bool,
+        execution_config: &ExecutionConfig,
+    ) -> Result<CallInfo, TransactionError> {
+        let mut native_state = state.create_transactional()?;
+        let native_result = self.native_execute(
+            &mut native_state,
+            &mut resources_manager.clone(),
+            sierra_program_and_entrypoints,
+            &tx_execution_context.clone(),
+            block_context,
+            &class_hash,
+            &execution_config.without_differential(),
+        );
+        // The class is compiled before anything runs natively, so there's nothing to compare the
+        // VM run with.
+        let compiled = match &native_result {
+            Err(TransactionError::NativeCompilation(_, message))
+                if execution_config.policy().fallback_to_vm() =>
+            {
+                tracing::warn!(
+                    "Running class {class_hash} on the VM only after failing to compile it: {message}"
+                );
+                execution_config
+                    .policy()
+                    .record_compilation_failure(class_hash);
+                execution_config.metrics().add_fallback();
+                false
             }
+            Err(TransactionError::NativeCompilation(..)) => return native_result,
+            _ => true,
+        };
+
+        let vm_result = self._execute(
+            state,
+            resources_manager,
+            block_context,
+            tx_execution_context,
+            contract_class,
+            class_hash,
+            support_reverted,
+            execution_config,
+        );
+
+        if let Some(report) = execution_config.differential_report().filter(|_| compiled) {
+            let differences = compare_executions(
+                (&vm_result, &state.cache().storage_writes),
+                (&native_result, &native_state.cache().storage_writes),
+            );
+            report.add_comparison((!differences.is_empty()).then(|| Divergence {
+                contract_address: self.contract_address.clone(),
+                class_hash,
+                entry_point_selector: self.entry_point_selector,
+                calldata: self.calldata.clone(),
+                differences,
+            }));
         }
+
+        vm_result
+    }
+
+    /// Builds the error of a failed execution, extending the call stack of the inner call that
+    /// caused the failure, if any.
+    fn build_revert_error(
+        &self,
+        class_hash: ClassHash,
+        error: &TransactionError,
+        tx_execution_context: &mut TransactionExecutionContext,
+    ) -> RevertError {
+        let frame = self.error_frame(class_hash, Some(error));
+        match tx_execution_context.revert_error.take() {
+            Some(mut revert_error) => {
+                revert_error.push_caller(frame);
+                revert_error
+            }
+            None => RevertError::from_error(frame, error),
+        }
+    }
+
+    /// Returns the frame describing this call in the call stack of a failed execution.
+    fn error_frame(&self, class_hash: ClassHash, error: Option<&TransactionError>) -> ErrorFrame {
+        ErrorFrame::new(
+            self.contract_address.clone(),
+            Some(class_hash),
+            self.entry_point_selector,
+            error,
+        )
     }
 
     /// Returns for version 0 the entry point with selector corresponding with self.entry_point_selector, or the
@@ -412,6 +579,7 @@ impl ExecutionEntryPoint {
         tx_execution_context: &mut TransactionExecutionContext,
         contract_class: Arc<ContractClass>,
         class_hash: ClassHash,
+        execution_config: &ExecutionConfig,
     ) -> Result<CallInfo, TransactionError> {
         let previous_cairo_usage = resources_manager.cairo_usage.clone();
         // fetch selected entry point
@@ -446,6 +614,7 @@ impl ExecutionEntryPoint {
             self.contract_address.clone(),
             block_context.clone(),
             initial_syscall_ptr,
+            execution_config.clone(),
         );
         let hint_processor =
             DeprecatedSyscallHintProcessor::new(syscall_handler, RunResources::default());
@@ -467,7 +636,24 @@ impl ExecutionEntryPoint {
         ];
 
         // cairo runner entry point
-        runner.run_from_entrypoint(entry_point.offset(), &entry_point_args, None)?;
+        if let Err(error) =
+            runner.run_from_entrypoint(entry_point.offset(), &entry_point_args, None)
+        {
+            // Keep the resources used up to the failure point, as they are charged when the
+            // execution gets reverted.
+            *resources_manager = runner
+                .hint_processor
+                .syscall_handler
+                .resources_manager
+                .clone();
+            *tx_execution_context = runner
+                .hint_processor
+                .syscall_handler
+                .tx_execution_context
+                .clone();
+            resources_manager.cairo_usage += &runner.get_partial_execution_resources();
+            return Err(error);
+        }
         runner.validate_and_process_os_context_for_version0_class(os_context)?;
 
         // When execution starts the stack holds entry_points_args + [ret_fp, ret_pc].
@@ -519,6 +705,7 @@ impl ExecutionEntryPoint {
         contract_class: Arc<CasmContractClass>,
         class_hash: ClassHash,
         support_reverted: bool,
+        execution_config: &ExecutionConfig,
     ) -> Result<CallInfo, TransactionError> {
         let previous_cairo_usage = resources_manager.cairo_usage.clone();
 
@@ -560,6 +747,7 @@ impl ExecutionEntryPoint {
             initial_syscall_ptr,
             support_reverted,
             self.entry_point_selector,
+            execution_config.clone(),
         );
         // create and attach a syscall hint processor to the starknet runner.
         let hint_processor = SyscallHintProcessor::new(
@@ -610,11 +798,26 @@ impl ExecutionEntryPoint {
         let ref_vec: Vec<&CairoArg> = entrypoint_args.iter().collect();
 
         // run the Cairo1 entrypoint
-        runner.run_from_entrypoint(
+        if let Err(error) = runner.run_from_entrypoint(
             entry_point.offset,
             &ref_vec,
             Some(program.data_len() + program_extra_data.len()),
-        )?;
+        ) {
+            // Keep the resources used up to the failure point, as they are charged when the
+            // execution gets reverted.
+            *resources_manager = runner
+                .hint_processor
+                .syscall_handler
+                .resources_manager
+                .clone();
+            *tx_execution_context = runner
+                .hint_processor
+                .syscall_handler
+                .tx_execution_context
+                .clone();
+            resources_manager.cairo_usage += &runner.get_partial_execution_resources();
+            return Err(error);
+        }
 
         runner
             .vm
@@ -667,9 +870,12 @@ impl ExecutionEntryPoint {
     fn native_execute<S: StateReader, C: ContractClassCache>(
         &self,
         _state: &mut CachedState<S, C>,
+        _resources_manager: &mut ExecutionResourcesManager,
         _sierra_program_and_entrypoints: Arc<(SierraProgram, ContractEntryPoints)>,
-        _tx_execution_context: &mut TransactionExecutionContext,
+        _tx_execution_context: &TransactionExecutionContext,
         _block_context: &BlockContext,
+        _class_hash: &ClassHash,
+        _execution_config: &ExecutionConfig,
     ) -> Result<CallInfo, TransactionError> {
         Err(TransactionError::SierraCompileError(
             "This version of SiR was compiled without the Cairo Native feature".to_string(),
@@ -681,32 +887,25 @@ impl ExecutionEntryPoint {
     fn native_execute<S: StateReader, C: ContractClassCache>(
         &self,
         state: &mut CachedState<S, C>,
+        resources_manager: &mut ExecutionResourcesManager,
         sierra_program_and_entrypoints: Arc<(SierraProgram, ContractEntryPoints)>,
         tx_execution_context: &TransactionExecutionContext,
         block_context: &BlockContext,
         class_hash: &ClassHash,
-        program_cache: Rc<RefCell<ProgramCache<'_, ClassHash>>>,
+        execution_config: &ExecutionConfig,
     ) -> Result<CallInfo, TransactionError> {
         use cairo_native::{
             executor::NativeExecutor, metadata::syscall_handler::SyscallHandlerMeta,
         };
 
         use crate::{
-            syscalls::{
-                business_logic_syscall_handler::SYSCALL_BASE,
-                native_syscall_handler::NativeSyscallHandler,
-            },
-            utils::NATIVE_CONTEXT,
+            execution::native_resources::{estimate_execution_resources, own_gas_consumed},
+            syscalls::native_syscall_handler::NativeSyscallHandler,
         };
 
-        // Ensure we're using the global context, if initialized.
-        if let Some(native_context) = NATIVE_CONTEXT.get() {
-            let c = program_cache.borrow();
-            match &*c {
-                ProgramCache::Aot(_) => {}
-                ProgramCache::Jit(jit) => assert_eq!(jit.context(), native_context),
-            }
-        }
+        let program_cache = execution_config.program_cache().ok_or_else(|| {
+            TransactionError::CustomError("Native execution requires a program cache".to_string())
+        })?;
 
         let sierra_program = &sierra_program_and_entrypoints.0;
         let contract_entrypoints = &sierra_program_and_entrypoints.1;
@@ -719,30 +918,14 @@ impl ExecutionEntryPoint {
         .iter()
         .find(|entry_point| entry_point.selector == self.entry_point_selector.to_biguint())
         .unwrap();
-        let native_executor: NativeExecutor = {
-            let mut cache = program_cache.borrow_mut();
-            let cache = &mut *cache;
-            match cache {
-                ProgramCache::Aot(cache) => {
-                    NativeExecutor::Aot(if let Some(executor) = cache.get(class_hash) {
-                        executor
-                    } else {
-                        cache.compile_and_insert(*class_hash, sierra_program, OptLevel::Default)
-                    })
-                }
-                ProgramCache::Jit(cache) => {
-                    NativeExecutor::Jit(if let Some(executor) = cache.get(class_hash) {
-                        executor
-                    } else {
-                        cache.compile_and_insert(*class_hash, sierra_program, OptLevel::Default)
-                    })
-                }
-            }
-        };
+        let native_executor = program_cache.get_or_compile(class_hash, sierra_program)?;
 
+        let previous_cairo_usage = resources_manager.cairo_usage.clone();
         let contract_storage_state =
             ContractStorageState::new(state, self.contract_address.clone());
 
+        // The inner calls share the resources manager, so that their resources are accounted
+        // the same way as when they are run by the VM.
         let mut syscall_handler = NativeSyscallHandler {
             starknet_storage_state: contract_storage_state,
             events: Vec::new(),
@@ -753,8 +936,9 @@ impl ExecutionEntryPoint {
             entry_point_selector: self.entry_point_selector,
             tx_execution_context: tx_execution_context.clone(),
             block_context: block_context.clone(),
-            program_cache: program_cache.clone(),
-            resources_manager: Default::default(),
+            execution_config: execution_config.clone(),
+            resources_manager: std::mem::take(resources_manager),
+            syscalls_gas: 0,
         };
 
         let syscall_meta = SyscallHandlerMeta::new(&mut syscall_handler);
@@ -780,8 +964,27 @@ impl ExecutionEntryPoint {
                 Some(self.initial_gas),
                 Some(&syscall_meta),
             ),
-        }
-        .map_err(|e| TransactionError::CustomError(format!("cairo-native error: {:?}", e)))?;
+        };
+        *resources_manager = std::mem::take(&mut syscall_handler.resources_manager);
+        let value = value
+            .map_err(|e| TransactionError::CustomError(format!("cairo-native error: {:?}", e)))?;
+
+        let gas_consumed = self
+            .initial_gas
+            .saturating_sub(block_context.syscall_costs().syscall_base_gas)
+            .saturating_sub(value.remaining_gas);
+        let own_gas = own_gas_consumed(
+            gas_consumed,
+            syscall_handler.syscalls_gas,
+            syscall_handler
+                .internal_calls
+                .iter()
+                .map(|call_info| call_info.gas_consumed)
+                .sum(),
+        );
+        resources_manager.cairo_usage +=
+            &estimate_execution_resources(own_gas, sierra_program, entry_point_id);
+        let execution_resources = &resources_manager.cairo_usage - &previous_cairo_usage;
 
         Ok(CallInfo {
             caller_address: self.caller_address.clone(),
@@ -793,17 +996,14 @@ impl ExecutionEntryPoint {
             entry_point_type: Some(self.entry_point_type),
             calldata: self.calldata.clone(),
             retdata: value.return_values,
-            execution_resources: None,
+            execution_resources: Some(execution_resources.filter_unused_builtins()),
             events: syscall_handler.events,
             storage_read_values: syscall_handler.starknet_storage_state.read_values,
             accessed_storage_keys: syscall_handler.starknet_storage_state.accessed_keys,
             failure_flag: value.failure_flag,
             l2_to_l1_messages: syscall_handler.l2_to_l1_messages,
             internal_calls: syscall_handler.internal_calls,
-            gas_consumed: self
-                .initial_gas
-                .saturating_sub(SYSCALL_BASE)
-                .saturating_sub(value.remaining_gas),
+            gas_consumed,
         })
     }
 }
@@ -1,6 +1,18 @@
+#[cfg(feature = "cairo-native")]
+pub mod aot_cache;
+pub mod backend;
+pub mod differential;
+pub mod events;
 pub mod execution_entry_point;
 pub mod gas_usage;
+pub mod native_resources;
 pub mod os_usage;
+pub mod policy;
+#[cfg(feature = "cairo-native")]
+pub mod program_cache;
+pub mod receipt;
+pub mod revert;
+use self::revert::{PanicError, RevertError};
 use crate::services::api::contract_classes::deprecated_contract_class::EntryPointType;
 use crate::transaction::VersionSpecificAccountTxFields;
 use crate::utils::parse_felt_array;
@@ -212,6 +224,13 @@ impl CallInfo {
         })
     }
 
+    /// Decodes the panic data of a failed Cairo 1 call into a human-readable error.
+    /// Returns `None` if the call didn't fail.
+    pub fn panic_error(&self) -> Option<PanicError> {
+        self.failure_flag
+            .then(|| PanicError::from_panic_data(&self.retdata))
+    }
+
     pub fn result(&self) -> CallResult {
         CallResult {
             gas_consumed: self.gas_consumed,
@@ -309,6 +328,25 @@ pub struct CallResult {
     pub retdata: Vec<MaybeRelocatable>,
 }
 
+impl CallResult {
+    /// Decodes the panic data of a failed Cairo 1 call into a human-readable error.
+    /// Returns `None` if the call didn't fail.
+    ///
+    /// Panic data is made of felts only, so relocatable values in the retdata are skipped.
+    pub fn panic_error(&self) -> Option<PanicError> {
+        if self.is_success {
+            return None;
+        }
+
+        let panic_data: Vec<Felt252> = self
+            .retdata
+            .iter()
+            .filter_map(|value| value.get_int_ref().cloned())
+            .collect();
+        Some(PanicError::from_panic_data(&panic_data))
+    }
+}
+
 impl From<CallInfo> for CallResult {
     fn from(info: CallInfo) -> Self {
         Self {
@@ -369,6 +407,9 @@ pub struct TransactionExecutionContext {
     pub(crate) nonce: Felt252,
     pub(crate) n_sent_messages: usize,
     pub(crate) _n_steps: u64,
+    /// Error of the most recent failed entry point execution, whose call stack is filled while
+    /// the failure propagates to the outermost call.
+    pub(crate) revert_error: Option<RevertError>,
     // pub(crate) use_cairo_native: bool,
 }
 
@@ -398,6 +439,7 @@ impl TransactionExecutionContext {
             version,
             n_sent_messages: 0,
             _n_steps,
+            revert_error: None,
         }
     }
 
@@ -497,7 +539,7 @@ impl TxInfoStruct {
 pub struct TransactionExecutionInfo {
     pub validate_info: Option<CallInfo>,
     pub call_info: Option<CallInfo>,
-    pub revert_error: Option<String>,
+    pub revert_error: Option<RevertError>,
     pub fee_transfer_info: Option<CallInfo>,
     pub actual_fee: u128,
     pub actual_resources: HashMap<String, usize>,
@@ -508,7 +550,7 @@ impl TransactionExecutionInfo {
     pub const fn new(
         validate_info: Option<CallInfo>,
         call_info: Option<CallInfo>,
-        revert_error: Option<String>,
+        revert_error: Option<RevertError>,
         fee_transfer_info: Option<CallInfo>,
         actual_fee: u128,
         actual_resources: HashMap<String, usize>,
@@ -567,7 +609,7 @@ impl TransactionExecutionInfo {
     pub const fn new_without_fee_info(
         validate_info: Option<CallInfo>,
         call_info: Option<CallInfo>,
-        revert_error: Option<String>,
+        revert_error: Option<RevertError>,
         actual_resources: HashMap<String, usize>,
         tx_type: Option<TransactionType>,
     ) -> Self {
@@ -623,11 +665,11 @@ impl TransactionExecutionInfo {
         Ok(sorted_messages)
     }
 
-    pub fn to_revert_error(self, revert_error: &str) -> Self {
+    pub fn to_revert_error(self, revert_error: impl Into<RevertError>) -> Self {
         TransactionExecutionInfo {
             validate_info: None,
             call_info: None,
-            revert_error: Some(revert_error.to_string()),
+            revert_error: Some(revert_error.into()),
             fee_transfer_info: None,
             ..self
         }
@@ -1025,4 +1067,30 @@ mod tests {
             ])
         )
     }
+
+    #[test]
+    fn test_call_info_panic_error() {
+        let call_info = CallInfo {
+            retdata: vec![
+                Felt252::from_bytes_be_slice(b"Out of gas"),
+                Felt252::from_bytes_be_slice(b"ENTRYPOINT_FAILED"),
+            ],
+            failure_flag: true,
+            ..Default::default()
+        };
+
+        let panic_error = call_info.panic_error().unwrap();
+        assert_eq!(
+            panic_error.to_string(),
+            "ENTRYPOINT_FAILED: 0x4f7574206f6620676173 ('Out of gas')"
+        );
+        assert_eq!(call_info.result().panic_error(), Some(panic_error));
+
+        let call_info = CallInfo {
+            failure_flag: false,
+            ..call_info
+        };
+        assert_eq!(call_info.panic_error(), None);
+        assert_eq!(call_info.result().panic_error(), None);
+    }
 }
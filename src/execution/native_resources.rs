@@ -0,0 +1,261 @@
+//! Execution resources of the calls run with Cairo Native.
+//!
+//! Cairo Native doesn't run the CASM of a class, so there's no trace to count the steps and
+//! builtins of a call from. They are estimated instead from the sierra gas spent by the call's own
+//! code: the gas cost of each sierra libfunc is derived from the CASM it compiles to, where every
+//! step costs [`STEP_GAS_COST`] and every builtin instance has its own cost, so the gas consumed
+//! is a close measure of the resources the VM would have used.
+//!
+//! The gas doesn't tell how much of it was spent on each builtin, so it's split between steps
+//! and builtins in the proportions of the libfuncs reachable from the entry point, as if each of
+//! them ran the same number of times.
+
+use crate::definitions::constants::{
+    BITWISE_GAS_COST, EC_OP_GAS_COST, PEDERSEN_GAS_COST, POSEIDON_GAS_COST, RANGE_CHECK_GAS_COST,
+    STEP_GAS_COST,
+};
+use cairo_lang_sierra::{
+    ids::FunctionId,
+    program::{GenBranchTarget, GenericArg, Program, Statement, StatementIdx},
+};
+use cairo_vm::vm::runners::{
+    builtin_runner::{
+        BITWISE_BUILTIN_NAME, EC_OP_BUILTIN_NAME, HASH_BUILTIN_NAME, POSEIDON_BUILTIN_NAME,
+        RANGE_CHECK_BUILTIN_NAME,
+    },
+    cairo_runner::ExecutionResources,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Steps assumed for the libfuncs that don't use builtins, most of which compile to a single
+/// CASM instruction or to none.
+const DEFAULT_LIBFUNC_STEPS: usize = 1;
+
+/// Estimates the resources used by the VM to run code consuming `gas` units of sierra gas, from
+/// the `entry_point` function of `program`.
+///
+/// `gas` must only include the gas spent by the code itself, without the gas charged for
+/// syscalls and inner calls, which are accounted separately.
+pub fn estimate_execution_resources(
+    gas: u128,
+    program: &Program,
+    entry_point: &FunctionId,
+) -> ExecutionResources {
+    let profile = LibfuncProfile::new(program, entry_point);
+    let profile_gas = profile.gas();
+
+    let mut builtins_gas = 0;
+    let builtin_instance_counter: HashMap<String, usize> = profile
+        .builtins
+        .iter()
+        .filter_map(|(builtin, count)| {
+            let instances = gas.saturating_mul(*count as u128) / profile_gas.max(1);
+            builtins_gas += instances * builtin_gas_cost(builtin);
+            let instances = usize::try_from(instances).unwrap_or(usize::MAX);
+            (instances > 0).then(|| (builtin.to_string(), instances))
+        })
+        .collect();
+
+    ExecutionResources {
+        n_steps: usize::try_from(gas.saturating_sub(builtins_gas) / STEP_GAS_COST)
+            .unwrap_or(usize::MAX),
+        n_memory_holes: 0,
+        builtin_instance_counter,
+    }
+}
+
+/// Returns the gas spent by a call's own code, out of the `gas_consumed` by the whole call.
+pub fn own_gas_consumed(gas_consumed: u128, syscalls_gas: u128, internal_calls_gas: u128) -> u128 {
+    gas_consumed
+        .saturating_sub(syscalls_gas)
+        .saturating_sub(internal_calls_gas)
+}
+
+// --------------------
+// LibfuncProfile structure
+// --------------------
+
+/// Steps and builtins used by running once each libfunc invocation reachable from an entry point.
+#[derive(Debug, Default)]
+struct LibfuncProfile {
+    n_steps: usize,
+    builtins: HashMap<&'static str, usize>,
+}
+
+impl LibfuncProfile {
+    /// Walks the statements reachable from `entry_point`, following the calls to other
+    /// functions of the program.
+    fn new(program: &Program, entry_point: &FunctionId) -> Self {
+        let functions: HashMap<&FunctionId, StatementIdx> = program
+            .funcs
+            .iter()
+            .map(|function| (&function.id, function.entry_point))
+            .collect();
+        let libfuncs: HashMap<_, _> = program
+            .libfunc_declarations
+            .iter()
+            .map(|declaration| (&declaration.id, &declaration.long_id))
+            .collect();
+
+        let mut profile = Self::default();
+        let mut visited = HashSet::new();
+        let mut pending: Vec<StatementIdx> =
+            functions.get(entry_point).copied().into_iter().collect();
+        while let Some(statement_idx) = pending.pop() {
+            if !visited.insert(statement_idx.0) {
+                continue;
+            }
+            let Some(Statement::Invocation(invocation)) = program.statements.get(statement_idx.0)
+            else {
+                continue;
+            };
+            let Some(long_id) = libfuncs.get(&invocation.libfunc_id) else {
+                continue;
+            };
+
+            let (n_steps, builtins) = libfunc_cost(&long_id.generic_id.0);
+            profile.n_steps += n_steps;
+            for (builtin, count) in builtins {
+                *profile.builtins.entry(*builtin).or_default() += *count;
+            }
+
+            pending.extend(long_id.generic_args.iter().filter_map(|arg| match arg {
+                GenericArg::UserFunc(function_id) => functions.get(function_id).copied(),
+                _ => None,
+            }));
+            pending.extend(
+                invocation
+                    .branches
+                    .iter()
+                    .map(|branch| match &branch.target {
+                        GenBranchTarget::Fallthrough => StatementIdx(statement_idx.0 + 1),
+                        GenBranchTarget::Statement(target) => *target,
+                    }),
+            );
+        }
+        profile
+    }
+
+    fn gas(&self) -> u128 {
+        self.n_steps as u128 * STEP_GAS_COST
+            + self
+                .builtins
+                .iter()
+                .map(|(builtin, count)| *count as u128 * builtin_gas_cost(builtin))
+                .sum::<u128>()
+    }
+}
+
+/// Returns the steps and builtins used by the successful branch of a libfunc, by the id of its
+/// generic libfunc, as accounted by the sierra gas costs.
+fn libfunc_cost(generic_id: &str) -> (usize, &'static [(&'static str, usize)]) {
+    match generic_id {
+        "pedersen" => (2, &[(HASH_BUILTIN_NAME, 1)]),
+        "hades_permutation" => (3, &[(POSEIDON_BUILTIN_NAME, 1)]),
+        "ec_state_add_mul" => (5, &[(EC_OP_BUILTIN_NAME, 1)]),
+        "bitwise" | "u8_bitwise" | "u16_bitwise" | "u32_bitwise" | "u64_bitwise" => {
+            (2, &[(BITWISE_BUILTIN_NAME, 1)])
+        }
+        "u128_byte_reverse" => (24, &[(BITWISE_BUILTIN_NAME, 4)]),
+        "withdraw_gas" | "withdraw_gas_all" | "downcast" => (3, &[(RANGE_CHECK_BUILTIN_NAME, 1)]),
+        "u128_overflowing_add" | "u128_overflowing_sub" => (3, &[(RANGE_CHECK_BUILTIN_NAME, 1)]),
+        "u128s_from_felt252" | "u128_try_from_felt252" => (2, &[(RANGE_CHECK_BUILTIN_NAME, 1)]),
+        "i128_try_from_felt252" => (3, &[(RANGE_CHECK_BUILTIN_NAME, 1)]),
+        "u128_safe_divmod" => (11, &[(RANGE_CHECK_BUILTIN_NAME, 4)]),
+        "u128_mul_guarantee_verify" => (23, &[(RANGE_CHECK_BUILTIN_NAME, 9)]),
+        "u256_safe_divmod" => (26, &[(RANGE_CHECK_BUILTIN_NAME, 6)]),
+        "u256_sqrt" => (30, &[(RANGE_CHECK_BUILTIN_NAME, 7)]),
+        "u256_guarantee_inv_mod_n" => (40, &[(RANGE_CHECK_BUILTIN_NAME, 9)]),
+        "u512_safe_divmod_by_u256" => (47, &[(RANGE_CHECK_BUILTIN_NAME, 12)]),
+        "bytes31_try_from_felt252" => (7, &[(RANGE_CHECK_BUILTIN_NAME, 3)]),
+        // Arithmetic of the signed integers smaller than i128.
+        id if id.ends_with("_overflowing_add_impl") || id.ends_with("_overflowing_sub_impl") => {
+            (6, &[(RANGE_CHECK_BUILTIN_NAME, 2)])
+        }
+        id if id.ends_with("_overflowing_add")
+            || id.ends_with("_overflowing_sub")
+            || id.ends_with("_diff") =>
+        {
+            (4, &[(RANGE_CHECK_BUILTIN_NAME, 1)])
+        }
+        id if id.ends_with("_safe_divmod") => (7, &[(RANGE_CHECK_BUILTIN_NAME, 3)]),
+        id if id.ends_with("_sqrt") => (9, &[(RANGE_CHECK_BUILTIN_NAME, 4)]),
+        id if id.ends_with("_try_from_felt252") => (4, &[(RANGE_CHECK_BUILTIN_NAME, 2)]),
+        _ => (DEFAULT_LIBFUNC_STEPS, &[]),
+    }
+}
+
+fn builtin_gas_cost(builtin: &str) -> u128 {
+    match builtin {
+        RANGE_CHECK_BUILTIN_NAME => RANGE_CHECK_GAS_COST,
+        HASH_BUILTIN_NAME => PEDERSEN_GAS_COST,
+        BITWISE_BUILTIN_NAME => BITWISE_GAS_COST,
+        EC_OP_BUILTIN_NAME => EC_OP_GAS_COST,
+        POSEIDON_BUILTIN_NAME => POSEIDON_GAS_COST,
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::calculate_sn_keccak;
+    use cairo_lang_starknet::contract_class::ContractClass as SierraContractClass;
+    use cairo_vm::Felt252;
+    use pretty_assertions_sorted::assert_eq;
+    use std::{fs::File, io::BufReader};
+
+    /// Returns the program of the sierra class at `path` and the function of its external entry
+    /// point `name`.
+    fn entry_point_function(path: &str, name: &str) -> (Program, FunctionId) {
+        let class: SierraContractClass =
+            serde_json::from_reader(BufReader::new(File::open(path).unwrap())).unwrap();
+        let program = class.extract_sierra_program().unwrap();
+        let selector = Felt252::from_bytes_be(&calculate_sn_keccak(name.as_bytes())).to_biguint();
+        let function_idx = class
+            .entry_points_by_type
+            .external
+            .iter()
+            .find(|entry_point| entry_point.selector == selector)
+            .unwrap()
+            .function_idx;
+        let function_id = program
+            .funcs
+            .iter()
+            .find(|function| function.id.id == function_idx as u64)
+            .unwrap()
+            .id
+            .clone();
+        (program, function_id)
+    }
+
+    #[test]
+    fn estimate_resources_from_gas() {
+        let gas = own_gas_consumed(1_234_567, 10_000, 200_000);
+        assert_eq!(gas, 1_024_567);
+
+        // The loop of `fib` withdraws gas on each iteration, which uses a range check.
+        let (program, fib) =
+            entry_point_function("starknet_programs/cairo2/fibonacci.sierra", "fib");
+        let resources = estimate_execution_resources(gas, &program, &fib);
+        let range_checks = resources.builtin_instance_counter[RANGE_CHECK_BUILTIN_NAME];
+        assert!(range_checks > 0);
+        assert_eq!(resources.n_memory_holes, 0);
+        // The gas left after the builtins is accounted as steps.
+        let accounted_gas =
+            resources.n_steps as u128 * STEP_GAS_COST + range_checks as u128 * RANGE_CHECK_GAS_COST;
+        assert!(gas - accounted_gas < STEP_GAS_COST);
+
+        assert_eq!(own_gas_consumed(100, 1_000, 0), 0);
+    }
+
+    #[test]
+    fn estimate_hash_builtins() {
+        // The address of a `LegacyMap` entry is hashed with pedersen.
+        let (program, balance_of) =
+            entry_point_function("starknet_programs/cairo2/erc20.sierra", "balance_of");
+        let resources = estimate_execution_resources(1_000_000, &program, &balance_of);
+        assert!(resources.builtin_instance_counter[HASH_BUILTIN_NAME] > 0);
+        assert!(resources.builtin_instance_counter[RANGE_CHECK_BUILTIN_NAME] > 0);
+    }
+}
@@ -2,7 +2,10 @@ use std::collections::HashMap;
 
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 
-use crate::{definitions::transaction_type::TransactionType, transaction::error::TransactionError};
+use crate::{
+    definitions::{syscall_costs::SyscallCosts, transaction_type::TransactionType},
+    transaction::error::TransactionError,
+};
 
 // Values compatible with blockifier version 0.5.0-dev.0
 pub(crate) const ESTIMATED_INVOKE_FUNCTION_STEPS: usize = 3549;
@@ -11,10 +14,10 @@ pub(crate) const ESTIMATED_DEPLOY_STEPS: usize = 0;
 pub(crate) const ESTIMATED_DEPLOY_ACCOUNT_STEPS: usize = 3798;
 pub(crate) const ESTIMATED_L1_HANDLER_STEPS: usize = 1157;
 
-/// Represents the operating system resources associated with syscalls and transactions.
+/// Represents the operating system resources associated with transactions. The resources of the
+/// syscalls are part of their [`SyscallCosts`].
 #[derive(Debug, Clone)]
 pub struct OsResources {
-    execute_syscalls: HashMap<String, ExecutionResources>,
     execute_txs_inner: HashMap<TransactionType, ExecutionResources>,
 }
 
@@ -77,240 +80,26 @@ impl Default for OsResources {
             ),
         ]);
 
-        let execute_syscalls = HashMap::from([
-            (
-                "call_contract".to_string(),
-                ExecutionResources {
-                    n_steps: 760,
-                    n_memory_holes: 4,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        20,
-                    )]),
-                },
-            ),
-            (
-                "delegate_call".to_string(),
-                ExecutionResources {
-                    n_steps: 713,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        19,
-                    )]),
-                },
-            ),
-            (
-                "delegate_l1_handler".to_string(),
-                ExecutionResources {
-                    n_steps: 692,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        15,
-                    )]),
-                },
-            ),
-            (
-                "deploy".to_string(),
-                ExecutionResources {
-                    n_steps: 1010,
-                    n_memory_holes: 13,
-                    builtin_instance_counter: HashMap::from([
-                        ("range_check_builtin".to_string(), 19),
-                        ("pedersen_builtin".to_string(), 7),
-                    ]),
-                },
-            ),
-            (
-                "emit_event".to_string(),
-                ExecutionResources {
-                    n_steps: 61,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        1,
-                    )]),
-                },
-            ),
-            (
-                "get_block_hash".to_string(),
-                ExecutionResources {
-                    n_steps: 74,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        2,
-                    )]),
-                },
-            ),
-            (
-                "get_block_number".to_string(),
-                ExecutionResources {
-                    n_steps: 40,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "get_block_timestamp".to_string(),
-                ExecutionResources {
-                    n_steps: 38,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "get_caller_address".to_string(),
-                ExecutionResources {
-                    n_steps: 62,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        1,
-                    )]),
-                },
-            ),
-            (
-                "get_contract_address".to_string(),
-                ExecutionResources {
-                    n_steps: 62,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        1,
-                    )]),
-                },
-            ),
-            (
-                "get_execution_info".to_string(),
-                ExecutionResources {
-                    n_steps: 62,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        1,
-                    )]),
-                },
-            ),
-            (
-                "get_sequencer_address".to_string(),
-                ExecutionResources {
-                    n_steps: 34,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "get_tx_info".to_string(),
-                ExecutionResources {
-                    n_steps: 62,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        1,
-                    )]),
-                },
-            ),
-            (
-                "get_tx_signature".to_string(),
-                ExecutionResources {
-                    n_steps: 44,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::new(),
-                },
-            ),
-            (
-                "library_call".to_string(),
-                ExecutionResources {
-                    n_steps: 751,
-                    n_memory_holes: 4,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        20,
-                    )]),
-                },
-            ),
-            (
-                "library_call_l1_handler".to_string(),
-                ExecutionResources {
-                    n_steps: 659,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        15,
-                    )]),
-                },
-            ),
-            (
-                "replace_class".to_string(),
-                ExecutionResources {
-                    n_steps: 98,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        1,
-                    )]),
-                },
-            ),
-            (
-                "send_message_to_l1".to_string(),
-                ExecutionResources {
-                    n_steps: 139,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        1,
-                    )]),
-                },
-            ),
-            (
-                "storage_read".to_string(),
-                ExecutionResources {
-                    n_steps: 87,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        1,
-                    )]),
-                },
-            ),
-            (
-                "storage_write".to_string(),
-                ExecutionResources {
-                    n_steps: 89,
-                    n_memory_holes: 0,
-                    builtin_instance_counter: HashMap::from([(
-                        "range_check_builtin".to_string(),
-                        1,
-                    )]),
-                },
-            ),
-        ]);
-
-        OsResources {
-            execute_syscalls,
-            execute_txs_inner,
-        }
+        OsResources { execute_txs_inner }
     }
 }
 
 /// Calculate the additional operating system resources required to execute a transaction
-/// given a set of syscalls invoked and a transaction type.
+/// given a set of syscalls invoked, a transaction type and the costs of the syscalls.
 pub fn get_additional_os_resources(
     syscall_counter: HashMap<String, u64>,
     tx_type: &TransactionType,
+    syscall_costs: &SyscallCosts,
 ) -> Result<ExecutionResources, TransactionError> {
     let os_resources = OsResources::default();
 
     let mut additional_os_resources = ExecutionResources::default();
 
     for (syscall, count) in syscall_counter {
-        let syscall_resources = &os_resources
-            .execute_syscalls
+        let syscall_resources = &syscall_costs
             .get(&syscall)
             .ok_or_else(|| TransactionError::ResourcesError)?
-            .clone()
+            .os_resources()
             * count as usize;
 
         additional_os_resources += &syscall_resources;
@@ -332,7 +121,12 @@ fn get_additional_os_resources_test() {
 
     let tx_type = TransactionType::InvokeFunction;
 
-    let additional_os_resources = get_additional_os_resources(syscall_counter, &tx_type).unwrap();
+    let additional_os_resources = get_additional_os_resources(
+        syscall_counter,
+        &tx_type,
+        SyscallCosts::for_version(Default::default()),
+    )
+    .unwrap();
     let expected_additional_os_resources = ExecutionResources {
         n_steps: 3990,
         n_memory_holes: 68,
@@ -0,0 +1,267 @@
+//! Per-class choice of the execution backend.
+//!
+//! A class with a sierra program can run either on the VM or on Cairo Native. When a native
+//! backend is configured, an [`ExecutionPolicy`] decides for each call whether its class actually
+//! runs natively, so that classes which are known to misbehave, are too big to compile quickly or
+//! are rarely called keep running on the VM.
+
+use crate::{execution::backend::ExecutionBackend, transaction::ClassHash};
+use cairo_lang_sierra::program::Program as SierraProgram;
+#[cfg(feature = "metrics")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Mutex, RwLock},
+};
+
+/// Libfuncs of the syscalls added after Starknet 0.13.1, which the pinned version of Cairo Native
+/// doesn't support.
+const UNSUPPORTED_NATIVE_LIBFUNCS: [&str; 2] =
+    ["sha256_process_block_syscall", "get_class_hash_at_syscall"];
+
+// --------------------
+// ExecutionPolicy structure
+// --------------------
+
+/// Decides which classes run natively when a native backend is configured.
+///
+/// By default every class runs natively, falling back to the VM when its program fails to
+/// compile. The classes using the `sha256_process_block` or `get_class_hash_at` syscalls always
+/// run on the VM, as Cairo Native doesn't support them yet.
+#[derive(Debug)]
+pub struct ExecutionPolicy {
+    /// When set, only these classes run natively.
+    allow_list: Option<HashSet<ClassHash>>,
+    /// Classes which always run on the VM.
+    deny_list: HashSet<ClassHash>,
+    /// Classes whose sierra program has more statements run on the VM.
+    max_program_size: Option<usize>,
+    /// Number of calls to a class run on the VM before it's compiled.
+    warm_up_calls: usize,
+    fallback_to_vm: bool,
+    calls: Mutex<HashMap<ClassHash, usize>>,
+    /// Classes which failed to compile, and run on the VM from then on.
+    failed: RwLock<HashSet<ClassHash>>,
+}
+
+impl ExecutionPolicy {
+    /// Only runs the classes of `allow_list` natively.
+    pub fn with_allow_list(mut self, allow_list: impl IntoIterator<Item = ClassHash>) -> Self {
+        self.allow_list = Some(allow_list.into_iter().collect());
+        self
+    }
+
+    /// Always runs the classes of `deny_list` on the VM, even if they're allowed.
+    pub fn with_deny_list(mut self, deny_list: impl IntoIterator<Item = ClassHash>) -> Self {
+        self.deny_list = deny_list.into_iter().collect();
+        self
+    }
+
+    /// Runs the classes whose sierra program has more than `max_program_size` statements on the
+    /// VM.
+    pub fn with_max_program_size(mut self, max_program_size: usize) -> Self {
+        self.max_program_size = Some(max_program_size);
+        self
+    }
+
+    /// Runs the first `warm_up_calls` calls to each class on the VM, so that only the classes
+    /// called often enough pay for their compilation.
+    pub fn with_warm_up_calls(mut self, warm_up_calls: usize) -> Self {
+        self.warm_up_calls = warm_up_calls;
+        self
+    }
+
+    /// Sets whether the classes failing to compile run on the VM instead, or fail the call.
+    pub fn with_fallback_to_vm(mut self, fallback_to_vm: bool) -> Self {
+        self.fallback_to_vm = fallback_to_vm;
+        self
+    }
+
+    pub fn fallback_to_vm(&self) -> bool {
+        self.fallback_to_vm
+    }
+
+    /// Returns whether a call to `class_hash`, whose sierra program is `program`, runs natively.
+    ///
+    /// Each call counts towards the warm-up of the class, so this must be called once per call.
+    pub fn runs_natively(&self, class_hash: &ClassHash, program: &SierraProgram) -> bool {
+        if uses_unsupported_libfuncs(program)
+            || self.deny_list.contains(class_hash)
+            || self.failed.read().unwrap().contains(class_hash)
+            || !self
+                .allow_list
+                .as_ref()
+                .map_or(true, |allow_list| allow_list.contains(class_hash))
+            || self
+                .max_program_size
+                .is_some_and(|max_program_size| program.statements.len() > max_program_size)
+        {
+            return false;
+        }
+
+        if self.warm_up_calls == 0 {
+            return true;
+        }
+        let mut calls = self.calls.lock().unwrap();
+        let calls = calls.entry(*class_hash).or_default();
+        *calls = calls.saturating_add(1);
+        *calls > self.warm_up_calls
+    }
+
+    /// Runs `class_hash` on the VM from now on, after its program failed to compile.
+    pub(crate) fn record_compilation_failure(&self, class_hash: ClassHash) {
+        self.failed.write().unwrap().insert(class_hash);
+    }
+}
+
+/// Returns whether `program` declares a libfunc which Cairo Native can't run.
+fn uses_unsupported_libfuncs(program: &SierraProgram) -> bool {
+    program.libfunc_declarations.iter().any(|declaration| {
+        UNSUPPORTED_NATIVE_LIBFUNCS.contains(&declaration.long_id.generic_id.0.as_str())
+    })
+}
+
+impl Default for ExecutionPolicy {
+    fn default() -> Self {
+        Self {
+            allow_list: None,
+            deny_list: HashSet::new(),
+            max_program_size: None,
+            warm_up_calls: 0,
+            fallback_to_vm: true,
+            calls: Mutex::new(HashMap::new()),
+            failed: RwLock::new(HashSet::new()),
+        }
+    }
+}
+
+// --------------------
+// BackendMetrics structure
+// --------------------
+
+/// Counts the calls executed by each backend. The counters are only kept with the `metrics`
+/// feature.
+#[derive(Debug, Default)]
+pub struct BackendMetrics {
+    #[cfg(feature = "metrics")]
+    vm_calls: AtomicUsize,
+    #[cfg(feature = "metrics")]
+    native_calls: AtomicUsize,
+    #[cfg(feature = "metrics")]
+    native_fallbacks: AtomicUsize,
+}
+
+#[cfg(feature = "metrics")]
+impl BackendMetrics {
+    pub fn vm_calls(&self) -> usize {
+        self.vm_calls.load(Ordering::Relaxed)
+    }
+
+    pub fn native_calls(&self) -> usize {
+        self.native_calls.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of calls which ran on the VM after their class failed to compile.
+    pub fn native_fallbacks(&self) -> usize {
+        self.native_fallbacks.load(Ordering::Relaxed)
+    }
+
+    #[inline(always)]
+    pub(crate) fn add_call(&self, backend: ExecutionBackend) {
+        if backend.is_native() {
+            self.native_calls.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.vm_calls.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn add_fallback(&self) {
+        self.native_fallbacks.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+#[cfg(not(feature = "metrics"))]
+impl BackendMetrics {
+    #[inline(always)]
+    pub(crate) fn add_call(&self, _backend: ExecutionBackend) {
+        // does nothing
+    }
+
+    #[inline(always)]
+    pub(crate) fn add_fallback(&self) {
+        // does nothing
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cairo_lang_sierra::{
+        ids::{ConcreteLibfuncId, GenericLibfuncId},
+        program::{ConcreteLibfuncLongId, LibfuncDeclaration},
+    };
+
+    fn program(n_statements: usize) -> SierraProgram {
+        SierraProgram {
+            type_declarations: Vec::new(),
+            libfunc_declarations: Vec::new(),
+            statements: vec![
+                cairo_lang_sierra::program::Statement::Return(Vec::new());
+                n_statements
+            ],
+            funcs: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn select_classes_running_natively() {
+        let allowed = ClassHash([1; 32]);
+        let denied = ClassHash([2; 32]);
+        let other = ClassHash([3; 32]);
+
+        let policy = ExecutionPolicy::default();
+        assert!(policy.runs_natively(&other, &program(0)));
+
+        let policy = ExecutionPolicy::default()
+            .with_allow_list([allowed, denied])
+            .with_deny_list([denied])
+            .with_max_program_size(10);
+        assert!(policy.runs_natively(&allowed, &program(10)));
+        assert!(!policy.runs_natively(&allowed, &program(11)));
+        assert!(!policy.runs_natively(&denied, &program(0)));
+        assert!(!policy.runs_natively(&other, &program(0)));
+
+        policy.record_compilation_failure(allowed);
+        assert!(!policy.runs_natively(&allowed, &program(0)));
+    }
+
+    #[test]
+    fn run_unsupported_syscalls_on_the_vm() {
+        let class_hash = ClassHash([1; 32]);
+        let policy = ExecutionPolicy::default();
+
+        for libfunc in UNSUPPORTED_NATIVE_LIBFUNCS {
+            let mut program = program(0);
+            program.libfunc_declarations.push(LibfuncDeclaration {
+                id: ConcreteLibfuncId::new(0),
+                long_id: ConcreteLibfuncLongId {
+                    generic_id: GenericLibfuncId::from_string(libfunc),
+                    generic_args: Vec::new(),
+                },
+            });
+            assert!(!policy.runs_natively(&class_hash, &program));
+        }
+    }
+
+    #[test]
+    fn run_on_the_vm_while_warming_up() {
+        let class_hash = ClassHash([1; 32]);
+        let policy = ExecutionPolicy::default().with_warm_up_calls(2);
+
+        assert!(!policy.runs_natively(&class_hash, &program(0)));
+        assert!(!policy.runs_natively(&class_hash, &program(0)));
+        assert!(policy.runs_natively(&class_hash, &program(0)));
+        assert!(!policy.runs_natively(&ClassHash([2; 32]), &program(0)));
+    }
+}
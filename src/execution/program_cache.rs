@@ -0,0 +1,426 @@
+//! Cache of the programs compiled by Cairo Native.
+//!
+//! The executors of Cairo Native can't leave the thread that created them, so a
+//! [`NativeProgramCache`] keeps the executors of each thread apart, while the set of cached classes
+//! is shared by all of them. The cache holds up to a fixed number of classes: when a new class
+//! doesn't fit, the least recently used one is evicted and every thread drops its executor the
+//! next time it uses the cache.
+//!
+//! Classes compiled ahead of time are compiled once into a shared library stored in an
+//! [`AotDiskCache`], which every thread then loads into its own executor. The disk cache is a
+//! temporary directory unless one is given, in which case the classes are only compiled once
+//! across restarts. Classes compiled just in time live in the memory of the thread that compiled
+//! them, so they're compiled by each thread running them.
+
+use crate::{
+    execution::aot_cache::AotDiskCache,
+    transaction::{error::TransactionError, ClassHash},
+    utils::get_native_context,
+};
+use cairo_lang_sierra::program::Program as SierraProgram;
+use cairo_native::{
+    cache::{AotProgramCache, JitProgramCache},
+    executor::{AotNativeExecutor, JitNativeExecutor, NativeExecutor},
+    OptLevel,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, PoisonError, Weak,
+    },
+};
+
+/// Number of classes kept by [`NativeProgramCache::default`].
+pub const DEFAULT_PROGRAM_CACHE_CAPACITY: usize = 256;
+
+static NEXT_CACHE_ID: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// The executors compiled by the current thread, for each cache.
+    static THREAD_EXECUTORS: RefCell<HashMap<usize, ThreadExecutors>> =
+        RefCell::new(HashMap::new());
+}
+
+/// How the cached programs are compiled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NativeCompilation {
+    /// Just in time, into memory.
+    Jit,
+    /// Ahead of time, into a shared library.
+    Aot,
+}
+
+// --------------------
+// NativeProgramCache structure
+// --------------------
+
+/// A bounded cache of programs compiled by Cairo Native, which can be shared between threads.
+///
+/// Cloning the cache is cheap, and the clones share the same classes.
+#[derive(Clone, Debug)]
+pub struct NativeProgramCache {
+    inner: Arc<SharedIndex>,
+}
+
+#[derive(Debug)]
+struct SharedIndex {
+    /// Identifies the executors of the cache in each thread.
+    id: usize,
+    compilation: NativeCompilation,
+    capacity: usize,
+    disk_cache: Option<AotDiskCache>,
+    /// Directory of the disk cache created by the cache itself, removed when it's dropped.
+    temp_dir: Option<PathBuf>,
+    /// Serializes the compilations of each class, so that it's only compiled by one thread.
+    compile_locks: Mutex<HashMap<ClassHash, Arc<Mutex<()>>>>,
+    entries: Mutex<Entries>,
+}
+
+#[derive(Debug, Default)]
+struct Entries {
+    /// The tick of the last use of each cached class.
+    last_used: HashMap<ClassHash, u64>,
+    tick: u64,
+    /// Number of classes evicted so far, used by the threads to know when to drop executors.
+    evictions: u64,
+}
+
+#[derive(Default)]
+struct ThreadExecutors {
+    /// The cache the executors belong to, which may have been dropped by another thread.
+    cache: Weak<SharedIndex>,
+    evictions: u64,
+    executors: HashMap<ClassHash, CachedExecutor>,
+}
+
+#[derive(Clone)]
+enum CachedExecutor {
+    Jit(Rc<JitNativeExecutor<'static>>),
+    Aot(Rc<AotNativeExecutor>),
+}
+
+impl NativeProgramCache {
+    /// Creates a cache holding up to `capacity` classes, compiled with `compilation`.
+    ///
+    /// Programs are compiled with the global native context. Classes compiled ahead of time are
+    /// stored in a temporary directory, removed when the cache is dropped.
+    pub fn new(compilation: NativeCompilation, capacity: usize) -> Self {
+        let id = NEXT_CACHE_ID.fetch_add(1, Ordering::Relaxed);
+        let temp_dir = (compilation == NativeCompilation::Aot).then(|| {
+            std::env::temp_dir().join(format!("cairo-native-{}-{id}", std::process::id()))
+        });
+        let disk_cache = temp_dir.as_ref().and_then(|dir| {
+            AotDiskCache::new(dir, OptLevel::Default)
+                .map_err(|e| tracing::warn!("Compiling classes in every thread: {e}"))
+                .ok()
+        });
+
+        Self::with_index(id, compilation, capacity, disk_cache, temp_dir)
+    }
+
+    /// Creates a cache holding up to `capacity` classes compiled ahead of time, which are loaded
+    /// from `disk_cache`, or compiled and stored in it if they're missing.
+    pub fn with_disk_cache(capacity: usize, disk_cache: AotDiskCache) -> Self {
+        Self::with_index(
+            NEXT_CACHE_ID.fetch_add(1, Ordering::Relaxed),
+            NativeCompilation::Aot,
+            capacity,
+            Some(disk_cache),
+            None,
+        )
+    }
+
+    fn with_index(
+        id: usize,
+        compilation: NativeCompilation,
+        capacity: usize,
+        disk_cache: Option<AotDiskCache>,
+        temp_dir: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            inner: Arc::new(SharedIndex {
+                id,
+                compilation,
+                capacity: capacity.max(1),
+                disk_cache,
+                temp_dir,
+                compile_locks: Mutex::new(HashMap::new()),
+                entries: Mutex::new(Entries::default()),
+            }),
+        }
+    }
+
+    pub fn compilation(&self) -> NativeCompilation {
+        self.inner.compilation
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity
+    }
+
+    /// Returns the disk cache storing the classes compiled ahead of time.
+    pub fn disk_cache(&self) -> Option<&AotDiskCache> {
+        self.inner.disk_cache.as_ref()
+    }
+
+    /// Returns the number of classes in the cache.
+    pub fn len(&self) -> usize {
+        self.inner.entries.lock().unwrap().last_used.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, class_hash: &ClassHash) -> bool {
+        self.inner
+            .entries
+            .lock()
+            .unwrap()
+            .last_used
+            .contains_key(class_hash)
+    }
+
+    /// Evicts every class from the cache.
+    pub fn clear(&self) {
+        let mut entries = self.inner.entries.lock().unwrap();
+        entries.evictions += entries.last_used.len() as u64;
+        entries.last_used.clear();
+    }
+
+    /// Returns the executor of `class_hash` for the current thread, compiling `program` if the
+    /// thread doesn't have it yet.
+    pub(crate) fn get_or_compile(
+        &self,
+        class_hash: &ClassHash,
+        program: &SierraProgram,
+    ) -> Result<NativeExecutor<'static>, TransactionError> {
+        let evictions = self.touch(class_hash);
+
+        let executor = self.with_thread_executors(|thread_executors| {
+            // Drop the executors of the classes evicted since the thread last used the cache.
+            if thread_executors.evictions != evictions {
+                let entries = self.inner.entries.lock().unwrap();
+                thread_executors
+                    .executors
+                    .retain(|class_hash, _| entries.last_used.contains_key(class_hash));
+                thread_executors.evictions = evictions;
+            }
+
+            if let Some(executor) = thread_executors.executors.get(class_hash) {
+                return Ok(executor.clone());
+            }
+
+            let executor = self.compile(class_hash, program).map_err(|e| {
+                self.inner
+                    .entries
+                    .lock()
+                    .unwrap()
+                    .last_used
+                    .remove(class_hash);
+                e
+            })?;
+            thread_executors
+                .executors
+                .insert(*class_hash, executor.clone());
+            Ok(executor)
+        })?;
+
+        Ok(match executor {
+            CachedExecutor::Jit(executor) => NativeExecutor::Jit(executor),
+            CachedExecutor::Aot(executor) => NativeExecutor::Aot(executor),
+        })
+    }
+
+    /// Calls `f` with the executors of the current thread, dropping first those of the caches
+    /// dropped from other threads, which can't drop them themselves.
+    fn with_thread_executors<R>(&self, f: impl FnOnce(&mut ThreadExecutors) -> R) -> R {
+        THREAD_EXECUTORS.with(|thread_executors| {
+            let mut thread_executors = thread_executors.borrow_mut();
+            thread_executors.retain(|_, executors| executors.cache.strong_count() > 0);
+            f(thread_executors
+                .entry(self.inner.id)
+                .or_insert_with(|| ThreadExecutors {
+                    cache: Arc::downgrade(&self.inner),
+                    ..Default::default()
+                }))
+        })
+    }
+
+    /// Marks `class_hash` as used, evicting the least recently used classes if the cache is full.
+    ///
+    /// Returns the number of evictions after the update.
+    fn touch(&self, class_hash: &ClassHash) -> u64 {
+        let mut entries = self.inner.entries.lock().unwrap();
+        entries.tick += 1;
+        let tick = entries.tick;
+        entries.last_used.insert(*class_hash, tick);
+
+        while entries.last_used.len() > self.inner.capacity {
+            let least_recently_used = entries
+                .last_used
+                .iter()
+                .min_by_key(|(_, last_used)| **last_used)
+                .map(|(class_hash, _)| *class_hash)
+                .unwrap();
+            entries.last_used.remove(&least_recently_used);
+            entries.evictions += 1;
+            self.inner
+                .compile_locks
+                .lock()
+                .unwrap()
+                .remove(&least_recently_used);
+        }
+
+        entries.evictions
+    }
+
+    /// Compiles `program`, turning the panics of Cairo Native on programs it can't compile into
+    /// errors.
+    fn compile(
+        &self,
+        class_hash: &ClassHash,
+        program: &SierraProgram,
+    ) -> Result<CachedExecutor, TransactionError> {
+        if let (NativeCompilation::Aot, Some(disk_cache)) =
+            (self.inner.compilation, &self.inner.disk_cache)
+        {
+            // The threads running the class while it's compiled wait to load its library.
+            let compile_lock = self
+                .inner
+                .compile_locks
+                .lock()
+                .unwrap()
+                .entry(*class_hash)
+                .or_default()
+                .clone();
+            let _compiling = compile_lock.lock().unwrap_or_else(PoisonError::into_inner);
+            match disk_cache.get_or_compile(class_hash, program) {
+                Ok(executor) => return Ok(CachedExecutor::Aot(Rc::new(executor))),
+                Err(e) => tracing::warn!("Compiling class {class_hash} in memory: {e}"),
+            }
+        }
+
+        panic::catch_unwind(AssertUnwindSafe(|| match self.inner.compilation {
+            NativeCompilation::Jit => CachedExecutor::Jit(
+                JitProgramCache::new(get_native_context()).compile_and_insert(
+                    *class_hash,
+                    program,
+                    OptLevel::Default,
+                ),
+            ),
+            NativeCompilation::Aot => CachedExecutor::Aot(
+                AotProgramCache::new(get_native_context()).compile_and_insert(
+                    *class_hash,
+                    program,
+                    OptLevel::Default,
+                ),
+            ),
+        }))
+        .map_err(|e| {
+            let message = e
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| e.downcast_ref::<&str>().map(|message| message.to_string()))
+                .unwrap_or_default();
+            TransactionError::NativeCompilation(*class_hash, message)
+        })
+    }
+}
+
+impl Default for NativeProgramCache {
+    fn default() -> Self {
+        Self::new(NativeCompilation::Jit, DEFAULT_PROGRAM_CACHE_CAPACITY)
+    }
+}
+
+impl Drop for SharedIndex {
+    fn drop(&mut self) {
+        // Other threads drop their executors the next time they use any cache, or when they exit.
+        let _ = THREAD_EXECUTORS.try_with(|thread_executors| {
+            thread_executors.borrow_mut().remove(&self.id);
+        });
+
+        if let Some(temp_dir) = &self.temp_dir {
+            match std::fs::remove_dir_all(temp_dir) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    tracing::warn!("Failed to remove {}: {e}", temp_dir.display())
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions_sorted::assert_eq;
+
+    #[test]
+    fn evict_least_recently_used_classes() {
+        let cache = NativeProgramCache::new(NativeCompilation::Jit, 2);
+        let class_hashes = [ClassHash([1; 32]), ClassHash([2; 32]), ClassHash([3; 32])];
+
+        assert_eq!(cache.touch(&class_hashes[0]), 0);
+        assert_eq!(cache.touch(&class_hashes[1]), 0);
+        assert_eq!(cache.touch(&class_hashes[0]), 0);
+        assert_eq!(cache.touch(&class_hashes[2]), 1);
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.contains(&class_hashes[0]));
+        assert!(!cache.contains(&class_hashes[1]));
+        assert!(cache.contains(&class_hashes[2]));
+
+        // Clones share the same classes.
+        cache.clone().clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn cache_is_shareable_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<NativeProgramCache>();
+
+        let cache = NativeProgramCache::new(NativeCompilation::Jit, 4);
+        std::thread::scope(|scope| {
+            for i in 0..4 {
+                let cache = cache.clone();
+                scope.spawn(move || cache.touch(&ClassHash([i; 32])));
+            }
+        });
+        assert_eq!(cache.len(), 4);
+    }
+
+    #[test]
+    fn executors_of_caches_dropped_by_other_threads_are_dropped() {
+        let cache = NativeProgramCache::new(NativeCompilation::Jit, 1);
+        let id = cache.inner.id;
+        cache.with_thread_executors(|_| ());
+
+        std::thread::spawn(move || drop(cache)).join().unwrap();
+        assert!(THREAD_EXECUTORS.with(|executors| executors.borrow().contains_key(&id)));
+
+        NativeProgramCache::new(NativeCompilation::Jit, 1).with_thread_executors(|_| ());
+        assert!(!THREAD_EXECUTORS.with(|executors| executors.borrow().contains_key(&id)));
+    }
+
+    #[test]
+    fn aot_libraries_are_stored_in_a_temporary_directory() {
+        let cache = NativeProgramCache::new(NativeCompilation::Aot, 1);
+        let dir = cache.disk_cache().unwrap().dir().to_path_buf();
+        assert!(dir.is_dir());
+
+        // Clones share the directory, which is removed along with the last one.
+        let clone = cache.clone();
+        drop(cache);
+        assert!(dir.is_dir());
+        drop(clone);
+        assert!(!dir.exists());
+    }
+}
@@ -0,0 +1,33 @@
+use super::{Event, TransactionExecutionInfo};
+use crate::transaction::error::TransactionError;
+use cairo_vm::Felt252;
+
+// --------------------
+// TransactionReceipt structure
+// --------------------
+
+/// The outcome of a transaction included in a block, along with the events it emitted in the
+/// order they were emitted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransactionReceipt {
+    pub transaction_hash: Felt252,
+    pub block_number: u64,
+    pub execution_info: TransactionExecutionInfo,
+    pub events: Vec<Event>,
+}
+
+impl TransactionReceipt {
+    pub fn new(
+        transaction_hash: Felt252,
+        block_number: u64,
+        execution_info: TransactionExecutionInfo,
+    ) -> Result<Self, TransactionError> {
+        let events = execution_info.get_sorted_events()?;
+        Ok(TransactionReceipt {
+            transaction_hash,
+            block_number,
+            execution_info,
+            events,
+        })
+    }
+}
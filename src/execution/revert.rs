@@ -0,0 +1,453 @@
+use crate::{
+    transaction::{error::TransactionError, Address, ClassHash},
+    utils::decode_byte_array,
+};
+use cairo_vm::{vm::errors::cairo_run_errors::CairoRunError, Felt252};
+use lazy_static::lazy_static;
+use std::fmt;
+
+lazy_static! {
+    /// Felt prepended to the panic data when a Cairo 1 contract panics with a `ByteArray`.
+    pub static ref BYTE_ARRAY_MAGIC: Felt252 = Felt252::from_hex(
+        "0x46a6158a16a947e5916b2a2ca68501a45e93d7110e81aa2d6438b1c57c879a3"
+    )
+    .unwrap();
+    /// Felt appended to the panic data by each call a Cairo 1 panic propagates through.
+    pub static ref ENTRYPOINT_FAILED: Felt252 = Felt252::from_bytes_be_slice(b"ENTRYPOINT_FAILED");
+}
+
+// --------------------
+// RevertError structure
+// --------------------
+
+/// Describes why an execution was reverted: the call stack leading to the failure, ordered from
+/// the outermost call to the one that failed, and the reason of the failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RevertError {
+    pub frames: Vec<ErrorFrame>,
+    pub reason: RevertReason,
+}
+
+impl RevertError {
+    /// Creates a revert error that didn't originate in an entry point call, such as a failed fee
+    /// check.
+    pub fn new(reason: RevertReason) -> Self {
+        RevertError {
+            frames: Vec::new(),
+            reason,
+        }
+    }
+
+    /// Creates a revert error from the error that made the given call fail.
+    pub(crate) fn from_error(frame: ErrorFrame, error: &TransactionError) -> Self {
+        let reason = match error {
+            TransactionError::CairoRunner(CairoRunError::VmException(exception)) => {
+                RevertReason::Error(exception.inner_exc.to_string())
+            }
+            error => RevertReason::Error(error.to_string()),
+        };
+
+        RevertError {
+            frames: vec![frame],
+            reason,
+        }
+    }
+
+    /// Creates a revert error for a call that panicked with the given panic data.
+    pub(crate) fn from_panic(frame: ErrorFrame, panic_data: Vec<Felt252>) -> Self {
+        RevertError {
+            frames: vec![frame],
+            reason: RevertReason::Panic(panic_data),
+        }
+    }
+
+    /// Adds a calling frame on top of the call stack.
+    pub(crate) fn push_caller(&mut self, frame: ErrorFrame) {
+        self.frames.insert(0, frame);
+    }
+}
+
+impl From<&str> for RevertError {
+    fn from(message: &str) -> Self {
+        RevertError::new(RevertReason::Error(message.to_string()))
+    }
+}
+
+impl From<String> for RevertError {
+    fn from(message: String) -> Self {
+        RevertError::new(RevertReason::Error(message))
+    }
+}
+
+impl fmt::Display for RevertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.frames.is_empty() {
+            return write!(f, "{}", self.reason);
+        }
+
+        writeln!(f, "Transaction execution has failed:")?;
+        for (index, frame) in self.frames.iter().enumerate() {
+            writeln!(f, "{index}: {frame}")?;
+        }
+        write!(f, "{}", self.reason)
+    }
+}
+
+// --------------------
+// ErrorFrame structure
+// --------------------
+
+/// A call in the stack of a failed execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ErrorFrame {
+    pub contract_address: Address,
+    pub class_hash: Option<ClassHash>,
+    pub entry_point_selector: Felt252,
+    /// Location where the call failed, if it was running on the VM.
+    pub location: Option<VmErrorLocation>,
+}
+
+impl ErrorFrame {
+    pub(crate) fn new(
+        contract_address: Address,
+        class_hash: Option<ClassHash>,
+        entry_point_selector: Felt252,
+        error: Option<&TransactionError>,
+    ) -> Self {
+        let location = match error {
+            Some(TransactionError::CairoRunner(CairoRunError::VmException(exception))) => {
+                Some(VmErrorLocation {
+                    pc: exception.pc.to_string(),
+                    traceback: exception.traceback.clone(),
+                })
+            }
+            _ => None,
+        };
+
+        ErrorFrame {
+            contract_address,
+            class_hash,
+            entry_point_selector,
+            location,
+        }
+    }
+}
+
+impl fmt::Display for ErrorFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Error in the called contract (contract address: {:#x}, class hash: ",
+            self.contract_address.0
+        )?;
+        match &self.class_hash {
+            Some(class_hash) => write!(f, "{class_hash}")?,
+            None => write!(f, "_")?,
+        }
+        writeln!(f, ", selector: {:#x}):", self.entry_point_selector)?;
+
+        if let Some(location) = &self.location {
+            writeln!(f, "Error at pc={}:", location.pc)?;
+            if let Some(traceback) = &location.traceback {
+                write!(f, "{traceback}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Position of the VM when a call failed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VmErrorLocation {
+    /// Program counter of the failing instruction, as reported by the VM.
+    pub pc: String,
+    /// Cairo traceback of the failure, when available.
+    pub traceback: Option<String>,
+}
+
+// --------------------
+// RevertReason structure
+// --------------------
+
+/// The cause of a reverted execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RevertReason {
+    /// A Cairo 1 entry point panicked with the given data.
+    Panic(Vec<Felt252>),
+    /// The execution failed with the given error.
+    Error(String),
+}
+
+impl fmt::Display for RevertReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevertReason::Panic(panic_data) => write!(
+                f,
+                "Execution failed. Failure reason: {}.",
+                format_panic_data(panic_data)
+            ),
+            RevertReason::Error(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+// --------------------
+// Panic data decoding
+// --------------------
+
+/// Formats Cairo 1 panic data the same way the sequencer does: a `ByteArray` is shown as a
+/// quoted string, while felts are shown in hex followed by their short string, if any.
+pub fn format_panic_data(panic_data: &[Felt252]) -> String {
+    if let Some(message) = decode_byte_array_panic(panic_data) {
+        return format!("\"{message}\"");
+    }
+
+    match panic_data {
+        [felt] => format_panic_felt(felt),
+        felts => format!(
+            "({})",
+            felts
+                .iter()
+                .map(format_panic_felt)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Formats a panic data felt as hex, followed by its short string representation if it has one.
+fn format_panic_felt(felt: &Felt252) -> String {
+    match decode_short_string(felt) {
+        Some(short_string) => format!("{felt:#x} ('{short_string}')"),
+        None => format!("{felt:#x}"),
+    }
+}
+
+/// Decodes a felt as a Cairo short string. Returns `None` if it isn't a non-empty string of
+/// printable ASCII characters.
+pub fn decode_short_string(felt: &Felt252) -> Option<String> {
+    let bytes = felt.to_bytes_be();
+    let bytes: Vec<u8> = bytes
+        .iter()
+        .copied()
+        .skip_while(|byte| *byte == 0)
+        .collect();
+
+    if bytes.is_empty()
+        || !bytes
+            .iter()
+            .all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+    {
+        return None;
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+/// Decodes the panic data of a `panic!` with a `ByteArray` message, which consists of the
+/// [`BYTE_ARRAY_MAGIC`] followed by the serialized `ByteArray`.
+pub fn decode_byte_array_panic(panic_data: &[Felt252]) -> Option<String> {
+    match panic_data.split_first() {
+        Some((magic, serialized)) if magic == &*BYTE_ARRAY_MAGIC => decode_byte_array(serialized),
+        _ => None,
+    }
+}
+
+// --------------------
+// PanicError structure
+// --------------------
+
+/// Human-readable error decoded from the panic data of a failed Cairo 1 call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PanicError {
+    /// Number of calls the panic propagated through, each of which appended an
+    /// `ENTRYPOINT_FAILED` to the panic data.
+    pub n_failed_calls: usize,
+    /// Panic data of the innermost panic, without the `ENTRYPOINT_FAILED` felts appended by the
+    /// calls it propagated through.
+    pub panic_data: Vec<Felt252>,
+}
+
+impl PanicError {
+    /// Splits the panic data of a failed call into the innermost panic and the calls it
+    /// propagated through.
+    pub fn from_panic_data(panic_data: &[Felt252]) -> Self {
+        let n_failed_calls = panic_data
+            .iter()
+            .rev()
+            .take_while(|felt| **felt == *ENTRYPOINT_FAILED)
+            .count();
+
+        PanicError {
+            n_failed_calls,
+            panic_data: panic_data[..panic_data.len() - n_failed_calls].to_vec(),
+        }
+    }
+
+    /// Decodes the innermost panic into its messages: a `ByteArray` is decoded as a single
+    /// message, while each felt is decoded as its own message, as a short string when possible
+    /// and in hex otherwise.
+    pub fn message(&self) -> Vec<String> {
+        match decode_byte_array_panic(&self.panic_data) {
+            Some(message) => vec![message],
+            None => self
+                .panic_data
+                .iter()
+                .map(|felt| decode_short_string(felt).unwrap_or_else(|| format!("{felt:#x}")))
+                .collect(),
+        }
+    }
+
+    /// Returns the innermost panic formatted by [`format_panic_data`], if the panic data had one.
+    pub fn reason(&self) -> Option<String> {
+        (!self.panic_data.is_empty()).then(|| format_panic_data(&self.panic_data))
+    }
+}
+
+impl fmt::Display for PanicError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut chain = vec!["ENTRYPOINT_FAILED".to_string(); self.n_failed_calls];
+        chain.extend(self.reason());
+        write!(f, "{}", chain.join(": "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_short_string_panic() {
+        let panic_data = vec![Felt252::from_bytes_be_slice(b"u256_sub Overflow")];
+
+        assert_eq!(
+            RevertReason::Panic(panic_data).to_string(),
+            "Execution failed. Failure reason: 0x753235365f737562204f766572666c6f77 ('u256_sub Overflow')."
+        );
+    }
+
+    #[test]
+    fn format_multiple_felts_panic() {
+        let panic_data = vec![
+            Felt252::from_bytes_be_slice(b"panic"),
+            Felt252::from(0xffff),
+            Felt252::from_bytes_be_slice(b"ENTRYPOINT_FAILED"),
+        ];
+
+        assert_eq!(
+            format_panic_data(&panic_data),
+            "(0x70616e6963 ('panic'), 0xffff, 0x454e545259504f494e545f4641494c4544 ('ENTRYPOINT_FAILED'))"
+        );
+    }
+
+    #[test]
+    fn format_byte_array_panic() {
+        let message = "This message is longer than thirty one bytes!";
+        let panic_data = vec![
+            *BYTE_ARRAY_MAGIC,
+            Felt252::ONE,
+            Felt252::from_bytes_be_slice(&message.as_bytes()[..31]),
+            Felt252::from_bytes_be_slice(&message.as_bytes()[31..]),
+            Felt252::from(message.len() - 31),
+        ];
+
+        assert_eq!(format_panic_data(&panic_data), format!("\"{message}\""));
+    }
+
+    #[test]
+    fn display_revert_error_with_frames() {
+        let mut revert_error = RevertError::from_panic(
+            ErrorFrame::new(Address(2.into()), Some(ClassHash([3; 32])), 4.into(), None),
+            vec![Felt252::from_bytes_be_slice(b"panic")],
+        );
+        revert_error.push_caller(ErrorFrame::new(Address(1.into()), None, 5.into(), None));
+
+        assert_eq!(
+            revert_error.to_string(),
+            format!(
+                "Transaction execution has failed:\n\
+                 0: Error in the called contract (contract address: 0x1, class hash: _, selector: 0x5):\n\n\
+                 1: Error in the called contract (contract address: 0x2, class hash: {}, selector: 0x4):\n\n\
+                 Execution failed. Failure reason: 0x70616e6963 ('panic').",
+                ClassHash([3; 32])
+            )
+        );
+    }
+
+    #[test]
+    fn decode_nested_panic_error() {
+        let panic_data = vec![
+            Felt252::from_bytes_be_slice(b"u256_sub Overflow"),
+            *ENTRYPOINT_FAILED,
+            *ENTRYPOINT_FAILED,
+        ];
+        let panic_error = PanicError::from_panic_data(&panic_data);
+
+        assert_eq!(panic_error.n_failed_calls, 2);
+        assert_eq!(panic_error.message(), vec!["u256_sub Overflow".to_string()]);
+        assert_eq!(
+            panic_error.to_string(),
+            "ENTRYPOINT_FAILED: ENTRYPOINT_FAILED: 0x753235365f737562204f766572666c6f77 ('u256_sub Overflow')"
+        );
+    }
+
+    #[test]
+    fn decode_multiple_felts_panic_error() {
+        let panic_data = vec![
+            Felt252::from_bytes_be_slice(b"panic"),
+            Felt252::from(0xffff),
+            *ENTRYPOINT_FAILED,
+        ];
+        let panic_error = PanicError::from_panic_data(&panic_data);
+
+        assert_eq!(panic_error.n_failed_calls, 1);
+        assert_eq!(
+            panic_error.message(),
+            vec!["panic".to_string(), "0xffff".to_string()]
+        );
+        assert_eq!(
+            panic_error.to_string(),
+            "ENTRYPOINT_FAILED: (0x70616e6963 ('panic'), 0xffff)"
+        );
+    }
+
+    #[test]
+    fn decode_byte_array_panic_error() {
+        let message = "Insufficient balance";
+        let panic_data = vec![
+            *BYTE_ARRAY_MAGIC,
+            Felt252::ZERO,
+            Felt252::from_bytes_be_slice(message.as_bytes()),
+            Felt252::from(message.len()),
+            *ENTRYPOINT_FAILED,
+        ];
+        let panic_error = PanicError::from_panic_data(&panic_data);
+
+        assert_eq!(panic_error.n_failed_calls, 1);
+        assert_eq!(panic_error.message(), vec![message.to_string()]);
+        assert_eq!(
+            panic_error.to_string(),
+            format!("ENTRYPOINT_FAILED: \"{message}\"")
+        );
+    }
+
+    #[test]
+    fn decode_byte_array_with_overflowing_length() {
+        let panic_data = [*BYTE_ARRAY_MAGIC, Felt252::from(u64::MAX), Felt252::ZERO];
+
+        assert_eq!(decode_byte_array_panic(&panic_data), None);
+        // The panic data is shown as plain felts instead.
+        let panic_error = PanicError::from_panic_data(&panic_data);
+        assert_eq!(panic_error.message().len(), panic_data.len());
+        assert_eq!(panic_error.to_string(), format_panic_data(&panic_data));
+    }
+
+    #[test]
+    fn decode_non_string_panic_error() {
+        let panic_error = PanicError::from_panic_data(&[Felt252::from(0x1234)]);
+
+        assert_eq!(panic_error.message(), vec!["0x1234".to_string()]);
+        assert_eq!(panic_error.reason(), Some("0x1234".to_string()));
+    }
+}
@@ -5,6 +5,7 @@
 use crate::{
     definitions::block_context::BlockContext,
     execution::{
+        backend::ExecutionConfig,
         execution_entry_point::{ExecutionEntryPoint, ExecutionResult},
         CallType, TransactionExecutionContext, TransactionExecutionInfo,
     },
@@ -35,17 +36,15 @@ pub use cairo_lang_starknet::{
     contract_class::ContractClass as SierraContractClass,
 };
 
-#[cfg(feature = "cairo-native")]
-use {
-    crate::transaction::ClassHash,
-    cairo_native::cache::ProgramCache,
-    std::{cell::RefCell, rc::Rc},
-};
-
+pub mod abi;
+pub mod cheats;
 pub mod core;
 pub mod definitions;
+#[cfg(feature = "devnet")]
+pub mod devnet;
 pub mod execution;
 pub mod hash_utils;
+pub mod mempool;
 pub mod parser_errors;
 pub mod runner;
 pub mod serde_structs;
@@ -67,9 +66,7 @@ pub fn simulate_transaction<S: StateReader, C: ContractClassCache>(
     skip_fee_transfer: bool,
     ignore_max_fee: bool,
     skip_nonce_check: bool,
-    #[cfg(feature = "cairo-native")] program_cache: Option<
-        Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-    >,
+    execution_config: &ExecutionConfig,
 ) -> Result<Vec<TransactionExecutionInfo>, TransactionError> {
     let mut cache_state = CachedState::new(Arc::new(state), contract_class_cache);
     let mut result = Vec::with_capacity(transactions.len());
@@ -85,8 +82,7 @@ pub fn simulate_transaction<S: StateReader, C: ContractClassCache>(
             &mut cache_state,
             block_context,
             remaining_gas,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
+            execution_config,
         )?;
         result.push(tx_result);
     }
@@ -99,9 +95,7 @@ pub fn estimate_fee<T, C>(
     transactions: &[Transaction],
     mut cached_state: CachedState<T, C>,
     block_context: &BlockContext,
-    #[cfg(feature = "cairo-native")] program_cache: Option<
-        Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-    >,
+    execution_config: &ExecutionConfig,
 ) -> Result<Vec<(u128, usize)>, TransactionError>
 where
     T: StateReader,
@@ -120,8 +114,7 @@ where
             &mut cached_state,
             block_context,
             100_000_000,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
+            execution_config,
         )?;
         if let Some(gas_usage) = transaction_result.actual_resources.get("l1_gas_usage") {
             result.push((transaction_result.actual_fee, *gas_usage));
@@ -129,7 +122,7 @@ where
             return Err(TransactionError::ResourcesError);
         };
 
-        cached_state.cache.update_initial_values();
+        cached_state.cache_mut().update_initial_values();
     }
 
     Ok(result)
@@ -142,9 +135,7 @@ pub fn call_contract<T: StateReader, C: ContractClassCache>(
     state: &mut CachedState<T, C>,
     block_context: BlockContext,
     caller_address: Address,
-    #[cfg(feature = "cairo-native")] program_cache: Option<
-        Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-    >,
+    execution_config: &ExecutionConfig,
 ) -> Result<Vec<Felt252>, TransactionError> {
     let contract_address = Address(contract_address);
     let class_hash = state.get_class_hash_at(&contract_address)?;
@@ -185,8 +176,7 @@ pub fn call_contract<T: StateReader, C: ContractClassCache>(
         &mut tx_execution_context,
         false,
         block_context.invoke_tx_max_n_steps,
-        #[cfg(feature = "cairo-native")]
-        program_cache,
+        execution_config,
     )?;
 
     let call_info = call_info.ok_or(TransactionError::CallInfoIsNone)?;
@@ -198,9 +188,7 @@ pub fn estimate_message_fee<T, C>(
     l1_handler: &L1Handler,
     mut cached_state: CachedState<T, C>,
     block_context: &BlockContext,
-    #[cfg(feature = "cairo-native")] program_cache: Option<
-        Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-    >,
+    execution_config: &ExecutionConfig,
 ) -> Result<(u128, usize), TransactionError>
 where
     T: StateReader,
@@ -214,8 +202,7 @@ where
         &mut cached_state,
         block_context,
         1_000_000,
-        #[cfg(feature = "cairo-native")]
-        program_cache,
+        execution_config,
     )?;
     let tx_fee = calculate_tx_fee(
         &transaction_result.actual_resources,
@@ -234,17 +221,9 @@ pub fn execute_transaction<S: StateReader, C: ContractClassCache>(
     state: &mut CachedState<S, C>,
     block_context: BlockContext,
     remaining_gas: u128,
-    #[cfg(feature = "cairo-native")] program_cache: Option<
-        Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-    >,
+    execution_config: &ExecutionConfig,
 ) -> Result<TransactionExecutionInfo, TransactionError> {
-    tx.execute(
-        state,
-        &block_context,
-        remaining_gas,
-        #[cfg(feature = "cairo-native")]
-        program_cache,
-    )
+    tx.execute(state, &block_context, remaining_gas, execution_config)
 }
 
 #[cfg(test)]
@@ -333,14 +312,8 @@ mod test {
         .unwrap();
         let transaction = Transaction::InvokeFunction(invoke_function);
 
-        let estimated_fee = estimate_fee(
-            &[transaction],
-            state,
-            &block_context,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
-        .unwrap();
+        let estimated_fee =
+            estimate_fee(&[transaction], state, &block_context, &Default::default()).unwrap();
         assert_eq!(estimated_fee[0], (1689, 1652));
     }
 
@@ -383,8 +356,7 @@ mod test {
             &mut state,
             BlockContext::default(),
             Address(0.into()),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -438,14 +410,8 @@ mod test {
         let mut block_context = BlockContext::default();
         block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
 
-        let estimated_fee = estimate_message_fee(
-            &l1_handler,
-            state,
-            &block_context,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
-        .unwrap();
+        let estimated_fee =
+            estimate_message_fee(&l1_handler, state, &block_context, &Default::default()).unwrap();
         assert_eq!(estimated_fee, (17690, 17675));
     }
 
@@ -506,8 +472,7 @@ mod test {
                 &block_context,
                 &mut ExecutionResourcesManager::default(),
                 1000000,
-                #[cfg(feature = "cairo-native")]
-                None,
+                &Default::default(),
             )
             .unwrap();
 
@@ -631,8 +596,7 @@ mod test {
             true,
             false,
             false,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -737,8 +701,7 @@ mod test {
             true,
             false,
             false,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -790,8 +753,7 @@ mod test {
             false,
             false,
             false,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     }
@@ -833,8 +795,7 @@ mod test {
             false,
             false,
             false,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     }
@@ -871,12 +832,7 @@ mod test {
         .unwrap();
 
         let _deploy_exec_info = deploy
-            .execute(
-                &mut state,
-                &block_context,
-                #[cfg(feature = "cairo-native")]
-                None,
-            )
+            .execute(&mut state, &block_context, &Default::default())
             .unwrap();
 
         let selector = *VALIDATE_ENTRY_POINT_SELECTOR;
@@ -907,8 +863,7 @@ mod test {
             false,
             false,
             false,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     }
@@ -956,8 +911,7 @@ mod test {
             false,
             false,
             false,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     }
@@ -1004,8 +958,7 @@ mod test {
             true,
             false,
             false,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     }
@@ -1073,8 +1026,7 @@ mod test {
             false,
             false, // won't have any effect
             false,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     }
@@ -1133,8 +1085,7 @@ mod test {
             false,
             false,
             false,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -1143,8 +1094,7 @@ mod test {
                 &[deploy, invoke_tx],
                 state,
                 block_context,
-                #[cfg(feature = "cairo-native")]
-                None,
+                &Default::default(),
             )
             .unwrap(),
             [(0, 1101), (0, 1652)]
@@ -1165,8 +1115,7 @@ mod test {
                 &mut state,
                 &block_context,
                 INITIAL_GAS_COST,
-                #[cfg(feature = "cairo-native")]
-                None,
+                &Default::default(),
             )
             .unwrap_err();
 
@@ -1275,8 +1224,7 @@ mod test {
             true,
             false,
             false,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap()[0]
             .actual_fee;
@@ -1292,8 +1240,7 @@ mod test {
             true,
             false,
             false,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap()[0]
             .actual_fee;
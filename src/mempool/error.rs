@@ -0,0 +1,29 @@
+use crate::{core::errors::state_errors::StateError, transaction::Address};
+use cairo_vm::Felt252;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum MempoolError {
+    #[error("{0} transactions can't be added to the mempool")]
+    UnsupportedTransaction(String),
+    #[error("Transaction {0:#x} is already in the mempool")]
+    DuplicateTransaction(Felt252),
+    #[error("Nonce {nonce} of account {address} is too low. Expected at least {expected}")]
+    NonceTooLow {
+        address: Address,
+        nonce: Felt252,
+        expected: Felt252,
+    },
+    #[error("Nonce {nonce} of account {address} is too far ahead of the account nonce {expected}")]
+    NonceTooHigh {
+        address: Address,
+        nonce: Felt252,
+        expected: Felt252,
+    },
+    #[error("Replacement of nonce {nonce} of account {address} is underpriced")]
+    ReplacementUnderpriced { address: Address, nonce: Felt252 },
+    #[error("Mempool is full and the transaction's fee is too low to evict another one")]
+    MempoolFull,
+    #[error(transparent)]
+    State(#[from] StateError),
+}
@@ -0,0 +1,781 @@
+//! In-memory transaction pool for local sequencers.
+//!
+//! A [`Mempool`] keeps the pending transactions of each account ordered by nonce. A transaction is
+//! ready once every lower nonce of its account has been executed or handed out, and
+//! [`Mempool::pop_batch`] hands out ready transactions by decreasing [`FeePriority`]. After each
+//! block, [`Mempool::update_nonces`] reads the account nonces from the state to drop the
+//! transactions made stale by the block.
+//!
+//! The mempool doesn't validate transactions: run
+//! [`Transaction::validate_stateless`](crate::transaction::Transaction::validate_stateless) before
+//! adding them.
+
+pub mod error;
+
+use self::error::MempoolError;
+use crate::{
+    state::state_api::StateReader,
+    transaction::{Address, Transaction, VersionSpecificAccountTxFields},
+};
+use cairo_vm::Felt252;
+use num_traits::ToPrimitive;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BinaryHeap, HashMap},
+};
+
+// --------------------
+// MempoolConfig structure
+// --------------------
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MempoolConfig {
+    /// Maximum number of transactions held by the mempool.
+    pub max_size: usize,
+    /// Maximum distance between the nonce of a transaction and the next nonce of its account.
+    pub max_nonce_gap: u64,
+    /// Minimum increase, in percent, of both the tip and the max price of a transaction replacing
+    /// another one with the same nonce.
+    pub replacement_fee_bump_percent: u128,
+    /// Number of seconds a transaction can stay in the mempool, if limited.
+    pub tx_ttl: Option<u64>,
+    /// L1 gas assumed to be used by transactions older than v3, whose max fee is divided by it to
+    /// compare them with the max price per unit of v3 transactions.
+    pub deprecated_tx_gas_estimate: u128,
+}
+
+impl Default for MempoolConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 10_000,
+            max_nonce_gap: 64,
+            replacement_fee_bump_percent: 10,
+            tx_ttl: Some(3600),
+            deprecated_tx_gas_estimate: 10_000,
+        }
+    }
+}
+
+// --------------------
+// FeePriority structure
+// --------------------
+
+/// Order of transactions of different accounts: by tip, then by max price.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FeePriority {
+    pub tip: u64,
+    /// The max price per unit of L1 gas. For transactions older than v3, which only have a max
+    /// fee, it's estimated by dividing the max fee by `deprecated_tx_gas_estimate`.
+    pub max_price: u128,
+}
+
+impl FeePriority {
+    pub fn new(
+        account_tx_fields: &VersionSpecificAccountTxFields,
+        deprecated_tx_gas_estimate: u128,
+    ) -> Self {
+        match account_tx_fields {
+            VersionSpecificAccountTxFields::Deprecated(max_fee) => Self {
+                tip: 0,
+                max_price: max_fee / deprecated_tx_gas_estimate.max(1),
+            },
+            VersionSpecificAccountTxFields::Current(fields) => Self {
+                tip: fields.tip,
+                max_price: fields.l1_resource_bounds.max_price_per_unit,
+            },
+        }
+    }
+
+    /// Whether a transaction with this priority can replace one with priority `replaced`.
+    fn can_replace(&self, replaced: &Self, bump_percent: u128) -> bool {
+        let bump = |value: u128| value.saturating_mul(100 + bump_percent) / 100;
+        self.tip as u128 >= bump(replaced.tip as u128) && self.max_price >= bump(replaced.max_price)
+    }
+}
+
+// --------------------
+// Mempool structure
+// --------------------
+
+struct PooledTransaction {
+    transaction: Transaction,
+    hash: Felt252,
+    priority: FeePriority,
+    received_at: u64,
+    /// Insertion order, used to break priority ties in favor of older transactions.
+    sequence: u64,
+}
+
+struct AccountQueue {
+    /// Nonce of the account in the state, when it was last read.
+    account_nonce: Felt252,
+    /// Nonce of the next transaction of the account to hand out.
+    next_nonce: Felt252,
+    transactions: BTreeMap<Felt252, PooledTransaction>,
+}
+
+impl AccountQueue {
+    fn new(account_nonce: Felt252) -> Self {
+        Self {
+            account_nonce,
+            next_nonce: account_nonce,
+            transactions: BTreeMap::new(),
+        }
+    }
+
+    fn ready(&self) -> Option<&PooledTransaction> {
+        self.transactions.get(&self.next_nonce)
+    }
+
+    /// Removes the transaction with `nonce` along with the following ones, which can't be
+    /// executed without it. Returns the removed transactions by increasing nonce.
+    fn remove_from(&mut self, nonce: Felt252) -> Vec<PooledTransaction> {
+        self.transactions.split_off(&nonce).into_values().collect()
+    }
+
+    /// Whether the queue holds no transactions and none was handed out since the account nonce
+    /// was read, so that dropping it loses nothing.
+    fn is_unused(&self) -> bool {
+        self.transactions.is_empty() && self.next_nonce == self.account_nonce
+    }
+}
+
+pub struct Mempool {
+    config: MempoolConfig,
+    accounts: HashMap<Address, AccountQueue>,
+    /// Sender and nonce of each pooled transaction, by hash.
+    hashes: HashMap<Felt252, (Address, Felt252)>,
+    next_sequence: u64,
+}
+
+impl Default for Mempool {
+    fn default() -> Self {
+        Self::new(MempoolConfig::default())
+    }
+}
+
+impl Mempool {
+    pub fn new(config: MempoolConfig) -> Self {
+        Self {
+            config,
+            accounts: HashMap::new(),
+            hashes: HashMap::new(),
+            next_sequence: 0,
+        }
+    }
+
+    pub fn config(&self) -> &MempoolConfig {
+        &self.config
+    }
+
+    pub fn len(&self) -> usize {
+        self.hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hashes.is_empty()
+    }
+
+    pub fn contains(&self, transaction_hash: &Felt252) -> bool {
+        self.hashes.contains_key(transaction_hash)
+    }
+
+    pub fn get(&self, transaction_hash: &Felt252) -> Option<&Transaction> {
+        let (address, nonce) = self.hashes.get(transaction_hash)?;
+        self.accounts
+            .get(address)?
+            .transactions
+            .get(nonce)
+            .map(|pooled| &pooled.transaction)
+    }
+
+    /// Adds a transaction received at timestamp `now`.
+    ///
+    /// The nonce of the sender is read from `state` the first time one of its transactions is
+    /// added. A transaction with the same nonce as a pooled one replaces it if both its tip and max
+    /// price are higher by at least [`MempoolConfig::replacement_fee_bump_percent`]. When the
+    /// mempool is full, the lowest priority transaction among the last transaction of each
+    /// account is evicted, provided it has a lower priority than the new one and it isn't a
+    /// transaction of the same sender the new one depends on.
+    pub fn add_transaction<S: StateReader>(
+        &mut self,
+        transaction: Transaction,
+        state: &S,
+        now: u64,
+    ) -> Result<(), MempoolError> {
+        let (address, nonce, hash, priority) =
+            transaction_metadata(&transaction, self.config.deprecated_tx_gas_estimate)?;
+        if self.contains(&hash) {
+            return Err(MempoolError::DuplicateTransaction(hash));
+        }
+
+        let next_nonce = match self.accounts.get(&address) {
+            Some(queue) => queue.next_nonce,
+            None => state.get_nonce_at(&address)?,
+        };
+        if nonce < next_nonce {
+            return Err(MempoolError::NonceTooLow {
+                address,
+                nonce,
+                expected: next_nonce,
+            });
+        }
+        if !within_nonce_gap(next_nonce, nonce, self.config.max_nonce_gap) {
+            return Err(MempoolError::NonceTooHigh {
+                address,
+                nonce,
+                expected: next_nonce,
+            });
+        }
+
+        let replaced_priority = self
+            .accounts
+            .get(&address)
+            .and_then(|queue| queue.transactions.get(&nonce))
+            .map(|pooled| pooled.priority);
+        match replaced_priority {
+            Some(replaced_priority) => {
+                if !priority
+                    .can_replace(&replaced_priority, self.config.replacement_fee_bump_percent)
+                {
+                    return Err(MempoolError::ReplacementUnderpriced { address, nonce });
+                }
+            }
+            None if self.len() >= self.config.max_size => {
+                self.evict_lower_than(priority, &address, nonce)?
+            }
+            None => {}
+        }
+
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        let queue = self
+            .accounts
+            .entry(address.clone())
+            .or_insert_with(|| AccountQueue::new(next_nonce));
+        let pooled = PooledTransaction {
+            transaction,
+            hash,
+            priority,
+            received_at: now,
+            sequence,
+        };
+        if let Some(replaced) = queue.transactions.insert(nonce, pooled) {
+            self.hashes.remove(&replaced.hash);
+        }
+        self.hashes.insert(hash, (address, nonce));
+
+        Ok(())
+    }
+
+    /// Removes a transaction from the mempool, returning it if it was pooled.
+    ///
+    /// The transactions of the sender with a higher nonce are dropped too, as they can't be
+    /// executed without the removed one.
+    pub fn remove(&mut self, transaction_hash: &Felt252) -> Option<Transaction> {
+        let (address, nonce) = self.hashes.get(transaction_hash)?.clone();
+        let queue = self.accounts.get_mut(&address)?;
+        let removed = queue.remove_from(nonce);
+        if queue.is_unused() {
+            self.accounts.remove(&address);
+        }
+
+        for pooled in removed.iter() {
+            self.hashes.remove(&pooled.hash);
+        }
+        removed.into_iter().next().map(|pooled| pooled.transaction)
+    }
+
+    /// Removes and returns up to `max_transactions` ready transactions, by decreasing priority.
+    ///
+    /// The transactions of each account are returned in nonce order, and the next nonce of their
+    /// accounts is advanced past them until the next call to [`Mempool::update_nonces`].
+    pub fn pop_batch(&mut self, max_transactions: usize) -> Vec<Transaction> {
+        let mut ready: BinaryHeap<_> = self
+            .accounts
+            .iter()
+            .filter_map(|(address, queue)| {
+                queue
+                    .ready()
+                    .map(|pooled| (pooled.priority, Reverse(pooled.sequence), address.0))
+            })
+            .collect();
+
+        let mut batch = Vec::new();
+        while batch.len() < max_transactions {
+            let Some((_, _, address)) = ready.pop() else {
+                break;
+            };
+            let Some(queue) = self.accounts.get_mut(&Address(address)) else {
+                continue;
+            };
+            let nonce = queue.next_nonce;
+            let Some(pooled) = queue.transactions.remove(&nonce) else {
+                continue;
+            };
+            queue.next_nonce = nonce + Felt252::ONE;
+            if let Some(next) = queue.ready() {
+                ready.push((next.priority, Reverse(next.sequence), address));
+            }
+
+            self.hashes.remove(&pooled.hash);
+            batch.push(pooled.transaction);
+        }
+
+        batch
+    }
+
+    /// Reads the nonce of every account with pooled transactions from `state`, which should
+    /// include the last executed block. Transactions with a lower nonce, and transactions too far
+    /// ahead of the account nonce, are dropped.
+    ///
+    /// Returns the number of dropped transactions.
+    pub fn update_nonces<S: StateReader>(&mut self, state: &S) -> Result<usize, MempoolError> {
+        let max_nonce_gap = self.config.max_nonce_gap;
+        let mut dropped = Vec::new();
+        for (address, queue) in self.accounts.iter_mut() {
+            let account_nonce = state.get_nonce_at(address)?;
+            queue.account_nonce = account_nonce;
+            queue.next_nonce = account_nonce;
+            queue.transactions.retain(|nonce, pooled| {
+                let keep = *nonce >= account_nonce
+                    && within_nonce_gap(account_nonce, *nonce, max_nonce_gap);
+                if !keep {
+                    dropped.push(pooled.hash);
+                }
+                keep
+            });
+        }
+        self.accounts
+            .retain(|_, queue| !queue.transactions.is_empty());
+
+        for hash in dropped.iter() {
+            self.hashes.remove(hash);
+        }
+        Ok(dropped.len())
+    }
+
+    /// Drops the transactions received more than [`MempoolConfig::tx_ttl`] seconds before `now`,
+    /// along with the transactions of the same account with a higher nonce, which can't be
+    /// executed without them.
+    ///
+    /// Returns the number of dropped transactions.
+    pub fn evict_expired(&mut self, now: u64) -> usize {
+        let Some(tx_ttl) = self.config.tx_ttl else {
+            return 0;
+        };
+
+        let mut dropped = Vec::new();
+        for queue in self.accounts.values_mut() {
+            let first_expired = queue
+                .transactions
+                .iter()
+                .find(|(_, pooled)| pooled.received_at.saturating_add(tx_ttl) <= now)
+                .map(|(nonce, _)| *nonce);
+            if let Some(nonce) = first_expired {
+                dropped.extend(queue.remove_from(nonce).iter().map(|pooled| pooled.hash));
+            }
+        }
+        self.accounts.retain(|_, queue| !queue.is_unused());
+
+        for hash in dropped.iter() {
+            self.hashes.remove(hash);
+        }
+        dropped.len()
+    }
+
+    /// Evicts the lowest priority transaction among the last transaction of each account, if its
+    /// priority is lower than `priority`. Evicting the last transaction of an account keeps the
+    /// others executable.
+    ///
+    /// The last transaction of `sender` is only a candidate when its nonce is higher than
+    /// `nonce`, the nonce of the transaction being added, which would otherwise depend on it.
+    fn evict_lower_than(
+        &mut self,
+        priority: FeePriority,
+        sender: &Address,
+        nonce: Felt252,
+    ) -> Result<(), MempoolError> {
+        let lowest = self
+            .accounts
+            .iter()
+            .filter_map(|(address, queue)| {
+                queue
+                    .transactions
+                    .last_key_value()
+                    .map(|(nonce, pooled)| (address, *nonce, pooled))
+            })
+            .filter(|(address, last_nonce, _)| *address != sender || *last_nonce > nonce)
+            .min_by_key(|(_, _, pooled)| (pooled.priority, Reverse(pooled.sequence)))
+            .map(|(address, nonce, pooled)| (address.clone(), nonce, pooled.priority));
+
+        match lowest {
+            Some((address, nonce, lowest_priority)) if lowest_priority < priority => {
+                if let Some(queue) = self.accounts.get_mut(&address) {
+                    for evicted in queue.remove_from(nonce) {
+                        self.hashes.remove(&evicted.hash);
+                    }
+                    if queue.is_unused() {
+                        self.accounts.remove(&address);
+                    }
+                }
+                Ok(())
+            }
+            _ => Err(MempoolError::MempoolFull),
+        }
+    }
+}
+
+/// Returns the sender, nonce, hash and priority of an account transaction.
+fn transaction_metadata(
+    transaction: &Transaction,
+    deprecated_tx_gas_estimate: u128,
+) -> Result<(Address, Felt252, Felt252, FeePriority), MempoolError> {
+    let priority = |account_tx_fields: &VersionSpecificAccountTxFields| {
+        FeePriority::new(account_tx_fields, deprecated_tx_gas_estimate)
+    };
+    let (nonce, hash, priority) = match transaction {
+        Transaction::InvokeFunction(tx) => {
+            let nonce = tx
+                .nonce()
+                .ok_or_else(|| MempoolError::UnsupportedTransaction("Invoke v0".to_string()))?;
+            (nonce, *tx.hash_value(), priority(tx.account_tx_fields()))
+        }
+        Transaction::DeclareDeprecated(tx) => (
+            tx.nonce,
+            tx.hash_value,
+            priority(&VersionSpecificAccountTxFields::Deprecated(tx.max_fee)),
+        ),
+        Transaction::Declare(tx) => (tx.nonce, tx.hash_value, priority(&tx.account_tx_fields)),
+        Transaction::DeployAccount(tx) => (
+            *tx.nonce(),
+            *tx.hash_value(),
+            priority(tx.account_tx_fields()),
+        ),
+        Transaction::Deploy(_) => {
+            return Err(MempoolError::UnsupportedTransaction("Deploy".to_string()))
+        }
+        Transaction::L1Handler(_) => {
+            return Err(MempoolError::UnsupportedTransaction(
+                "L1Handler".to_string(),
+            ))
+        }
+    };
+
+    Ok((transaction.contract_address(), nonce, hash, priority))
+}
+
+/// Whether `nonce`, which must not be lower than `next_nonce`, is at most `max_nonce_gap` ahead.
+fn within_nonce_gap(next_nonce: Felt252, nonce: Felt252, max_nonce_gap: u64) -> bool {
+    (nonce - next_nonce)
+        .to_u64()
+        .map_or(false, |gap| gap <= max_nonce_gap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        definitions::constants::EXECUTE_ENTRY_POINT_SELECTOR,
+        state::in_memory_state_reader::InMemoryStateReader,
+        transaction::{CurrentAccountTxFields, InvokeFunction, ResourceBounds},
+    };
+    use pretty_assertions_sorted::assert_eq;
+
+    const GAS_ESTIMATE: u128 = 10_000;
+
+    /// Returns an invoke v1 transaction whose hash is `hash`, with a max fee worth `max_price`
+    /// per unit of gas.
+    fn invoke(sender: u64, nonce: u64, max_price: u128, hash: u64) -> Transaction {
+        Transaction::InvokeFunction(
+            InvokeFunction::new_with_tx_hash(
+                Address(sender.into()),
+                *EXECUTE_ENTRY_POINT_SELECTOR,
+                VersionSpecificAccountTxFields::Deprecated(max_price * GAS_ESTIMATE),
+                Felt252::ONE,
+                vec![],
+                vec![],
+                Some(nonce.into()),
+                hash.into(),
+            )
+            .unwrap(),
+        )
+    }
+
+    /// Returns an invoke v3 transaction whose hash is `hash`.
+    fn invoke_v3(sender: u64, nonce: u64, max_price_per_unit: u128, hash: u64) -> Transaction {
+        let account_tx_fields = VersionSpecificAccountTxFields::Current(CurrentAccountTxFields {
+            l1_resource_bounds: ResourceBounds {
+                max_amount: 1_000_000,
+                max_price_per_unit,
+            },
+            ..Default::default()
+        });
+
+        Transaction::InvokeFunction(
+            InvokeFunction::new_with_tx_hash(
+                Address(sender.into()),
+                *EXECUTE_ENTRY_POINT_SELECTOR,
+                account_tx_fields,
+                Felt252::THREE,
+                vec![],
+                vec![],
+                Some(nonce.into()),
+                hash.into(),
+            )
+            .unwrap(),
+        )
+    }
+
+    fn hashes(transactions: &[Transaction]) -> Vec<Felt252> {
+        transactions
+            .iter()
+            .map(|transaction| transaction_metadata(transaction, GAS_ESTIMATE).unwrap().2)
+            .collect()
+    }
+
+    #[test]
+    fn pop_batch_orders_by_nonce_and_priority() {
+        let state = InMemoryStateReader::default();
+        let mut mempool = Mempool::default();
+
+        mempool
+            .add_transaction(invoke(1, 1, 300, 11), &state, 0)
+            .unwrap();
+        mempool
+            .add_transaction(invoke(1, 0, 100, 10), &state, 0)
+            .unwrap();
+        mempool
+            .add_transaction(invoke(2, 0, 200, 20), &state, 0)
+            .unwrap();
+        // Not ready: nonce 2 of the first account is missing.
+        mempool
+            .add_transaction(invoke(1, 3, 900, 13), &state, 0)
+            .unwrap();
+        assert_eq!(mempool.len(), 4);
+
+        let batch = mempool.pop_batch(10);
+        assert_eq!(
+            hashes(&batch),
+            vec![Felt252::from(20), Felt252::from(10), Felt252::from(11)]
+        );
+        assert_eq!(mempool.len(), 1);
+        assert!(mempool.contains(&Felt252::from(13)));
+
+        // Handed out nonces can't be reused until the nonces are updated.
+        assert_matches!(
+            mempool.add_transaction(invoke(1, 1, 1000, 12), &state, 0),
+            Err(MempoolError::NonceTooLow { .. })
+        );
+    }
+
+    #[test]
+    fn deprecated_and_v3_transactions_are_ordered_by_price_per_unit() {
+        let state = InMemoryStateReader::default();
+        let mut mempool = Mempool::default();
+
+        // A max fee of 1000 * GAS_ESTIMATE is worth less per unit than a max price of 2000, even
+        // though it's a much larger number.
+        mempool
+            .add_transaction(invoke(1, 0, 1000, 1), &state, 0)
+            .unwrap();
+        mempool
+            .add_transaction(invoke_v3(2, 0, 2000, 2), &state, 0)
+            .unwrap();
+        mempool
+            .add_transaction(invoke_v3(3, 0, 500, 3), &state, 0)
+            .unwrap();
+
+        assert_eq!(
+            hashes(&mempool.pop_batch(10)),
+            vec![Felt252::from(2), Felt252::from(1), Felt252::from(3)]
+        );
+    }
+
+    #[test]
+    fn add_transaction_checks_nonce() {
+        let mut state = InMemoryStateReader::default();
+        state
+            .address_to_nonce_mut()
+            .insert(Address(1.into()), 5.into());
+        let mut mempool = Mempool::default();
+
+        assert_matches!(
+            mempool.add_transaction(invoke(1, 4, 100, 1), &state, 0),
+            Err(MempoolError::NonceTooLow { .. })
+        );
+        assert_matches!(
+            mempool.add_transaction(invoke(1, 70, 100, 2), &state, 0),
+            Err(MempoolError::NonceTooHigh { .. })
+        );
+        mempool
+            .add_transaction(invoke(1, 69, 100, 3), &state, 0)
+            .unwrap();
+        assert_matches!(
+            mempool.add_transaction(invoke(1, 6, 100, 3), &state, 0),
+            Err(MempoolError::DuplicateTransaction(_))
+        );
+    }
+
+    #[test]
+    fn replace_by_fee() {
+        let state = InMemoryStateReader::default();
+        let mut mempool = Mempool::default();
+
+        mempool
+            .add_transaction(invoke(1, 0, 100, 1), &state, 0)
+            .unwrap();
+        assert_matches!(
+            mempool.add_transaction(invoke(1, 0, 109, 2), &state, 0),
+            Err(MempoolError::ReplacementUnderpriced { .. })
+        );
+        mempool
+            .add_transaction(invoke(1, 0, 110, 3), &state, 0)
+            .unwrap();
+
+        assert_eq!(mempool.len(), 1);
+        assert!(!mempool.contains(&Felt252::from(1)));
+        assert!(mempool.get(&Felt252::from(3)).is_some());
+    }
+
+    #[test]
+    fn full_mempool_evicts_lowest_priority() {
+        let state = InMemoryStateReader::default();
+        let mut mempool = Mempool::new(MempoolConfig {
+            max_size: 2,
+            ..Default::default()
+        });
+
+        mempool
+            .add_transaction(invoke(1, 0, 100, 1), &state, 0)
+            .unwrap();
+        mempool
+            .add_transaction(invoke(2, 0, 200, 2), &state, 0)
+            .unwrap();
+        assert_matches!(
+            mempool.add_transaction(invoke(3, 0, 100, 3), &state, 0),
+            Err(MempoolError::MempoolFull)
+        );
+
+        mempool
+            .add_transaction(invoke(3, 0, 300, 4), &state, 0)
+            .unwrap();
+        assert_eq!(mempool.len(), 2);
+        assert!(!mempool.contains(&Felt252::from(1)));
+    }
+
+    #[test]
+    fn full_mempool_keeps_transactions_of_the_sender() {
+        let state = InMemoryStateReader::default();
+        let mut mempool = Mempool::new(MempoolConfig {
+            max_size: 3,
+            ..Default::default()
+        });
+
+        mempool
+            .add_transaction(invoke(1, 0, 200, 1), &state, 0)
+            .unwrap();
+        mempool
+            .add_transaction(invoke(1, 1, 100, 2), &state, 0)
+            .unwrap();
+        mempool
+            .add_transaction(invoke(2, 0, 150, 3), &state, 0)
+            .unwrap();
+
+        // Nonce 2 depends on nonce 1, so the next lowest priority transaction is evicted.
+        mempool
+            .add_transaction(invoke(1, 2, 300, 4), &state, 0)
+            .unwrap();
+        assert!(mempool.contains(&Felt252::from(2)));
+        assert!(!mempool.contains(&Felt252::from(3)));
+
+        // With no other candidate, the transaction is rejected.
+        assert_matches!(
+            mempool.add_transaction(invoke(1, 3, 400, 5), &state, 0),
+            Err(MempoolError::MempoolFull)
+        );
+        assert_eq!(mempool.len(), 3);
+    }
+
+    #[test]
+    fn remove_drops_following_nonces() {
+        let state = InMemoryStateReader::default();
+        let mut mempool = Mempool::default();
+
+        for nonce in 0..3 {
+            mempool
+                .add_transaction(invoke(1, nonce, 100, nonce + 1), &state, 0)
+                .unwrap();
+        }
+
+        assert_eq!(
+            mempool
+                .remove(&Felt252::from(2))
+                .map(|transaction| hashes(&[transaction])),
+            Some(vec![Felt252::from(2)])
+        );
+        assert_eq!(mempool.len(), 1);
+        assert!(!mempool.contains(&Felt252::from(3)));
+
+        // The account queue is dropped once empty.
+        mempool.remove(&Felt252::from(1)).unwrap();
+        assert!(mempool.is_empty());
+        assert!(mempool.accounts.is_empty());
+    }
+
+    #[test]
+    fn evict_expired_drops_following_nonces() {
+        let state = InMemoryStateReader::default();
+        let mut mempool = Mempool::default();
+
+        mempool
+            .add_transaction(invoke(1, 0, 100, 1), &state, 100)
+            .unwrap();
+        mempool
+            .add_transaction(invoke(1, 1, 100, 2), &state, 0)
+            .unwrap();
+        mempool
+            .add_transaction(invoke(1, 2, 100, 3), &state, 100)
+            .unwrap();
+        mempool
+            .add_transaction(invoke(2, 0, 100, 4), &state, 0)
+            .unwrap();
+
+        assert_eq!(mempool.evict_expired(3600), 3);
+        assert_eq!(mempool.len(), 1);
+        assert!(mempool.contains(&Felt252::from(1)));
+        assert_eq!(mempool.accounts.len(), 1);
+    }
+
+    #[test]
+    fn update_nonces_and_evict_expired() {
+        let mut state = InMemoryStateReader::default();
+        let mut mempool = Mempool::default();
+
+        mempool
+            .add_transaction(invoke(1, 0, 100, 1), &state, 0)
+            .unwrap();
+        mempool
+            .add_transaction(invoke(1, 1, 100, 2), &state, 0)
+            .unwrap();
+        mempool
+            .add_transaction(invoke(1, 2, 100, 3), &state, 100)
+            .unwrap();
+        mempool
+            .add_transaction(invoke(2, 0, 100, 4), &state, 0)
+            .unwrap();
+        assert_eq!(hashes(&mempool.pop_batch(1)), vec![Felt252::from(1)]);
+
+        // The block included the first two transactions of the first account, the second one
+        // through another node.
+        state
+            .address_to_nonce_mut()
+            .insert(Address(1.into()), 2.into());
+        assert_eq!(mempool.update_nonces(&state).unwrap(), 1);
+        assert!(!mempool.contains(&Felt252::from(2)));
+
+        assert_eq!(mempool.evict_expired(3600), 1);
+        assert_eq!(mempool.len(), 1);
+        assert_eq!(hashes(&mempool.pop_batch(10)), vec![Felt252::from(3)]);
+    }
+}
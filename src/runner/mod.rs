@@ -167,6 +167,19 @@ where
         Ok(self.cairo_runner.get_execution_resources(&self.vm)?)
     }
 
+    /// Returns the resources used by a run that was interrupted by an error, up to the failure
+    /// point. Falls back to counting only the executed steps if the builtins usage can't be
+    /// computed from the unfinished run.
+    pub fn get_partial_execution_resources(&mut self) -> ExecutionResources {
+        self.vm.segments.compute_effective_sizes();
+        self.get_execution_resources()
+            .unwrap_or_else(|_| ExecutionResources {
+                n_steps: self.vm.get_current_step(),
+                n_memory_holes: 0,
+                builtin_instance_counter: HashMap::new(),
+            })
+    }
+
     /// Return a vector that holds the data and pointers used to build the CallResult
     pub fn get_return_values(&self) -> Result<Vec<Felt252>, TransactionError> {
         let ret_data = self.vm.get_return_values(2)?;
@@ -168,6 +168,7 @@ impl From<StarknetRsContractClass> for CompiledClass {
                     entry_points_by_type,
                     abi,
                     hinted_class_hash,
+                    class_json: None,
                 }))
             }
         }
@@ -14,6 +14,7 @@ use serde_json::Value;
 use starknet_api::deprecated_contract_class::{ContractClassAbiEntry, EntryPoint};
 use std::collections::HashMap;
 use std::path::Path;
+use std::sync::Arc;
 
 pub type AbiType = Vec<ContractClassAbiEntry>;
 
@@ -72,7 +73,7 @@ impl From<starknet_api::deprecated_contract_class::EntryPointType> for EntryPoin
 //         Contract Class
 // -------------------------------
 
-#[derive(Clone, Debug, Eq, Getters, PartialEq)]
+#[derive(Clone, Debug, Getters)]
 pub struct ContractClass {
     #[getset(get = "pub")]
     pub(crate) program: Program,
@@ -82,8 +83,24 @@ pub struct ContractClass {
     pub(crate) entry_points_by_type: HashMap<EntryPointType, Vec<ContractEntryPoint>>,
     #[getset(get = "pub")]
     pub(crate) abi: Option<AbiType>,
+    /// JSON the class was parsed from, kept with [`ContractClass::with_class_json`]. The
+    /// compiled program can't be rebuilt from the parsed [`Program`], so this is what
+    /// transactions serialize the class from.
+    pub(crate) class_json: Option<Arc<str>>,
 }
 
+impl PartialEq for ContractClass {
+    fn eq(&self, other: &Self) -> bool {
+        // The JSON isn't compared, as a class is the same whether or not its JSON is known.
+        self.program == other.program
+            && self.hinted_class_hash == other.hinted_class_hash
+            && self.entry_points_by_type == other.entry_points_by_type
+            && self.abi == other.abi
+    }
+}
+
+impl Eq for ContractClass {}
+
 impl ContractClass {
     pub fn new(
         program_json: Value,
@@ -104,6 +121,7 @@ impl ContractClass {
             program,
             entry_points_by_type,
             abi,
+            class_json: None,
         })
     }
 
@@ -126,6 +144,7 @@ impl ContractClass {
             program,
             entry_points_by_type,
             abi,
+            class_json: None,
         })
     }
 
@@ -156,9 +175,17 @@ impl ContractClass {
             program,
             entry_points_by_type,
             abi: contract_class.abi,
+            class_json: None,
         })
     }
 
+    /// Keeps the JSON the class was parsed from, which is needed to serialize the transactions
+    /// declaring or deploying it. It isn't kept by default, as it's as large as the class.
+    pub fn with_class_json(mut self, class_json: impl Into<Arc<str>>) -> Self {
+        self.class_json = Some(class_json.into());
+        self
+    }
+
     /// Parses a [`ContractClass`] from a compiled Cairo 0 program's JSON
     /// at the given file path.
     pub fn from_path<F>(path: F) -> Result<Self, ProgramError>
@@ -200,6 +227,7 @@ impl FromStr for ContractClass {
             program,
             entry_points_by_type,
             abi: contract_class.abi,
+            class_json: None,
         })
     }
 }
@@ -0,0 +1,242 @@
+//! Block hash contract handling.
+//!
+//! At the start of each block, the OS writes the hash of the block `STORED_BLOCK_HASH_BUFFER`
+//! blocks earlier into the storage of the block hash contract (address 1), keyed by its number.
+//! This is where the `get_block_hash` syscall reads block hashes from.
+
+use super::state_api::{State, StateReader};
+use crate::{
+    core::errors::state_errors::StateError,
+    definitions::{
+        block_context::BlockContext,
+        constants::{BLOCK_HASH_CONTRACT_ADDRESS, STORED_BLOCK_HASH_BUFFER},
+    },
+};
+use cairo_vm::Felt252;
+use starknet_api::block::Block;
+
+/// The number and hash of a block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockNumberHashPair {
+    pub number: u64,
+    pub hash: Felt252,
+}
+
+/// Processes the start of block `block_number` the same way the OS does, writing the given block
+/// hash into the block hash contract.
+///
+/// The hash of block `block_number - STORED_BLOCK_HASH_BUFFER` is required unless the block is
+/// one of the first `STORED_BLOCK_HASH_BUFFER` blocks, and the hash of any other block is
+/// rejected.
+pub fn pre_process_block<S: State>(
+    state: &mut S,
+    old_block_number_and_hash: Option<BlockNumberHashPair>,
+    block_number: u64,
+) -> Result<(), StateError> {
+    let old_block_number = block_number.checked_sub(STORED_BLOCK_HASH_BUFFER);
+    match (old_block_number_and_hash, old_block_number) {
+        (Some(BlockNumberHashPair { number, hash }), Some(old_block_number))
+            if number == old_block_number =>
+        {
+            write_block_hash(state, number, hash);
+            Ok(())
+        }
+        (Some(BlockNumberHashPair { number, .. }), _) => Err(StateError::UnexpectedOldBlockHash {
+            old_block_number: number,
+            block_number,
+        }),
+        (None, Some(old_block_number)) => Err(StateError::MissingOldBlockHash {
+            old_block_number,
+            block_number,
+        }),
+        (None, None) => Ok(()),
+    }
+}
+
+/// Processes the start of the block described by `block_context`, taking the hash to write from
+/// [`BlockContext::blocks`].
+pub fn pre_process_block_from_context<S: State>(
+    state: &mut S,
+    block_context: &BlockContext,
+) -> Result<(), StateError> {
+    pre_process_block(
+        state,
+        old_block_hash_from_context(block_context),
+        block_context.block_info.block_number,
+    )
+}
+
+/// Processes the start of block `block_number`, taking the hash to write from `reader`.
+pub fn pre_process_block_from_reader<S: State, R: StateReader>(
+    state: &mut S,
+    reader: &R,
+    block_number: u64,
+) -> Result<(), StateError> {
+    let old_block_number_and_hash = old_block_hash_from_reader(reader, block_number)?;
+    pre_process_block(state, old_block_number_and_hash, block_number)
+}
+
+/// Writes the hash of a block into the block hash contract.
+pub fn write_block_hash<S: State>(state: &mut S, block_number: u64, block_hash: Felt252) {
+    state.set_storage_at(
+        &(
+            BLOCK_HASH_CONTRACT_ADDRESS.clone(),
+            Felt252::from(block_number).to_bytes_be(),
+        ),
+        block_hash,
+    );
+}
+
+/// Writes the hashes of all the blocks of [`BlockContext::blocks`] accessible from the current
+/// block, so that `get_block_hash` works for the whole window and not only for the blocks
+/// processed since the state was created.
+pub fn write_block_hashes_from_context<S: State>(state: &mut S, block_context: &BlockContext) {
+    let current_block_number = block_context.block_info.block_number;
+    for (block_number, block) in block_context.blocks.iter() {
+        if block_number + STORED_BLOCK_HASH_BUFFER <= current_block_number {
+            write_block_hash(state, *block_number, block_hash(block));
+        }
+    }
+}
+
+/// Returns the hash of block `current_block - STORED_BLOCK_HASH_BUFFER` if it is in
+/// [`BlockContext::blocks`].
+pub fn old_block_hash_from_context(block_context: &BlockContext) -> Option<BlockNumberHashPair> {
+    let number = block_context
+        .block_info
+        .block_number
+        .checked_sub(STORED_BLOCK_HASH_BUFFER)?;
+    block_context
+        .blocks
+        .get(&number)
+        .map(|block| BlockNumberHashPair {
+            number,
+            hash: block_hash(block),
+        })
+}
+
+/// Returns the hash of block `block_number - STORED_BLOCK_HASH_BUFFER` if `reader` knows it.
+pub fn old_block_hash_from_reader<R: StateReader>(
+    reader: &R,
+    block_number: u64,
+) -> Result<Option<BlockNumberHashPair>, StateError> {
+    let Some(number) = block_number.checked_sub(STORED_BLOCK_HASH_BUFFER) else {
+        return Ok(None);
+    };
+    Ok(reader
+        .get_block_hash(number)?
+        .map(|hash| BlockNumberHashPair { number, hash }))
+}
+
+fn block_hash(block: &Block) -> Felt252 {
+    Felt252::from_bytes_be_slice(block.header.block_hash.0.bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{
+        cached_state::CachedState, contract_class_cache::PermanentContractClassCache,
+        in_memory_state_reader::InMemoryStateReader,
+    };
+    use starknet_api::{
+        block::{BlockHash, BlockHeader, BlockNumber},
+        hash::StarkFelt,
+    };
+    use std::{collections::BTreeMap, sync::Arc};
+
+    fn stored_block_hash<S: StateReader>(state: &S, block_number: u64) -> Felt252 {
+        state
+            .get_storage_at(&(
+                BLOCK_HASH_CONTRACT_ADDRESS.clone(),
+                Felt252::from(block_number).to_bytes_be(),
+            ))
+            .unwrap()
+    }
+
+    fn block(number: u64) -> Block {
+        Block {
+            header: BlockHeader {
+                block_hash: BlockHash(StarkFelt::from(number * 100)),
+                block_number: BlockNumber(number),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn empty_state() -> CachedState<InMemoryStateReader, PermanentContractClassCache> {
+        CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        )
+    }
+
+    #[test]
+    fn pre_process_block_requires_old_block_hash() {
+        let mut state = empty_state();
+
+        assert!(pre_process_block(&mut state, None, 9).is_ok());
+        assert_matches!(
+            pre_process_block(&mut state, None, 10),
+            Err(StateError::MissingOldBlockHash {
+                old_block_number: 0,
+                block_number: 10
+            })
+        );
+
+        let old_block = BlockNumberHashPair {
+            number: 5,
+            hash: Felt252::from(0x1234),
+        };
+        pre_process_block(&mut state, Some(old_block), 15).unwrap();
+        assert_eq!(stored_block_hash(&state, 5), Felt252::from(0x1234));
+        // Only the hash of the block `STORED_BLOCK_HASH_BUFFER` blocks earlier can be written.
+        assert_matches!(
+            pre_process_block(&mut state, Some(old_block), 16),
+            Err(StateError::UnexpectedOldBlockHash {
+                old_block_number: 5,
+                block_number: 16
+            })
+        );
+        assert_matches!(
+            pre_process_block(&mut state, Some(old_block), 5),
+            Err(StateError::UnexpectedOldBlockHash { .. })
+        );
+    }
+
+    #[test]
+    fn pre_process_block_from_block_context() {
+        let mut state = empty_state();
+        let mut block_context = BlockContext::default();
+        block_context.block_info.block_number = 20;
+        block_context.blocks = (0..=10).map(|number| (number, block(number))).collect();
+
+        pre_process_block_from_context(&mut state, &block_context).unwrap();
+        assert_eq!(stored_block_hash(&state, 10), Felt252::from(1000));
+        assert_eq!(stored_block_hash(&state, 9), Felt252::ZERO);
+
+        write_block_hashes_from_context(&mut state, &block_context);
+        for number in 0..=10 {
+            assert_eq!(
+                stored_block_hash(&state, number),
+                Felt252::from(number * 100)
+            );
+        }
+    }
+
+    #[test]
+    fn pre_process_block_from_state_reader() {
+        let mut state_reader = InMemoryStateReader::default();
+        state_reader.block_hashes = BTreeMap::from([(32, Felt252::from(0xabc))]);
+        let mut state = empty_state();
+
+        pre_process_block_from_reader(&mut state, &state_reader, 42).unwrap();
+        assert_eq!(stored_block_hash(&state, 32), Felt252::from(0xabc));
+
+        assert_matches!(
+            pre_process_block_from_reader(&mut state, &state_reader, 43),
+            Err(StateError::MissingOldBlockHash { .. })
+        );
+    }
+}
@@ -23,16 +23,33 @@ use std::{
 
 pub const UNINITIALIZED_CLASS_HASH: &ClassHash = &ClassHash([0u8; 32]);
 
+/// Identifies a snapshot taken with [`CachedState::snapshot`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SnapshotId(usize);
+
+/// The contents of a [`CachedState`] when a snapshot was taken.
+#[derive(Clone, Debug)]
+struct Snapshot {
+    id: SnapshotId,
+    cache: Arc<StateCache>,
+    contract_classes: Arc<HashMap<ClassHash, CompiledClass>>,
+}
+
 /// Represents a cached state of contract classes with optional caches.
+///
+/// The cache and the private contract class cache are kept behind an [`Arc`] and are copied on
+/// write, so that cloning the state and taking snapshots of it doesn't copy them.
 #[derive(Clone, Default, Debug, Getters, MutGetters)]
 pub struct CachedState<T: StateReader, C: ContractClassCache> {
     pub state_reader: Arc<T>,
-    #[getset(get = "pub", get_mut = "pub")]
-    pub(crate) cache: StateCache,
+    pub(crate) cache: Arc<StateCache>,
 
     #[getset(get = "pub", get_mut = "pub")]
     pub(crate) contract_class_cache: Arc<C>,
-    pub(crate) contract_class_cache_private: Arc<RwLock<HashMap<ClassHash, CompiledClass>>>,
+    pub(crate) contract_class_cache_private: Arc<RwLock<Arc<HashMap<ClassHash, CompiledClass>>>>,
+
+    snapshots: Vec<Snapshot>,
+    next_snapshot_id: usize,
 
     #[cfg(feature = "metrics")]
     cache_hits: usize,
@@ -70,10 +87,12 @@ impl<T: StateReader, C: ContractClassCache> CachedState<T, C> {
     /// Constructor, creates a new cached state.
     pub fn new(state_reader: Arc<T>, contract_classes: Arc<C>) -> Self {
         Self {
-            cache: StateCache::default(),
+            cache: Arc::default(),
             state_reader,
             contract_class_cache: contract_classes,
-            contract_class_cache_private: Arc::new(RwLock::new(HashMap::new())),
+            contract_class_cache_private: Arc::default(),
+            snapshots: Vec::new(),
+            next_snapshot_id: 0,
 
             #[cfg(feature = "metrics")]
             cache_hits: 0,
@@ -89,10 +108,12 @@ impl<T: StateReader, C: ContractClassCache> CachedState<T, C> {
         contract_classes: Arc<C>,
     ) -> Self {
         Self {
-            cache,
+            cache: Arc::new(cache),
             state_reader,
             contract_class_cache: contract_classes,
-            contract_class_cache_private: Arc::new(RwLock::new(HashMap::new())),
+            contract_class_cache_private: Arc::default(),
+            snapshots: Vec::new(),
+            next_snapshot_id: 0,
 
             #[cfg(feature = "metrics")]
             cache_hits: 0,
@@ -108,6 +129,8 @@ impl<T: StateReader, C: ContractClassCache> CachedState<T, C> {
             cache: self.cache.clone(),
             contract_class_cache: self.contract_class_cache.clone(),
             contract_class_cache_private: self.contract_class_cache_private.clone(),
+            snapshots: self.snapshots.clone(),
+            next_snapshot_id: self.next_snapshot_id,
             #[cfg(feature = "metrics")]
             cache_hits: self.cache_hits,
             #[cfg(feature = "metrics")]
@@ -115,15 +138,25 @@ impl<T: StateReader, C: ContractClassCache> CachedState<T, C> {
         }
     }
 
+    /// Returns the cache of the state.
+    pub fn cache(&self) -> &StateCache {
+        &self.cache
+    }
+
+    /// Returns the cache of the state for modifying it, which copies it first if it's shared
+    /// with a clone or a snapshot of the state.
+    pub fn cache_mut(&mut self) -> &mut StateCache {
+        Arc::make_mut(&mut self.cache)
+    }
+
     pub fn drain_private_contract_class_cache(
         &self,
     ) -> Result<impl Iterator<Item = (ClassHash, CompiledClass)>, StateError> {
-        Ok(self
+        let contract_classes = self
             .contract_class_cache_private
             .read()
-            .map_err(|_| StateError::FailedToReadContractClassCache)?
-            .clone()
-            .into_iter())
+            .map_err(|_| StateError::FailedToReadContractClassCache)?;
+        Ok(HashMap::clone(&contract_classes).into_iter())
     }
 
     /// Creates a copy of this state with an empty cache for saving changes and applying them
@@ -134,12 +167,61 @@ impl<T: StateReader, C: ContractClassCache> CachedState<T, C> {
             cache: self.cache.clone(),
             contract_class_cache: self.contract_class_cache.clone(),
             contract_class_cache_private: self.contract_class_cache_private.clone(),
+            snapshots: Vec::new(),
+            next_snapshot_id: 0,
             #[cfg(feature = "metrics")]
             cache_hits: 0,
             #[cfg(feature = "metrics")]
             cache_misses: 0,
         })
     }
+
+    /// Takes a snapshot of the state, which can be restored later with
+    /// [`CachedState::revert_to`].
+    ///
+    /// The state reader is never written to, so the snapshot only keeps the cache and the
+    /// private contract class cache. It shares them with the state instead of copying them:
+    /// each of them is copied once, by the first modification made after the snapshot, and
+    /// reverting to the snapshot doesn't copy anything.
+    pub fn snapshot(&mut self) -> Result<SnapshotId, StateError> {
+        let contract_classes = self
+            .contract_class_cache_private
+            .read()
+            .map_err(|_| StateError::FailedToReadContractClassCache)?
+            .clone();
+
+        let id = SnapshotId(self.next_snapshot_id);
+        self.next_snapshot_id += 1;
+        self.snapshots.push(Snapshot {
+            id,
+            cache: self.cache.clone(),
+            contract_classes,
+        });
+        Ok(id)
+    }
+
+    /// Restores the state to the given snapshot, discarding it along with every snapshot taken
+    /// after it.
+    ///
+    /// The private contract class cache is shared with the transactional states created from
+    /// this one, so it's replaced by the one kept by the snapshot instead of being modified.
+    /// Contract classes declared afterwards are no longer visible from those states.
+    pub fn revert_to(&mut self, snapshot_id: SnapshotId) -> Result<(), StateError> {
+        let position = self
+            .snapshots
+            .iter()
+            .position(|snapshot| snapshot.id == snapshot_id)
+            .ok_or(StateError::InvalidSnapshot(snapshot_id))?;
+        let Snapshot {
+            cache,
+            contract_classes,
+            ..
+        } = self.snapshots.split_off(position).swap_remove(0);
+
+        self.contract_class_cache_private = Arc::new(RwLock::new(contract_classes));
+        self.cache = cache;
+        Ok(())
+    }
 }
 
 impl<T: StateReader, C: ContractClassCache> StateReader for CachedState<T, C> {
@@ -202,7 +284,7 @@ impl<T: StateReader, C: ContractClassCache> StateReader for CachedState<T, C> {
         } else if let Some(compiled_class) =
             self.contract_class_cache().get_contract_class(*class_hash)
         {
-            private_cache.insert(*class_hash, compiled_class.clone());
+            Arc::make_mut(&mut private_cache).insert(*class_hash, compiled_class.clone());
             return Ok(compiled_class);
         }
 
@@ -216,17 +298,22 @@ impl<T: StateReader, C: ContractClassCache> StateReader for CachedState<T, C> {
                 .contract_class_cache()
                 .get_contract_class(*compiled_class_hash)
             {
-                private_cache.insert(*class_hash, casm_class.clone());
+                Arc::make_mut(&mut private_cache).insert(*class_hash, casm_class.clone());
                 return Ok(casm_class);
             }
         }
 
         // II: FETCHING FROM STATE_READER
         let contract_class = self.state_reader.get_contract_class(class_hash)?;
-        private_cache.insert(*class_hash, contract_class.clone());
+        Arc::make_mut(&mut private_cache).insert(*class_hash, contract_class.clone());
 
         Ok(contract_class)
     }
+
+    /// Returns the hash of a block, as known by the state reader.
+    fn get_block_hash(&self, block_number: u64) -> Result<Option<Felt252>, StateError> {
+        self.state_reader.get_block_hash(block_number)
+    }
 }
 
 impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
@@ -236,10 +323,11 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
         class_hash: &ClassHash,
         contract_class: &CompiledClass,
     ) -> Result<(), StateError> {
-        self.contract_class_cache_private
+        let mut contract_classes = self
+            .contract_class_cache_private
             .write()
-            .map_err(|_| StateError::FailedToReadContractClassCache)?
-            .insert(*class_hash, contract_class.clone());
+            .map_err(|_| StateError::FailedToReadContractClassCache)?;
+        Arc::make_mut(&mut contract_classes).insert(*class_hash, contract_class.clone());
 
         Ok(())
     }
@@ -266,7 +354,7 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
             _ => {}
         }
 
-        self.cache
+        self.cache_mut()
             .class_hash_writes
             .insert(deploy_contract_address.clone(), class_hash);
         Ok(())
@@ -274,14 +362,14 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
 
     fn increment_nonce(&mut self, contract_address: &Address) -> Result<(), StateError> {
         let new_nonce = self.get_nonce_at(contract_address)? + Felt252::from(1);
-        self.cache
+        self.cache_mut()
             .nonce_writes
             .insert(contract_address.clone(), new_nonce);
         Ok(())
     }
 
     fn set_storage_at(&mut self, storage_entry: &StorageEntry, value: Felt252) {
-        self.cache
+        self.cache_mut()
             .storage_writes
             .insert(storage_entry.clone(), value);
     }
@@ -297,7 +385,7 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
             ));
         }
 
-        self.cache
+        self.cache_mut()
             .class_hash_writes
             .insert(deploy_contract_address, class_hash);
         Ok(())
@@ -311,7 +399,7 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
         let class_hash = ClassHash::from(*class_hash);
         let compiled_class_hash = ClassHash::from(*compiled_class_hash);
 
-        self.cache
+        self.cache_mut()
             .compiled_class_hash_writes
             .insert(class_hash, compiled_class_hash);
         Ok(())
@@ -320,7 +408,7 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
     fn apply_state_update(&mut self, state_updates: &StateDiff) -> Result<(), StateError> {
         let storage_updates = to_cache_state_storage_mapping(&state_updates.storage_updates);
 
-        self.cache.update_writes(
+        self.cache_mut().update_writes(
             &state_updates.address_to_class_hash,
             &state_updates.class_hash_to_compiled_class,
             &state_updates.address_to_nonce,
@@ -393,7 +481,7 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
             None => {
                 self.add_miss();
                 let class_hash = self.state_reader.get_class_hash_at(contract_address)?;
-                self.cache
+                self.cache_mut()
                     .class_hash_initial_values
                     .insert(contract_address.clone(), class_hash);
                 Ok(class_hash)
@@ -405,7 +493,7 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
         if self.cache.get_nonce(contract_address).is_none() {
             self.add_miss();
             let nonce = self.state_reader.get_nonce_at(contract_address)?;
-            self.cache
+            self.cache_mut()
                 .nonce_initial_values
                 .insert(contract_address.clone(), nonce);
         } else {
@@ -429,7 +517,7 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
             None => {
                 self.add_miss();
                 let value = self.state_reader.get_storage_at(storage_entry)?;
-                self.cache
+                self.cache_mut()
                     .storage_initial_values
                     .insert(storage_entry.clone(), value);
                 Ok(value)
@@ -453,7 +541,7 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
                 self.add_miss();
                 let compiled_class_hash = self.state_reader.get_compiled_class_hash(class_hash)?;
                 let address = Address(Felt252::from_bytes_be(&compiled_class_hash.0));
-                self.cache
+                self.cache_mut()
                     .class_hash_initial_values
                     .insert(address, compiled_class_hash);
                 Ok(compiled_class_hash)
@@ -483,10 +571,7 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
             self.contract_class_cache().get_contract_class(*class_hash)
         {
             self.add_hit();
-            self.contract_class_cache_private
-                .write()
-                .map_err(|_| StateError::FailedToReadContractClassCache)?
-                .insert(*class_hash, compiled_class.clone());
+            self.set_contract_class(class_hash, &compiled_class)?;
             return Ok(compiled_class);
         }
 
@@ -508,10 +593,7 @@ impl<T: StateReader, C: ContractClassCache> State for CachedState<T, C> {
                 .get_contract_class(*compiled_class_hash)
             {
                 self.add_hit();
-                self.contract_class_cache_private
-                    .write()
-                    .map_err(|_| StateError::FailedToReadContractClassCache)?
-                    .insert(*class_hash, casm_class.clone());
+                self.set_contract_class(class_hash, &casm_class)?;
                 return Ok(casm_class);
             }
         }
@@ -546,37 +628,30 @@ impl<T: StateReader, C: ContractClassCache> CachedState<T, C> {
     // the initial value for that key will be fetched from the state_reader and inserted into the cache's storage_initial_values
     // The same process is applied to class hash and nonce values.
     fn update_initial_values_of_write_only_accesses(&mut self) -> Result<(), StateError> {
+        let cache = Arc::make_mut(&mut self.cache);
         // Update storage_initial_values with keys in storage_writes
-        for storage_entry in self.cache.storage_writes.keys() {
-            if !self
-                .cache
-                .storage_initial_values
-                .contains_key(storage_entry)
-            {
+        for storage_entry in cache.storage_writes.keys() {
+            if !cache.storage_initial_values.contains_key(storage_entry) {
                 // This key was first accessed via write, so we need to cache its initial value
-                self.cache.storage_initial_values.insert(
+                cache.storage_initial_values.insert(
                     storage_entry.clone(),
                     self.state_reader.get_storage_at(storage_entry)?,
                 );
             }
         }
-        for address in self.cache.class_hash_writes.keys() {
-            if !self.cache.class_hash_initial_values.contains_key(address) {
+        for address in cache.class_hash_writes.keys() {
+            if !cache.class_hash_initial_values.contains_key(address) {
                 // This key was first accessed via write, so we need to cache its initial value
-                self.cache.class_hash_initial_values.insert(
+                cache.class_hash_initial_values.insert(
                     address.clone(),
                     self.state_reader.get_class_hash_at(address)?,
                 );
             }
         }
-        for contract_address in self.cache.nonce_writes.keys() {
-            if !self
-                .cache
-                .nonce_initial_values
-                .contains_key(contract_address)
-            {
+        for contract_address in cache.nonce_writes.keys() {
+            if !cache.nonce_initial_values.contains_key(contract_address) {
                 // This key was first accessed via write, so we need to cache its initial value
-                self.cache.nonce_initial_values.insert(
+                cache.nonce_initial_values.insert(
                     contract_address.clone(),
                     self.state_reader.get_nonce_at(contract_address)?,
                 );
@@ -701,6 +776,126 @@ mod tests {
             .is_zero());
     }
 
+    /// This test verifies that reverting to a snapshot restores the cache as it was, discarding
+    /// the snapshots taken after it.
+    #[test]
+    fn revert_to_snapshot() {
+        let mut cached_state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        let address = Address(4242.into());
+        let storage_entry: StorageEntry = (address.clone(), [101; 32]);
+
+        cached_state.set_storage_at(&storage_entry, Felt252::ONE);
+        let first_snapshot = cached_state.snapshot().unwrap();
+        cached_state.set_storage_at(&storage_entry, Felt252::TWO);
+        cached_state.increment_nonce(&address).unwrap();
+        let second_snapshot = cached_state.snapshot().unwrap();
+        cached_state.set_storage_at(&storage_entry, Felt252::THREE);
+
+        cached_state.revert_to(second_snapshot).unwrap();
+        assert_eq!(
+            cached_state.get_storage_at(&storage_entry).unwrap(),
+            Felt252::TWO
+        );
+        assert_eq!(cached_state.get_nonce_at(&address).unwrap(), Felt252::ONE);
+
+        cached_state.revert_to(first_snapshot).unwrap();
+        assert_eq!(
+            cached_state.get_storage_at(&storage_entry).unwrap(),
+            Felt252::ONE
+        );
+        assert_eq!(cached_state.get_nonce_at(&address).unwrap(), Felt252::ZERO);
+
+        assert_matches!(
+            cached_state.revert_to(second_snapshot),
+            Err(StateError::InvalidSnapshot(id)) if id == second_snapshot
+        );
+    }
+
+    /// This test verifies that a snapshot shares the cache with the state until the state is
+    /// modified.
+    #[test]
+    fn snapshot_shares_cache_until_written() {
+        let mut cached_state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        let storage_entry: StorageEntry = (Address(4242.into()), [101; 32]);
+
+        cached_state.set_storage_at(&storage_entry, Felt252::ONE);
+        cached_state.snapshot().unwrap();
+        assert!(Arc::ptr_eq(
+            &cached_state.cache,
+            &cached_state.snapshots[0].cache
+        ));
+
+        cached_state.set_storage_at(&storage_entry, Felt252::TWO);
+        assert!(!Arc::ptr_eq(
+            &cached_state.cache,
+            &cached_state.snapshots[0].cache
+        ));
+        assert_eq!(
+            cached_state.snapshots[0].cache.storage_writes[&storage_entry],
+            Felt252::ONE
+        );
+    }
+
+    /// This test verifies that reverting to a snapshot removes the contract classes declared
+    /// after it.
+    #[test]
+    fn revert_to_snapshot_removes_declared_classes() {
+        let mut cached_state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        let contract_class = CompiledClass::Deprecated(Arc::new(
+            ContractClass::from_path("starknet_programs/raw_contract_classes/class_with_abi.json")
+                .unwrap(),
+        ));
+
+        cached_state
+            .set_contract_class(&ClassHash([1; 32]), &contract_class)
+            .unwrap();
+        let snapshot = cached_state.snapshot().unwrap();
+        cached_state
+            .set_contract_class(&ClassHash([2; 32]), &contract_class)
+            .unwrap();
+
+        cached_state.revert_to(snapshot).unwrap();
+        assert!(cached_state.get_contract_class(&ClassHash([1; 32])).is_ok());
+        assert!(cached_state
+            .get_contract_class(&ClassHash([2; 32]))
+            .is_err());
+    }
+
+    /// This test verifies that reverting a transactional state to a snapshot doesn't remove the
+    /// contract classes seen by the state it was created from.
+    #[test]
+    fn revert_transactional_state_keeps_parent_classes() {
+        let mut cached_state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        let contract_class = CompiledClass::Deprecated(Arc::new(
+            ContractClass::from_path("starknet_programs/raw_contract_classes/class_with_abi.json")
+                .unwrap(),
+        ));
+
+        let mut transactional_state = cached_state.create_transactional().unwrap();
+        let snapshot = transactional_state.snapshot().unwrap();
+        cached_state
+            .set_contract_class(&ClassHash([1; 32]), &contract_class)
+            .unwrap();
+
+        transactional_state.revert_to(snapshot).unwrap();
+        assert!(cached_state.get_contract_class(&ClassHash([1; 32])).is_ok());
+        assert!(transactional_state
+            .get_contract_class(&ClassHash([1; 32]))
+            .is_err());
+    }
+
     /// This test checks if deploying a contract works as expected.
     #[test]
     fn cached_state_deploy_contract_test() {
@@ -901,9 +1096,9 @@ mod tests {
         let storage_key_one = Felt252::from(1).to_bytes_be();
         let storage_key_two = Felt252::from(2).to_bytes_be();
 
-        cached_state.cache.storage_initial_values =
+        cached_state.cache_mut().storage_initial_values =
             HashMap::from([((address_one.clone(), storage_key_one), Felt252::from(1))]);
-        cached_state.cache.storage_writes = HashMap::from([
+        cached_state.cache_mut().storage_writes = HashMap::from([
             ((address_one.clone(), storage_key_one), Felt252::from(1)),
             ((address_one.clone(), storage_key_two), Felt252::from(1)),
             ((address_two.clone(), storage_key_one), Felt252::from(1)),
@@ -956,7 +1151,7 @@ mod tests {
 
         // Simulate a cache hit by adding the address to the cache and querying it again.
         cached_state
-            .cache
+            .cache_mut()
             .class_hash_writes
             .insert(address.clone(), ClassHash([0; 32]));
         let _ = <CachedState<_, _> as State>::get_class_hash_at(&mut cached_state, &address);
@@ -8,7 +8,7 @@ use crate::{
 };
 use cairo_vm::Felt252;
 use getset::{Getters, MutGetters};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 /// A [StateReader] that holds all the data in memory.
 ///
@@ -26,6 +26,8 @@ pub struct InMemoryStateReader {
     pub class_hash_to_compiled_class: HashMap<ClassHash, CompiledClass>,
     #[getset(get_mut = "pub")]
     pub class_hash_to_compiled_class_hash: HashMap<ClassHash, CompiledClassHash>,
+    #[getset(get_mut = "pub")]
+    pub block_hashes: BTreeMap<u64, Felt252>,
 }
 
 impl InMemoryStateReader {
@@ -38,6 +40,8 @@ impl InMemoryStateReader {
     /// - `class_hash_to_contract_class` - A HashMap from class hashes to their contract classes.
     /// - `casm_contract_classes` - A [CasmClassCache].
     /// - `class_hash_to_compiled_class_hash` - A HashMap from class hashes to their compiled class hashes.
+    ///
+    /// Block hashes are empty and can be added with [`InMemoryStateReader::block_hashes_mut`].
     pub const fn new(
         address_to_class_hash: HashMap<Address, ClassHash>,
         address_to_nonce: HashMap<Address, Felt252>,
@@ -51,6 +55,7 @@ impl InMemoryStateReader {
             address_to_storage,
             class_hash_to_compiled_class,
             class_hash_to_compiled_class_hash,
+            block_hashes: BTreeMap::new(),
         }
     }
 
@@ -126,6 +131,10 @@ impl StateReader for InMemoryStateReader {
             Err(StateError::MissingCasmClass(compiled_class_hash))
         }
     }
+
+    fn get_block_hash(&self, block_number: u64) -> Result<Option<Felt252>, StateError> {
+        Ok(self.block_hashes.get(&block_number).copied())
+    }
 }
 
 #[cfg(test)]
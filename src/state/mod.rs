@@ -12,12 +12,14 @@ use cairo_vm::{vm::runners::cairo_runner::ExecutionResources, Felt252};
 use getset::Getters;
 use std::{collections::HashMap, sync::Arc};
 
+pub mod block_hash;
 pub mod cached_state;
 pub mod contract_class_cache;
 pub(crate) mod contract_storage_state;
 pub mod in_memory_state_reader;
 pub mod state_api;
 pub mod state_cache;
+pub mod storage_path;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct BlockInfo {
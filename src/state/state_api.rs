@@ -26,6 +26,11 @@ pub trait StateReader {
         &self,
         class_hash: &ClassHash,
     ) -> Result<CompiledClassHash, StateError>;
+    /// Returns the hash of the given block, if the reader has access to it.
+    /// Used to populate the block hash contract, see [`crate::state::block_hash`].
+    fn get_block_hash(&self, _block_number: u64) -> Result<Option<Felt252>, StateError> {
+        Ok(None)
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
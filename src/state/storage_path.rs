@@ -0,0 +1,328 @@
+//! Storage addresses of Cairo 1 storage variables.
+//!
+//! A [`StoragePath`] starts at a storage variable, whose address is the `sn_keccak` of its name,
+//! and is extended with the keys of `LegacyMap` entries, each of them hashed into the path with
+//! pedersen. Map keys and values are serialized into felts by [`StorageValue`], the same way as
+//! Cairo's `LegacyHash` and `Store` traits do: keys spanning several felts (like `u256` or tuples)
+//! are hashed one felt at a time, which is also how nested maps are addressed, and values spanning
+//! several slots (like `u256` or structs) are stored in consecutive addresses starting at the
+//! path's address.
+
+use super::{state_api::State, state_cache::StorageEntry};
+use crate::{
+    core::errors::state_errors::StateError,
+    transaction::Address,
+    utils::{calculate_sn_keccak, field_element_to_felt},
+};
+use cairo_vm::Felt252;
+use num_traits::ToPrimitive;
+use starknet_api::core::L2_ADDRESS_UPPER_BOUND;
+use starknet_crypto::{pedersen_hash, FieldElement};
+
+// --------------------
+// StoragePath structure
+// --------------------
+
+/// The path to a value stored by a Cairo 1 contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoragePath {
+    /// The pedersen chain of the variable and keys, before being reduced to a storage address.
+    hash: Felt252,
+}
+
+impl StoragePath {
+    /// Creates the path to the storage variable `name`.
+    pub fn var(name: &str) -> Self {
+        Self {
+            hash: Felt252::from_bytes_be(&calculate_sn_keccak(name.as_bytes())),
+        }
+    }
+
+    /// Extends the path to the entry at `key` of the map it points to.
+    pub fn entry<K: StorageValue>(self, key: &K) -> Self {
+        let hash = key
+            .to_felts()
+            .iter()
+            .fold(self.hash, |hash, felt| pedersen(&hash, felt));
+        Self { hash }
+    }
+
+    /// Returns the storage address the path points to.
+    pub fn address(&self) -> Felt252 {
+        let bound = Felt252::from_bytes_be(&L2_ADDRESS_UPPER_BOUND.to_bytes_be());
+        self.hash.mod_floor(&bound.try_into().unwrap())
+    }
+
+    /// Returns the storage entry of the `offset`-th slot of the value stored at the path in
+    /// `contract_address`.
+    pub fn storage_entry(&self, contract_address: &Address, offset: usize) -> StorageEntry {
+        (
+            contract_address.clone(),
+            (self.address() + Felt252::from(offset)).to_bytes_be(),
+        )
+    }
+
+    /// Reads the value stored at the path in `contract_address`.
+    pub fn read<T: StorageValue, S: State>(
+        &self,
+        state: &mut S,
+        contract_address: &Address,
+    ) -> Result<T, StateError> {
+        let felts = (0..T::SIZE)
+            .map(|offset| state.get_storage_at(&self.storage_entry(contract_address, offset)))
+            .collect::<Result<Vec<_>, _>>()?;
+        T::from_felts(&felts)
+    }
+
+    /// Writes `value` at the path in `contract_address`.
+    pub fn write<T: StorageValue, S: State>(
+        &self,
+        state: &mut S,
+        contract_address: &Address,
+        value: &T,
+    ) {
+        for (offset, felt) in value.to_felts().into_iter().enumerate() {
+            state.set_storage_at(&self.storage_entry(contract_address, offset), felt);
+        }
+    }
+}
+
+fn pedersen(x: &Felt252, y: &Felt252) -> Felt252 {
+    // Felts are always below the field's prime, so they are valid field elements.
+    let to_field_element =
+        |felt: &Felt252| FieldElement::from_bytes_be(&felt.to_bytes_be()).unwrap();
+    field_element_to_felt(&pedersen_hash(&to_field_element(x), &to_field_element(y)))
+}
+
+// --------------------
+// StorageValue trait
+// --------------------
+
+/// A value that can be stored in, or used as a map key of, a contract's storage.
+pub trait StorageValue: Sized {
+    /// Number of felts the value is serialized into.
+    const SIZE: usize;
+
+    fn to_felts(&self) -> Vec<Felt252>;
+
+    /// Deserializes the value from exactly [`StorageValue::SIZE`] felts.
+    fn from_felts(felts: &[Felt252]) -> Result<Self, StateError>;
+}
+
+impl StorageValue for Felt252 {
+    const SIZE: usize = 1;
+
+    fn to_felts(&self) -> Vec<Felt252> {
+        vec![*self]
+    }
+
+    fn from_felts(felts: &[Felt252]) -> Result<Self, StateError> {
+        Ok(felts[0])
+    }
+}
+
+impl StorageValue for Address {
+    const SIZE: usize = 1;
+
+    fn to_felts(&self) -> Vec<Felt252> {
+        vec![self.0]
+    }
+
+    fn from_felts(felts: &[Felt252]) -> Result<Self, StateError> {
+        Ok(Address(felts[0]))
+    }
+}
+
+impl StorageValue for bool {
+    const SIZE: usize = 1;
+
+    fn to_felts(&self) -> Vec<Felt252> {
+        vec![Felt252::from(*self as u8)]
+    }
+
+    fn from_felts(felts: &[Felt252]) -> Result<Self, StateError> {
+        if felts[0] == Felt252::ZERO {
+            Ok(false)
+        } else if felts[0] == Felt252::ONE {
+            Ok(true)
+        } else {
+            Err(StateError::InvalidStorageValue(felts[0], "bool"))
+        }
+    }
+}
+
+macro_rules! impl_storage_value_for_uint {
+    ($($ty:ident => $to_primitive:ident),*) => {
+        $(
+            impl StorageValue for $ty {
+                const SIZE: usize = 1;
+
+                fn to_felts(&self) -> Vec<Felt252> {
+                    vec![Felt252::from(*self)]
+                }
+
+                fn from_felts(felts: &[Felt252]) -> Result<Self, StateError> {
+                    felts[0]
+                        .$to_primitive()
+                        .ok_or(StateError::InvalidStorageValue(felts[0], stringify!($ty)))
+                }
+            }
+        )*
+    };
+}
+
+impl_storage_value_for_uint!(
+    u8 => to_u8,
+    u16 => to_u16,
+    u32 => to_u32,
+    u64 => to_u64,
+    u128 => to_u128
+);
+
+/// A Cairo `u256`, stored as its low and high halves.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct U256 {
+    pub low: u128,
+    pub high: u128,
+}
+
+impl StorageValue for U256 {
+    const SIZE: usize = 2;
+
+    fn to_felts(&self) -> Vec<Felt252> {
+        vec![Felt252::from(self.low), Felt252::from(self.high)]
+    }
+
+    fn from_felts(felts: &[Felt252]) -> Result<Self, StateError> {
+        Ok(Self {
+            low: u128::from_felts(&felts[..1])?,
+            high: u128::from_felts(&felts[1..])?,
+        })
+    }
+}
+
+/// Tuples are stored member by member, the same way as structs, so structs can be read and
+/// written as tuples of their members.
+macro_rules! impl_storage_value_for_tuple {
+    ($($ty:ident $value:ident),*) => {
+        impl<$($ty: StorageValue),*> StorageValue for ($($ty,)*) {
+            const SIZE: usize = 0 $(+ $ty::SIZE)*;
+
+            fn to_felts(&self) -> Vec<Felt252> {
+                let ($($value,)*) = self;
+                let mut felts = Vec::with_capacity(Self::SIZE);
+                $(felts.extend($value.to_felts());)*
+                felts
+            }
+
+            fn from_felts(felts: &[Felt252]) -> Result<Self, StateError> {
+                let mut felts = felts;
+                $(
+                    let ($value, rest) = felts.split_at($ty::SIZE);
+                    felts = rest;
+                    let $value = $ty::from_felts($value)?;
+                )*
+                let _ = felts;
+                Ok(($($value,)*))
+            }
+        }
+    };
+}
+
+impl_storage_value_for_tuple!(A a, B b);
+impl_storage_value_for_tuple!(A a, B b, C c);
+impl_storage_value_for_tuple!(A a, B b, C c, D d);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        state::{
+            cached_state::CachedState, contract_class_cache::PermanentContractClassCache,
+            in_memory_state_reader::InMemoryStateReader,
+        },
+        utils::{get_erc20_balance_var_addresses, get_storage_var_address},
+    };
+    use assert_matches::assert_matches;
+    use pretty_assertions_sorted::assert_eq;
+    use std::sync::Arc;
+
+    #[test]
+    fn storage_addresses_match_cairo_0_derivation() {
+        let account = Address(0x1234.into());
+
+        assert_eq!(
+            StoragePath::var("counter").address(),
+            get_storage_var_address("counter", &[]).unwrap()
+        );
+        assert_eq!(
+            StoragePath::var("allowances")
+                .entry(&(account.clone(), Address(0x5678.into())))
+                .address(),
+            get_storage_var_address("allowances", &[account.0, 0x5678.into()]).unwrap()
+        );
+        // Nested maps are addressed the same way as maps keyed by tuples.
+        assert_eq!(
+            StoragePath::var("allowances")
+                .entry(&account)
+                .entry(&Address(0x5678.into()))
+                .address(),
+            StoragePath::var("allowances")
+                .entry(&(account.clone(), Address(0x5678.into())))
+                .address()
+        );
+
+        let (low_key, high_key) = get_erc20_balance_var_addresses(&account).unwrap();
+        let balance = StoragePath::var("ERC20_balances").entry(&account);
+        assert_eq!(balance.storage_entry(&account, 0).1, low_key);
+        assert_eq!(balance.storage_entry(&account, 1).1, high_key);
+    }
+
+    #[test]
+    fn read_and_write_typed_values() {
+        let mut state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        let contract_address = Address(0x100.into());
+        let balance = StoragePath::var("balances").entry(&U256 { low: 7, high: 0 });
+        let position = StoragePath::var("positions").entry(&Address(0x1234.into()));
+
+        let amount = U256 {
+            low: u128::MAX,
+            high: 3,
+        };
+        balance.write(&mut state, &contract_address, &amount);
+        assert_eq!(
+            balance
+                .read::<U256, _>(&mut state, &contract_address)
+                .unwrap(),
+            amount
+        );
+
+        // A struct `{ x: u64, y: u64, active: bool }` spanning three slots.
+        position.write(&mut state, &contract_address, &(10u64, 20u64, true));
+        assert_eq!(
+            position
+                .read::<(u64, u64, bool), _>(&mut state, &contract_address)
+                .unwrap(),
+            (10, 20, true)
+        );
+        assert_eq!(
+            state
+                .get_storage_at(&position.storage_entry(&contract_address, 1))
+                .unwrap(),
+            Felt252::from(20)
+        );
+
+        balance.write(
+            &mut state,
+            &contract_address,
+            &(Felt252::from(u128::MAX) + Felt252::ONE),
+        );
+        assert_matches!(
+            balance.read::<u128, _>(&mut state, &contract_address),
+            Err(StateError::InvalidStorageValue(_, "u128"))
+        );
+    }
+}
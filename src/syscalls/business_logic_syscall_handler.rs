@@ -1,10 +1,11 @@
 use super::{
     syscall_handler_errors::SyscallHandlerError,
-    syscall_info::get_syscall_size_from_name,
+    syscall_info::{get_syscall_min_version_from_name, get_syscall_size_from_name},
     syscall_request::{
         CallContractRequest, DeployRequest, EmitEventRequest, FromPtr, GetBlockHashRequest,
-        GetBlockTimestampRequest, KeccakRequest, LibraryCallRequest, ReplaceClassRequest,
-        SendMessageToL1Request, StorageReadRequest, StorageWriteRequest, SyscallRequest,
+        GetBlockTimestampRequest, GetClassHashAtRequest, KeccakRequest, LibraryCallRequest,
+        ReplaceClassRequest, SendMessageToL1Request, Sha256ProcessBlockRequest, StorageReadRequest,
+        StorageWriteRequest, SyscallRequest,
     },
     syscall_response::{
         CallContractResponse, DeployResponse, FailureReason, GetBlockHashResponse,
@@ -17,10 +18,11 @@ use crate::{
         block_context::BlockContext,
         constants::{
             BLOCK_HASH_CONTRACT_ADDRESS, CONSTRUCTOR_ENTRY_POINT_SELECTOR, EVENT_MAX_DATA_LENGTH,
-            EVENT_MAX_KEYS_LENGTH, MAX_N_EMITTED_EVENTS,
+            EVENT_MAX_KEYS_LENGTH, MAX_N_EMITTED_EVENTS, STORED_BLOCK_HASH_BUFFER,
         },
     },
     execution::{
+        backend::ExecutionConfig,
         execution_entry_point::{ExecutionEntryPoint, ExecutionResult},
         CallInfo, CallResult, CallType, OrderedEvent, OrderedL2ToL1Message,
         TransactionExecutionContext,
@@ -39,8 +41,8 @@ use crate::{
         state_api::{State, StateReader},
         BlockInfo, ExecutionResourcesManager,
     },
-    transaction::{error::TransactionError, Address, ClassHash},
-    utils::{calculate_sn_keccak, felt_to_hash, get_big_int, get_felt_range},
+    transaction::{error::TransactionError, Address, ClassHash, VersionSpecificAccountTxFields},
+    utils::{calculate_sn_keccak, felt_to_hash, get_big_int, get_felt_range, sha256_process_block},
 };
 use cairo_vm::Felt252;
 use cairo_vm::{
@@ -57,16 +59,6 @@ use std::{
     ops::{Add, Sub},
 };
 
-#[cfg(feature = "cairo-native")]
-use {
-    cairo_native::cache::ProgramCache,
-    std::{cell::RefCell, rc::Rc},
-};
-
-pub(crate) const STEP: u128 = 100;
-pub(crate) const SYSCALL_BASE: u128 = 100 * STEP;
-pub(crate) const KECCAK_ROUND_COST: u128 = 180000;
-
 lazy_static! {
     /// Felt->syscall map that was extracted from new_syscalls.json (Cairo 1.0 syscalls)
     static ref SELECTOR_TO_SYSCALL: HashMap<Felt252, &'static str> = {
@@ -91,39 +83,11 @@ lazy_static! {
             map.insert(Felt252::from_bytes_be(&calculate_sn_keccak("get_block_timestamp".as_bytes())), "get_block_timestamp");
             map.insert(Felt252::from_bytes_be(&calculate_sn_keccak("get_block_number".as_bytes())), "get_block_number");
             map.insert(Felt252::from_bytes_be_slice("Keccak".as_bytes()), "keccak");
+            map.insert(Felt252::from_bytes_be_slice("Sha256ProcessBlock".as_bytes()), "sha256_process_block");
+            map.insert(Felt252::from_bytes_be_slice("GetClassHashAt".as_bytes()), "get_class_hash_at");
 
             map
     };
-
-    // TODO: There is no reason why this could not be in the syscall enum itself AFAICT
-    // Taken from starkware/starknet/constants.py in cairo-lang
-    // See further documentation on cairo_programs/constants.cairo
-    /// Maps syscall name to gas costs
-    pub(crate) static ref SYSCALL_GAS_COST: HashMap<&'static str, u128> = {
-        let mut map = HashMap::new();
-
-        map.insert("initial", 100_000_000 * STEP);
-        map.insert("entry_point_initial_budget", 100 * STEP);
-
-        map.insert("entry_point", map["entry_point_initial_budget"] + 500 * STEP);
-        map.insert("fee_transfer", map["entry_point"] + 100 * STEP);
-        map.insert("transaction", 2 * map["entry_point"] + map["fee_transfer"] + 100 * STEP);
-
-        map.insert("call_contract", SYSCALL_BASE + 10 * STEP + map["entry_point"]);
-        map.insert("deploy", SYSCALL_BASE + 200 * STEP + map["entry_point"]);
-        map.insert("get_execution_info", SYSCALL_BASE + 10 * STEP);
-        map.insert("library_call", map["call_contract"]);
-        map.insert("replace_class", SYSCALL_BASE + 50 * STEP);
-        map.insert("storage_read", SYSCALL_BASE + 50 * STEP);
-        map.insert("storage_write", SYSCALL_BASE + 50 * STEP);
-        map.insert("emit_event", SYSCALL_BASE + 10 * STEP);
-        map.insert("send_message_to_l1", SYSCALL_BASE + 50 * STEP);
-        map.insert("get_block_timestamp", 0);
-        map.insert("keccak", 0);
-        map.insert("get_block_hash", SYSCALL_BASE + 50 * STEP);
-
-        map
-    };
 }
 
 /// Structure representing the [BusinessLogicSyscallHandler].
@@ -158,6 +122,8 @@ pub struct BusinessLogicSyscallHandler<'a, S: StateReader, C: ContractClassCache
     /// Map selectors to their corresponding syscall names.
     pub(crate) selector_to_syscall: &'a HashMap<Felt252, &'static str>,
     pub(crate) execution_info_ptr: Option<Relocatable>,
+    /// Configuration used to execute the contracts called through syscalls.
+    pub(crate) execution_config: ExecutionConfig,
 }
 
 // TODO: execution entry point may no be a parameter field, but there is no way to generate a default for now
@@ -175,6 +141,7 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         syscall_ptr: Relocatable,
         support_reverted: bool,
         entry_point_selector: Felt252,
+        execution_config: ExecutionConfig,
     ) -> Self {
         let events = Vec::new();
         let read_only_segments = Vec::new();
@@ -198,6 +165,7 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
             entry_point_selector,
             selector_to_syscall: &SELECTOR_TO_SYSCALL,
             execution_info_ptr: None,
+            execution_config,
         }
     }
 
@@ -269,6 +237,7 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
             entry_point_selector,
             selector_to_syscall: &SELECTOR_TO_SYSCALL,
             execution_info_ptr: None,
+            execution_config: ExecutionConfig::default(),
         }
     }
 
@@ -284,9 +253,6 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         vm: &mut VirtualMachine,
         remaining_gas: u128,
         execution_entry_point: ExecutionEntryPoint,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<SyscallResponse, SyscallHandlerError> {
         let ExecutionResult {
             call_info,
@@ -300,13 +266,14 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
                 &mut self.tx_execution_context,
                 false,
                 self.block_context.invoke_tx_max_n_steps,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
+                &self.execution_config,
             )
             .map_err(|err| SyscallHandlerError::ExecutionError(err.to_string()))?;
 
         let call_info = call_info.ok_or(SyscallHandlerError::ExecutionError(
-            revert_error.unwrap_or_else(|| "Execution error".to_string()),
+            revert_error
+                .map(|e| e.to_string())
+                .unwrap_or_else(|| "Execution error".to_string()),
         ))?;
 
         let retdata_maybe_reloc = call_info
@@ -371,9 +338,6 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         class_hash_bytes: ClassHash,
         constructor_calldata: Vec<Felt252>,
         remaining_gas: u128,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<CallResult, StateError> {
         let compiled_class = if let Ok(compiled_class) = self
             .starknet_storage_state
@@ -427,13 +391,14 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
                 &mut self.tx_execution_context,
                 self.support_reverted,
                 self.block_context.invoke_tx_max_n_steps,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
+                &self.execution_config,
             )
             .map_err(|_| StateError::ExecutionEntryPoint)?;
 
         let call_info = call_info.ok_or(StateError::CustomError(
-            revert_error.unwrap_or_else(|| "Execution error".to_string()),
+            revert_error
+                .map(|e| e.to_string())
+                .unwrap_or_else(|| "Execution error".to_string()),
         ))?;
 
         self.internal_calls.push(call_info.clone());
@@ -451,15 +416,21 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         &mut self,
         vm: &mut VirtualMachine,
         syscall_ptr: Relocatable,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<(), SyscallHandlerError> {
         let selector = get_big_int(vm, syscall_ptr)?;
         let syscall_name = self.selector_to_syscall.get(&selector).ok_or(
             SyscallHandlerError::SelectorNotInHandlerMap(selector.to_string()),
         )?;
 
+        let min_version = get_syscall_min_version_from_name(syscall_name);
+        if self.block_context.starknet_version < min_version {
+            return Err(SyscallHandlerError::UnsupportedSyscall(
+                syscall_name.to_string(),
+                min_version,
+                self.block_context.starknet_version,
+            ));
+        }
+
         let initial_gas: Felt252 = get_big_int(vm, (syscall_ptr + 1)?)?;
         let initial_gas = initial_gas
             .to_u128()
@@ -472,9 +443,10 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         let request = self.read_and_validate_syscall_request(vm, &mut syscall_ptr, syscall_name)?;
 
         // Check and reduce gas (after validating the syscall selector for consistency wth the OS).
-        let required_gas = SYSCALL_GAS_COST
-            .get(syscall_name)
-            .map(|&x| x.saturating_sub(SYSCALL_BASE))
+        let required_gas = self
+            .block_context
+            .syscall_costs()
+            .required_gas(syscall_name)
             .ok_or(SyscallHandlerError::SelectorDoesNotHaveAssociatedGas(
                 selector.to_string(),
             ))?;
@@ -495,13 +467,7 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         } else {
             // Execute with remaining gas.
             let remaining_gas = initial_gas - required_gas;
-            self.execute_syscall(
-                request,
-                remaining_gas,
-                vm,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            )?
+            self.execute_syscall(request, remaining_gas, vm)?
         };
 
         // Write response to the syscall segment.
@@ -519,32 +485,11 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         request: SyscallRequest,
         remaining_gas: u128,
         vm: &mut VirtualMachine,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<SyscallResponse, SyscallHandlerError> {
         match request {
-            SyscallRequest::LibraryCall(req) => self.library_call(
-                vm,
-                req,
-                remaining_gas,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            ),
-            SyscallRequest::CallContract(req) => self.call_contract(
-                vm,
-                req,
-                remaining_gas,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            ),
-            SyscallRequest::Deploy(req) => self.deploy(
-                vm,
-                req,
-                remaining_gas,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            ),
+            SyscallRequest::LibraryCall(req) => self.library_call(vm, req, remaining_gas),
+            SyscallRequest::CallContract(req) => self.call_contract(vm, req, remaining_gas),
+            SyscallRequest::Deploy(req) => self.deploy(vm, req, remaining_gas),
             SyscallRequest::StorageRead(req) => self.storage_read(vm, req, remaining_gas),
             SyscallRequest::StorageWrite(req) => self.storage_write(vm, req, remaining_gas),
             SyscallRequest::GetExecutionInfo => self.get_execution_info(vm, remaining_gas),
@@ -557,6 +502,10 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
             SyscallRequest::GetBlockHash(req) => self.get_block_hash(vm, req, remaining_gas),
             SyscallRequest::ReplaceClass(req) => self.replace_class(vm, req, remaining_gas),
             SyscallRequest::Keccak(req) => self.keccak(vm, req, remaining_gas),
+            SyscallRequest::Sha256ProcessBlock(req) => {
+                self.sha256_process_block(vm, req, remaining_gas)
+            }
+            SyscallRequest::GetClassHashAt(req) => self.get_class_hash_at(req, remaining_gas),
         }
     }
 
@@ -570,7 +519,9 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         let block_number = request.block_number;
         let current_block_number = self.block_context.block_info.block_number;
 
-        if current_block_number < 10 || block_number > current_block_number - 10 {
+        if current_block_number < STORED_BLOCK_HASH_BUFFER
+            || block_number > current_block_number - STORED_BLOCK_HASH_BUFFER
+        {
             let out_of_range_felt =
                 Felt252::from_bytes_be_slice("Block number out of range".as_bytes());
             let retdata_start =
@@ -803,37 +754,42 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         Ok(execution_info_ptr)
     }
 
+    /// Extends the tx info with the fields of the v2 `TxInfo`, which Cairo 1 contracts read
+    /// regardless of the transaction's version. Deprecated transactions don't have these fields,
+    /// so they are exposed as empty, the same way Cairo Native does.
     fn allocate_version_specific_tx_info(
         &mut self,
         vm: &mut VirtualMachine,
         tx_info_data: &mut Vec<MaybeRelocatable>,
     ) -> Result<(), SyscallHandlerError> {
-        match self.tx_execution_context.account_tx_fields.clone() {
-            crate::transaction::VersionSpecificAccountTxFields::Deprecated(_) => {
-                tx_info_data.extend_from_slice(&[
-                    Felt252::ZERO.into(), // Resource Bounds (start ptr).
-                    Felt252::ZERO.into(), // Resource Bounds (end ptr).
-                    Felt252::ZERO.into(), // Tip.
-                    Felt252::ZERO.into(), // Paymaster Data (start ptr).
-                    Felt252::ZERO.into(), // Paymaster Data (end ptr).
-                    Felt252::ZERO.into(), // Nonce DA mode.
-                    Felt252::ZERO.into(), // Fee DA mode.
-                    Felt252::ZERO.into(), // Account deployment Data (start ptr).
-                    Felt252::ZERO.into(), // Account deployment Data (end ptr).
-                ])
-            }
-            crate::transaction::VersionSpecificAccountTxFields::Current(fields) => {
-                // Allocate resource bounds
-                lazy_static! {
-                    static ref L1_GAS: Felt252 = Felt252::from_hex(
-                        "0x00000000000000000000000000000000000000000000000000004c315f474153"
-                    )
-                    .unwrap();
-                    static ref L2_GAS: Felt252 = Felt252::from_hex(
-                        "0x00000000000000000000000000000000000000000000000000004c325f474153"
-                    )
-                    .unwrap();
-                };
+        lazy_static! {
+            static ref L1_GAS: Felt252 = Felt252::from_hex(
+                "0x00000000000000000000000000000000000000000000000000004c315f474153"
+            )
+            .unwrap();
+            static ref L2_GAS: Felt252 = Felt252::from_hex(
+                "0x00000000000000000000000000000000000000000000000000004c325f474153"
+            )
+            .unwrap();
+        };
+
+        let (
+            resource_bounds_data,
+            tip,
+            paymaster_data,
+            nonce_data_availability_mode,
+            fee_data_availability_mode,
+            account_deployment_data,
+        ) = match &self.tx_execution_context.account_tx_fields {
+            VersionSpecificAccountTxFields::Deprecated(_) => (
+                Vec::new(),
+                Felt252::ZERO,
+                Vec::new(),
+                Felt252::ZERO,
+                Felt252::ZERO,
+                Vec::new(),
+            ),
+            VersionSpecificAccountTxFields::Current(fields) => {
                 let mut resource_bounds_data = vec![
                     *L1_GAS,
                     fields.l1_resource_bounds.max_amount.into(),
@@ -846,28 +802,35 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
                         resource_bounds.max_price_per_unit.into(),
                     ])
                 }
-                let (resource_bounds_start_ptr, resource_bounds_end_ptr) =
-                    self.allocate_felt_segment(vm, &resource_bounds_data)?;
-                // Allocate paymaster data
-                let (paymaster_data_start_ptr, paymaster_data_end_ptr) =
-                    self.allocate_felt_segment(vm, &fields.paymaster_data)?;
-                // Allocate account deployment data
-                let (account_deployment_start_ptr, account_deployment_end_ptr) =
-                    self.allocate_felt_segment(vm, &fields.account_deployment_data)?;
-                // Extend tx_info_data with version specific data
-                tx_info_data.extend_from_slice(&[
-                    resource_bounds_start_ptr.into(), // Resource Bounds (start ptr).
-                    resource_bounds_end_ptr.into(),   // Resource Bounds (end ptr).
-                    Felt252::from(fields.tip).into(), // Tip.
-                    paymaster_data_start_ptr.into(),  // Paymaster Data (start ptr).
-                    paymaster_data_end_ptr.into(),    // Paymaster Data (end ptr).
-                    Into::<Felt252>::into(fields.nonce_data_availability_mode).into(), // Nonce DA mode.
-                    Into::<Felt252>::into(fields.fee_data_availability_mode).into(), // Fee DA mode.
-                    account_deployment_start_ptr.into(), // Account deployment Data (start ptr).
-                    account_deployment_end_ptr.into(),   // Account deployment Data (end ptr).
-                ])
+                (
+                    resource_bounds_data,
+                    Felt252::from(fields.tip),
+                    fields.paymaster_data.clone(),
+                    fields.nonce_data_availability_mode.into(),
+                    fields.fee_data_availability_mode.into(),
+                    fields.account_deployment_data.clone(),
+                )
             }
-        }
+        };
+
+        let (resource_bounds_start_ptr, resource_bounds_end_ptr) =
+            self.allocate_felt_segment(vm, &resource_bounds_data)?;
+        let (paymaster_data_start_ptr, paymaster_data_end_ptr) =
+            self.allocate_felt_segment(vm, &paymaster_data)?;
+        let (account_deployment_start_ptr, account_deployment_end_ptr) =
+            self.allocate_felt_segment(vm, &account_deployment_data)?;
+
+        tx_info_data.extend_from_slice(&[
+            resource_bounds_start_ptr.into(),    // Resource Bounds (start ptr).
+            resource_bounds_end_ptr.into(),      // Resource Bounds (end ptr).
+            tip.into(),                          // Tip.
+            paymaster_data_start_ptr.into(),     // Paymaster Data (start ptr).
+            paymaster_data_end_ptr.into(),       // Paymaster Data (end ptr).
+            nonce_data_availability_mode.into(), // Nonce DA mode.
+            fee_data_availability_mode.into(),   // Fee DA mode.
+            account_deployment_start_ptr.into(), // Account deployment Data (start ptr).
+            account_deployment_end_ptr.into(),   // Account deployment Data (end ptr).
+        ]);
         Ok(())
     }
 
@@ -889,9 +852,6 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         vm: &mut VirtualMachine,
         request: CallContractRequest,
         remaining_gas: u128,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<SyscallResponse, SyscallHandlerError> {
         let calldata = get_felt_range(vm, request.calldata_start, request.calldata_end)?;
         let execution_entry_point = ExecutionEntryPoint::new(
@@ -905,13 +865,7 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
             remaining_gas,
         );
 
-        self.call_contract_helper(
-            vm,
-            remaining_gas,
-            execution_entry_point,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
-        )
+        self.call_contract_helper(vm, remaining_gas, execution_entry_point)
     }
 
     /// Performs a storage read operation.
@@ -951,9 +905,6 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         vm: &VirtualMachine,
         request: DeployRequest,
         remaining_gas: u128,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<(Address, CallResult), SyscallHandlerError> {
         if !(request.deploy_from_zero.is_zero() || request.deploy_from_zero.is_one()) {
             return Err(SyscallHandlerError::DeployFromZero(
@@ -1004,8 +955,6 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
             class_hash_bytes,
             constructor_calldata,
             remaining_gas,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
         )?;
 
         Ok((contract_address, result))
@@ -1017,17 +966,8 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         vm: &mut VirtualMachine,
         syscall_request: DeployRequest,
         mut remaining_gas: u128,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<SyscallResponse, SyscallHandlerError> {
-        let (contract_address, result) = self.syscall_deploy(
-            vm,
-            syscall_request,
-            remaining_gas,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
-        )?;
+        let (contract_address, result) = self.syscall_deploy(vm, syscall_request, remaining_gas)?;
 
         remaining_gas -= result.gas_consumed;
 
@@ -1079,6 +1019,8 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
             "send_message_to_l1" => SendMessageToL1Request::from_ptr(vm, syscall_ptr),
             "replace_class" => ReplaceClassRequest::from_ptr(vm, syscall_ptr),
             "keccak" => KeccakRequest::from_ptr(vm, syscall_ptr),
+            "sha256_process_block" => Sha256ProcessBlockRequest::from_ptr(vm, syscall_ptr),
+            "get_class_hash_at" => GetClassHashAtRequest::from_ptr(vm, syscall_ptr),
             _ => Err(SyscallHandlerError::UnknownSyscall(
                 syscall_name.to_string(),
             )),
@@ -1158,9 +1100,6 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         vm: &mut VirtualMachine,
         request: LibraryCallRequest,
         remaining_gas: u128,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<SyscallResponse, SyscallHandlerError> {
         let calldata = get_felt_range(vm, request.calldata_start, request.calldata_end)?;
         let class_hash = ClassHash::from(request.class_hash);
@@ -1175,13 +1114,7 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
             remaining_gas,
         );
 
-        self.call_contract_helper(
-            vm,
-            remaining_gas,
-            execution_entry_point,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
-        )
+        self.call_contract_helper(vm, remaining_gas, execution_entry_point)
     }
 
     /// Get the time stamp of the block.
@@ -1234,17 +1167,18 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
             });
         }
         let n_chunks = length / 17;
+        let keccak_round_gas = self.block_context.syscall_costs().keccak_round_gas;
         let mut state = [0u64; 25];
         for i in 0..n_chunks {
             // TODO: check this before the loop, taking care to preserve functionality.
-            if gas < KECCAK_ROUND_COST {
+            if gas < keccak_round_gas {
                 let response = self.failure_from_error_msg(vm, b"Syscall out of gas")?;
                 return Ok(SyscallResponse {
                     gas,
                     body: Some(response),
                 });
             }
-            gas -= KECCAK_ROUND_COST;
+            gas -= keccak_round_gas;
             let chunk_start = (request.input_start + i * 17)?;
             let chunk = get_felt_range(vm, chunk_start, (chunk_start + 17)?)?;
             for (i, val) in chunk.iter().enumerate() {
@@ -1267,6 +1201,66 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         })
     }
 
+    /// Applies the SHA-256 compression function to the given state and input block.
+    fn sha256_process_block(
+        &mut self,
+        vm: &mut VirtualMachine,
+        request: Sha256ProcessBlockRequest,
+        remaining_gas: u128,
+    ) -> Result<SyscallResponse, SyscallHandlerError> {
+        let to_u32 = |felt: &Felt252| {
+            felt.to_u32().ok_or_else(|| {
+                SyscallHandlerError::Conversion("Felt252".to_string(), "u32".to_string())
+            })
+        };
+
+        let mut state = [0u32; 8];
+        let state_felts = get_felt_range(vm, request.state_ptr, (request.state_ptr + 8)?)?;
+        for (word, felt) in state.iter_mut().zip(&state_felts) {
+            *word = to_u32(felt)?;
+        }
+        let mut block = [0u32; 16];
+        let block_felts = get_felt_range(vm, request.input_start, (request.input_start + 16)?)?;
+        for (word, felt) in block.iter_mut().zip(&block_felts) {
+            *word = to_u32(felt)?;
+        }
+
+        sha256_process_block(&mut state, &block);
+
+        let state_ptr = self.allocate_segment(
+            vm,
+            state
+                .iter()
+                .map(|word| Felt252::from(*word).into())
+                .collect(),
+        )?;
+
+        Ok(SyscallResponse {
+            gas: remaining_gas,
+            body: Some(ResponseBody::Sha256ProcessBlock { state_ptr }),
+        })
+    }
+
+    /// Returns the class hash of the contract deployed at the given address, or zero if there's
+    /// none.
+    fn get_class_hash_at(
+        &mut self,
+        request: GetClassHashAtRequest,
+        remaining_gas: u128,
+    ) -> Result<SyscallResponse, SyscallHandlerError> {
+        let class_hash = self
+            .starknet_storage_state
+            .state
+            .get_class_hash_at(&request.contract_address)?;
+
+        Ok(SyscallResponse {
+            gas: remaining_gas,
+            body: Some(ResponseBody::GetClassHashAt {
+                class_hash: Felt252::from_bytes_be(&class_hash.0),
+            }),
+        })
+    }
+
     // TODO: refactor code to use this function
     /// Constructs a failure response from an error message.
     fn failure_from_error_msg(
@@ -1283,3 +1277,208 @@ impl<'a, S: StateReader, C: ContractClassCache> BusinessLogicSyscallHandler<'a,
         }))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        definitions::block_context::StarknetVersion,
+        state::{
+            contract_class_cache::PermanentContractClassCache,
+            in_memory_state_reader::InMemoryStateReader,
+        },
+    };
+    use assert_matches::assert_matches;
+    use cairo_vm::vm::vm_core::VirtualMachine;
+    use pretty_assertions_sorted::assert_eq;
+    use std::sync::Arc;
+
+    #[test]
+    fn deprecated_tx_info_has_empty_v2_fields() {
+        let mut state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        let mut syscall_handler = BusinessLogicSyscallHandler::default_with_state(&mut state);
+        let mut vm = VirtualMachine::new(false);
+
+        let execution_info_ptr = syscall_handler
+            .get_or_allocate_execution_info(&mut vm)
+            .unwrap();
+        let tx_info_ptr = vm
+            .get_relocatable((execution_info_ptr + 1).unwrap())
+            .unwrap();
+
+        // Resource bounds, paymaster data and account deployment data are empty spans.
+        for (start, end) in [(8, 9), (11, 12), (15, 16)] {
+            let start = vm.get_relocatable((tx_info_ptr + start).unwrap()).unwrap();
+            let end = vm.get_relocatable((tx_info_ptr + end).unwrap()).unwrap();
+            assert_eq!(start, end);
+        }
+        for field in [10, 13, 14] {
+            assert_eq!(
+                vm.get_integer((tx_info_ptr + field).unwrap())
+                    .unwrap()
+                    .into_owned(),
+                Felt252::ZERO
+            );
+        }
+    }
+
+    #[test]
+    fn sha256_process_block_requires_starknet_0_13_2() {
+        let mut state = CachedState::new(
+            Arc::new(InMemoryStateReader::default()),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        let mut syscall_handler = BusinessLogicSyscallHandler::default_with_state(&mut state);
+        let mut vm = VirtualMachine::new(false);
+
+        let initial_state: Vec<MaybeRelocatable> = [
+            0x6a09e667_u32,
+            0xbb67ae85,
+            0x3c6ef372,
+            0xa54ff53a,
+            0x510e527f,
+            0x9b05688c,
+            0x1f83d9ab,
+            0x5be0cd19,
+        ]
+        .iter()
+        .map(|word| Felt252::from(*word).into())
+        .collect();
+        // The padded block of "abc".
+        let mut block = vec![MaybeRelocatable::from(Felt252::from(0x61626380_u32))];
+        block.extend(vec![Felt252::ZERO.into(); 14]);
+        block.push(Felt252::from(0x18).into());
+
+        let state_ptr = vm.add_memory_segment();
+        vm.load_data(state_ptr, &initial_state).unwrap();
+        let input_start = vm.add_memory_segment();
+        vm.load_data(input_start, &block).unwrap();
+        let syscall_ptr = vm.add_memory_segment();
+        vm.load_data(
+            syscall_ptr,
+            &vec![
+                Felt252::from_bytes_be_slice(b"Sha256ProcessBlock").into(),
+                Felt252::from(10_000_000).into(),
+                state_ptr.into(),
+                input_start.into(),
+            ],
+        )
+        .unwrap();
+
+        assert_matches!(
+            syscall_handler.syscall(&mut vm, syscall_ptr),
+            Err(SyscallHandlerError::UnsupportedSyscall(..))
+        );
+
+        syscall_handler
+            .block_context
+            .set_starknet_version(StarknetVersion::V0_13_2);
+        syscall_handler.syscall(&mut vm, syscall_ptr).unwrap();
+
+        let response = (syscall_ptr + 4).unwrap();
+        assert_eq!(
+            vm.get_integer(response).unwrap().into_owned(),
+            Felt252::from(
+                10_000_000
+                    - syscall_handler
+                        .block_context
+                        .syscall_costs()
+                        .required_gas("sha256_process_block")
+                        .unwrap()
+            )
+        );
+        assert_eq!(
+            vm.get_integer((response + 1).unwrap())
+                .unwrap()
+                .into_owned(),
+            Felt252::ZERO
+        );
+        let new_state_ptr = vm.get_relocatable((response + 2).unwrap()).unwrap();
+        let new_state = get_felt_range(&vm, new_state_ptr, (new_state_ptr + 8).unwrap()).unwrap();
+        let expected_state: Vec<Felt252> = [
+            0xba7816bf_u32,
+            0x8f01cfea,
+            0x414140de,
+            0x5dae2223,
+            0xb00361a3,
+            0x96177a9c,
+            0xb410ff61,
+            0xf20015ad,
+        ]
+        .iter()
+        .map(|word| Felt252::from(*word))
+        .collect();
+        assert_eq!(new_state, expected_state);
+    }
+
+    /// Runs the `get_class_hash_at` syscall for `address` and returns the class hash.
+    fn get_class_hash_at<S: StateReader, C: ContractClassCache>(
+        syscall_handler: &mut BusinessLogicSyscallHandler<S, C>,
+        vm: &mut VirtualMachine,
+        address: &Address,
+    ) -> Result<Felt252, SyscallHandlerError> {
+        let syscall_ptr = vm.add_memory_segment();
+        vm.load_data(
+            syscall_ptr,
+            &vec![
+                Felt252::from_bytes_be_slice(b"GetClassHashAt").into(),
+                Felt252::from(10_000_000).into(),
+                address.0.into(),
+            ],
+        )
+        .unwrap();
+        syscall_handler.syscall(vm, syscall_ptr)?;
+
+        let response = (syscall_ptr + 3).unwrap();
+        assert_eq!(
+            vm.get_integer((response + 1).unwrap())
+                .unwrap()
+                .into_owned(),
+            Felt252::ZERO
+        );
+        Ok(vm
+            .get_integer((response + 2).unwrap())
+            .unwrap()
+            .into_owned())
+    }
+
+    #[test]
+    fn get_class_hash_at_requires_starknet_0_13_4() {
+        let deployed_address = Address(1.into());
+        let class_hash = ClassHash([1; 32]);
+        let mut state_reader = InMemoryStateReader::default();
+        state_reader
+            .address_to_class_hash_mut()
+            .insert(deployed_address.clone(), class_hash);
+        let mut state = CachedState::new(
+            Arc::new(state_reader),
+            Arc::new(PermanentContractClassCache::default()),
+        );
+        let mut syscall_handler = BusinessLogicSyscallHandler::default_with_state(&mut state);
+        let mut vm = VirtualMachine::new(false);
+
+        syscall_handler
+            .block_context
+            .set_starknet_version(StarknetVersion::V0_13_3);
+        assert_matches!(
+            get_class_hash_at(&mut syscall_handler, &mut vm, &deployed_address),
+            Err(SyscallHandlerError::UnsupportedSyscall(..))
+        );
+
+        syscall_handler
+            .block_context
+            .set_starknet_version(StarknetVersion::V0_13_4);
+        assert_eq!(
+            get_class_hash_at(&mut syscall_handler, &mut vm, &deployed_address).unwrap(),
+            Felt252::from_bytes_be(&class_hash.0)
+        );
+        // Addresses without a deployed contract have a zero class hash.
+        assert_eq!(
+            get_class_hash_at(&mut syscall_handler, &mut vm, &Address(2.into())).unwrap(),
+            Felt252::ZERO
+        );
+    }
+}
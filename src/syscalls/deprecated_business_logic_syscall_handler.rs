@@ -17,6 +17,7 @@ use crate::{
         constants::{CONSTRUCTOR_ENTRY_POINT_SELECTOR, INITIAL_GAS_COST},
     },
     execution::{
+        backend::ExecutionConfig,
         execution_entry_point::{ExecutionEntryPoint, ExecutionResult},
         *,
     },
@@ -44,12 +45,6 @@ use cairo_vm::{
 };
 use num_traits::{One, ToPrimitive, Zero};
 
-#[cfg(feature = "cairo-native")]
-use {
-    cairo_native::cache::ProgramCache,
-    std::{cell::RefCell, rc::Rc},
-};
-
 //* -----------------------------------
 //* DeprecatedBLSyscallHandler implementation
 //* -----------------------------------
@@ -80,10 +75,13 @@ pub struct DeprecatedBLSyscallHandler<'a, S: StateReader, C: ContractClassCache>
     pub(crate) internal_calls: Vec<CallInfo>,
     /// Get the expected pointer to the syscall
     pub(crate) expected_syscall_ptr: Relocatable,
+    /// Configuration used to execute the contracts called through syscalls
+    pub(crate) execution_config: ExecutionConfig,
 }
 
 impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S, C> {
     /// Constructor creates a new [DeprecatedBLSyscallHandler] instance
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         tx_execution_context: TransactionExecutionContext,
         state: &'a mut CachedState<S, C>,
@@ -92,6 +90,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         contract_address: Address,
         block_context: BlockContext,
         syscall_ptr: Relocatable,
+        execution_config: ExecutionConfig,
     ) -> Self {
         let events = Vec::new();
         let read_only_segments = Vec::new();
@@ -114,6 +113,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
             starknet_storage_state,
             internal_calls,
             expected_syscall_ptr: syscall_ptr,
+            execution_config,
         }
     }
 
@@ -184,6 +184,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
             starknet_storage_state,
             internal_calls,
             expected_syscall_ptr,
+            execution_config: ExecutionConfig::default(),
         }
     }
 
@@ -234,9 +235,6 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         contract_address: &Address,
         class_hash_bytes: ClassHash,
         constructor_calldata: Vec<Felt252>,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<(), StateError> {
         let contract_class = self
             .starknet_storage_state
@@ -279,8 +277,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
                 &mut self.tx_execution_context,
                 false,
                 self.block_context.invoke_tx_max_n_steps,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
+                &self.execution_config,
             )
             .map_err(|_| StateError::ExecutionEntryPoint)?;
 
@@ -337,9 +334,6 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         &mut self,
         vm: &VirtualMachine,
         syscall_ptr: Relocatable,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<Address, SyscallHandlerError> {
         let request = match self.read_and_validate_syscall_request("deploy", vm, syscall_ptr)? {
             DeprecatedSyscallRequest::Deploy(request) => request,
@@ -392,8 +386,6 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
             &deploy_contract_address,
             class_hash_bytes,
             constructor_calldata,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
         )?;
         Ok(deploy_contract_address)
     }
@@ -404,9 +396,6 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         syscall_name: &str,
         vm: &VirtualMachine,
         syscall_ptr: Relocatable,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<Vec<Felt252>, SyscallHandlerError> {
         let request = self.read_and_validate_syscall_request(syscall_name, vm, syscall_ptr)?;
 
@@ -507,13 +496,14 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
                 &mut self.tx_execution_context,
                 false,
                 self.block_context.invoke_tx_max_n_steps,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
+                &self.execution_config,
             )
             .map_err(|e| SyscallHandlerError::ExecutionError(e.to_string()))?;
 
         let call_info = call_info.ok_or(SyscallHandlerError::ExecutionError(
-            revert_error.unwrap_or_else(|| "Execution error".to_string()),
+            revert_error
+                .map(|e| e.to_string())
+                .unwrap_or_else(|| "Execution error".to_string()),
         ))?;
 
         let retdata = call_info.retdata.clone();
@@ -551,17 +541,8 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         &mut self,
         vm: &mut VirtualMachine,
         syscall_ptr: Relocatable,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<(), SyscallHandlerError> {
-        self.call_contract_and_write_response(
-            "delegate_l1_handler",
-            vm,
-            syscall_ptr,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
-        )
+        self.call_contract_and_write_response("delegate_l1_handler", vm, syscall_ptr)
     }
 
     /// Gets the address of the contract who raised the system call.
@@ -690,16 +671,8 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         &mut self,
         vm: &mut VirtualMachine,
         syscall_ptr: Relocatable,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<(), SyscallHandlerError> {
-        let contract_address = self.syscall_deploy(
-            vm,
-            syscall_ptr,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
-        )?;
+        let contract_address = self.syscall_deploy(vm, syscall_ptr)?;
 
         let response = DeprecatedDeployResponse::new(
             contract_address.0,
@@ -720,17 +693,8 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         syscall_name: &str,
         vm: &mut VirtualMachine,
         syscall_ptr: Relocatable,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<(), SyscallHandlerError> {
-        let retdata = self.syscall_call_contract(
-            syscall_name,
-            vm,
-            syscall_ptr,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
-        )?;
+        let retdata = self.syscall_call_contract(syscall_name, vm, syscall_ptr)?;
 
         let retdata_maybe_reloc = retdata
             .clone()
@@ -881,17 +845,8 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         &mut self,
         vm: &mut VirtualMachine,
         syscall_ptr: Relocatable,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<(), SyscallHandlerError> {
-        self.call_contract_and_write_response(
-            "library_call",
-            vm,
-            syscall_ptr,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
-        )
+        self.call_contract_and_write_response("library_call", vm, syscall_ptr)
     }
 
     /// Calls the requested function specific to an L1 handler
@@ -899,17 +854,8 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         &mut self,
         vm: &mut VirtualMachine,
         syscall_ptr: Relocatable,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<(), SyscallHandlerError> {
-        self.call_contract_and_write_response(
-            "library_call_l1_handler",
-            vm,
-            syscall_ptr,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
-        )
+        self.call_contract_and_write_response("library_call_l1_handler", vm, syscall_ptr)
     }
 
     /// Executes a contract call
@@ -917,17 +863,8 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         &mut self,
         vm: &mut VirtualMachine,
         syscall_ptr: Relocatable,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<(), SyscallHandlerError> {
-        self.call_contract_and_write_response(
-            "call_contract",
-            vm,
-            syscall_ptr,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
-        )
+        self.call_contract_and_write_response("call_contract", vm, syscall_ptr)
     }
 
     /// Executes a delegate call
@@ -935,17 +872,8 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedBLSyscallHandler<'a, S
         &mut self,
         vm: &mut VirtualMachine,
         syscall_ptr: Relocatable,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
     ) -> Result<(), SyscallHandlerError> {
-        self.call_contract_and_write_response(
-            "delegate_call",
-            vm,
-            syscall_ptr,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
-        )
+        self.call_contract_and_write_response("delegate_call", vm, syscall_ptr)
     }
 
     /// Reads a value from the storage state using the specified address.
@@ -1133,12 +1061,7 @@ mod tests {
         );
 
         assert_matches!(
-            syscall.syscall_deploy(
-                &vm,
-                relocatable!(1, 0),
-                #[cfg(feature = "cairo-native")]
-                None,
-            ),
+            syscall.syscall_deploy(&vm, relocatable!(1, 0),),
             Err(SyscallHandlerError::DeployFromZero(4))
         )
     }
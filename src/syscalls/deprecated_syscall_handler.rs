@@ -82,13 +82,7 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedSyscallHintProcessor<'
             ADDR_IS_250 => other_syscalls::addr_is_250(vm, hint_data),
             DEPLOY => {
                 let syscall_ptr = get_syscall_ptr(vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
-                self.syscall_handler.deploy(
-                    vm,
-                    syscall_ptr,
-                    // TODO: Get the program_cache somehow.
-                    #[cfg(feature = "cairo-native")]
-                    None,
-                )
+                self.syscall_handler.deploy(vm, syscall_ptr)
             }
             EMIT_EVENT_CODE => {
                 let syscall_ptr = get_syscall_ptr(vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
@@ -112,33 +106,16 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedSyscallHintProcessor<'
             }
             LIBRARY_CALL => {
                 let syscall_ptr = get_syscall_ptr(vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
-                self.syscall_handler.library_call(
-                    vm,
-                    syscall_ptr,
-                    // TODO: Get the program_cache somehow.
-                    #[cfg(feature = "cairo-native")]
-                    None,
-                )
+                self.syscall_handler.library_call(vm, syscall_ptr)
             }
             LIBRARY_CALL_L1_HANDLER => {
                 let syscall_ptr = get_syscall_ptr(vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
-                self.syscall_handler.library_call_l1_handler(
-                    vm,
-                    syscall_ptr,
-                    // TODO: Get the program_cache somehow.
-                    #[cfg(feature = "cairo-native")]
-                    None,
-                )
+                self.syscall_handler
+                    .library_call_l1_handler(vm, syscall_ptr)
             }
             CALL_CONTRACT => {
                 let syscall_ptr = get_syscall_ptr(vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
-                self.syscall_handler.call_contract(
-                    vm,
-                    syscall_ptr,
-                    // TODO: Get the program_cache somehow.
-                    #[cfg(feature = "cairo-native")]
-                    None,
-                )
+                self.syscall_handler.call_contract(vm, syscall_ptr)
             }
             STORAGE_READ => {
                 let syscall_ptr = get_syscall_ptr(vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
@@ -166,23 +143,11 @@ impl<'a, S: StateReader, C: ContractClassCache> DeprecatedSyscallHintProcessor<'
             }
             DELEGATE_CALL => {
                 let syscall_ptr = get_syscall_ptr(vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
-                self.syscall_handler.delegate_call(
-                    vm,
-                    syscall_ptr,
-                    // TODO: Get the program_cache somehow.
-                    #[cfg(feature = "cairo-native")]
-                    None,
-                )
+                self.syscall_handler.delegate_call(vm, syscall_ptr)
             }
             DELEGATE_L1_HANDLER => {
                 let syscall_ptr = get_syscall_ptr(vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
-                self.syscall_handler.delegate_l1_handler(
-                    vm,
-                    syscall_ptr,
-                    // TODO: Get the program_cache somehow.
-                    #[cfg(feature = "cairo-native")]
-                    None,
-                )
+                self.syscall_handler.delegate_l1_handler(vm, syscall_ptr)
             }
             REPLACE_CLASS => {
                 let syscall_ptr = get_syscall_ptr(vm, &hint_data.ids_data, &hint_data.ap_tracking)?;
@@ -550,6 +515,7 @@ mod tests {
             nonce: 263.into(),
             n_sent_messages: 52,
             _n_steps: 100000,
+            revert_error: None,
         };
         syscall_handler_hint_processor
             .syscall_handler
@@ -883,6 +849,7 @@ mod tests {
             nonce: 263.into(),
             n_sent_messages: 52,
             _n_steps: 10000,
+            revert_error: None,
         };
         syscall_handler_hint_processor
             .syscall_handler
@@ -1247,8 +1214,7 @@ mod tests {
                 &mut transactional,
                 &BlockContext::default(),
                 0,
-                #[cfg(feature = "cairo-native")]
-                None,
+                &Default::default(),
             )
             .unwrap();
 
@@ -3,11 +3,12 @@ use crate::{
     definitions::{
         block_context::BlockContext,
         constants::{
-            CONSTRUCTOR_ENTRY_POINT_SELECTOR, EVENT_MAX_DATA_LENGTH, EVENT_MAX_KEYS_LENGTH,
-            MAX_N_EMITTED_EVENTS,
+            BLOCK_HASH_CONTRACT_ADDRESS, CONSTRUCTOR_ENTRY_POINT_SELECTOR, EVENT_MAX_DATA_LENGTH,
+            EVENT_MAX_KEYS_LENGTH, MAX_N_EMITTED_EVENTS, STORED_BLOCK_HASH_BUFFER,
         },
     },
     execution::{
+        backend::ExecutionConfig,
         execution_entry_point::{ExecutionEntryPoint, ExecutionResult},
         CallInfo, CallResult, CallType, OrderedEvent, OrderedL2ToL1Message,
         TransactionExecutionContext,
@@ -22,29 +23,26 @@ use crate::{
         ExecutionResourcesManager,
     },
     syscalls::{
-        business_logic_syscall_handler::{KECCAK_ROUND_COST, SYSCALL_BASE, SYSCALL_GAS_COST},
         syscall_handler_errors::SyscallHandlerError,
+        syscall_info::get_syscall_min_version_from_name,
     },
     transaction::{error::TransactionError, Address, ClassHash},
     utils::felt_to_hash,
     ContractClassCache, EntryPointType, VersionSpecificAccountTxFields,
 };
-use cairo_native::{
-    cache::ProgramCache,
-    starknet::{
-        BlockInfo, ExecutionInfo, ExecutionInfoV2, ResourceBounds, Secp256k1Point, Secp256r1Point,
-        StarkNetSyscallHandler, SyscallResult, TxInfo, TxV2Info, U256,
-    },
+use cairo_native::starknet::{
+    BlockInfo, ExecutionInfo, ExecutionInfoV2, ResourceBounds, Secp256k1Point, Secp256r1Point,
+    StarkNetSyscallHandler, SyscallResult, TxInfo, TxV2Info, U256,
 };
 use cairo_vm::Felt252;
 use k256::elliptic_curve::sec1::{FromEncodedPoint, ToEncodedPoint};
 use sec1::point::Coordinates;
 use sha3::digest::generic_array::GenericArray;
 use starknet::core::utils::cairo_short_string_to_felt;
-use std::{cell::RefCell, iter::once, rc::Rc};
+use std::iter::once;
 
 #[derive(Debug)]
-pub struct NativeSyscallHandler<'a, 'cache, S, C>
+pub struct NativeSyscallHandler<'a, S, C>
 where
     S: StateReader,
     C: ContractClassCache,
@@ -59,16 +57,29 @@ where
     pub(crate) tx_execution_context: TransactionExecutionContext,
     pub(crate) block_context: BlockContext,
     pub(crate) internal_calls: Vec<CallInfo>,
-    pub(crate) program_cache: Rc<RefCell<ProgramCache<'cache, ClassHash>>>,
+    pub(crate) execution_config: ExecutionConfig,
+    /// Gas charged for the syscalls, including their base cost.
+    pub(crate) syscalls_gas: u128,
 }
 
-impl<'a, 'cache, S: StateReader, C: ContractClassCache> NativeSyscallHandler<'a, 'cache, S, C> {
+impl<'a, S: StateReader, C: ContractClassCache> NativeSyscallHandler<'a, S, C> {
     /// Generic code that needs to be run on all syscalls.
     fn handle_syscall_request(&mut self, gas: &mut u128, syscall_name: &str) -> SyscallResult<()> {
-        let required_gas = SYSCALL_GAS_COST
+        let min_version = get_syscall_min_version_from_name(syscall_name);
+        if self.block_context.starknet_version < min_version {
+            tracing::debug!(
+                "{syscall_name} requires Starknet {min_version}, but the block is executed with Starknet {}",
+                self.block_context.starknet_version
+            );
+            return Err(vec![Felt252::from_bytes_be_slice(b"Unsupported syscall")]);
+        }
+
+        let syscall_costs = self.block_context.syscall_costs();
+        let syscall_gas = syscall_costs
             .get(syscall_name)
-            .map(|&x| x.saturating_sub(SYSCALL_BASE))
+            .and_then(|cost| cost.gas)
             .unwrap_or(0);
+        let required_gas = syscall_gas.saturating_sub(syscall_costs.syscall_base_gas);
 
         if *gas < required_gas {
             let out_of_gas_felt = Felt252::from_bytes_be_slice("Out of gas".as_bytes());
@@ -77,16 +88,22 @@ impl<'a, 'cache, S: StateReader, C: ContractClassCache> NativeSyscallHandler<'a,
         }
 
         *gas = gas.saturating_sub(required_gas);
+        self.syscalls_gas += syscall_gas;
 
         self.resources_manager
             .increment_syscall_counter(syscall_name, 1);
 
         Ok(())
     }
+
+    // The `StarkNetSyscallHandler` of the pinned Cairo Native doesn't declare the
+    // `sha256_process_block` and `get_class_hash_at` syscalls added after Starknet 0.13.1, so
+    // classes using them can only be run by the VM until Cairo Native is updated. The
+    // `ExecutionPolicy` never runs them natively.
 }
 
-impl<'a, 'cache, S: StateReader, C: ContractClassCache> StarkNetSyscallHandler
-    for NativeSyscallHandler<'a, 'cache, S, C>
+impl<'a, S: StateReader, C: ContractClassCache> StarkNetSyscallHandler
+    for NativeSyscallHandler<'a, S, C>
 {
     fn get_block_hash(
         &mut self,
@@ -98,18 +115,19 @@ impl<'a, 'cache, S: StateReader, C: ContractClassCache> StarkNetSyscallHandler
 
         let current_block_number = self.block_context.block_info.block_number;
 
-        if current_block_number < 10 || block_number > current_block_number - 10 {
+        if current_block_number < STORED_BLOCK_HASH_BUFFER
+            || block_number > current_block_number - STORED_BLOCK_HASH_BUFFER
+        {
             let out_of_range_felt =
                 Felt252::from_bytes_be_slice("Block number out of range".as_bytes());
             return Err(vec![out_of_range_felt]);
         }
         let key: Felt252 = block_number.into();
-        let block_hash_address = Address(1.into());
 
         match self
             .starknet_storage_state
             .state
-            .get_storage_at(&(block_hash_address, key.to_bytes_be()))
+            .get_storage_at(&(BLOCK_HASH_CONTRACT_ADDRESS.clone(), key.to_bytes_be()))
         {
             Ok(value) => Ok(value),
             Err(e) => Err(vec![Felt252::from_bytes_be_slice(e.to_string().as_bytes())]),
@@ -267,11 +285,13 @@ impl<'a, 'cache, S: StateReader, C: ContractClassCache> StarkNetSyscallHandler
             &mut self.tx_execution_context,
             false,
             self.block_context.invoke_tx_max_n_steps,
-            Some(self.program_cache.clone()),
+            &self.execution_config,
         )?;
 
         let call_info = call_info.ok_or(SyscallHandlerError::ExecutionError(
-            revert_error.unwrap_or_else(|| "Execution error".to_string()),
+            revert_error
+                .map(|e| e.to_string())
+                .unwrap_or_else(|| "Execution error".to_string()),
         ))?;
 
         let remaining_gas = gas.saturating_sub(call_info.gas_consumed);
@@ -331,7 +351,7 @@ impl<'a, 'cache, S: StateReader, C: ContractClassCache> StarkNetSyscallHandler
                 &mut self.tx_execution_context,
                 false,
                 self.block_context.invoke_tx_max_n_steps,
-                Some(self.program_cache.clone()),
+                &self.execution_config,
             )
             .unwrap();
 
@@ -463,15 +483,16 @@ impl<'a, 'cache, S: StateReader, C: ContractClassCache> StarkNetSyscallHandler
         }
 
         let n_chunks = length / 17;
+        let keccak_round_gas = self.block_context.syscall_costs().keccak_round_gas;
         let mut state = [0u64; 25];
 
         for i in 0..n_chunks {
-            if *gas < KECCAK_ROUND_COST {
+            if *gas < keccak_round_gas {
                 let error_msg = b"Syscall out of gas";
                 let felt_error = Felt252::from_bytes_be_slice(error_msg);
                 return Err(vec![felt_error]);
             }
-            *gas -= KECCAK_ROUND_COST;
+            *gas -= keccak_round_gas;
             let chunk = &input[i * 17..(i + 1) * 17]; //(request.input_start + i * 17)?;
             for (i, val) in chunk.iter().enumerate() {
                 state[i] ^= val;
@@ -1072,7 +1093,7 @@ impl<'a, 'cache, S: StateReader, C: ContractClassCache> StarkNetSyscallHandler
     }
 }
 
-impl<'a, 'cache, S, C> NativeSyscallHandler<'a, 'cache, S, C>
+impl<'a, S, C> NativeSyscallHandler<'a, S, C>
 where
     S: StateReader,
     C: ContractClassCache,
@@ -1132,7 +1153,7 @@ where
                 &mut self.tx_execution_context,
                 false,
                 u64::MAX,
-                Some(self.program_cache.clone()),
+                &self.execution_config,
             )
             .map_err(|_| StateError::ExecutionEntryPoint)?;
 
@@ -1239,11 +1260,9 @@ mod test {
         cached_state::CachedState, contract_class_cache::PermanentContractClassCache,
         in_memory_state_reader::InMemoryStateReader,
     };
-    use cairo_native::{cache::JitProgramCache, context::NativeContext};
 
     #[derive(Default)]
     struct TestContext {
-        pub native_context: NativeContext,
         pub cached_state: CachedState<InMemoryStateReader, PermanentContractClassCache>,
     }
 
@@ -1265,9 +1284,8 @@ mod test {
                 tx_execution_context: TransactionExecutionContext::default(),
                 block_context: BlockContext::default(),
                 internal_calls: Vec::new(),
-                program_cache: Rc::new(RefCell::new(ProgramCache::Jit(JitProgramCache::new(
-                    &self.native_context,
-                )))),
+                execution_config: ExecutionConfig::default(),
+                syscalls_gas: 0,
             }
         }
     }
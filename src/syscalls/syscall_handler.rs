@@ -71,13 +71,7 @@ impl<'a, S: StateReader, C: ContractClassCache> HintProcessorLogic
                     StarknetHint::SystemCall { system } => {
                         let syscall_ptr = as_relocatable(vm, system)?;
                         self.syscall_handler
-                            .syscall(
-                                vm,
-                                syscall_ptr,
-                                // TODO: Get the program_cache somehow.
-                                #[cfg(feature = "cairo-native")]
-                                None,
-                            )
+                            .syscall(vm, syscall_ptr)
                             .map_err(|err| {
                                 HintError::CustomHint(
                                     format!("Syscall handler invocation error: {err}")
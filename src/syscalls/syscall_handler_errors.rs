@@ -1,5 +1,6 @@
 use crate::core::errors::hash_errors::HashError;
 use crate::core::errors::state_errors::StateError;
+use crate::definitions::block_context::StarknetVersion;
 use cairo_vm::Felt252;
 use cairo_vm::{
     types::errors::math_errors::MathError,
@@ -13,6 +14,8 @@ use thiserror::Error;
 pub enum SyscallHandlerError {
     #[error("Unknown syscall: {0}")]
     UnknownSyscall(String),
+    #[error("The syscall {0} requires Starknet {1}, but the block is executed with Starknet {2}")]
+    UnsupportedSyscall(String, StarknetVersion, StarknetVersion),
     #[error("The selector '{0}' is not in the syscall handler's selector to syscall map")]
     SelectorNotInHandlerMap(String),
     #[error("The selector '{0}' does not have an associated cost")]
@@ -1,3 +1,5 @@
+use crate::definitions::block_context::StarknetVersion;
+
 pub fn get_syscall_size_from_name(syscall_name: &str) -> usize {
     match syscall_name {
         "emit_event" => 4,
@@ -11,10 +13,21 @@ pub fn get_syscall_size_from_name(syscall_name: &str) -> usize {
         "replace_class" => 1,
         "keccak" => 2,
         "get_block_hash" => 1,
+        "sha256_process_block" => 2,
+        "get_class_hash_at" => 1,
         _ => unimplemented!(),
     }
 }
 
+/// Returns the first version of Starknet supporting the syscall.
+pub fn get_syscall_min_version_from_name(syscall_name: &str) -> StarknetVersion {
+    match syscall_name {
+        "sha256_process_block" => StarknetVersion::V0_13_2,
+        "get_class_hash_at" => StarknetVersion::V0_13_4,
+        _ => StarknetVersion::V0_13_0,
+    }
+}
+
 pub fn get_deprecated_syscall_size_from_name(syscall_name: &str) -> usize {
     match syscall_name {
         "call_contract" => 7,
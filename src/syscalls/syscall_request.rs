@@ -48,6 +48,10 @@ pub(crate) enum SyscallRequest {
     ReplaceClass(ReplaceClassRequest),
     /// Computes the Keccak256 hash of the given data.
     Keccak(KeccakRequest),
+    /// Applies the SHA-256 compression function to a state and a block.
+    Sha256ProcessBlock(Sha256ProcessBlockRequest),
+    /// Gets the class hash of a deployed contract.
+    GetClassHashAt(GetClassHashAtRequest),
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -188,6 +192,21 @@ pub(crate) struct KeccakRequest {
     pub(crate) input_end: Relocatable,
 }
 
+/// Applies the SHA-256 compression function to the given state and input block.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Sha256ProcessBlockRequest {
+    /// The state, made of 8 u32 words.
+    pub(crate) state_ptr: Relocatable,
+    /// The input block start, made of 16 u32 words.
+    pub(crate) input_start: Relocatable,
+}
+
+/// Gets the class hash of the contract deployed at the given address.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct GetClassHashAtRequest {
+    pub(crate) contract_address: Address,
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~
 //  Into<SyscallRequest> implementations
 // ~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -252,6 +271,18 @@ impl From<KeccakRequest> for SyscallRequest {
     }
 }
 
+impl From<Sha256ProcessBlockRequest> for SyscallRequest {
+    fn from(request: Sha256ProcessBlockRequest) -> SyscallRequest {
+        SyscallRequest::Sha256ProcessBlock(request)
+    }
+}
+
+impl From<GetClassHashAtRequest> for SyscallRequest {
+    fn from(request: GetClassHashAtRequest) -> SyscallRequest {
+        SyscallRequest::GetClassHashAt(request)
+    }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~
 //  FromPtr trait
 // ~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -440,3 +471,30 @@ impl FromPtr for KeccakRequest {
         .into())
     }
 }
+
+impl FromPtr for Sha256ProcessBlockRequest {
+    fn from_ptr(
+        vm: &VirtualMachine,
+        syscall_ptr: Relocatable,
+    ) -> Result<SyscallRequest, SyscallHandlerError> {
+        let state_ptr = get_relocatable(vm, syscall_ptr)?;
+        let input_start = get_relocatable(vm, &syscall_ptr + 1)?;
+
+        Ok(Sha256ProcessBlockRequest {
+            state_ptr,
+            input_start,
+        }
+        .into())
+    }
+}
+
+impl FromPtr for GetClassHashAtRequest {
+    fn from_ptr(
+        vm: &VirtualMachine,
+        syscall_ptr: Relocatable,
+    ) -> Result<SyscallRequest, SyscallHandlerError> {
+        let contract_address = Address(get_big_int(vm, syscall_ptr)?);
+
+        Ok(GetClassHashAtRequest { contract_address }.into())
+    }
+}
@@ -12,6 +12,8 @@ pub(crate) enum ResponseBody {
     GetExecutionInfo { exec_info_ptr: Relocatable },
     GetBlockHash(GetBlockHashResponse),
     Keccak(KeccakResponse),
+    Sha256ProcessBlock { state_ptr: Relocatable },
+    GetClassHashAt { class_hash: Felt252 },
 }
 /// Wraps around any response body. It also contains the remaining gas after the execution.
 pub(crate) struct SyscallResponse {
@@ -64,6 +66,10 @@ impl SyscallResponse {
                 cairo_args.push(hash_low.into());
                 cairo_args.push(hash_high.into());
             }
+            Some(ResponseBody::Sha256ProcessBlock { state_ptr }) => {
+                cairo_args.push(state_ptr.into())
+            }
+            Some(ResponseBody::GetClassHashAt { class_hash }) => cairo_args.push(class_hash.into()),
             None => {}
         }
         cairo_args
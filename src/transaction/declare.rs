@@ -1,7 +1,10 @@
 use super::fee::{calculate_tx_fee, charge_fee, check_fee_bounds, run_post_execution_fee_checks};
 use super::{
-    check_account_tx_fields_version, get_tx_version, ResourceBounds, Transaction,
-    VersionSpecificAccountTxFields,
+    check_account_tx_fields_version, get_tx_version,
+    serialization::{
+        sierra_class_from_json, sierra_class_to_json, JsonTransactionType, TransactionJson,
+    },
+    ResourceBounds, Transaction, VersionSpecificAccountTxFields,
 };
 use crate::core::contract_address::{compute_casm_class_hash, compute_sierra_class_hash};
 use crate::definitions::constants::VALIDATE_RETDATA;
@@ -19,8 +22,8 @@ use crate::{
         transaction_type::TransactionType,
     },
     execution::{
-        execution_entry_point::ExecutionEntryPoint, CallType, TransactionExecutionContext,
-        TransactionExecutionInfo,
+        backend::ExecutionConfig, execution_entry_point::ExecutionEntryPoint, CallType,
+        TransactionExecutionContext, TransactionExecutionInfo,
     },
     state::{
         state_api::{State, StateReader},
@@ -39,12 +42,6 @@ use num_traits::Zero;
 use std::fmt::Debug;
 use std::sync::Arc;
 
-#[cfg(feature = "cairo-native")]
-use {
-    cairo_native::cache::ProgramCache,
-    std::{cell::RefCell, rc::Rc},
-};
-
 /// Represents a declare transaction in the starknet network.
 /// Declare creates a blueprint of a contract class that is used to deploy instances of the contract
 /// Declare is meant to be used with the new cairo contract syntax, starting from Cairo1.
@@ -259,6 +256,133 @@ impl Declare {
         )
     }
 
+    /// Returns the JSON fields of the transaction. The Sierra class is included if present,
+    /// while the CASM class never is. See [`super::serialization`].
+    pub(crate) fn to_json_fields(&self) -> Result<TransactionJson, TransactionError> {
+        let mut fields =
+            TransactionJson::new(JsonTransactionType::Declare, self.hash_value, self.version);
+        fields.sender_address = Some(self.sender_address.0);
+        fields.class_hash = Some(self.sierra_class_hash);
+        fields.compiled_class_hash = Some(self.compiled_class_hash);
+        fields.contract_class = self
+            .sierra_contract_class
+            .as_ref()
+            .map(sierra_class_to_json)
+            .transpose()?;
+        fields.signature = Some(self.signature.clone());
+        fields.nonce = Some(self.nonce);
+        fields.set_account_tx_fields(&self.account_tx_fields);
+        Ok(fields)
+    }
+
+    /// Creates a `Declare` from its JSON fields. The Sierra class hash is computed from the
+    /// contract class when missing. See [`super::serialization`].
+    pub(crate) fn from_json_fields(fields: TransactionJson) -> Result<Self, TransactionError> {
+        let transaction_hash = fields.transaction_hash()?;
+        let version = get_tx_version(fields.version);
+        if version < Felt252::TWO {
+            return Err(TransactionError::UnsupportedTxVersion(
+                "Declare".to_string(),
+                fields.version,
+                vec![2, 3],
+            ));
+        }
+
+        let account_tx_fields = fields.account_tx_fields()?;
+        let sierra_contract_class = fields
+            .contract_class
+            .map(sierra_class_from_json)
+            .transpose()?;
+        let sierra_class_hash = match (fields.class_hash, &sierra_contract_class) {
+            (Some(class_hash), _) => class_hash,
+            (None, Some(sierra_contract_class)) => {
+                compute_sierra_class_hash(sierra_contract_class)?
+            }
+            (None, None) => return Err(TransactionError::DeclareNoSierraOrCasm),
+        };
+
+        Self::new_with_sierra_class_hash_and_tx_hash(
+            sierra_contract_class,
+            sierra_class_hash,
+            None,
+            TransactionJson::required(fields.compiled_class_hash, "compiled_class_hash")?,
+            Address(TransactionJson::required(
+                fields.sender_address,
+                "sender_address",
+            )?),
+            account_tx_fields,
+            fields.version,
+            fields.signature.unwrap_or_default(),
+            fields.nonce.unwrap_or_default(),
+            transaction_hash,
+        )
+    }
+
+    /// Creates a `Declare` from the fields of a JSON-RPC `BROADCASTED_DECLARE_TXN`, computing its
+    /// hash for `chain_id`. The Sierra class is compiled, and the hash of the resulting CASM class
+    /// must match `compiled_class_hash`.
+    pub(crate) fn from_broadcasted_json_fields(
+        fields: TransactionJson,
+        chain_id: Felt252,
+    ) -> Result<Self, TransactionError> {
+        let version = get_tx_version(fields.version);
+        if version < Felt252::TWO {
+            return Err(TransactionError::UnsupportedTxVersion(
+                "Declare".to_string(),
+                fields.version,
+                vec![2, 3],
+            ));
+        }
+
+        let account_tx_fields = fields.account_tx_fields()?;
+        let sierra_contract_class = sierra_class_from_json(TransactionJson::required(
+            fields.contract_class,
+            "contract_class",
+        )?)?;
+        let sierra_class_hash = compute_sierra_class_hash(&sierra_contract_class)?;
+        let casm_class =
+            CasmContractClass::from_contract_class(sierra_contract_class.clone(), true)
+                .map_err(|e| TransactionError::SierraCompileError(e.to_string()))?;
+
+        let compiled_class_hash =
+            TransactionJson::required(fields.compiled_class_hash, "compiled_class_hash")?;
+        let casm_class_hash = compute_casm_class_hash(&casm_class)?;
+        if casm_class_hash != compiled_class_hash {
+            return Err(TransactionError::InvalidCompiledClassHash(
+                casm_class_hash.to_string(),
+                compiled_class_hash.to_string(),
+            ));
+        }
+
+        let sender_address = Address(TransactionJson::required(
+            fields.sender_address,
+            "sender_address",
+        )?);
+        let nonce = fields.nonce.unwrap_or_default();
+        let hash_value = calculate_declare_transaction_hash(
+            sierra_class_hash,
+            compiled_class_hash,
+            fields.version,
+            nonce,
+            &sender_address,
+            chain_id,
+            &account_tx_fields,
+        )?;
+
+        Self::new_with_sierra_class_hash_and_tx_hash(
+            Some(sierra_contract_class),
+            sierra_class_hash,
+            Some(casm_class),
+            compiled_class_hash,
+            sender_address,
+            account_tx_fields,
+            fields.version,
+            fields.signature.unwrap_or_default(),
+            nonce,
+            hash_value,
+        )
+    }
+
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~
     //  Account Functions
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -334,7 +458,7 @@ impl Declare {
     /// ## Parameter:
     /// - state: An state that implements the State and StateReader traits.
     /// - block_context: The block that contains the execution context
-    #[tracing::instrument(level = "debug", ret, err, skip(self, state, block_context, program_cache), fields(
+    #[tracing::instrument(level = "debug", ret, err, skip(self, state, block_context, execution_config), fields(
         tx_type = ?TransactionType::Declare,
         self.version = ?self.version,
         self.sierra_class_hash = ?self.sierra_class_hash,
@@ -347,9 +471,7 @@ impl Declare {
         &self,
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<TransactionExecutionInfo, TransactionError> {
         if !(self.version == Felt252::TWO || self.version == Felt252::THREE) {
             return Err(TransactionError::UnsupportedTxVersion(
@@ -375,8 +497,7 @@ impl Declare {
                 block_context,
                 &mut resources_manager,
                 INITIAL_GAS_COST,
-                #[cfg(feature = "cairo-native")]
-                program_cache.clone(),
+                execution_config,
             )?
         };
         self.compile_and_store_casm_class(state)?;
@@ -393,6 +514,7 @@ impl Declare {
             storage_changes,
             None,
             execution_result.n_reverted_steps,
+            block_context.syscall_costs(),
         )?;
 
         let calculated_fee = calculate_tx_fee(
@@ -420,8 +542,7 @@ impl Declare {
             block_context,
             &mut tx_execution_context,
             self.skip_fee_transfer,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
+            execution_config,
         )?;
 
         let mut tx_exec_info = TransactionExecutionInfo::new_without_fee_info(
@@ -492,9 +613,7 @@ impl Declare {
         block_context: &BlockContext,
         resources_manager: &mut ExecutionResourcesManager,
         remaining_gas: u128,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<ExecutionResult, TransactionError> {
         let calldata = [self.compiled_class_hash].to_vec();
 
@@ -521,10 +640,9 @@ impl Declare {
                 block_context,
                 resources_manager,
                 &mut tx_execution_context,
-                true,
+                false,
                 block_context.validate_max_n_steps,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
+                execution_config,
             )?
         };
 
@@ -914,8 +1032,7 @@ mod tests {
         let result = declare.execute(
             &mut CachedState::<InMemoryStateReader, PermanentContractClassCache>::default(),
             &BlockContext::default(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         );
 
         assert_matches!(
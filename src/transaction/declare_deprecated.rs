@@ -9,6 +9,7 @@ use crate::state::contract_class_cache::ContractClassCache;
 use crate::state::state_api::{State, StateReader};
 use crate::{
     execution::{
+        backend::ExecutionConfig,
         execution_entry_point::{ExecutionEntryPoint, ExecutionResult},
         CallInfo, TransactionExecutionContext, TransactionExecutionInfo,
     },
@@ -25,16 +26,16 @@ use num_traits::Zero;
 use super::fee::{
     calculate_tx_fee, charge_fee, estimate_minimal_l1_gas, run_post_execution_fee_checks,
 };
-use super::{get_tx_version, Address, ClassHash, Transaction};
+use super::{
+    get_tx_version,
+    serialization::{
+        deprecated_class_from_json, deprecated_class_to_json, JsonTransactionType, TransactionJson,
+    },
+    Address, ClassHash, Transaction,
+};
 use std::fmt::Debug;
 use std::sync::Arc;
 
-#[cfg(feature = "cairo-native")]
-use {
-    cairo_native::cache::ProgramCache,
-    std::{cell::RefCell, rc::Rc},
-};
-
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 ///  Represents an internal transaction in the StarkNet network that is a declaration of a Cairo
 ///  contract class.
@@ -174,6 +175,96 @@ impl DeclareDeprecated {
         Ok(internal_declare)
     }
 
+    /// Returns the JSON fields of the transaction. See [`super::serialization`].
+    pub(crate) fn to_json_fields(&self) -> Result<TransactionJson, TransactionError> {
+        let mut fields =
+            TransactionJson::new(JsonTransactionType::Declare, self.hash_value, self.version);
+        fields.sender_address = Some(self.sender_address.0);
+        fields.class_hash = Some(Felt252::from_bytes_be(&self.class_hash.0));
+        fields.contract_class = Some(deprecated_class_to_json(&self.contract_class)?);
+        fields.max_fee = Some(self.max_fee.into());
+        fields.signature = Some(self.signature.clone());
+        fields.nonce = Some(self.nonce);
+        Ok(fields)
+    }
+
+    /// Creates a `DeclareDeprecated` from its JSON fields, which must include the contract class.
+    /// See [`super::serialization`].
+    pub(crate) fn from_json_fields(fields: TransactionJson) -> Result<Self, TransactionError> {
+        let transaction_hash = fields.transaction_hash()?;
+        let max_fee = fields.account_tx_fields()?.max_fee();
+        let contract_class = deprecated_class_from_json(TransactionJson::required(
+            fields.contract_class,
+            "contract_class",
+        )?)?;
+        let sender_address = Address(TransactionJson::required(
+            fields.sender_address,
+            "sender_address",
+        )?);
+        let signature = fields.signature.unwrap_or_default();
+        let nonce = fields.nonce.unwrap_or_default();
+
+        match fields.class_hash {
+            Some(class_hash) => Self::new_with_tx_and_class_hash(
+                contract_class,
+                sender_address,
+                max_fee,
+                fields.version,
+                signature,
+                nonce,
+                transaction_hash,
+                felt_to_hash(&class_hash),
+            ),
+            None => Self::new_with_tx_hash(
+                contract_class,
+                sender_address,
+                max_fee,
+                fields.version,
+                signature,
+                nonce,
+                transaction_hash,
+            ),
+        }
+    }
+
+    /// Creates a `DeclareDeprecated` from the fields of a JSON-RPC `BROADCASTED_DECLARE_TXN`,
+    /// computing its hash for `chain_id`.
+    pub(crate) fn from_broadcasted_json_fields(
+        fields: TransactionJson,
+        chain_id: Felt252,
+    ) -> Result<Self, TransactionError> {
+        let max_fee = fields.account_tx_fields()?.max_fee();
+        let contract_class = deprecated_class_from_json(TransactionJson::required(
+            fields.contract_class,
+            "contract_class",
+        )?)?;
+        let sender_address = Address(TransactionJson::required(
+            fields.sender_address,
+            "sender_address",
+        )?);
+        let nonce = fields.nonce.unwrap_or_default();
+
+        // The hash is computed with the version as given, which may be a query version.
+        let hash_value = calculate_declare_deprecated_transaction_hash(
+            &contract_class,
+            chain_id,
+            &sender_address,
+            max_fee,
+            fields.version,
+            nonce,
+        )?;
+
+        Self::new_with_tx_hash(
+            contract_class,
+            sender_address,
+            max_fee,
+            fields.version,
+            fields.signature.unwrap_or_default(),
+            nonce,
+            hash_value,
+        )
+    }
+
     /// Returns the calldata.
     pub fn get_calldata(&self) -> Vec<Felt252> {
         let bytes = Felt252::from_bytes_be(&self.class_hash.0);
@@ -186,9 +277,7 @@ impl DeclareDeprecated {
         &self,
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<TransactionExecutionInfo, TransactionError> {
         // validate transaction
         let mut resources_manager = ExecutionResourcesManager::default();
@@ -199,8 +288,7 @@ impl DeclareDeprecated {
                 state,
                 block_context,
                 &mut resources_manager,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
+                execution_config,
             )?
         };
         let changes = state.count_actual_state_changes(Some((
@@ -214,6 +302,7 @@ impl DeclareDeprecated {
             changes,
             None,
             0,
+            block_context.syscall_costs(),
         )
         .map_err(|_| TransactionError::ResourcesCalculation)?;
 
@@ -249,9 +338,7 @@ impl DeclareDeprecated {
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
         resources_manager: &mut ExecutionResourcesManager,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<Option<CallInfo>, TransactionError> {
         if self.version.is_zero() {
             return Ok(None);
@@ -277,8 +364,7 @@ impl DeclareDeprecated {
             &mut self.get_execution_context(block_context.invoke_tx_max_n_steps),
             false,
             block_context.validate_max_n_steps,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
+            execution_config,
         )?;
 
         let call_info = call_info.ok_or(TransactionError::CallInfoIsNone)?;
@@ -341,7 +427,7 @@ impl DeclareDeprecated {
 
     /// Calculates actual fee used by the transaction using the execution
     /// info returned by apply(), then updates the transaction execution info with the data of the fee.
-    #[tracing::instrument(level = "debug", ret, err, skip(self, state, block_context, program_cache), fields(
+    #[tracing::instrument(level = "debug", ret, err, skip(self, state, block_context, execution_config), fields(
         tx_type = ?TransactionType::Declare,
         self.version = ?self.version,
         self.class_hash = ?self.class_hash,
@@ -353,9 +439,7 @@ impl DeclareDeprecated {
         &self,
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<TransactionExecutionInfo, TransactionError> {
         if !(self.version == Felt252::ZERO || self.version == Felt252::ONE) {
             return Err(TransactionError::UnsupportedTxVersion(
@@ -371,12 +455,7 @@ impl DeclareDeprecated {
             self.check_fee_balance(state, block_context)?;
         }
 
-        let mut tx_exec_info = self.apply(
-            state,
-            block_context,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
-        )?;
+        let mut tx_exec_info = self.apply(state, block_context, execution_config)?;
 
         let mut tx_execution_context =
             self.get_execution_context(block_context.invoke_tx_max_n_steps);
@@ -403,8 +482,7 @@ impl DeclareDeprecated {
             block_context,
             &mut tx_execution_context,
             self.skip_fee_transfer,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
+            execution_config,
         )?;
 
         state.set_contract_class(
@@ -578,12 +656,7 @@ mod tests {
         // ---------------------
         assert_eq!(
             internal_declare
-                .apply(
-                    &mut state,
-                    &BlockContext::default(),
-                    #[cfg(feature = "cairo-native")]
-                    None,
-                )
+                .apply(&mut state, &BlockContext::default(), &Default::default(),)
                 .unwrap(),
             transaction_exec_info
         );
@@ -657,23 +730,13 @@ mod tests {
         .unwrap();
 
         internal_declare
-            .execute(
-                &mut state,
-                &BlockContext::default(),
-                #[cfg(feature = "cairo-native")]
-                None,
-            )
+            .execute(&mut state, &BlockContext::default(), &Default::default())
             .unwrap();
 
         assert!(state.get_contract_class(&class_hash).is_ok());
 
         second_internal_declare
-            .execute(
-                &mut state,
-                &BlockContext::default(),
-                #[cfg(feature = "cairo-native")]
-                None,
-            )
+            .execute(&mut state, &BlockContext::default(), &Default::default())
             .unwrap();
 
         assert!(state.get_contract_class(&class_hash).is_ok());
@@ -736,20 +799,11 @@ mod tests {
         .unwrap();
 
         internal_declare
-            .execute(
-                &mut state,
-                &BlockContext::default(),
-                #[cfg(feature = "cairo-native")]
-                None,
-            )
+            .execute(&mut state, &BlockContext::default(), &Default::default())
             .unwrap();
 
-        let expected_error = internal_declare.execute(
-            &mut state,
-            &BlockContext::default(),
-            #[cfg(feature = "cairo-native")]
-            None,
-        );
+        let expected_error =
+            internal_declare.execute(&mut state, &BlockContext::default(), &Default::default());
 
         // ---------------------
         //      Comparison
@@ -789,12 +843,8 @@ mod tests {
         )
         .unwrap();
 
-        let internal_declare_error = internal_declare.execute(
-            &mut state,
-            &BlockContext::default(),
-            #[cfg(feature = "cairo-native")]
-            None,
-        );
+        let internal_declare_error =
+            internal_declare.execute(&mut state, &BlockContext::default(), &Default::default());
 
         assert!(internal_declare_error.is_err());
         assert_matches!(
@@ -861,12 +911,7 @@ mod tests {
 
         // We expect a fee transfer failure because the fee token contract is not set up
         assert_matches!(
-            internal_declare.execute(
-                &mut state,
-                &BlockContext::default(),
-                #[cfg(feature = "cairo-native")]
-                None,
-            ),
+            internal_declare.execute(&mut state, &BlockContext::default(), &Default::default(),),
             Err(TransactionError::MaxFeeExceedsBalance(_, _, _))
         );
     }
@@ -964,22 +1009,11 @@ mod tests {
         bock_context.starknet_os_config.gas_price = GasPrices::new(12, 0);
         assert!(
             declare
-                .execute(
-                    &mut state,
-                    &bock_context,
-                    #[cfg(feature = "cairo-native")]
-                    None,
-                )
+                .execute(&mut state, &bock_context, &Default::default(),)
                 .unwrap()
                 .actual_fee
                 > simulate_declare
-                    .execute(
-                        &mut state_copy,
-                        &bock_context,
-                        0,
-                        #[cfg(feature = "cairo-native")]
-                        None,
-                    )
+                    .execute(&mut state_copy, &bock_context, 0, &Default::default(),)
                     .unwrap()
                     .actual_fee,
         );
@@ -1006,8 +1040,7 @@ mod tests {
         let result = internal_declare.execute(
             &mut CachedState::<InMemoryStateReader, PermanentContractClassCache>::default(),
             &BlockContext::default(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         );
 
         assert_matches!(
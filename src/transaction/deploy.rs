@@ -1,4 +1,9 @@
-use super::{Address, ClassHash, Transaction, VersionSpecificAccountTxFields};
+use super::{
+    serialization::{
+        deprecated_class_from_json, deprecated_class_to_json, JsonTransactionType, TransactionJson,
+    },
+    Address, ClassHash, Transaction, VersionSpecificAccountTxFields,
+};
 use crate::{
     core::{
         contract_address::compute_deprecated_class_hash, errors::hash_errors::HashError,
@@ -10,6 +15,7 @@ use crate::{
         transaction_type::TransactionType,
     },
     execution::{
+        backend::ExecutionConfig,
         execution_entry_point::{ExecutionEntryPoint, ExecutionResult},
         CallInfo, TransactionExecutionContext, TransactionExecutionInfo,
     },
@@ -37,12 +43,6 @@ use std::sync::Arc;
 
 use std::fmt::Debug;
 
-#[cfg(feature = "cairo-native")]
-use {
-    cairo_native::cache::ProgramCache,
-    std::{cell::RefCell, rc::Rc},
-};
-
 /// Represents a Deploy Transaction in the starknet network
 #[derive(Debug, Clone)]
 pub struct Deploy {
@@ -131,6 +131,42 @@ impl Deploy {
         })
     }
 
+    /// Returns the JSON fields of the transaction. See [`super::serialization`].
+    pub(crate) fn to_json_fields(&self) -> Result<TransactionJson, TransactionError> {
+        let CompiledClass::Deprecated(contract_class) = &self.contract_class else {
+            return Err(TransactionError::Serialization(
+                "deploy transactions can only be serialized with a Cairo 0 class".to_string(),
+            ));
+        };
+
+        let mut fields =
+            TransactionJson::new(JsonTransactionType::Deploy, self.hash_value, self.version);
+        fields.contract_class = Some(deprecated_class_to_json(contract_class)?);
+        fields.contract_address = Some(self.contract_address.0);
+        fields.contract_address_salt = Some(self.contract_address_salt);
+        fields.class_hash = Some(Felt252::from_bytes_be(&self.contract_hash.0));
+        fields.constructor_calldata = Some(self.constructor_calldata.clone());
+        Ok(fields)
+    }
+
+    /// Creates a `Deploy` from its JSON fields, which must include the contract class. See
+    /// [`super::serialization`].
+    pub(crate) fn from_json_fields(fields: TransactionJson) -> Result<Self, TransactionError> {
+        let transaction_hash = fields.transaction_hash()?;
+        let contract_class = deprecated_class_from_json(TransactionJson::required(
+            fields.contract_class,
+            "contract_class",
+        )?)?;
+
+        Ok(Self::new_with_tx_hash(
+            TransactionJson::required(fields.contract_address_salt, "contract_address_salt")?,
+            contract_class,
+            fields.constructor_calldata.unwrap_or_default(),
+            fields.version,
+            transaction_hash,
+        )?)
+    }
+
     /// Returns the class hash of the deployed contract
     pub const fn class_hash(&self) -> ClassHash {
         self.contract_hash
@@ -160,32 +196,27 @@ impl Deploy {
         &self,
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<TransactionExecutionInfo, TransactionError> {
         state.set_contract_class(&self.contract_hash, &self.contract_class)?;
         state.deploy_contract(self.contract_address.clone(), self.contract_hash)?;
 
         if self.constructor_entry_points_empty(self.contract_class.clone())? {
             // Contract has no constructors
-            Ok(self.handle_empty_constructor(state)?)
+            Ok(self.handle_empty_constructor(state, block_context)?)
         } else {
-            self.invoke_constructor(
-                state,
-                block_context,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            )
+            self.invoke_constructor(state, block_context, execution_config)
         }
     }
 
     /// Executes the contract without constructor
     /// ## Parameters
     /// - state: A state that implements the [`State`] and [`StateReader`] traits.
+    /// - block_context: The block's execution context.
     pub fn handle_empty_constructor<S: State + StateReader>(
         &self,
         state: &mut S,
+        block_context: &BlockContext,
     ) -> Result<TransactionExecutionInfo, TransactionError> {
         if !self.constructor_calldata.is_empty() {
             return Err(TransactionError::EmptyConstructorCalldata);
@@ -208,6 +239,7 @@ impl Deploy {
             changes,
             None,
             0,
+            block_context.syscall_costs(),
         )?;
 
         Ok(TransactionExecutionInfo::new_without_fee_info(
@@ -227,9 +259,7 @@ impl Deploy {
         &self,
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<TransactionExecutionInfo, TransactionError> {
         let call = ExecutionEntryPoint::new(
             self.contract_address.clone(),
@@ -262,10 +292,10 @@ impl Deploy {
             block_context,
             &mut resources_manager,
             &mut tx_execution_context,
-            true,
+            // A contract whose constructor fails isn't deployed, so the transaction is rejected.
+            false,
             block_context.validate_max_n_steps,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
+            execution_config,
         )?;
 
         let changes = state.count_actual_state_changes(None)?;
@@ -276,6 +306,7 @@ impl Deploy {
             changes,
             None,
             n_reverted_steps,
+            block_context.syscall_costs(),
         )?;
 
         Ok(TransactionExecutionInfo::new_without_fee_info(
@@ -292,7 +323,7 @@ impl Deploy {
     /// ## Parameters
     /// - state: A state that implements the [`State`] and [`StateReader`] traits.
     /// - block_context: The block's execution context.
-    #[tracing::instrument(level = "debug", ret, err, skip(self, state, block_context, program_cache), fields(
+    #[tracing::instrument(level = "debug", ret, err, skip(self, state, block_context, execution_config), fields(
         tx_type = ?TransactionType::Deploy,
         self.version = ?self.version,
         self.contract_hash = ?self.contract_hash,
@@ -304,16 +335,9 @@ impl Deploy {
         &self,
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<TransactionExecutionInfo, TransactionError> {
-        let mut tx_exec_info = self.apply(
-            state,
-            block_context,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
-        )?;
+        let mut tx_exec_info = self.apply(state, block_context, execution_config)?;
         let (fee_transfer_info, actual_fee) = (None, 0);
         tx_exec_info.set_fee_info(actual_fee, fee_transfer_info);
 
@@ -381,12 +405,7 @@ mod tests {
         let block_context = Default::default();
 
         let _result = internal_deploy
-            .apply(
-                &mut state,
-                &block_context,
-                #[cfg(feature = "cairo-native")]
-                None,
-            )
+            .apply(&mut state, &block_context, &Default::default())
             .unwrap();
 
         assert_eq!(
@@ -438,13 +457,39 @@ mod tests {
 
         let block_context = Default::default();
 
-        let result = internal_deploy.execute(
+        let result = internal_deploy.execute(&mut state, &block_context, &Default::default());
+        assert_matches!(result.unwrap_err(), TransactionError::CairoRunner(..))
+    }
+
+    #[test]
+    fn failing_cairo0_constructor_is_not_reverted() {
+        let state_reader = Arc::new(InMemoryStateReader::default());
+        let mut state = CachedState::new(
+            state_reader,
+            Arc::new(PermanentContractClassCache::default()),
+        );
+
+        let contract_class =
+            ContractClass::from_path("starknet_programs/constructor.json").unwrap();
+
+        // The constructor takes a single argument.
+        let internal_deploy = Deploy::new(
+            0.into(),
+            contract_class,
+            vec![10.into(), 20.into()],
+            0.into(),
+            0.into(),
+        )
+        .unwrap();
+
+        // The transaction fails instead of being included as reverted with the contract deployed.
+        let result = Transaction::Deploy(internal_deploy).execute(
             &mut state,
-            &block_context,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
+            0,
+            &Default::default(),
         );
-        assert_matches!(result.unwrap_err(), TransactionError::CairoRunner(..))
+        assert_matches!(result, Err(TransactionError::CairoRunner(..)));
     }
 
     #[test]
@@ -480,12 +525,7 @@ mod tests {
 
         let block_context = Default::default();
 
-        let result = internal_deploy.execute(
-            &mut state,
-            &block_context,
-            #[cfg(feature = "cairo-native")]
-            None,
-        );
+        let result = internal_deploy.execute(&mut state, &block_context, &Default::default());
         assert_matches!(
             result.unwrap_err(),
             TransactionError::EmptyConstructorCalldata
@@ -504,6 +544,7 @@ mod tests {
             program: contract_class.program,
             entry_points_by_type: HashMap::new(),
             abi: None,
+            class_json: None,
         };
 
         // Should fail when compouting the hash due to a failed contract class
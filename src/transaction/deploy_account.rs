@@ -1,7 +1,8 @@
 use super::fee::{calculate_tx_fee, charge_fee, check_fee_bounds, run_post_execution_fee_checks};
 use super::{
-    check_account_tx_fields_version, get_tx_version, Address, ClassHash, CurrentAccountTxFields,
-    ResourceBounds, VersionSpecificAccountTxFields,
+    check_account_tx_fields_version, get_tx_version,
+    serialization::{JsonTransactionType, TransactionJson},
+    Address, ClassHash, CurrentAccountTxFields, ResourceBounds, VersionSpecificAccountTxFields,
 };
 use super::{invoke_function::verify_no_calls_to_other_contracts, Transaction};
 use crate::definitions::constants::VALIDATE_RETDATA;
@@ -22,8 +23,8 @@ use crate::{
         transaction_type::TransactionType,
     },
     execution::{
-        execution_entry_point::ExecutionEntryPoint, CallInfo, TransactionExecutionContext,
-        TransactionExecutionInfo,
+        backend::ExecutionConfig, execution_entry_point::ExecutionEntryPoint, CallInfo,
+        TransactionExecutionContext, TransactionExecutionInfo,
     },
     hash_utils::calculate_contract_address,
     services::api::{
@@ -43,12 +44,6 @@ use num_traits::Zero;
 use starknet_api::transaction::Resource;
 use std::fmt::Debug;
 
-#[cfg(feature = "cairo-native")]
-use {
-    cairo_native::cache::ProgramCache,
-    std::{cell::RefCell, rc::Rc},
-};
-
 /// Struct representing the state selector, containing contract addresses and class hashes.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct StateSelector {
@@ -67,8 +62,11 @@ pub struct DeployAccount {
     class_hash: ClassHash,
     #[getset(get = "pub")]
     constructor_calldata: Vec<Felt252>,
+    #[getset(get = "pub")]
     version: Felt252,
+    #[getset(get = "pub")]
     nonce: Felt252,
+    #[getset(get = "pub")]
     account_tx_fields: VersionSpecificAccountTxFields,
     #[getset(get = "pub")]
     hash_value: Felt252,
@@ -168,6 +166,73 @@ impl DeployAccount {
         })
     }
 
+    /// Returns the JSON fields of the transaction. See [`super::serialization`].
+    pub(crate) fn to_json_fields(&self) -> Result<TransactionJson, TransactionError> {
+        let mut fields = TransactionJson::new(
+            JsonTransactionType::DeployAccount,
+            self.hash_value,
+            self.version,
+        );
+        fields.contract_address = Some(self.contract_address.0);
+        fields.class_hash = Some(Felt252::from_bytes_be(&self.class_hash.0));
+        fields.contract_address_salt = Some(self.contract_address_salt);
+        fields.constructor_calldata = Some(self.constructor_calldata.clone());
+        fields.signature = Some(self.signature.clone());
+        fields.nonce = Some(self.nonce);
+        fields.set_account_tx_fields(&self.account_tx_fields);
+        // Deploy account transactions don't have account deployment data.
+        fields.account_deployment_data = None;
+        Ok(fields)
+    }
+
+    /// Creates a `DeployAccount` from its JSON fields. See [`super::serialization`].
+    pub(crate) fn from_json_fields(fields: TransactionJson) -> Result<Self, TransactionError> {
+        let transaction_hash = fields.transaction_hash()?;
+        let account_tx_fields = fields.account_tx_fields()?;
+
+        Self::new_with_tx_hash(
+            ClassHash::from(TransactionJson::required(fields.class_hash, "class_hash")?),
+            account_tx_fields,
+            fields.version,
+            fields.nonce.unwrap_or_default(),
+            fields.constructor_calldata.unwrap_or_default(),
+            fields.signature.unwrap_or_default(),
+            TransactionJson::required(fields.contract_address_salt, "contract_address_salt")?,
+            transaction_hash,
+        )
+    }
+
+    /// Creates a `DeployAccount` from the fields of a JSON-RPC `BROADCASTED_DEPLOY_ACCOUNT_TXN`,
+    /// computing its hash for `chain_id`.
+    pub(crate) fn from_broadcasted_json_fields(
+        mut fields: TransactionJson,
+        chain_id: Felt252,
+    ) -> Result<Self, TransactionError> {
+        let class_hash = TransactionJson::required(fields.class_hash, "class_hash")?;
+        let contract_address_salt =
+            TransactionJson::required(fields.contract_address_salt, "contract_address_salt")?;
+        let constructor_calldata = fields.constructor_calldata.as_deref().unwrap_or_default();
+        let contract_address = Address(calculate_contract_address(
+            &contract_address_salt,
+            &class_hash,
+            constructor_calldata,
+            Address(Felt252::ZERO),
+        )?);
+
+        fields.transaction_hash = Some(calculate_deploy_account_transaction_hash(
+            fields.version,
+            fields.nonce.unwrap_or_default(),
+            &contract_address,
+            contract_address_salt,
+            class_hash,
+            constructor_calldata,
+            chain_id,
+            &fields.account_tx_fields()?,
+        )?);
+
+        Self::from_json_fields(fields)
+    }
+
     pub fn get_state_selector(&self, _block_context: BlockContext) -> StateSelector {
         StateSelector {
             contract_addresses: vec![self.contract_address.clone()],
@@ -175,7 +240,7 @@ impl DeployAccount {
         }
     }
 
-    #[tracing::instrument(level = "debug", ret, err, skip(self, state, block_context, program_cache), fields(
+    #[tracing::instrument(level = "debug", ret, err, skip(self, state, block_context, execution_config), fields(
         tx_type = ?TransactionType::DeployAccount,
         self.version = ?self.version,
         self.class_hash = ?self.class_hash,
@@ -188,9 +253,7 @@ impl DeployAccount {
         &self,
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<TransactionExecutionInfo, TransactionError> {
         if !(self.version == Felt252::ONE || self.version == Felt252::THREE) {
             return Err(TransactionError::UnsupportedTxVersion(
@@ -206,12 +269,7 @@ impl DeployAccount {
             self.check_fee_balance(state, block_context)?;
         }
 
-        let mut tx_exec_info = self.apply(
-            state,
-            block_context,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
-        )?;
+        let mut tx_exec_info = self.apply(state, block_context, execution_config)?;
 
         let mut tx_execution_context =
             self.get_execution_context(block_context.invoke_tx_max_n_steps);
@@ -238,8 +296,7 @@ impl DeployAccount {
             block_context,
             &mut tx_execution_context,
             self.skip_fee_transfer,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
+            execution_config,
         )?;
 
         tx_exec_info.set_fee_info(actual_fee, fee_transfer_info);
@@ -269,9 +326,7 @@ impl DeployAccount {
         &self,
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<TransactionExecutionInfo, TransactionError> {
         let contract_class = state.get_contract_class(&self.class_hash)?;
 
@@ -283,8 +338,7 @@ impl DeployAccount {
             state,
             block_context,
             &mut resources_manager,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
+            execution_config,
         )?;
 
         let validate_info = if self.skip_validate {
@@ -294,8 +348,7 @@ impl DeployAccount {
                 state,
                 block_context,
                 &mut resources_manager,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
+                execution_config,
             )?
         };
 
@@ -310,6 +363,7 @@ impl DeployAccount {
             )))?,
             None,
             0,
+            block_context.syscall_costs(),
         )
         .map_err::<TransactionError, _>(|_| TransactionError::ResourcesCalculation)?;
 
@@ -329,9 +383,7 @@ impl DeployAccount {
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
         resources_manager: &mut ExecutionResourcesManager,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<CallInfo, TransactionError> {
         if self.constructor_entry_points_empty(contract_class)? {
             if !self.constructor_calldata.is_empty() {
@@ -348,8 +400,7 @@ impl DeployAccount {
                 state,
                 block_context,
                 resources_manager,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
+                execution_config,
             )
         }
     }
@@ -408,9 +459,7 @@ impl DeployAccount {
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
         resources_manager: &mut ExecutionResourcesManager,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<CallInfo, TransactionError> {
         let entry_point = ExecutionEntryPoint::new(
             self.contract_address.clone(),
@@ -433,8 +482,7 @@ impl DeployAccount {
                 &mut self.get_execution_context(block_context.validate_max_n_steps),
                 false,
                 block_context.validate_max_n_steps,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
+                execution_config,
             )?
         };
 
@@ -460,9 +508,7 @@ impl DeployAccount {
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
         resources_manager: &mut ExecutionResourcesManager,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<Option<CallInfo>, TransactionError> {
         let call = ExecutionEntryPoint::new(
             self.contract_address.clone(),
@@ -491,8 +537,7 @@ impl DeployAccount {
                 &mut self.get_execution_context(block_context.validate_max_n_steps),
                 false,
                 block_context.validate_max_n_steps,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
+                execution_config,
             )?
         };
 
@@ -717,21 +762,11 @@ mod tests {
             .set_contract_class(class_hash, &CompiledClass::Deprecated(Arc::new(contract)))
             .unwrap();
         internal_deploy
-            .execute(
-                &mut state,
-                &block_context,
-                #[cfg(feature = "cairo-native")]
-                None,
-            )
+            .execute(&mut state, &block_context, &Default::default())
             .unwrap();
         assert_matches!(
             internal_deploy_error
-                .execute(
-                    &mut state,
-                    &block_context,
-                    #[cfg(feature = "cairo-native")]
-                    None,
-                )
+                .execute(&mut state, &block_context, &Default::default(),)
                 .unwrap_err(),
             TransactionError::State(StateError::ContractAddressUnavailable(..))
         )
@@ -770,12 +805,7 @@ mod tests {
             .set_contract_class(class_hash, &CompiledClass::Deprecated(Arc::new(contract)))
             .unwrap();
         internal_deploy
-            .execute(
-                &mut state,
-                &block_context,
-                #[cfg(feature = "cairo-native")]
-                None,
-            )
+            .execute(&mut state, &block_context, &Default::default())
             .unwrap();
     }
 
@@ -798,8 +828,7 @@ mod tests {
         let result = internal_declare.execute(
             &mut CachedState::<InMemoryStateReader, PermanentContractClassCache>::default(),
             &BlockContext::default(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         );
 
         assert_matches!(
@@ -116,6 +116,8 @@ pub enum TransactionError {
     InvalidTxContext,
     #[error("{0:?}")]
     SierraCompileError(String),
+    #[error("Failed to compile class {0} with Cairo Native: {1}")]
+    NativeCompilation(ClassHash, String),
     #[error("Invalid builtin found in contract class: {0}")]
     InvalidBuiltinContractClass(String),
     #[error("The hash of sierra contract classs is not equal to compiled class hash")]
@@ -166,6 +168,26 @@ pub enum TransactionError {
     DeprecatedAccountTxFieldsVInV3TX,
     #[error("Non V3 Transactions can't be created with non deprecated account tx fields")]
     CurrentAccountTxFieldsInNonV3TX,
+    #[error("Failed to (de)serialize transaction: {0}")]
+    Serialization(String),
+    #[error("Missing transaction field {0}")]
+    MissingTransactionField(String),
+    #[error("{0} transactions can't be broadcasted")]
+    NotBroadcastable(String),
+    #[error("Calldata length ({0}) exceeds the maximum length ({1})")]
+    CalldataTooLong(usize, usize),
+    #[error("Signature length ({0}) exceeds the maximum length ({1})")]
+    SignatureTooLong(usize, usize),
+    #[error("Declared contract bytecode size ({0}) exceeds the maximum size ({1})")]
+    ContractBytecodeTooLarge(usize, usize),
+    #[error("Declared contract class size ({0}) exceeds the maximum size ({1})")]
+    ContractClassTooLarge(usize, usize),
+    #[error("Declared contract class has an invalid Sierra version")]
+    InvalidSierraVersion,
+    #[error("Unsupported Sierra version {0}. Supported versions: {1} to {2}")]
+    UnsupportedSierraVersion(String, String, String),
+    #[error("Only L1 data availability mode is supported")]
+    UnsupportedDataAvailabilityMode,
     // Variant used to detect revert errors in revertible transactions
     #[error(transparent)]
     FeeCheck(#[from] FeeCheckError),
@@ -8,6 +8,7 @@ use crate::{
         constants::{INITIAL_GAS_COST, TRANSFER_ENTRY_POINT_SELECTOR},
     },
     execution::{
+        backend::ExecutionConfig,
         execution_entry_point::{ExecutionEntryPoint, ExecutionResult},
         gas_usage::get_onchain_data_segment_length,
         os_usage::{
@@ -29,13 +30,6 @@ use cairo_vm::Felt252;
 use num_traits::{ToPrimitive, Zero};
 use std::collections::HashMap;
 
-#[cfg(feature = "cairo-native")]
-use {
-    crate::transaction::ClassHash,
-    cairo_native::cache::ProgramCache,
-    std::{cell::RefCell, rc::Rc},
-};
-
 // second element is the actual fee that the transaction uses
 pub type FeeInfo = (Option<CallInfo>, u128);
 
@@ -46,9 +40,7 @@ pub(crate) fn execute_fee_transfer<S: StateReader, C: ContractClassCache>(
     block_context: &BlockContext,
     tx_execution_context: &mut TransactionExecutionContext,
     actual_fee: u128,
-    #[cfg(feature = "cairo-native")] program_cache: Option<
-        Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-    >,
+    execution_config: &ExecutionConfig,
 ) -> Result<CallInfo, TransactionError> {
     let fee_token_address = block_context
         .get_fee_token_address_by_fee_type(&tx_execution_context.account_tx_fields.fee_type())
@@ -77,8 +69,7 @@ pub(crate) fn execute_fee_transfer<S: StateReader, C: ContractClassCache>(
             tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
+            execution_config,
         )
         .map_err(|e| TransactionError::FeeTransferError(Box::new(e)))?;
 
@@ -165,9 +156,7 @@ pub fn charge_fee<S: StateReader, C: ContractClassCache>(
     block_context: &BlockContext,
     tx_execution_context: &mut TransactionExecutionContext,
     skip_fee_transfer: bool,
-    #[cfg(feature = "cairo-native")] program_cache: Option<
-        Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-    >,
+    execution_config: &ExecutionConfig,
 ) -> Result<FeeInfo, TransactionError> {
     let max_fee = tx_execution_context.account_tx_fields.max_fee();
     if max_fee.is_zero() {
@@ -188,8 +177,7 @@ pub fn charge_fee<S: StateReader, C: ContractClassCache>(
             block_context,
             tx_execution_context,
             actual_fee,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
+            execution_config,
         )?)
     };
 
@@ -399,8 +387,7 @@ mod tests {
             &block_context,
             &mut tx_execution_context,
             skip_fee_transfer,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -441,8 +428,7 @@ mod tests {
             &block_context,
             &mut tx_execution_context,
             skip_fee_transfer,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -1,8 +1,9 @@
 use super::{
     check_account_tx_fields_version,
     fee::{calculate_tx_fee, charge_fee, check_fee_bounds, run_post_execution_fee_checks},
-    get_tx_version, Address, CurrentAccountTxFields, ResourceBounds, Transaction,
-    VersionSpecificAccountTxFields,
+    get_tx_version,
+    serialization::{JsonTransactionType, TransactionJson},
+    Address, CurrentAccountTxFields, ResourceBounds, Transaction, VersionSpecificAccountTxFields,
 };
 use crate::{
     core::transaction_hash::calculate_invoke_transaction_hash,
@@ -14,6 +15,7 @@ use crate::{
         transaction_type::TransactionType,
     },
     execution::{
+        backend::ExecutionConfig,
         execution_entry_point::{ExecutionEntryPoint, ExecutionResult},
         CallInfo, TransactionExecutionContext, TransactionExecutionInfo,
     },
@@ -35,13 +37,6 @@ use num_traits::Zero;
 use starknet_api::transaction::Resource;
 use std::fmt::Debug;
 
-#[cfg(feature = "cairo-native")]
-use {
-    crate::transaction::ClassHash,
-    cairo_native::cache::ProgramCache,
-    std::{cell::RefCell, rc::Rc},
-};
-
 /// Represents an InvokeFunction transaction in the starknet network.
 #[derive(Debug, Getters, Clone)]
 pub struct InvokeFunction {
@@ -50,15 +45,19 @@ pub struct InvokeFunction {
     entry_point_selector: Felt252,
     #[allow(dead_code)]
     entry_point_type: EntryPointType,
+    #[getset(get = "pub")]
     calldata: Vec<Felt252>,
     tx_type: TransactionType,
+    #[getset(get = "pub")]
     version: Felt252,
     validate_entry_point_selector: Felt252,
     #[getset(get = "pub")]
     hash_value: Felt252,
     #[getset(get = "pub")]
     signature: Vec<Felt252>,
+    #[getset(get = "pub")]
     account_tx_fields: VersionSpecificAccountTxFields,
+    #[getset(get = "pub")]
     nonce: Option<Felt252>,
     skip_validation: bool,
     skip_execute: bool,
@@ -135,6 +134,87 @@ impl InvokeFunction {
         })
     }
 
+    /// Returns the JSON fields of the transaction. See [`super::serialization`].
+    pub(crate) fn to_json_fields(&self) -> Result<TransactionJson, TransactionError> {
+        let mut fields =
+            TransactionJson::new(JsonTransactionType::Invoke, self.hash_value, self.version);
+        if self.version.is_zero() {
+            fields.contract_address = Some(self.contract_address.0);
+            fields.entry_point_selector = Some(self.entry_point_selector);
+        } else {
+            fields.sender_address = Some(self.contract_address.0);
+        }
+        fields.calldata = Some(self.calldata.clone());
+        fields.signature = Some(self.signature.clone());
+        fields.nonce = self.nonce;
+        fields.set_account_tx_fields(&self.account_tx_fields);
+        Ok(fields)
+    }
+
+    /// Creates an `InvokeFunction` from its JSON fields. See [`super::serialization`].
+    pub(crate) fn from_json_fields(fields: TransactionJson) -> Result<Self, TransactionError> {
+        let transaction_hash = fields.transaction_hash()?;
+        let account_tx_fields = fields.account_tx_fields()?;
+        let (contract_address, entry_point_selector) = Self::json_address_and_selector(&fields)?;
+
+        Self::new_with_tx_hash(
+            contract_address,
+            entry_point_selector,
+            account_tx_fields,
+            fields.version,
+            fields.calldata.unwrap_or_default(),
+            fields.signature.unwrap_or_default(),
+            fields.nonce,
+            transaction_hash,
+        )
+    }
+
+    /// Creates an `InvokeFunction` from the fields of a JSON-RPC `BROADCASTED_INVOKE_TXN`,
+    /// computing its hash for `chain_id`.
+    pub(crate) fn from_broadcasted_json_fields(
+        mut fields: TransactionJson,
+        chain_id: Felt252,
+    ) -> Result<Self, TransactionError> {
+        let (contract_address, entry_point_selector) = Self::json_address_and_selector(&fields)?;
+        fields.transaction_hash = Some(calculate_invoke_transaction_hash(
+            chain_id,
+            &contract_address,
+            entry_point_selector,
+            fields.version,
+            fields.nonce,
+            fields.calldata.as_deref().unwrap_or_default(),
+            &fields.account_tx_fields()?,
+        )?);
+
+        Self::from_json_fields(fields)
+    }
+
+    /// Version 0 invokes call the given entry point of `contract_address`, later versions call
+    /// `__execute__` on the `sender_address` account.
+    fn json_address_and_selector(
+        fields: &TransactionJson,
+    ) -> Result<(Address, Felt252), TransactionError> {
+        let (contract_address, entry_point_selector) = if get_tx_version(fields.version).is_zero() {
+            (
+                fields.contract_address.or(fields.sender_address),
+                TransactionJson::required(fields.entry_point_selector, "entry_point_selector")?,
+            )
+        } else {
+            (
+                fields.sender_address.or(fields.contract_address),
+                *EXECUTE_ENTRY_POINT_SELECTOR,
+            )
+        };
+
+        Ok((
+            Address(TransactionJson::required(
+                contract_address,
+                "sender_address",
+            )?),
+            entry_point_selector,
+        ))
+    }
+
     /// Creates a `InvokeFunction` from a starknet api `InvokeTransaction`.
     pub fn from_invoke_transaction(
         tx: starknet_api::transaction::InvokeTransaction,
@@ -251,9 +331,7 @@ impl InvokeFunction {
         block_context: &BlockContext,
         resources_manager: &mut ExecutionResourcesManager,
         remaining_gas: u128,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<Option<CallInfo>, TransactionError> {
         if self.version.is_zero() || self.skip_validation {
             return Ok(None);
@@ -277,8 +355,7 @@ impl InvokeFunction {
             &mut self.get_execution_context(block_context.validate_max_n_steps)?,
             false,
             block_context.validate_max_n_steps,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
+            execution_config,
         )?;
 
         // Validate the return data
@@ -318,9 +395,7 @@ impl InvokeFunction {
         block_context: &BlockContext,
         resources_manager: &mut ExecutionResourcesManager,
         remaining_gas: u128,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<ExecutionResult, TransactionError> {
         let call = ExecutionEntryPoint::new(
             self.contract_address.clone(),
@@ -337,10 +412,10 @@ impl InvokeFunction {
             block_context,
             resources_manager,
             &mut self.get_execution_context(block_context.invoke_tx_max_n_steps)?,
-            true,
+            // Version 0 transactions predate reverts, so a failing execution rejects them.
+            !self.version.is_zero(),
             block_context.invoke_tx_max_n_steps,
-            #[cfg(feature = "cairo-native")]
-            program_cache,
+            execution_config,
         )
     }
 
@@ -355,9 +430,7 @@ impl InvokeFunction {
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
         mut remaining_gas: u128,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<TransactionExecutionInfo, TransactionError> {
         let mut resources_manager = ExecutionResourcesManager::default();
         let validate_info = if self.skip_validation {
@@ -368,8 +441,7 @@ impl InvokeFunction {
                 block_context,
                 &mut resources_manager,
                 remaining_gas,
-                #[cfg(feature = "cairo-native")]
-                program_cache.clone(),
+                execution_config,
             )?
         };
 
@@ -390,8 +462,7 @@ impl InvokeFunction {
                 block_context,
                 &mut resources_manager,
                 remaining_gas,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
+                execution_config,
             )?
         };
         let changes = state.count_actual_state_changes(Some((
@@ -405,6 +476,7 @@ impl InvokeFunction {
             changes,
             None,
             n_reverted_steps,
+            block_context.syscall_costs(),
         )?;
         let transaction_execution_info = TransactionExecutionInfo::new_without_fee_info(
             validate_info,
@@ -422,7 +494,7 @@ impl InvokeFunction {
     /// - state: A state that implements the [`State`] and [`StateReader`] traits.
     /// - block_context: The block's execution context.
     /// - remaining_gas: The amount of gas that the transaction disposes.
-    #[tracing::instrument(level = "debug", ret, err, skip(self, state, block_context, program_cache), fields(
+    #[tracing::instrument(level = "debug", ret, err, skip(self, state, block_context, execution_config), fields(
         tx_type = ?TransactionType::InvokeFunction,
         self.version = ?self.version,
         self.hash_value = ?self.hash_value,
@@ -435,9 +507,7 @@ impl InvokeFunction {
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
         remaining_gas: u128,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<TransactionExecutionInfo, TransactionError> {
         if !(self.version == Felt252::ZERO
             || self.version == Felt252::ONE
@@ -462,8 +532,7 @@ impl InvokeFunction {
             &mut transactional_state,
             block_context,
             remaining_gas,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
+            execution_config,
         );
         #[cfg(feature = "replay_benchmark")]
         // Add initial values to cache despite tx outcome
@@ -493,7 +562,7 @@ impl InvokeFunction {
 
         if let Some(revert_error) = tx_exec_info.revert_error.clone() {
             // execution error
-            tx_exec_info = tx_exec_info.to_revert_error(&revert_error);
+            tx_exec_info = tx_exec_info.to_revert_error(revert_error);
         } else {
             match run_post_execution_fee_checks(
                 &mut transactional_state,
@@ -510,7 +579,7 @@ impl InvokeFunction {
                     )?)?;
                 }
                 Err(TransactionError::FeeCheck(error)) => {
-                    tx_exec_info = tx_exec_info.to_revert_error(&error.to_string());
+                    tx_exec_info = tx_exec_info.to_revert_error(error.to_string());
                 }
                 error => error?,
             }
@@ -525,8 +594,7 @@ impl InvokeFunction {
             block_context,
             &mut tx_execution_context,
             self.skip_fee_transfer,
-            #[cfg(feature = "cairo-native")]
-            program_cache.clone(),
+            execution_config,
         )?;
 
         tx_exec_info.set_fee_info(actual_fee, fee_transfer_info);
@@ -649,6 +717,7 @@ mod tests {
     use super::*;
     use crate::{
         definitions::{block_context::GasPrices, constants::VALIDATE_DECLARE_ENTRY_POINT_SELECTOR},
+        execution::revert::RevertReason,
         services::api::contract_classes::{
             compiled_class::CompiledClass, deprecated_contract_class::ContractClass,
         },
@@ -789,8 +858,7 @@ mod tests {
                 &mut transactional,
                 &BlockContext::default(),
                 0,
-                #[cfg(feature = "cairo-native")]
-                None,
+                &Default::default(),
             )
             .unwrap();
         state
@@ -865,13 +933,7 @@ mod tests {
             .unwrap();
 
         let result = internal_invoke_function
-            .execute(
-                &mut state,
-                &BlockContext::default(),
-                0,
-                #[cfg(feature = "cairo-native")]
-                None,
-            )
+            .execute(&mut state, &BlockContext::default(), 0, &Default::default())
             .unwrap();
 
         assert_eq!(result.tx_type, Some(TransactionType::InvokeFunction));
@@ -943,8 +1005,7 @@ mod tests {
             &mut transactional,
             &BlockContext::default(),
             0,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         );
 
         assert!(expected_error.is_err());
@@ -1012,8 +1073,7 @@ mod tests {
                 &mut transactional,
                 &BlockContext::default(),
                 0,
-                #[cfg(feature = "cairo-native")]
-                None,
+                &Default::default(),
             )
             .unwrap();
         state
@@ -1090,8 +1150,7 @@ mod tests {
             &mut transactional,
             &BlockContext::default(),
             0,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         );
 
         assert!(expected_error.is_err());
@@ -1154,13 +1213,8 @@ mod tests {
 
         let block_context = BlockContext::default();
 
-        let result = internal_invoke_function.execute(
-            &mut state,
-            &block_context,
-            0,
-            #[cfg(feature = "cairo-native")]
-            None,
-        );
+        let result =
+            internal_invoke_function.execute(&mut state, &block_context, 0, &Default::default());
         assert!(result.is_err());
         assert_matches!(
             result.unwrap_err(),
@@ -1222,13 +1276,7 @@ mod tests {
         block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
 
         let tx_info = internal_invoke_function
-            .execute(
-                &mut state,
-                &block_context,
-                0,
-                #[cfg(feature = "cairo-native")]
-                None,
-            )
+            .execute(&mut state, &block_context, 0, &Default::default())
             .unwrap();
         let expected_actual_fee = 899;
         let expected_tx_info = tx_info.clone().to_revert_error(
@@ -1292,21 +1340,14 @@ mod tests {
             .unwrap();
 
         internal_invoke_function
-            .execute(
-                &mut state,
-                &BlockContext::default(),
-                0,
-                #[cfg(feature = "cairo-native")]
-                None,
-            )
+            .execute(&mut state, &BlockContext::default(), 0, &Default::default())
             .unwrap();
 
         let expected_error = internal_invoke_function.execute(
             &mut state,
             &BlockContext::default(),
             0,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         );
 
         assert!(expected_error.is_err());
@@ -1371,8 +1412,7 @@ mod tests {
             &mut state,
             &BlockContext::default(),
             0,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         );
 
         assert!(expected_error.is_err());
@@ -1410,7 +1450,7 @@ mod tests {
             entry_point_type: EntryPointType::External,
             calldata: vec![],
             tx_type: TransactionType::InvokeFunction,
-            version: 0.into(),
+            version: 1.into(),
             validate_entry_point_selector: 0.into(),
             hash_value: 0.into(),
             signature: Vec::new(),
@@ -1456,19 +1496,13 @@ mod tests {
         let state_before_execution = state.clone_for_testing();
 
         let result = internal_invoke_function
-            .execute(
-                &mut state,
-                &BlockContext::default(),
-                0,
-                #[cfg(feature = "cairo-native")]
-                None,
-            )
+            .execute(&mut state, &BlockContext::default(), 0, &Default::default())
             .unwrap();
 
         assert!(result.call_info.is_none());
         assert_eq!(
-            result.revert_error,
-            Some(format!(
+            result.revert_error.unwrap().reason,
+            RevertReason::Error(format!(
                 "Requested entry point with selector {entry_point_selector:#x} was not found"
             ))
         );
@@ -1496,6 +1530,69 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_v0_transaction_wrong_entry_point_is_not_reverted() {
+        let entry_point_selector = Felt252::from_bytes_be(&calculate_sn_keccak(b"factorial_"));
+
+        let internal_invoke_function = InvokeFunction {
+            contract_address: Address(0.into()),
+            entry_point_selector,
+            entry_point_type: EntryPointType::External,
+            calldata: vec![],
+            tx_type: TransactionType::InvokeFunction,
+            version: 0.into(),
+            validate_entry_point_selector: 0.into(),
+            hash_value: 0.into(),
+            signature: Vec::new(),
+            account_tx_fields: Default::default(),
+            nonce: Some(0.into()),
+            skip_validation: true,
+            skip_execute: false,
+            skip_fee_transfer: true,
+            skip_nonce_check: false,
+        };
+
+        let mut state_reader = InMemoryStateReader::default();
+        let class_hash: ClassHash = ClassHash([1; 32]);
+        let program_data = include_bytes!("../../starknet_programs/cairo2/factorial.casm");
+        let contract_class: CasmContractClass = serde_json::from_slice(program_data).unwrap();
+        let contract_address = Address(0.into());
+
+        state_reader
+            .address_to_class_hash_mut()
+            .insert(contract_address.clone(), class_hash);
+        state_reader
+            .address_to_nonce
+            .insert(contract_address, Felt252::ZERO);
+        state_reader
+            .class_hash_to_compiled_class_hash
+            .insert(class_hash, class_hash);
+
+        let casm_contract_class_cache = PermanentContractClassCache::default();
+        casm_contract_class_cache.set_contract_class(
+            class_hash,
+            CompiledClass::Casm {
+                casm: Arc::new(contract_class),
+                sierra: None,
+            },
+        );
+
+        let mut state =
+            CachedState::new(Arc::new(state_reader), Arc::new(casm_contract_class_cache));
+
+        // Version 0 transactions can't be reverted, so the failure rejects the transaction.
+        let result = internal_invoke_function.execute(
+            &mut state,
+            &BlockContext::default(),
+            0,
+            &Default::default(),
+        );
+        assert_matches!(
+            result,
+            Err(TransactionError::EntryPointNotFound(selector)) if selector == entry_point_selector
+        );
+    }
+
     #[test]
     fn invoke_wrong_version() {
         // declare tx
@@ -1514,8 +1611,7 @@ mod tests {
             &mut CachedState::<InMemoryStateReader, PermanentContractClassCache>::default(),
             &BlockContext::default(),
             u128::MAX,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         );
 
         assert_matches!(
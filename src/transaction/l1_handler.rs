@@ -1,4 +1,7 @@
-use super::{Address, Transaction};
+use super::{
+    serialization::{JsonTransactionType, TransactionJson},
+    Address, Transaction,
+};
 use crate::{
     core::transaction_hash::{
         deprecated::deprecated_calculate_transaction_hash_common, TransactionHashPrefix,
@@ -9,6 +12,7 @@ use crate::{
         transaction_type::TransactionType,
     },
     execution::{
+        backend::ExecutionConfig,
         execution_entry_point::{ExecutionEntryPoint, ExecutionResult},
         TransactionExecutionContext, TransactionExecutionInfo,
     },
@@ -26,13 +30,6 @@ use cairo_vm::Felt252;
 use getset::Getters;
 use num_traits::Zero;
 
-#[cfg(feature = "cairo-native")]
-use {
-    crate::transaction::ClassHash,
-    cairo_native::cache::ProgramCache,
-    std::{cell::RefCell, rc::Rc},
-};
-
 #[allow(dead_code)]
 #[derive(Debug, Getters, Clone)]
 /// Represents an L1Handler transaction in the StarkNet network.
@@ -105,8 +102,39 @@ impl L1Handler {
         })
     }
 
+    /// Returns the JSON fields of the transaction. See [`super::serialization`].
+    pub(crate) fn to_json_fields(&self) -> Result<TransactionJson, TransactionError> {
+        let mut fields = TransactionJson::new(
+            JsonTransactionType::L1Handler,
+            self.hash_value,
+            L1_HANDLER_VERSION.into(),
+        );
+        fields.contract_address = Some(self.contract_address.0);
+        fields.entry_point_selector = Some(self.entry_point_selector);
+        fields.calldata = Some(self.calldata.clone());
+        fields.nonce = self.nonce;
+        fields.paid_fee_on_l1 = self.paid_fee_on_l1;
+        Ok(fields)
+    }
+
+    /// Creates an `L1Handler` from its JSON fields. See [`super::serialization`].
+    pub(crate) fn from_json_fields(fields: TransactionJson) -> Result<Self, TransactionError> {
+        let transaction_hash = fields.transaction_hash()?;
+        Self::new_with_tx_hash(
+            Address(TransactionJson::required(
+                fields.contract_address,
+                "contract_address",
+            )?),
+            TransactionJson::required(fields.entry_point_selector, "entry_point_selector")?,
+            fields.calldata.unwrap_or_default(),
+            fields.nonce.unwrap_or_default(),
+            fields.paid_fee_on_l1,
+            transaction_hash,
+        )
+    }
+
     /// Applies self to 'state' by executing the L1-handler entry point.
-    #[tracing::instrument(level = "debug", ret, err, skip(self, state, block_context, program_cache), fields(
+    #[tracing::instrument(level = "debug", ret, err, skip(self, state, block_context, execution_config), fields(
         tx_type = ?TransactionType::L1Handler,
         self.hash_value = ?self.hash_value,
         self.contract_address = ?self.contract_address,
@@ -118,9 +146,7 @@ impl L1Handler {
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
         remaining_gas: u128,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<TransactionExecutionInfo, TransactionError> {
         let mut resources_manager = ExecutionResourcesManager::default();
         let entrypoint = ExecutionEntryPoint::new(
@@ -148,8 +174,7 @@ impl L1Handler {
                 &mut self.get_execution_context(block_context.invoke_tx_max_n_steps)?,
                 true,
                 block_context.invoke_tx_max_n_steps,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
+                execution_config,
             )?
         };
 
@@ -161,6 +186,7 @@ impl L1Handler {
             changes,
             Some(self.get_payload_size()),
             n_reverted_steps,
+            block_context.syscall_costs(),
         )?;
 
         // Enforce L1 fees.
@@ -322,13 +348,7 @@ mod test {
         block_context.starknet_os_config.gas_price = GasPrices::new(1, 0);
 
         let tx_exec = l1_handler
-            .execute(
-                &mut state,
-                &block_context,
-                100000,
-                #[cfg(feature = "cairo-native")]
-                None,
-            )
+            .execute(&mut state, &block_context, 100000, &Default::default())
             .unwrap();
 
         let expected_tx_exec = expected_tx_exec_info();
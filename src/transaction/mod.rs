@@ -6,7 +6,7 @@ use crate::{
         block_context::{BlockContext, FeeType},
         constants::{QUERY_VERSION_0, QUERY_VERSION_1, QUERY_VERSION_2, QUERY_VERSION_3},
     },
-    execution::TransactionExecutionInfo,
+    execution::{backend::ExecutionConfig, TransactionExecutionInfo},
     services::api::contract_classes::compiled_class::CompiledClass,
     state::{
         cached_state::CachedState, contract_class_cache::ContractClassCache, state_api::StateReader,
@@ -20,6 +20,7 @@ pub use deploy_account::DeployAccount;
 use error::TransactionError;
 pub use invoke_function::InvokeFunction;
 pub use l1_handler::L1Handler;
+pub use serialization::TransactionFormat;
 
 pub mod declare;
 pub mod declare_deprecated;
@@ -29,17 +30,13 @@ pub mod error;
 pub mod fee;
 pub mod invoke_function;
 pub mod l1_handler;
+pub mod serialization;
+pub mod validation;
 
 use cairo_vm::Felt252;
 use serde::{Deserialize, Serialize};
 use starknet_api::transaction::Resource;
 
-#[cfg(feature = "cairo-native")]
-use {
-    cairo_native::cache::ProgramCache,
-    std::{cell::RefCell, rc::Rc},
-};
-
 #[derive(Clone, PartialEq, Hash, Eq, Default, Serialize, Deserialize)]
 pub struct Address(pub Felt252);
 
@@ -182,6 +179,18 @@ impl Transaction {
         }
     }
 
+    /// returns the hash of the transaction.
+    pub fn hash_value(&self) -> Felt252 {
+        match self {
+            Transaction::Deploy(tx) => tx.hash_value,
+            Transaction::InvokeFunction(tx) => *tx.hash_value(),
+            Transaction::DeclareDeprecated(tx) => tx.hash_value,
+            Transaction::Declare(tx) => tx.hash_value,
+            Transaction::DeployAccount(tx) => *tx.hash_value(),
+            Transaction::L1Handler(tx) => *tx.hash_value(),
+        }
+    }
+
     /// execute the transaction in cairo-vm and returns a TransactionExecutionInfo structure.
     ///## Parameters:
     ///- state: a structure that implements State and StateReader traits.
@@ -192,49 +201,21 @@ impl Transaction {
         state: &mut CachedState<S, C>,
         block_context: &BlockContext,
         remaining_gas: u128,
-        #[cfg(feature = "cairo-native")] program_cache: Option<
-            Rc<RefCell<ProgramCache<'_, ClassHash>>>,
-        >,
+        execution_config: &ExecutionConfig,
     ) -> Result<TransactionExecutionInfo, TransactionError> {
         match self {
-            Transaction::DeclareDeprecated(tx) => tx.execute(
-                state,
-                block_context,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            ),
-            Transaction::Declare(tx) => tx.execute(
-                state,
-                block_context,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            ),
-            Transaction::Deploy(tx) => tx.execute(
-                state,
-                block_context,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            ),
-            Transaction::DeployAccount(tx) => tx.execute(
-                state,
-                block_context,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            ),
-            Transaction::InvokeFunction(tx) => tx.execute(
-                state,
-                block_context,
-                remaining_gas,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            ),
-            Transaction::L1Handler(tx) => tx.execute(
-                state,
-                block_context,
-                remaining_gas,
-                #[cfg(feature = "cairo-native")]
-                program_cache,
-            ),
+            Transaction::DeclareDeprecated(tx) => {
+                tx.execute(state, block_context, execution_config)
+            }
+            Transaction::Declare(tx) => tx.execute(state, block_context, execution_config),
+            Transaction::Deploy(tx) => tx.execute(state, block_context, execution_config),
+            Transaction::DeployAccount(tx) => tx.execute(state, block_context, execution_config),
+            Transaction::InvokeFunction(tx) => {
+                tx.execute(state, block_context, remaining_gas, execution_config)
+            }
+            Transaction::L1Handler(tx) => {
+                tx.execute(state, block_context, remaining_gas, execution_config)
+            }
         }
     }
 
@@ -413,7 +394,7 @@ pub fn declare_tx_from_sn_api_transaction(
     }
 }
 
-#[derive(Clone, Debug, Default, Copy)]
+#[derive(Clone, Debug, Default, Copy, PartialEq, Eq)]
 pub enum DataAvailabilityMode {
     #[default]
     L1,
@@ -0,0 +1,1050 @@
+//! JSON serialization of transactions in the formats used by the network.
+//!
+//! Transactions serialize to the JSON-RPC `BROADCASTED_*_TXN` objects, extended with the
+//! `transaction_hash` (as well as the `class_hash` of declares and the `contract_address` of
+//! deploy accounts) so they can be deserialized back without recomputing them.
+//! [`Transaction::to_json`] can also produce the feeder gateway format, and deserialization
+//! accepts both.
+//!
+//! The Cairo 0 class of deprecated declare and deploy transactions is serialized with its
+//! program compressed, from the JSON the class was parsed from. That JSON is only kept when asked
+//! for with [`ContractClass::with_class_json`], and classes without it can't be serialized.
+
+use super::{
+    check_account_tx_fields_version, error::TransactionError, get_tx_version,
+    CurrentAccountTxFields, DataAvailabilityMode, Declare, DeclareDeprecated, Deploy,
+    DeployAccount, InvokeFunction, L1Handler, ResourceBounds, Transaction,
+    VersionSpecificAccountTxFields,
+};
+use crate::services::api::contract_classes::deprecated_contract_class::ContractClass;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use cairo_lang_starknet::contract_class::ContractClass as SierraContractClass;
+use cairo_vm::Felt252;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use num_traits::ToPrimitive;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::{
+    io::{Read, Write},
+    str::FromStr,
+};
+
+/// Network format of a serialized transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TransactionFormat {
+    /// The JSON-RPC `BROADCASTED_*_TXN` format.
+    #[default]
+    JsonRpc,
+    /// The feeder gateway format: `INVOKE_FUNCTION` type, numeric data availability modes and
+    /// uppercase resource names.
+    FeederGateway,
+}
+
+impl Transaction {
+    /// Serializes the transaction in the given format.
+    pub fn to_json(&self, format: TransactionFormat) -> Result<Value, TransactionError> {
+        let value = serde_json::to_value(self)
+            .map_err(|err| TransactionError::Serialization(err.to_string()))?;
+
+        Ok(match format {
+            TransactionFormat::JsonRpc => value,
+            TransactionFormat::FeederGateway => to_feeder_gateway_format(value),
+        })
+    }
+
+    /// Deserializes a transaction in either of the network formats.
+    pub fn from_json(value: Value) -> Result<Self, TransactionError> {
+        let fields: TransactionJson = serde_json::from_value(value)
+            .map_err(|err| TransactionError::Serialization(err.to_string()))?;
+        Self::from_json_fields(fields)
+    }
+
+    /// Creates a transaction from a JSON-RPC `BROADCASTED_INVOKE_TXN`, `BROADCASTED_DECLARE_TXN`
+    /// or `BROADCASTED_DEPLOY_ACCOUNT_TXN`, computing its hash for `chain_id`.
+    ///
+    /// Query versions are hashed as given, while the transaction gets the matching regular
+    /// version. The Sierra class of declares is compiled to CASM.
+    pub fn from_broadcasted_json(
+        value: Value,
+        chain_id: Felt252,
+    ) -> Result<Self, TransactionError> {
+        let fields: TransactionJson = serde_json::from_value(value)
+            .map_err(|err| TransactionError::Serialization(err.to_string()))?;
+        let version = get_tx_version(fields.version);
+        check_account_tx_fields_version(&fields.account_tx_fields()?, version)?;
+
+        match TransactionJson::required(fields.tx_type, "type")? {
+            JsonTransactionType::Invoke => {
+                check_broadcasted_version("Invoke", fields.version, &[0, 1, 3])?;
+                InvokeFunction::from_broadcasted_json_fields(fields, chain_id)
+                    .map(Transaction::InvokeFunction)
+            }
+            JsonTransactionType::Declare if version < Felt252::TWO => {
+                check_broadcasted_version("Declare", fields.version, &[0, 1, 2, 3])?;
+                DeclareDeprecated::from_broadcasted_json_fields(fields, chain_id)
+                    .map(Transaction::DeclareDeprecated)
+            }
+            JsonTransactionType::Declare => {
+                check_broadcasted_version("Declare", fields.version, &[0, 1, 2, 3])?;
+                Declare::from_broadcasted_json_fields(fields, chain_id)
+                    .map(|tx| Transaction::Declare(Box::new(tx)))
+            }
+            JsonTransactionType::DeployAccount => {
+                check_broadcasted_version("DeployAccount", fields.version, &[1, 3])?;
+                DeployAccount::from_broadcasted_json_fields(fields, chain_id)
+                    .map(Transaction::DeployAccount)
+            }
+            JsonTransactionType::Deploy => {
+                Err(TransactionError::NotBroadcastable("Deploy".to_string()))
+            }
+            JsonTransactionType::L1Handler => {
+                Err(TransactionError::NotBroadcastable("L1Handler".to_string()))
+            }
+        }
+    }
+
+    pub(crate) fn to_json_fields(&self) -> Result<TransactionJson, TransactionError> {
+        match self {
+            Transaction::DeclareDeprecated(tx) => tx.to_json_fields(),
+            Transaction::Declare(tx) => tx.to_json_fields(),
+            Transaction::Deploy(tx) => tx.to_json_fields(),
+            Transaction::DeployAccount(tx) => tx.to_json_fields(),
+            Transaction::InvokeFunction(tx) => tx.to_json_fields(),
+            Transaction::L1Handler(tx) => tx.to_json_fields(),
+        }
+    }
+
+    pub(crate) fn from_json_fields(fields: TransactionJson) -> Result<Self, TransactionError> {
+        Ok(match TransactionJson::required(fields.tx_type, "type")? {
+            JsonTransactionType::Declare if get_tx_version(fields.version) < Felt252::TWO => {
+                Transaction::DeclareDeprecated(DeclareDeprecated::from_json_fields(fields)?)
+            }
+            JsonTransactionType::Declare => {
+                Transaction::Declare(Box::new(Declare::from_json_fields(fields)?))
+            }
+            JsonTransactionType::Deploy => Transaction::Deploy(Deploy::from_json_fields(fields)?),
+            JsonTransactionType::DeployAccount => {
+                Transaction::DeployAccount(DeployAccount::from_json_fields(fields)?)
+            }
+            JsonTransactionType::Invoke => {
+                Transaction::InvokeFunction(InvokeFunction::from_json_fields(fields)?)
+            }
+            JsonTransactionType::L1Handler => {
+                Transaction::L1Handler(L1Handler::from_json_fields(fields)?)
+            }
+        })
+    }
+}
+
+fn check_broadcasted_version(
+    tx_type: &str,
+    version: Felt252,
+    supported_versions: &[usize],
+) -> Result<(), TransactionError> {
+    let tx_version = get_tx_version(version);
+    if supported_versions
+        .iter()
+        .any(|supported| Felt252::from(*supported) == tx_version)
+    {
+        Ok(())
+    } else {
+        Err(TransactionError::UnsupportedTxVersion(
+            tx_type.to_string(),
+            version,
+            supported_versions.to_vec(),
+        ))
+    }
+}
+
+macro_rules! impl_json_serde {
+    ($($tx:ty),*) => {
+        $(
+            impl Serialize for $tx {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    self.to_json_fields()
+                        .map_err(serde::ser::Error::custom)?
+                        .serialize(serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $tx {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    <$tx>::from_json_fields(TransactionJson::deserialize(deserializer)?)
+                        .map_err(de::Error::custom)
+                }
+            }
+        )*
+    };
+}
+
+impl_json_serde!(
+    Transaction,
+    DeclareDeprecated,
+    Declare,
+    Deploy,
+    DeployAccount,
+    InvokeFunction,
+    L1Handler
+);
+
+// --------------------
+// TransactionJson structure
+// --------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum JsonTransactionType {
+    #[serde(rename = "INVOKE", alias = "INVOKE_FUNCTION")]
+    Invoke,
+    #[serde(rename = "DECLARE")]
+    Declare,
+    #[serde(rename = "DEPLOY")]
+    Deploy,
+    #[serde(rename = "DEPLOY_ACCOUNT")]
+    DeployAccount,
+    #[serde(rename = "L1_HANDLER")]
+    L1Handler,
+}
+
+/// Fields of all the transaction types, as found in their JSON objects.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct TransactionJson {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub(crate) tx_type: Option<JsonTransactionType>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "option_felt_hex")]
+    pub(crate) transaction_hash: Option<Felt252>,
+    #[serde(with = "felt_hex")]
+    pub(crate) version: Felt252,
+    #[serde(skip_serializing_if = "Option::is_none", with = "option_felt_hex")]
+    pub(crate) sender_address: Option<Felt252>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "option_felt_hex")]
+    pub(crate) contract_address: Option<Felt252>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "option_felt_hex")]
+    pub(crate) entry_point_selector: Option<Felt252>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "option_felts_hex")]
+    pub(crate) calldata: Option<Vec<Felt252>>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "option_felt_hex")]
+    pub(crate) class_hash: Option<Felt252>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "option_felt_hex")]
+    pub(crate) compiled_class_hash: Option<Felt252>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) contract_class: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "option_felt_hex")]
+    pub(crate) contract_address_salt: Option<Felt252>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "option_felts_hex")]
+    pub(crate) constructor_calldata: Option<Vec<Felt252>>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "option_felt_hex")]
+    pub(crate) max_fee: Option<Felt252>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "option_felts_hex")]
+    pub(crate) signature: Option<Vec<Felt252>>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "option_felt_hex")]
+    pub(crate) nonce: Option<Felt252>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) resource_bounds: Option<JsonResourceBoundsMapping>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "option_felt_hex")]
+    pub(crate) tip: Option<Felt252>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "option_felts_hex")]
+    pub(crate) paymaster_data: Option<Vec<Felt252>>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "option_felts_hex")]
+    pub(crate) account_deployment_data: Option<Vec<Felt252>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) nonce_data_availability_mode: Option<DataAvailabilityMode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) fee_data_availability_mode: Option<DataAvailabilityMode>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "option_felt_hex")]
+    pub(crate) paid_fee_on_l1: Option<Felt252>,
+}
+
+impl TransactionJson {
+    pub(crate) fn new(
+        tx_type: JsonTransactionType,
+        transaction_hash: Felt252,
+        version: Felt252,
+    ) -> Self {
+        Self {
+            tx_type: Some(tx_type),
+            transaction_hash: Some(transaction_hash),
+            version,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn transaction_hash(&self) -> Result<Felt252, TransactionError> {
+        Self::required(self.transaction_hash, "transaction_hash")
+    }
+
+    /// Returns the value of a field that must be present for the transaction type.
+    pub(crate) fn required<T>(value: Option<T>, field: &str) -> Result<T, TransactionError> {
+        value.ok_or_else(|| TransactionError::MissingTransactionField(field.to_string()))
+    }
+
+    /// Sets `max_fee` for deprecated fields, or the v3 fields otherwise.
+    pub(crate) fn set_account_tx_fields(
+        &mut self,
+        account_tx_fields: &VersionSpecificAccountTxFields,
+    ) {
+        match account_tx_fields {
+            VersionSpecificAccountTxFields::Deprecated(max_fee) => {
+                self.max_fee = Some(Felt252::from(*max_fee));
+            }
+            VersionSpecificAccountTxFields::Current(fields) => {
+                self.resource_bounds = Some(JsonResourceBoundsMapping {
+                    l1_gas: (&fields.l1_resource_bounds).into(),
+                    l2_gas: fields.l2_resource_bounds.as_ref().map(Into::into),
+                });
+                self.tip = Some(Felt252::from(fields.tip));
+                self.paymaster_data = Some(fields.paymaster_data.clone());
+                self.account_deployment_data = Some(fields.account_deployment_data.clone());
+                self.nonce_data_availability_mode = Some(fields.nonce_data_availability_mode);
+                self.fee_data_availability_mode = Some(fields.fee_data_availability_mode);
+            }
+        }
+    }
+
+    /// Returns the v3 fields for v3 transactions, or `max_fee` otherwise.
+    pub(crate) fn account_tx_fields(
+        &self,
+    ) -> Result<VersionSpecificAccountTxFields, TransactionError> {
+        if get_tx_version(self.version) != Felt252::THREE {
+            let max_fee = self.max_fee.unwrap_or_default();
+            return Ok(VersionSpecificAccountTxFields::Deprecated(to_integer(
+                max_fee, "max_fee",
+            )?));
+        }
+
+        let resource_bounds = Self::required(self.resource_bounds.as_ref(), "resource_bounds")?;
+        Ok(VersionSpecificAccountTxFields::Current(
+            CurrentAccountTxFields {
+                l1_resource_bounds: resource_bounds.l1_gas.to_resource_bounds()?,
+                l2_resource_bounds: resource_bounds
+                    .l2_gas
+                    .as_ref()
+                    .map(JsonResourceBounds::to_resource_bounds)
+                    .transpose()?,
+                tip: to_integer(self.tip.unwrap_or_default(), "tip")?,
+                nonce_data_availability_mode: self.nonce_data_availability_mode.unwrap_or_default(),
+                fee_data_availability_mode: self.fee_data_availability_mode.unwrap_or_default(),
+                paymaster_data: self.paymaster_data.clone().unwrap_or_default(),
+                account_deployment_data: self.account_deployment_data.clone().unwrap_or_default(),
+            },
+        ))
+    }
+}
+
+fn to_integer<T: TryFrom<u128>>(value: Felt252, field: &str) -> Result<T, TransactionError> {
+    value
+        .to_u128()
+        .and_then(|value| T::try_from(value).ok())
+        .ok_or_else(|| TransactionError::Serialization(format!("{field} {value:#x} out of range")))
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct JsonResourceBoundsMapping {
+    #[serde(alias = "L1_GAS")]
+    l1_gas: JsonResourceBounds,
+    /// Omitted when the transaction has no L2 gas bounds, which isn't the same as zero bounds
+    /// for `get_execution_info`.
+    #[serde(alias = "L2_GAS", default, skip_serializing_if = "Option::is_none")]
+    l2_gas: Option<JsonResourceBounds>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct JsonResourceBounds {
+    #[serde(with = "felt_hex")]
+    max_amount: Felt252,
+    #[serde(with = "felt_hex")]
+    max_price_per_unit: Felt252,
+}
+
+impl JsonResourceBounds {
+    fn to_resource_bounds(&self) -> Result<ResourceBounds, TransactionError> {
+        Ok(ResourceBounds {
+            max_amount: to_integer(self.max_amount, "max_amount")?,
+            max_price_per_unit: to_integer(self.max_price_per_unit, "max_price_per_unit")?,
+        })
+    }
+}
+
+impl From<&ResourceBounds> for JsonResourceBounds {
+    fn from(value: &ResourceBounds) -> Self {
+        Self {
+            max_amount: value.max_amount.into(),
+            max_price_per_unit: value.max_price_per_unit.into(),
+        }
+    }
+}
+
+impl Serialize for DataAvailabilityMode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match self {
+            DataAvailabilityMode::L1 => "L1",
+            DataAvailabilityMode::L2 => "L2",
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for DataAvailabilityMode {
+    /// Accepts both the JSON-RPC names and the feeder gateway numbers.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::String(mode) if mode == "L1" => Ok(DataAvailabilityMode::L1),
+            Value::String(mode) if mode == "L2" => Ok(DataAvailabilityMode::L2),
+            Value::Number(mode) if mode.as_u64() == Some(0) => Ok(DataAvailabilityMode::L1),
+            Value::Number(mode) if mode.as_u64() == Some(1) => Ok(DataAvailabilityMode::L2),
+            mode => Err(de::Error::custom(format!(
+                "invalid data availability mode {mode}"
+            ))),
+        }
+    }
+}
+
+/// Converts a JSON-RPC transaction object into the feeder gateway format.
+fn to_feeder_gateway_format(mut value: Value) -> Value {
+    if let Some(fields) = value.as_object_mut() {
+        if fields.get("type") == Some(&Value::from("INVOKE")) {
+            fields.insert("type".to_string(), "INVOKE_FUNCTION".into());
+        }
+        for key in ["nonce_data_availability_mode", "fee_data_availability_mode"] {
+            if let Some(mode) = fields.get_mut(key) {
+                *mode = (if *mode == "L2" { 1 } else { 0 }).into();
+            }
+        }
+        if let Some(Value::Object(resource_bounds)) = fields.get_mut("resource_bounds") {
+            for (from, to) in [("l1_gas", "L1_GAS"), ("l2_gas", "L2_GAS")] {
+                if let Some(bounds) = resource_bounds.remove(from) {
+                    resource_bounds.insert(to.to_string(), bounds);
+                }
+            }
+        }
+    }
+    value
+}
+
+// --------------------
+// Contract classes
+// --------------------
+
+/// Serializes a Sierra class the way JSON-RPC does, with the ABI as a string.
+pub(crate) fn sierra_class_to_json(
+    contract_class: &SierraContractClass,
+) -> Result<Value, TransactionError> {
+    let mut value = serde_json::to_value(contract_class)
+        .map_err(|err| TransactionError::Serialization(err.to_string()))?;
+    if let Some(fields) = value.as_object_mut() {
+        fields.remove("sierra_program_debug_info");
+        let abi = fields.remove("abi").unwrap_or(Value::Null);
+        let abi = match abi {
+            Value::Null => String::new(),
+            abi => abi.to_string(),
+        };
+        fields.insert("abi".to_string(), abi.into());
+    }
+    Ok(value)
+}
+
+/// Deserializes a Sierra class, with its ABI either as a string or as JSON.
+pub(crate) fn sierra_class_from_json(
+    mut value: Value,
+) -> Result<SierraContractClass, TransactionError> {
+    if let Some(fields) = value.as_object_mut() {
+        if let Some(Value::String(abi)) = fields.get("abi") {
+            let abi = match abi.as_str() {
+                "" => Value::Null,
+                abi => serde_json::from_str(abi)
+                    .map_err(|err| TransactionError::Serialization(err.to_string()))?,
+            };
+            fields.insert("abi".to_string(), abi);
+        }
+    }
+    serde_json::from_value(value).map_err(|err| TransactionError::Serialization(err.to_string()))
+}
+
+/// Serializes a Cairo 0 class with its program compressed (base64 of the gzipped JSON), as in
+/// `DEPRECATED_CONTRACT_CLASS`.
+pub(crate) fn deprecated_class_to_json(
+    contract_class: &ContractClass,
+) -> Result<Value, TransactionError> {
+    let class_json = contract_class.class_json.as_deref().ok_or_else(|| {
+        TransactionError::Serialization(
+            "the JSON of the Cairo 0 class wasn't kept, so it can't be serialized".to_string(),
+        )
+    })?;
+    let mut value: Value = serde_json::from_str(class_json)
+        .map_err(|err| TransactionError::Serialization(err.to_string()))?;
+
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(value["program"].to_string().as_bytes())
+        .map_err(|err| TransactionError::Serialization(err.to_string()))?;
+    let compressed = encoder
+        .finish()
+        .map_err(|err| TransactionError::Serialization(err.to_string()))?;
+    value["program"] = STANDARD.encode(compressed).into();
+
+    Ok(value)
+}
+
+/// Deserializes a Cairo 0 class, with its program either compressed (base64 of the gzipped JSON)
+/// or as JSON.
+pub(crate) fn deprecated_class_from_json(
+    mut value: Value,
+) -> Result<ContractClass, TransactionError> {
+    if let Some(Value::String(program)) = value.get("program") {
+        let compressed = STANDARD
+            .decode(program)
+            .map_err(|err| TransactionError::Serialization(err.to_string()))?;
+        let mut program = String::new();
+        GzDecoder::new(compressed.as_slice())
+            .read_to_string(&mut program)
+            .map_err(|err| TransactionError::Serialization(err.to_string()))?;
+        value["program"] = serde_json::from_str(&program)
+            .map_err(|err| TransactionError::Serialization(err.to_string()))?;
+    }
+    let class_json = value.to_string();
+    Ok(ContractClass::from_str(&class_json)?.with_class_json(class_json))
+}
+
+// --------------------
+// Felt serialization
+// --------------------
+
+/// A felt serialized as a hex string. Deserializes from hex or decimal strings, and from numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct HexFelt(Felt252);
+
+impl Serialize for HexFelt {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:#x}", self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for HexFelt {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let felt = match Value::deserialize(deserializer)? {
+            Value::String(felt) if felt.starts_with("0x") || felt.starts_with("0X") => {
+                Felt252::from_hex(&felt).ok()
+            }
+            Value::String(felt) => Felt252::from_dec_str(&felt).ok(),
+            Value::Number(felt) => felt.as_u64().map(Felt252::from),
+            _ => None,
+        };
+        felt.map(HexFelt)
+            .ok_or_else(|| de::Error::custom("invalid felt"))
+    }
+}
+
+mod felt_hex {
+    use super::*;
+
+    pub(super) fn serialize<S: Serializer>(
+        felt: &Felt252,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        HexFelt(*felt).serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Felt252, D::Error> {
+        HexFelt::deserialize(deserializer).map(|felt| felt.0)
+    }
+}
+
+mod option_felt_hex {
+    use super::*;
+
+    pub(super) fn serialize<S: Serializer>(
+        felt: &Option<Felt252>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        felt.map(HexFelt).serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Felt252>, D::Error> {
+        Option::<HexFelt>::deserialize(deserializer).map(|felt| felt.map(|felt| felt.0))
+    }
+}
+
+mod option_felts_hex {
+    use super::*;
+
+    pub(super) fn serialize<S: Serializer>(
+        felts: &Option<Vec<Felt252>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        felts
+            .as_ref()
+            .map(|felts| felts.iter().copied().map(HexFelt).collect::<Vec<_>>())
+            .serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Vec<Felt252>>, D::Error> {
+        Option::<Vec<HexFelt>>::deserialize(deserializer)
+            .map(|felts| felts.map(|felts| felts.into_iter().map(|felt| felt.0).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::contract_address::compute_casm_class_hash,
+        definitions::{
+            block_context::StarknetChainId,
+            constants::{EXECUTE_ENTRY_POINT_SELECTOR, QUERY_VERSION_1},
+        },
+        transaction::{Address, ClassHash},
+    };
+    use cairo_lang_starknet::casm_contract_class::CasmContractClass;
+    use pretty_assertions_sorted::assert_eq;
+    use serde_json::json;
+    use std::{fs::File, io::BufReader};
+
+    fn invoke_v1() -> Transaction {
+        Transaction::InvokeFunction(
+            InvokeFunction::new_with_tx_hash(
+                Address(0x1234.into()),
+                Felt252::ZERO,
+                VersionSpecificAccountTxFields::Deprecated(1000),
+                Felt252::ONE,
+                vec![1.into(), 2.into()],
+                vec![0xabc.into()],
+                Some(5.into()),
+                0x99.into(),
+            )
+            .unwrap(),
+        )
+    }
+
+    fn deploy_account_v3() -> Transaction {
+        let account_tx_fields = VersionSpecificAccountTxFields::Current(CurrentAccountTxFields {
+            l1_resource_bounds: ResourceBounds {
+                max_amount: 0x100,
+                max_price_per_unit: 0x2000,
+            },
+            l2_resource_bounds: Some(ResourceBounds::default()),
+            tip: 3,
+            nonce_data_availability_mode: DataAvailabilityMode::L1,
+            fee_data_availability_mode: DataAvailabilityMode::L2,
+            paymaster_data: vec![7.into()],
+            account_deployment_data: vec![],
+        });
+
+        Transaction::DeployAccount(
+            DeployAccount::new_with_tx_hash(
+                ClassHash::from(Felt252::from(0x42)),
+                account_tx_fields,
+                Felt252::THREE,
+                Felt252::ZERO,
+                vec![8.into()],
+                vec![],
+                0x55.into(),
+                0x77.into(),
+            )
+            .unwrap(),
+        )
+    }
+
+    fn fibonacci_sierra_class() -> SierraContractClass {
+        let file = File::open("starknet_programs/cairo2/fibonacci.sierra").unwrap();
+        serde_json::from_reader(BufReader::new(file)).unwrap()
+    }
+
+    fn l1_handler() -> Transaction {
+        Transaction::L1Handler(
+            L1Handler::new_with_tx_hash(
+                Address(0x1.into()),
+                0x2.into(),
+                vec![0x3.into()],
+                0x4.into(),
+                Some(0x5.into()),
+                0x6.into(),
+            )
+            .unwrap(),
+        )
+    }
+
+    fn declare_v2() -> Transaction {
+        Transaction::Declare(Box::new(
+            Declare::new_with_tx_hash(
+                &fibonacci_sierra_class(),
+                None,
+                0x123.into(),
+                Address(0x1.into()),
+                VersionSpecificAccountTxFields::Deprecated(10),
+                Felt252::TWO,
+                vec![],
+                Felt252::ONE,
+                0x456.into(),
+            )
+            .unwrap(),
+        ))
+    }
+
+    fn fibonacci_deprecated_class() -> ContractClass {
+        let class_json = std::fs::read_to_string("starknet_programs/fibonacci.json").unwrap();
+        ContractClass::from_str(&class_json)
+            .unwrap()
+            .with_class_json(class_json)
+    }
+
+    fn declare_v1() -> Transaction {
+        Transaction::DeclareDeprecated(
+            DeclareDeprecated::new_with_tx_hash(
+                fibonacci_deprecated_class(),
+                Address(0x1.into()),
+                10,
+                Felt252::ONE,
+                vec![0xabc.into()],
+                Felt252::TWO,
+                0x789.into(),
+            )
+            .unwrap(),
+        )
+    }
+
+    fn deploy() -> Transaction {
+        Transaction::Deploy(
+            Deploy::new_with_tx_hash(
+                0x55.into(),
+                fibonacci_deprecated_class(),
+                vec![0x8.into()],
+                Felt252::ZERO,
+                0x99.into(),
+            )
+            .unwrap(),
+        )
+    }
+
+    fn round_trip(tx: &Transaction, format: TransactionFormat) -> Value {
+        let value = tx.to_json(format).unwrap();
+        let deserialized = Transaction::from_json(value.clone()).unwrap();
+        assert_eq!(deserialized.to_json(format).unwrap(), value);
+        value
+    }
+
+    #[test]
+    fn every_variant_round_trips() {
+        let txs = [
+            invoke_v1(),
+            deploy_account_v3(),
+            l1_handler(),
+            declare_v2(),
+            declare_v1(),
+            deploy(),
+        ];
+
+        for tx in &txs {
+            for format in [TransactionFormat::JsonRpc, TransactionFormat::FeederGateway] {
+                round_trip(tx, format);
+            }
+        }
+    }
+
+    #[test]
+    fn invoke_json_rpc_format() {
+        let value = round_trip(&invoke_v1(), TransactionFormat::JsonRpc);
+
+        assert_eq!(
+            value,
+            json!({
+                "type": "INVOKE",
+                "transaction_hash": "0x99",
+                "version": "0x1",
+                "sender_address": "0x1234",
+                "calldata": ["0x1", "0x2"],
+                "max_fee": "0x3e8",
+                "signature": ["0xabc"],
+                "nonce": "0x5",
+            })
+        );
+    }
+
+    #[test]
+    fn deploy_account_v3_feeder_gateway_format() {
+        let tx = deploy_account_v3();
+        let value = round_trip(&tx, TransactionFormat::FeederGateway);
+
+        assert_eq!(value["type"], "DEPLOY_ACCOUNT");
+        assert_eq!(value["nonce_data_availability_mode"], 0);
+        assert_eq!(value["fee_data_availability_mode"], 1);
+        assert_eq!(
+            value["resource_bounds"],
+            json!({
+                "L1_GAS": { "max_amount": "0x100", "max_price_per_unit": "0x2000" },
+                "L2_GAS": { "max_amount": "0x0", "max_price_per_unit": "0x0" },
+            })
+        );
+        assert_eq!(value["paymaster_data"], json!(["0x7"]));
+        assert_eq!(value.get("account_deployment_data"), None);
+
+        // Both formats deserialize into the same transaction.
+        let rpc_value = tx.to_json(TransactionFormat::JsonRpc).unwrap();
+        assert_eq!(rpc_value["fee_data_availability_mode"], "L2");
+        assert_eq!(
+            Transaction::from_json(value)
+                .unwrap()
+                .to_json(TransactionFormat::JsonRpc)
+                .unwrap(),
+            rpc_value
+        );
+    }
+
+    #[test]
+    fn l1_handler_round_trip() {
+        let value = round_trip(&l1_handler(), TransactionFormat::JsonRpc);
+        assert_eq!(value["type"], "L1_HANDLER");
+        assert_eq!(value["paid_fee_on_l1"], "0x5");
+    }
+
+    #[test]
+    fn declare_round_trip() {
+        let value = round_trip(&declare_v2(), TransactionFormat::JsonRpc);
+        assert!(value["contract_class"]["abi"].is_string());
+        assert_eq!(value["compiled_class_hash"], "0x123");
+
+        // The class hash is computed from the class when missing.
+        let mut without_class_hash = value.clone();
+        without_class_hash
+            .as_object_mut()
+            .unwrap()
+            .remove("class_hash");
+        assert_eq!(
+            Transaction::from_json(without_class_hash)
+                .unwrap()
+                .to_json(TransactionFormat::JsonRpc)
+                .unwrap(),
+            value
+        );
+    }
+
+    #[test]
+    fn declare_deprecated_round_trip() {
+        let value = round_trip(&declare_v1(), TransactionFormat::JsonRpc);
+        assert!(value["contract_class"]["program"].is_string());
+        assert!(value["contract_class"]["entry_points_by_type"].is_object());
+
+        let Transaction::DeclareDeprecated(declare) = Transaction::from_json(value).unwrap() else {
+            panic!("expected a deprecated declare transaction");
+        };
+        assert_eq!(declare.contract_class, fibonacci_deprecated_class());
+    }
+
+    #[test]
+    fn deploy_round_trip() {
+        let tx = deploy();
+        let value = round_trip(&tx, TransactionFormat::JsonRpc);
+        assert!(value["contract_class"]["program"].is_string());
+
+        // The class hash and address are computed from the deserialized class.
+        let (Transaction::Deploy(expected), Transaction::Deploy(deploy)) =
+            (tx, Transaction::from_json(value).unwrap())
+        else {
+            panic!("expected deploy transactions");
+        };
+        assert_eq!(deploy.contract_hash, expected.contract_hash);
+        assert_eq!(deploy.contract_address, expected.contract_address);
+    }
+
+    #[test]
+    fn deserialize_numbers_and_decimal_strings() {
+        let tx = Transaction::from_json(json!({
+            "type": "INVOKE_FUNCTION",
+            "transaction_hash": "153",
+            "version": 1,
+            "sender_address": "0x1234",
+            "calldata": [1, "2"],
+            "max_fee": "1000",
+            "signature": ["0xabc"],
+            "nonce": 5,
+        }))
+        .unwrap();
+
+        assert_eq!(
+            tx.to_json(TransactionFormat::JsonRpc).unwrap(),
+            invoke_v1().to_json(TransactionFormat::JsonRpc).unwrap()
+        );
+    }
+
+    #[test]
+    fn deserialize_missing_fields() {
+        let mut value = invoke_v1().to_json(TransactionFormat::JsonRpc).unwrap();
+        value.as_object_mut().unwrap().remove("transaction_hash");
+        assert_matches!(
+            Transaction::from_json(value).err(),
+            Some(TransactionError::MissingTransactionField(field)) if field == "transaction_hash"
+        );
+
+        let mut value = deploy_account_v3()
+            .to_json(TransactionFormat::JsonRpc)
+            .unwrap();
+        value.as_object_mut().unwrap().remove("resource_bounds");
+        assert_matches!(
+            Transaction::from_json(value).err(),
+            Some(TransactionError::MissingTransactionField(field)) if field == "resource_bounds"
+        );
+    }
+
+    fn broadcasted_invoke(version: Felt252) -> Value {
+        json!({
+            "type": "INVOKE",
+            "version": format!("{version:#x}"),
+            "sender_address": "0x1234",
+            "calldata": ["0x1", "0x2"],
+            "max_fee": "0x3e8",
+            "signature": ["0xabc"],
+            "nonce": "0x5",
+        })
+    }
+
+    #[test]
+    fn broadcasted_invoke_hash() {
+        let chain_id = StarknetChainId::TestNet.to_felt();
+        let tx =
+            Transaction::from_broadcasted_json(broadcasted_invoke(Felt252::ONE), chain_id).unwrap();
+
+        let expected = InvokeFunction::new(
+            Address(0x1234.into()),
+            *EXECUTE_ENTRY_POINT_SELECTOR,
+            VersionSpecificAccountTxFields::Deprecated(1000),
+            Felt252::ONE,
+            vec![1.into(), 2.into()],
+            vec![0xabc.into()],
+            chain_id,
+            Some(5.into()),
+        )
+        .unwrap();
+        let Transaction::InvokeFunction(invoke) = &tx else {
+            panic!("expected an invoke transaction");
+        };
+        assert_eq!(invoke.hash_value(), expected.hash_value());
+
+        // Query versions are hashed as given but executed as the regular version.
+        let query_tx =
+            Transaction::from_broadcasted_json(broadcasted_invoke(*QUERY_VERSION_1), chain_id)
+                .unwrap();
+        let query_value = query_tx.to_json(TransactionFormat::JsonRpc).unwrap();
+        assert_eq!(query_value["version"], "0x1");
+        assert!(
+            query_value["transaction_hash"]
+                != tx.to_json(TransactionFormat::JsonRpc).unwrap()["transaction_hash"]
+        );
+    }
+
+    #[test]
+    fn broadcasted_deploy_account_hash() {
+        let chain_id = StarknetChainId::TestNet.to_felt();
+        let tx = Transaction::from_broadcasted_json(
+            json!({
+                "type": "DEPLOY_ACCOUNT",
+                "version": "0x1",
+                "class_hash": "0x42",
+                "contract_address_salt": "0x55",
+                "constructor_calldata": ["0x8"],
+                "max_fee": "0x10",
+                "signature": [],
+                "nonce": "0x0",
+            }),
+            chain_id,
+        )
+        .unwrap();
+
+        let expected = DeployAccount::new(
+            ClassHash::from(Felt252::from(0x42)),
+            VersionSpecificAccountTxFields::Deprecated(0x10),
+            Felt252::ONE,
+            Felt252::ZERO,
+            vec![8.into()],
+            vec![],
+            0x55.into(),
+            chain_id,
+        )
+        .unwrap();
+        let Transaction::DeployAccount(deploy_account) = tx else {
+            panic!("expected a deploy account transaction");
+        };
+        assert_eq!(deploy_account.hash_value(), expected.hash_value());
+        assert_eq!(
+            deploy_account.contract_address(),
+            expected.contract_address()
+        );
+    }
+
+    #[test]
+    fn broadcasted_declare_compiles_sierra_class() {
+        let chain_id = StarknetChainId::TestNet.to_felt();
+        let sierra_contract_class = fibonacci_sierra_class();
+        let casm_class =
+            CasmContractClass::from_contract_class(sierra_contract_class.clone(), true).unwrap();
+        let compiled_class_hash = compute_casm_class_hash(&casm_class).unwrap();
+
+        let mut value = json!({
+            "type": "DECLARE",
+            "version": "0x2",
+            "sender_address": "0x1",
+            "compiled_class_hash": format!("{compiled_class_hash:#x}"),
+            "contract_class": sierra_class_to_json(&sierra_contract_class).unwrap(),
+            "max_fee": "0xa",
+            "signature": [],
+            "nonce": "0x1",
+        });
+        let tx = Transaction::from_broadcasted_json(value.clone(), chain_id).unwrap();
+
+        let expected = Declare::new(
+            &sierra_contract_class,
+            None,
+            compiled_class_hash,
+            chain_id,
+            Address(0x1.into()),
+            VersionSpecificAccountTxFields::Deprecated(10),
+            Felt252::TWO,
+            vec![],
+            Felt252::ONE,
+        )
+        .unwrap();
+        let Transaction::Declare(declare) = tx else {
+            panic!("expected a declare transaction");
+        };
+        assert_eq!(declare.hash_value, expected.hash_value);
+        assert_eq!(declare.casm_class, Some(casm_class));
+
+        value["compiled_class_hash"] = "0x123".into();
+        assert_matches!(
+            Transaction::from_broadcasted_json(value, chain_id).err(),
+            Some(TransactionError::InvalidCompiledClassHash(_, _))
+        );
+    }
+
+    #[test]
+    fn broadcasted_unsupported_transactions() {
+        let chain_id = StarknetChainId::TestNet.to_felt();
+
+        assert_matches!(
+            Transaction::from_broadcasted_json(broadcasted_invoke(Felt252::TWO), chain_id).err(),
+            Some(TransactionError::UnsupportedTxVersion(_, _, _))
+        );
+
+        let mut v3 = broadcasted_invoke(Felt252::THREE);
+        v3["resource_bounds"] = json!({
+            "l1_gas": { "max_amount": "0x1", "max_price_per_unit": "0x1" },
+        });
+        assert!(Transaction::from_broadcasted_json(v3, chain_id).is_ok());
+
+        let l1_handler = json!({
+            "type": "L1_HANDLER",
+            "version": "0x0",
+            "contract_address": "0x1",
+            "entry_point_selector": "0x2",
+            "calldata": [],
+            "nonce": "0x0",
+        });
+        assert_matches!(
+            Transaction::from_broadcasted_json(l1_handler, chain_id).err(),
+            Some(TransactionError::NotBroadcastable(_))
+        );
+    }
+}
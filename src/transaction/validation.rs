@@ -0,0 +1,463 @@
+//! Mempool admission checks.
+//!
+//! [`Transaction::validate_stateless`] performs the checks a gateway runs on a transaction before
+//! accepting it, none of which require executing it or reading the state.
+//! [`Transaction::validate_against_state`] adds the cheap checks that only read the sender's nonce
+//! and fee token balance.
+
+use super::{
+    check_account_tx_fields_version,
+    error::TransactionError,
+    fee::{check_fee_bounds, AccountTxType},
+    get_tx_version,
+    serialization::sierra_class_to_json,
+    Address, DataAvailabilityMode, Declare, Transaction, VersionSpecificAccountTxFields,
+};
+use crate::{
+    definitions::block_context::BlockContext, state::state_api::StateReader,
+    utils::get_erc20_balance_var_addresses,
+};
+use cairo_vm::Felt252;
+use num_traits::{ToPrimitive, Zero};
+
+/// Limits enforced by [`Transaction::validate_stateless`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StatelessValidationConfig {
+    pub max_calldata_length: usize,
+    pub max_signature_length: usize,
+    /// Maximum number of felts of the Sierra program of a declared class.
+    pub max_bytecode_size: usize,
+    /// Maximum size in bytes of a declared class, serialized as in JSON-RPC.
+    pub max_raw_class_size: usize,
+    /// Minimum Sierra version (major, minor, patch) of a declared class.
+    pub min_sierra_version: (usize, usize, usize),
+    /// Maximum Sierra version of a declared class. Any patch version is accepted.
+    pub max_sierra_version: (usize, usize, usize),
+    /// Whether v3 transactions may use L2 as their nonce or fee data availability mode.
+    pub allow_l2_data_availability: bool,
+}
+
+impl Default for StatelessValidationConfig {
+    fn default() -> Self {
+        Self {
+            max_calldata_length: 4000,
+            max_signature_length: 4000,
+            max_bytecode_size: 81920,
+            max_raw_class_size: 4089446,
+            min_sierra_version: (1, 1, 0),
+            max_sierra_version: (1, 4, 0),
+            allow_l2_data_availability: false,
+        }
+    }
+}
+
+/// The fields of an account transaction that admission checks look at.
+struct AccountTxFields<'a> {
+    tx_type: &'static str,
+    account_tx_type: AccountTxType,
+    supported_versions: &'static [usize],
+    version: Felt252,
+    sender_address: &'a Address,
+    nonce: Felt252,
+    calldata: &'a [Felt252],
+    signature: &'a [Felt252],
+    account_tx_fields: VersionSpecificAccountTxFields,
+}
+
+impl Transaction {
+    /// Checks that the transaction could be accepted into a mempool without executing it:
+    /// - its version is supported and matches its fee fields,
+    /// - its calldata and signature aren't too long,
+    /// - its max fee or L1 gas bounds cover the minimal cost of the transaction type,
+    /// - its data availability modes are allowed,
+    /// - the Sierra class of declares isn't too large and has a supported Sierra version.
+    ///
+    /// Deploy and L1 handler transactions can't be submitted by users, and are rejected.
+    pub fn validate_stateless(
+        &self,
+        block_context: &BlockContext,
+        config: &StatelessValidationConfig,
+    ) -> Result<(), TransactionError> {
+        let fields = self.account_fields()?;
+
+        let version = get_tx_version(fields.version);
+        if !fields
+            .supported_versions
+            .iter()
+            .any(|supported| Felt252::from(*supported) == version)
+        {
+            return Err(TransactionError::UnsupportedTxVersion(
+                fields.tx_type.to_string(),
+                fields.version,
+                fields.supported_versions.to_vec(),
+            ));
+        }
+        check_account_tx_fields_version(&fields.account_tx_fields, version)?;
+
+        if fields.calldata.len() > config.max_calldata_length {
+            return Err(TransactionError::CalldataTooLong(
+                fields.calldata.len(),
+                config.max_calldata_length,
+            ));
+        }
+        if fields.signature.len() > config.max_signature_length {
+            return Err(TransactionError::SignatureTooLong(
+                fields.signature.len(),
+                config.max_signature_length,
+            ));
+        }
+
+        check_fee_bounds(
+            &fields.account_tx_fields,
+            block_context,
+            fields.account_tx_type,
+        )?;
+
+        if let VersionSpecificAccountTxFields::Current(current) = &fields.account_tx_fields {
+            let uses_l2 = current.nonce_data_availability_mode == DataAvailabilityMode::L2
+                || current.fee_data_availability_mode == DataAvailabilityMode::L2;
+            if uses_l2 && !config.allow_l2_data_availability {
+                return Err(TransactionError::UnsupportedDataAvailabilityMode);
+            }
+        }
+
+        if let Transaction::Declare(declare) = self {
+            validate_declared_class(declare, config)?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks the transaction against the sender's state: its nonce must not be lower than the
+    /// account nonce, and the account's fee token balance must cover its max fee. Nonces higher
+    /// than the account nonce are accepted, as the mempool may hold the transactions in between.
+    pub fn validate_against_state<S: StateReader>(
+        &self,
+        state: &S,
+        block_context: &BlockContext,
+    ) -> Result<(), TransactionError> {
+        let fields = self.account_fields()?;
+
+        let account_nonce = state.get_nonce_at(fields.sender_address)?;
+        if fields.nonce < account_nonce {
+            return Err(TransactionError::InvalidTransactionNonce(
+                account_nonce.to_string(),
+                fields.nonce.to_string(),
+            ));
+        }
+
+        let max_fee = fields.account_tx_fields.max_fee();
+        let fee_token_address =
+            block_context.get_fee_token_address_by_fee_type(&fields.account_tx_fields.fee_type());
+        let (low_key, high_key) = get_erc20_balance_var_addresses(fields.sender_address)?;
+        let balance_low = state.get_storage_at(&(fee_token_address.clone(), low_key))?;
+        let balance_high = state.get_storage_at(&(fee_token_address.clone(), high_key))?;
+        // The fee is at most 128 bits, while balance is 256 bits (split into two 128 bit words).
+        if balance_high.is_zero() && balance_low < Felt252::from(max_fee) {
+            return Err(TransactionError::MaxFeeExceedsBalance(
+                max_fee,
+                balance_low,
+                balance_high,
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn account_fields(&self) -> Result<AccountTxFields, TransactionError> {
+        Ok(match self {
+            Transaction::InvokeFunction(tx) => AccountTxFields {
+                tx_type: "Invoke",
+                account_tx_type: AccountTxType::Invoke,
+                supported_versions: &[1, 3],
+                version: *tx.version(),
+                sender_address: tx.contract_address(),
+                nonce: tx.nonce().unwrap_or_default(),
+                calldata: tx.calldata(),
+                signature: tx.signature(),
+                account_tx_fields: tx.account_tx_fields().clone(),
+            },
+            Transaction::DeclareDeprecated(tx) => AccountTxFields {
+                tx_type: "Declare",
+                account_tx_type: AccountTxType::Declare,
+                supported_versions: &[1],
+                version: tx.version,
+                sender_address: &tx.sender_address,
+                nonce: tx.nonce,
+                calldata: &[],
+                signature: &tx.signature,
+                account_tx_fields: VersionSpecificAccountTxFields::Deprecated(tx.max_fee),
+            },
+            Transaction::Declare(tx) => AccountTxFields {
+                tx_type: "Declare",
+                account_tx_type: AccountTxType::Declare,
+                supported_versions: &[2, 3],
+                version: tx.version,
+                sender_address: &tx.sender_address,
+                nonce: tx.nonce,
+                calldata: &[],
+                signature: &tx.signature,
+                account_tx_fields: tx.account_tx_fields.clone(),
+            },
+            Transaction::DeployAccount(tx) => AccountTxFields {
+                tx_type: "DeployAccount",
+                account_tx_type: AccountTxType::DeployAccount,
+                supported_versions: &[1, 3],
+                version: *tx.version(),
+                sender_address: tx.contract_address(),
+                nonce: *tx.nonce(),
+                calldata: tx.constructor_calldata(),
+                signature: tx.signature(),
+                account_tx_fields: tx.account_tx_fields().clone(),
+            },
+            Transaction::Deploy(_) => {
+                return Err(TransactionError::NotBroadcastable("Deploy".to_string()))
+            }
+            Transaction::L1Handler(_) => {
+                return Err(TransactionError::NotBroadcastable("L1Handler".to_string()))
+            }
+        })
+    }
+}
+
+fn validate_declared_class(
+    declare: &Declare,
+    config: &StatelessValidationConfig,
+) -> Result<(), TransactionError> {
+    // Declares built from a CASM class alone have nothing to check.
+    let Some(sierra_contract_class) = &declare.sierra_contract_class else {
+        return Ok(());
+    };
+
+    let bytecode_size = sierra_contract_class.sierra_program.len();
+    if bytecode_size > config.max_bytecode_size {
+        return Err(TransactionError::ContractBytecodeTooLarge(
+            bytecode_size,
+            config.max_bytecode_size,
+        ));
+    }
+
+    let raw_class_size = sierra_class_to_json(sierra_contract_class)?
+        .to_string()
+        .len();
+    if raw_class_size > config.max_raw_class_size {
+        return Err(TransactionError::ContractClassTooLarge(
+            raw_class_size,
+            config.max_raw_class_size,
+        ));
+    }
+
+    // The Sierra version is encoded in the first three felts of the program.
+    let version: Option<Vec<usize>> = sierra_contract_class
+        .sierra_program
+        .iter()
+        .take(3)
+        .map(|felt| felt.value.to_usize())
+        .collect();
+    let version = match version.as_deref() {
+        Some(&[major, minor, patch]) => (major, minor, patch),
+        _ => return Err(TransactionError::InvalidSierraVersion),
+    };
+    let (max_major, max_minor, _) = config.max_sierra_version;
+    if version < config.min_sierra_version || (version.0, version.1) > (max_major, max_minor) {
+        return Err(TransactionError::UnsupportedSierraVersion(
+            format!("{}.{}.{}", version.0, version.1, version.2),
+            format!(
+                "{}.{}.{}",
+                config.min_sierra_version.0,
+                config.min_sierra_version.1,
+                config.min_sierra_version.2
+            ),
+            format!("{max_major}.{max_minor}"),
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        core::contract_address::compute_casm_class_hash,
+        definitions::constants::EXECUTE_ENTRY_POINT_SELECTOR,
+        state::in_memory_state_reader::InMemoryStateReader,
+        transaction::{CurrentAccountTxFields, InvokeFunction, ResourceBounds},
+    };
+    use cairo_lang_starknet::{
+        casm_contract_class::CasmContractClass,
+        contract_class::ContractClass as SierraContractClass,
+    };
+    use std::{fs::File, io::BufReader};
+
+    const SENDER: u64 = 0x1234;
+
+    fn invoke(
+        account_tx_fields: VersionSpecificAccountTxFields,
+        version: Felt252,
+        calldata: Vec<Felt252>,
+        nonce: u64,
+    ) -> Transaction {
+        Transaction::InvokeFunction(
+            InvokeFunction::new_with_tx_hash(
+                Address(SENDER.into()),
+                *EXECUTE_ENTRY_POINT_SELECTOR,
+                account_tx_fields,
+                version,
+                calldata,
+                vec![],
+                Some(nonce.into()),
+                Felt252::ZERO,
+            )
+            .unwrap(),
+        )
+    }
+
+    fn invoke_v1(max_fee: u128, nonce: u64) -> Transaction {
+        invoke(
+            VersionSpecificAccountTxFields::Deprecated(max_fee),
+            Felt252::ONE,
+            vec![],
+            nonce,
+        )
+    }
+
+    fn invoke_v3(nonce_data_availability_mode: DataAvailabilityMode) -> Transaction {
+        invoke(
+            VersionSpecificAccountTxFields::Current(CurrentAccountTxFields {
+                l1_resource_bounds: ResourceBounds {
+                    max_amount: 1_000_000,
+                    max_price_per_unit: u64::MAX as u128,
+                },
+                nonce_data_availability_mode,
+                ..Default::default()
+            }),
+            Felt252::THREE,
+            vec![],
+            0,
+        )
+    }
+
+    #[test]
+    fn validate_stateless_limits() {
+        let block_context = BlockContext::default();
+        let config = StatelessValidationConfig::default();
+
+        assert!(invoke_v1(u64::MAX as u128, 0)
+            .validate_stateless(&block_context, &config)
+            .is_ok());
+        assert!(invoke_v3(DataAvailabilityMode::L1)
+            .validate_stateless(&block_context, &config)
+            .is_ok());
+
+        assert_matches!(
+            invoke_v1(0, 0).validate_stateless(&block_context, &config),
+            Err(TransactionError::MaxFeeTooLow(0, _))
+        );
+        assert_matches!(
+            invoke(
+                VersionSpecificAccountTxFields::Deprecated(u64::MAX as u128),
+                Felt252::ONE,
+                vec![Felt252::ONE; 4001],
+                0
+            )
+            .validate_stateless(&block_context, &config),
+            Err(TransactionError::CalldataTooLong(4001, 4000))
+        );
+        assert_matches!(
+            invoke_v3(DataAvailabilityMode::L2).validate_stateless(&block_context, &config),
+            Err(TransactionError::UnsupportedDataAvailabilityMode)
+        );
+        assert!(invoke_v3(DataAvailabilityMode::L2)
+            .validate_stateless(
+                &block_context,
+                &StatelessValidationConfig {
+                    allow_l2_data_availability: true,
+                    ..Default::default()
+                }
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn validate_stateless_declared_class() {
+        let block_context = BlockContext::default();
+        let file = File::open("starknet_programs/cairo2/fibonacci.sierra").unwrap();
+        let sierra_contract_class: SierraContractClass =
+            serde_json::from_reader(BufReader::new(file)).unwrap();
+        let casm_class =
+            CasmContractClass::from_contract_class(sierra_contract_class.clone(), true).unwrap();
+        let declare = Transaction::Declare(Box::new(
+            Declare::new_with_tx_hash(
+                &sierra_contract_class,
+                None,
+                compute_casm_class_hash(&casm_class).unwrap(),
+                Address(SENDER.into()),
+                VersionSpecificAccountTxFields::Deprecated(u64::MAX as u128),
+                Felt252::TWO,
+                vec![],
+                Felt252::ZERO,
+                Felt252::ZERO,
+            )
+            .unwrap(),
+        ));
+
+        assert!(declare
+            .validate_stateless(&block_context, &StatelessValidationConfig::default())
+            .is_ok());
+        assert_matches!(
+            declare.validate_stateless(
+                &block_context,
+                &StatelessValidationConfig {
+                    max_bytecode_size: 10,
+                    ..Default::default()
+                }
+            ),
+            Err(TransactionError::ContractBytecodeTooLarge(_, 10))
+        );
+        assert_matches!(
+            declare.validate_stateless(
+                &block_context,
+                &StatelessValidationConfig {
+                    min_sierra_version: (99, 0, 0),
+                    ..Default::default()
+                }
+            ),
+            Err(TransactionError::UnsupportedSierraVersion(_, _, _))
+        );
+    }
+
+    #[test]
+    fn validate_against_state_checks_nonce_and_balance() {
+        let block_context = BlockContext::default();
+        let sender = Address(SENDER.into());
+        let (balance_key, _) = get_erc20_balance_var_addresses(&sender).unwrap();
+        let fee_token_address = block_context
+            .get_fee_token_address_by_fee_type(
+                &VersionSpecificAccountTxFields::Deprecated(0).fee_type(),
+            )
+            .clone();
+
+        let mut state_reader = InMemoryStateReader::default();
+        state_reader.address_to_nonce_mut().insert(sender, 3.into());
+        state_reader
+            .address_to_storage_mut()
+            .insert((fee_token_address, balance_key), 1000.into());
+
+        assert!(invoke_v1(1000, 3)
+            .validate_against_state(&state_reader, &block_context)
+            .is_ok());
+        // Future nonces are accepted.
+        assert!(invoke_v1(1000, 5)
+            .validate_against_state(&state_reader, &block_context)
+            .is_ok());
+        assert_matches!(
+            invoke_v1(1000, 2).validate_against_state(&state_reader, &block_context),
+            Err(TransactionError::InvalidTransactionNonce(_, _))
+        );
+        assert_matches!(
+            invoke_v1(1001, 3).validate_against_state(&state_reader, &block_context),
+            Err(TransactionError::MaxFeeExceedsBalance(1001, _, _))
+        );
+    }
+}
@@ -3,7 +3,7 @@ use crate::services::api::contract_classes::deprecated_contract_class::EntryPoin
 use crate::state::state_api::{State, StateChangesCount};
 use crate::transaction::{Address, ClassHash};
 use crate::{
-    definitions::transaction_type::TransactionType,
+    definitions::{syscall_costs::SyscallCosts, transaction_type::TransactionType},
     execution::{
         gas_usage::calculate_tx_gas_usage, os_usage::get_additional_os_resources, CallInfo,
     },
@@ -17,7 +17,7 @@ use cairo_vm::{serde::deserialize_program::BuiltinName, vm::runners::builtin_run
 use cairo_vm::{types::relocatable::Relocatable, vm::vm_core::VirtualMachine};
 use num_traits::ToPrimitive;
 use serde_json::Value;
-use sha3::{Digest, Keccak256, Keccak256Core};
+use sha3::{digest::generic_array::GenericArray, Digest, Keccak256, Keccak256Core};
 use starknet::core::types::FromByteArrayError;
 use starknet_api::core::L2_ADDRESS_UPPER_BOUND;
 use starknet_crypto::{pedersen_hash, FieldElement};
@@ -178,6 +178,7 @@ pub fn calculate_tx_resources(
     state_changes: StateChangesCount,
     l1_handler_payload_size: Option<usize>,
     n_reverted_steps: usize,
+    syscall_costs: &SyscallCosts,
 ) -> Result<HashMap<String, usize>, TransactionError> {
     let non_optional_calls: Vec<CallInfo> = call_info.iter().flatten().cloned().collect();
 
@@ -194,7 +195,8 @@ pub fn calculate_tx_resources(
     let tx_syscall_counter = resources_manager.syscall_counter;
 
     // Add additional Cairo resources needed for the OS to run the transaction.
-    let additional_resources = get_additional_os_resources(tx_syscall_counter, &tx_type)?;
+    let additional_resources =
+        get_additional_os_resources(tx_syscall_counter, &tx_type, syscall_costs)?;
     let new_resources = &cairo_usage + &additional_resources;
     let mut filtered_builtins = new_resources.filter_unused_builtins();
 
@@ -387,6 +389,68 @@ pub fn calculate_sn_keccak(data: &[u8]) -> [u8; 32] {
     result
 }
 
+/// Applies the SHA-256 compression function to `state` with a block of 16 words.
+pub(crate) fn sha256_process_block(state: &mut [u32; 8], block: &[u32; 16]) {
+    let mut bytes = [0u8; 64];
+    for (chunk, word) in bytes.chunks_exact_mut(4).zip(block) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    sha2::compress256(state, &[GenericArray::clone_from_slice(&bytes)]);
+}
+
+//* ------------------------
+//*     ByteArray utils
+//* ------------------------
+
+/// Number of bytes packed in each full word of a serialized `ByteArray`.
+pub const BYTES_IN_BYTE_ARRAY_WORD: usize = 31;
+
+/// Returns the number of felts of a serialized Cairo `ByteArray` with `n_full_words` full words,
+/// or `None` if it doesn't fit in a `usize`.
+pub fn byte_array_serialized_len(n_full_words: &Felt252) -> Option<usize> {
+    n_full_words.to_usize()?.checked_add(3)
+}
+
+/// Serializes a `ByteArray`: the number of full words, the full words of 31 bytes each, the
+/// pending word and its length in bytes.
+pub fn encode_byte_array(bytes: &[u8], out: &mut Vec<Felt252>) {
+    let chunks = bytes.chunks_exact(BYTES_IN_BYTE_ARRAY_WORD);
+    let pending_word = chunks.remainder();
+
+    out.push(Felt252::from(bytes.len() / BYTES_IN_BYTE_ARRAY_WORD));
+    out.extend(chunks.map(Felt252::from_bytes_be_slice));
+    out.push(if pending_word.is_empty() {
+        Felt252::ZERO
+    } else {
+        Felt252::from_bytes_be_slice(pending_word)
+    });
+    out.push(Felt252::from(pending_word.len()));
+}
+
+/// Decodes a serialized Cairo `ByteArray` into a string. Returns `None` if `serialized` isn't
+/// exactly one `ByteArray` or if its bytes aren't valid UTF-8.
+pub fn decode_byte_array(serialized: &[Felt252]) -> Option<String> {
+    let (n_full_words, words) = serialized.split_first()?;
+    if serialized.len() != byte_array_serialized_len(n_full_words)? {
+        return None;
+    }
+    let (pending_word_len, words) = words.split_last()?;
+    let (pending_word, full_words) = words.split_last()?;
+
+    let pending_word_len = pending_word_len.to_usize()?;
+    if pending_word_len >= BYTES_IN_BYTE_ARRAY_WORD {
+        return None;
+    }
+
+    let mut bytes = Vec::new();
+    for word in full_words {
+        bytes.extend_from_slice(&word.to_bytes_be()[32 - BYTES_IN_BYTE_ARRAY_WORD..]);
+    }
+    bytes.extend_from_slice(&pending_word.to_bytes_be()[32 - pending_word_len..]);
+
+    String::from_utf8(bytes).ok()
+}
+
 //* ------------------------
 //*      Other utils
 //* ------------------------
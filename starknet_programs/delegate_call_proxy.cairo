@@ -0,0 +1,37 @@
+%lang starknet
+
+from starkware.starknet.common.syscalls import (
+    DELEGATE_CALL_SELECTOR, CallContractRequest, CallContract
+)
+
+func delegate_call{syscall_ptr: felt*}(
+    contract_address: felt, function_selector: felt, calldata_size: felt, calldata: felt*
+) -> (retdata_size: felt, retdata: felt*) {
+    let syscall = [cast(syscall_ptr, CallContract*)];
+    assert syscall.request = CallContractRequest(
+        selector=DELEGATE_CALL_SELECTOR,
+        contract_address=contract_address,
+        function_selector=function_selector,
+        calldata_size=calldata_size,
+        calldata=calldata,
+    );
+    %{ syscall_handler.delegate_call(segments=segments, syscall_ptr=ids.syscall_ptr) %}
+    let response = syscall.response;
+
+    let syscall_ptr = syscall_ptr + CallContract.SIZE;
+    return (retdata_size=response.retdata_size, retdata=response.retdata);
+}
+
+@external
+@raw_output
+func test_delegate_call{syscall_ptr: felt*}(
+    contract_address: felt, function_selector: felt, calldata_len: felt, calldata: felt*
+) -> (retdata_size: felt, retdata: felt*) {
+    let (retdata_size: felt, retdata: felt*) = delegate_call(
+        contract_address=contract_address,
+        function_selector=function_selector,
+        calldata_size=calldata_len,
+        calldata=calldata,
+    );
+    return (retdata_size=retdata_size, retdata=retdata);
+}
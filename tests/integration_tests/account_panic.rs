@@ -4,6 +4,7 @@ use cairo_vm::Felt252;
 use starknet_in_rust::{
     core::contract_address::compute_casm_class_hash,
     definitions::{block_context::BlockContext, constants::TRANSACTION_VERSION},
+    execution::revert::RevertReason,
     services::api::contract_classes::compiled_class::CompiledClass,
     state::{
         cached_state::CachedState,
@@ -113,19 +114,20 @@ fn account_panic() {
 
     let tx = Transaction::InvokeFunction(invoke);
     let exec_info = tx
-        .execute(
-            &mut state,
-            &block_context,
-            u128::MAX,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &block_context, u128::MAX, &Default::default())
         .expect("failed to invoke");
-    let call_info = exec_info.call_info.as_ref().unwrap();
 
-    assert_eq!(exec_info.revert_error, None);
+    // The panic in `__execute__` reverts the transaction.
+    assert!(exec_info.call_info.is_none());
+    let revert_error = exec_info.revert_error.unwrap();
 
     // 482670963043u128 == 'panic'
-    assert_eq!(call_info.retdata[0], 482670963043u128.into());
-    assert!(call_info.failure_flag);
+    assert_eq!(
+        revert_error.reason,
+        RevertReason::Panic(vec![482670963043u128.into()])
+    );
+    assert_eq!(
+        revert_error.reason.to_string(),
+        "Execution failed. Failure reason: 0x70616e6963 ('panic')."
+    );
 }
@@ -118,8 +118,7 @@ fn storage_write_read() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -143,8 +142,7 @@ fn storage_write_read() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     assert_eq!(call_info.call_info.unwrap().retdata, [25.into()]);
@@ -169,8 +167,7 @@ fn storage_write_read() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -194,8 +191,7 @@ fn storage_write_read() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     assert_eq!(call_info.call_info.unwrap().retdata, [125.into()])
@@ -343,8 +339,7 @@ fn library_call() {
                 &mut tx_execution_context,
                 false,
                 block_context.invoke_tx_max_n_steps(),
-                #[cfg(feature = "cairo-native")]
-                None,
+                &Default::default(),
             )
             .unwrap()
             .call_info
@@ -472,8 +467,7 @@ fn call_contract_storage_write_read() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -497,8 +491,7 @@ fn call_contract_storage_write_read() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     assert_eq!(call_info.call_info.unwrap().retdata, [25.into()]);
@@ -523,8 +516,7 @@ fn call_contract_storage_write_read() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -548,8 +540,7 @@ fn call_contract_storage_write_read() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     assert_eq!(call_info.call_info.unwrap().retdata, [125.into()])
@@ -624,8 +615,7 @@ fn emit_event() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     assert_eq!(
@@ -747,8 +737,7 @@ fn deploy_cairo1_from_cairo1() {
         &mut tx_execution_context,
         false,
         block_context.invoke_tx_max_n_steps(),
-        #[cfg(feature = "cairo-native")]
-        None,
+        &Default::default(),
     );
 
     assert!(call_info.is_ok());
@@ -854,8 +843,7 @@ fn deploy_cairo0_from_cairo1_without_constructor() {
         &mut tx_execution_context,
         false,
         block_context.invoke_tx_max_n_steps(),
-        #[cfg(feature = "cairo-native")]
-        None,
+        &Default::default(),
     );
 
     assert!(call_info.is_ok());
@@ -962,8 +950,7 @@ fn deploy_cairo0_from_cairo1_with_constructor() {
         &mut tx_execution_context,
         false,
         block_context.invoke_tx_max_n_steps(),
-        #[cfg(feature = "cairo-native")]
-        None,
+        &Default::default(),
     );
 
     assert!(call_info.is_ok());
@@ -1070,8 +1057,7 @@ fn deploy_cairo0_and_invoke() {
         &mut tx_execution_context,
         false,
         block_context.invoke_tx_max_n_steps(),
-        #[cfg(feature = "cairo-native")]
-        None,
+        &Default::default(),
     );
 
     assert!(call_info.is_ok());
@@ -1118,8 +1104,7 @@ fn deploy_cairo0_and_invoke() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -1196,8 +1181,7 @@ fn test_send_message_to_l1_syscall() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -1298,8 +1282,7 @@ fn test_get_execution_info() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -1421,8 +1404,7 @@ fn replace_class_internal() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     // Check that the class was indeed replaced in storage
@@ -1557,8 +1539,7 @@ fn replace_class_contract_call() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     assert_eq!(result.call_info.unwrap().retdata, vec![25.into()]);
@@ -1586,8 +1567,7 @@ fn replace_class_contract_call() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -1614,8 +1594,7 @@ fn replace_class_contract_call() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     assert_eq!(result.call_info.clone().unwrap().retdata, vec![17.into()]);
@@ -1738,8 +1717,7 @@ fn replace_class_contract_call_same_transaction() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     assert_eq!(
@@ -1862,8 +1840,7 @@ fn call_contract_upgrade_cairo_0_to_cairo_1_same_transaction() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     assert_eq!(
@@ -1984,8 +1961,7 @@ fn call_contract_downgrade_cairo_1_to_cairo_0_same_transaction() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     assert_eq!(
@@ -2103,8 +2079,7 @@ fn call_contract_replace_class_cairo_0() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     assert_eq!(
@@ -2183,8 +2158,7 @@ fn test_out_of_gas_failure() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     let call_info = call_info.call_info.unwrap();
@@ -2265,8 +2239,7 @@ fn deploy_syscall_failure_uninitialized_class_hash() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     assert_eq!(
@@ -2360,8 +2333,7 @@ fn deploy_syscall_failure_in_constructor() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     // Check that we get the error from the constructor
@@ -2440,8 +2412,7 @@ fn storage_read_no_value() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     // As the value doesn't exist in storage, it's value will be 0
@@ -2515,8 +2486,7 @@ fn storage_read_unavailable_address_domain() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -2593,8 +2563,7 @@ fn storage_write_unavailable_address_domain() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -2702,8 +2671,7 @@ fn library_call_failure() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -2814,8 +2782,7 @@ fn send_messages_to_l1_different_contract_calls() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     let l1_to_l2_messages = call_info
@@ -2939,8 +2906,7 @@ fn send_messages_to_l1_different_contract_calls_cairo1_to_cairo0() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     let l1_to_l2_messages = call_info
@@ -3063,8 +3029,7 @@ fn send_messages_to_l1_different_contract_calls_cairo0_to_cairo1() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     let l1_to_l2_messages = call_info
@@ -3160,8 +3125,7 @@ fn keccak_syscall() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -3274,8 +3238,7 @@ fn library_call_recursive_50_calls() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap()
         .call_info
@@ -3425,8 +3388,7 @@ fn call_contract_storage_write_read_recursive_50_calls() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -3450,8 +3412,7 @@ fn call_contract_storage_write_read_recursive_50_calls() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     assert_eq!(call_info.call_info.unwrap().retdata, [25.into()]);
@@ -3476,8 +3437,7 @@ fn call_contract_storage_write_read_recursive_50_calls() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap()
         .call_info
@@ -3506,8 +3466,7 @@ fn call_contract_storage_write_read_recursive_50_calls() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     assert_eq!(call_info.call_info.unwrap().retdata, [75.into()])
@@ -3631,8 +3590,7 @@ fn call_contract_storage_write_read_recursive_100_calls() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -3656,8 +3614,7 @@ fn call_contract_storage_write_read_recursive_100_calls() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     assert_eq!(call_info.call_info.unwrap().retdata, [25.into()]);
@@ -3682,8 +3639,7 @@ fn call_contract_storage_write_read_recursive_100_calls() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap()
         .call_info
@@ -3712,8 +3668,7 @@ fn call_contract_storage_write_read_recursive_100_calls() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     assert_eq!(call_info.call_info.unwrap().retdata, [125.into()])
@@ -3804,8 +3759,7 @@ fn test_get_execution_info_v2() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
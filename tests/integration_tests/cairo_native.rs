@@ -1,23 +1,35 @@
 #![cfg(feature = "cairo-native")]
 
 use assert_matches::assert_matches;
+use cairo_lang_sierra::{
+    ids::{ConcreteLibfuncId, GenericLibfuncId},
+    program::{ConcreteLibfuncLongId, LibfuncDeclaration},
+};
 use cairo_vm::Felt252;
 use pretty_assertions_sorted::*;
 use starknet_in_rust::hash_utils::calculate_contract_address;
 use starknet_in_rust::{
-    definitions::{block_context::BlockContext, constants::TRANSACTION_VERSION},
+    definitions::{
+        block_context::{BlockContext, FeeType},
+        constants::TRANSACTION_VERSION,
+    },
     execution::{
+        backend::{ExecutionBackend, ExecutionConfig},
         execution_entry_point::{ExecutionEntryPoint, ExecutionResult},
         CallType, TransactionExecutionContext,
     },
-    services::api::contract_classes::compiled_class::CompiledClass,
+    services::api::contract_classes::{
+        compiled_class::CompiledClass,
+        deprecated_contract_class::ContractClass as DeprecatedContractClass,
+    },
     state::{
         cached_state::CachedState,
         contract_class_cache::{ContractClassCache, PermanentContractClassCache},
         in_memory_state_reader::InMemoryStateReader,
+        state_api::StateReader,
         ExecutionResourcesManager,
     },
-    transaction::{Address, ClassHash},
+    transaction::{fee::calculate_tx_fee, Address, ClassHash},
     utils::{calculate_sn_keccak, felt_to_hash},
     CasmContractClass, ContractClass as SierraContractClass, EntryPointType,
 };
@@ -827,6 +839,466 @@ fn get_execution_info_test() {
     assert_eq_sorted!(result_vm, result_native);
 }
 
+#[test]
+fn native_fee_matches_vm() {
+    let corpus: [(&str, &str, &[Felt252]); 4] = [
+        (
+            "starknet_programs/cairo2/fibonacci.cairo",
+            "fib",
+            &[Felt252::ONE, Felt252::ONE, 1000.into()],
+        ),
+        (
+            "starknet_programs/cairo2/factorial_tr.cairo",
+            "factorial",
+            &[1000.into()],
+        ),
+        // Uses range checks.
+        (
+            "starknet_programs/cairo2/math_lib.cairo",
+            "square_root",
+            &[1_000_000.into()],
+        ),
+        // Uses pedersen to hash the address of the balance.
+        (
+            "starknet_programs/cairo2/erc20.cairo",
+            "balance_of",
+            &[1234.into()],
+        ),
+    ];
+    let block_context = BlockContext::default();
+    let fee = |execution_result: &ExecutionResult| {
+        let execution_resources = execution_result
+            .call_info
+            .as_ref()
+            .and_then(|call_info| call_info.execution_resources.clone())
+            .expect("missing execution resources");
+        let mut resources = execution_resources.builtin_instance_counter;
+        resources.insert("n_steps".to_string(), execution_resources.n_steps);
+        resources.insert("l1_gas_usage".to_string(), 0);
+        calculate_tx_fee(&resources, &block_context, &FeeType::Eth).unwrap()
+    };
+
+    for (path, entry_point, calldata) in corpus {
+        let address = Address(1.into());
+        let mut state = TestStateSetup::default();
+        state
+            .load_contract_at_address(ClassHash([1; 32]), address.clone(), path)
+            .unwrap();
+        let mut state = state.finalize();
+
+        let (result_vm, result_native) = state
+            .execute_with_resources(
+                &address,
+                &address,
+                (
+                    EntryPointType::External,
+                    &Felt252::from_bytes_be(&calculate_sn_keccak(entry_point.as_bytes())),
+                ),
+                calldata,
+            )
+            .unwrap();
+
+        // The resources of native executions are estimated from the gas they consume.
+        let (fee_vm, fee_native) = (fee(&result_vm), fee(&result_native));
+        assert!(
+            fee_vm.abs_diff(fee_native) * 50 <= fee_vm,
+            "{path}: the native fee {fee_native} is more than 2% off the VM fee {fee_vm}"
+        );
+    }
+}
+
+#[test]
+fn differential_execution_matches_vm() {
+    let address = Address(1.into());
+    let mut state = TestStateSetup::default();
+    state
+        .load_contract_at_address(
+            ClassHash([1; 32]),
+            address.clone(),
+            "starknet_programs/cairo2/fibonacci.cairo",
+        )
+        .unwrap();
+    let mut state = state.finalize();
+
+    let block_context = BlockContext::default();
+    let execution_config = ExecutionConfig::new(ExecutionBackend::NativeJit).with_differential();
+    let execution_result = ExecutionEntryPoint::new(
+        address.clone(),
+        vec![Felt252::ONE, Felt252::ONE, 100.into()],
+        Felt252::from_bytes_be(&calculate_sn_keccak(b"fib")),
+        address,
+        EntryPointType::External,
+        Some(CallType::Delegate),
+        Some(ClassHash([1; 32])),
+        u128::MAX,
+    )
+    .execute(
+        &mut state.state_native,
+        &block_context,
+        &mut ExecutionResourcesManager::default(),
+        &mut TransactionExecutionContext::new(
+            Address(Felt252::default()),
+            Felt252::default(),
+            Vec::default(),
+            Default::default(),
+            10.into(),
+            block_context.invoke_tx_max_n_steps(),
+            *TRANSACTION_VERSION,
+        ),
+        false,
+        block_context.invoke_tx_max_n_steps(),
+        &execution_config,
+    )
+    .unwrap();
+
+    let report = execution_config.differential_report().unwrap();
+    assert!(execution_result.call_info.is_some());
+    assert_eq!(report.compared_calls(), 1);
+    assert_eq!(report.divergences(), Vec::new());
+}
+
+#[test]
+fn classes_with_unsupported_syscalls_run_on_the_vm() {
+    let class_hash = ClassHash([1; 32]);
+    let address = Address(1.into());
+    let mut state = TestStateSetup::default();
+    state
+        .load_contract_at_address(
+            class_hash,
+            address.clone(),
+            "starknet_programs/cairo2/fibonacci.cairo",
+        )
+        .unwrap();
+
+    // Cairo Native doesn't support the `get_class_hash_at` syscall.
+    state.declare_libfunc(class_hash, "get_class_hash_at_syscall");
+    let mut state = state.finalize();
+
+    let block_context = BlockContext::default();
+    let execution_config = ExecutionConfig::new(ExecutionBackend::NativeJit);
+    let execution_result = ExecutionEntryPoint::new(
+        address.clone(),
+        vec![Felt252::ONE, Felt252::ONE, 10.into()],
+        Felt252::from_bytes_be(&calculate_sn_keccak(b"fib")),
+        address,
+        EntryPointType::External,
+        Some(CallType::Delegate),
+        Some(class_hash),
+        u128::MAX,
+    )
+    .execute(
+        &mut state.state_native,
+        &block_context,
+        &mut ExecutionResourcesManager::default(),
+        &mut TransactionExecutionContext::new(
+            Address(Felt252::default()),
+            Felt252::default(),
+            Vec::default(),
+            Default::default(),
+            10.into(),
+            block_context.invoke_tx_max_n_steps(),
+            *TRANSACTION_VERSION,
+        ),
+        false,
+        block_context.invoke_tx_max_n_steps(),
+        &execution_config,
+    )
+    .unwrap();
+
+    // The class ran on the VM without being compiled.
+    let call_info = execution_result.call_info.unwrap();
+    assert_eq!(call_info.retdata, vec![89.into()]);
+    assert!(!execution_config
+        .program_cache()
+        .unwrap()
+        .contains(&class_hash));
+}
+
+#[test]
+fn differential_execution_skips_classes_failing_to_compile() {
+    let class_hash = ClassHash([1; 32]);
+    let address = Address(1.into());
+    let mut state = TestStateSetup::default();
+    state
+        .load_contract_at_address(
+            class_hash,
+            address.clone(),
+            "starknet_programs/cairo2/fibonacci.cairo",
+        )
+        .unwrap();
+    state.declare_libfunc(class_hash, "unknown_libfunc");
+    let mut state = state.finalize();
+
+    let block_context = BlockContext::default();
+    let execution_config = ExecutionConfig::new(ExecutionBackend::NativeJit).with_differential();
+    let execution_result = ExecutionEntryPoint::new(
+        address.clone(),
+        vec![Felt252::ONE, Felt252::ONE, 10.into()],
+        Felt252::from_bytes_be(&calculate_sn_keccak(b"fib")),
+        address,
+        EntryPointType::External,
+        Some(CallType::Delegate),
+        Some(class_hash),
+        u128::MAX,
+    )
+    .execute(
+        &mut state.state_native,
+        &block_context,
+        &mut ExecutionResourcesManager::default(),
+        &mut TransactionExecutionContext::new(
+            Address(Felt252::default()),
+            Felt252::default(),
+            Vec::default(),
+            Default::default(),
+            10.into(),
+            block_context.invoke_tx_max_n_steps(),
+            *TRANSACTION_VERSION,
+        ),
+        false,
+        block_context.invoke_tx_max_n_steps(),
+        &execution_config,
+    )
+    .unwrap();
+
+    // The VM run isn't compared, and the class runs on the VM from then on.
+    assert_eq!(execution_result.call_info.unwrap().retdata, vec![89.into()]);
+    let report = execution_config.differential_report().unwrap();
+    assert_eq!(report.compared_calls(), 0);
+    let Some(CompiledClass::Casm {
+        sierra: Some(sierra),
+        ..
+    }) = state
+        .state_native
+        .contract_class_cache()
+        .get_contract_class(class_hash)
+    else {
+        panic!("the class has no sierra program");
+    };
+    assert!(!execution_config
+        .policy()
+        .runs_natively(&class_hash, &sierra.0));
+}
+
+#[test]
+fn cairo0_calls_to_cairo1_use_the_program_cache() {
+    let address = Address(1111.into());
+    let class_hash = ClassHash([1; 32]);
+    let send_msg_address = Address(1.into()); // Hardcoded in the Cairo 0 contract.
+    let send_msg_class_hash = ClassHash([2; 32]);
+
+    let mut state = TestStateSetup::default();
+    state
+        .load_contract_at_address(
+            send_msg_class_hash,
+            send_msg_address,
+            "starknet_programs/cairo2/send_simple_message_to_l1.cairo",
+        )
+        .unwrap();
+    state
+        .load_deprecated_contract_at_address(
+            class_hash,
+            address.clone(),
+            "starknet_programs/send_messages_contract_call.json",
+        )
+        .unwrap();
+    let mut state = state.finalize();
+
+    let execution_config = ExecutionConfig::new(ExecutionBackend::NativeJit);
+    let execution_result = state
+        .execute_natively(
+            &address,
+            class_hash,
+            Felt252::from_bytes_be(&calculate_sn_keccak(b"send_sequential_messages")),
+            &[25.into(), 50.into(), 75.into()],
+            &execution_config,
+        )
+        .unwrap();
+
+    // The Cairo 1 contract is called twice by the Cairo 0 one, running natively with the
+    // program cache of the config.
+    let call_info = execution_result.call_info.unwrap();
+    assert_eq!(call_info.internal_calls.len(), 2);
+    assert!(execution_config
+        .program_cache()
+        .unwrap()
+        .contains(&send_msg_class_hash));
+    assert_eq!(execution_config.program_cache().unwrap().len(), 1);
+}
+
+#[test]
+fn cairo0_library_calls_to_cairo1_use_the_program_cache() {
+    let address = Address(1111.into());
+    let class_hash = ClassHash([1; 32]);
+    let echo_class_hash = ClassHash([2; 32]);
+
+    let mut state = TestStateSetup::default();
+    state
+        .load_contract(echo_class_hash, "starknet_programs/cairo2/echo.cairo")
+        .unwrap();
+    state
+        .load_deprecated_contract_at_address(
+            class_hash,
+            address.clone(),
+            "starknet_programs/test_contract.json",
+        )
+        .unwrap();
+    let mut state = state.finalize();
+
+    let execution_config = ExecutionConfig::new(ExecutionBackend::NativeJit);
+    let execution_result = state
+        .execute_natively(
+            &address,
+            class_hash,
+            Felt252::from_bytes_be(&calculate_sn_keccak(b"test_library_call")),
+            &[
+                Felt252::from_bytes_be(&echo_class_hash.0),
+                Felt252::from_bytes_be(&calculate_sn_keccak(b"echo")),
+                1.into(),
+                99.into(),
+            ],
+            &execution_config,
+        )
+        .unwrap();
+
+    let call_info = execution_result.call_info.unwrap();
+    assert_eq!(call_info.retdata, vec![99.into()]);
+    assert_eq!(call_info.internal_calls.len(), 1);
+    assert_eq!(
+        call_info.internal_calls[0].class_hash,
+        Some(echo_class_hash)
+    );
+    assert!(execution_config
+        .program_cache()
+        .unwrap()
+        .contains(&echo_class_hash));
+}
+
+#[test]
+fn cairo0_delegate_calls_to_cairo1_use_the_program_cache() {
+    let address = Address(1111.into());
+    let class_hash = ClassHash([1; 32]);
+    let echo_address = Address(2222.into());
+    let echo_class_hash = ClassHash([2; 32]);
+
+    let mut state = TestStateSetup::default();
+    state
+        .load_contract_at_address(
+            echo_class_hash,
+            echo_address.clone(),
+            "starknet_programs/cairo2/echo.cairo",
+        )
+        .unwrap();
+    state
+        .load_deprecated_contract_at_address(
+            class_hash,
+            address.clone(),
+            "starknet_programs/delegate_call_proxy.json",
+        )
+        .unwrap();
+    let mut state = state.finalize();
+
+    let execution_config = ExecutionConfig::new(ExecutionBackend::NativeJit);
+    let execution_result = state
+        .execute_natively(
+            &address,
+            class_hash,
+            Felt252::from_bytes_be(&calculate_sn_keccak(b"test_delegate_call")),
+            &[
+                echo_address.0,
+                Felt252::from_bytes_be(&calculate_sn_keccak(b"echo")),
+                1.into(),
+                99.into(),
+            ],
+            &execution_config,
+        )
+        .unwrap();
+
+    // The Cairo 1 class runs natively in the context of the Cairo 0 contract.
+    let call_info = execution_result.call_info.unwrap();
+    assert_eq!(call_info.retdata, vec![99.into()]);
+    assert_eq!(call_info.internal_calls.len(), 1);
+    assert_eq!(call_info.internal_calls[0].contract_address, address);
+    assert_eq!(
+        call_info.internal_calls[0].class_hash,
+        Some(echo_class_hash)
+    );
+    assert!(execution_config
+        .program_cache()
+        .unwrap()
+        .contains(&echo_class_hash));
+}
+
+#[test]
+fn cairo0_deploys_of_cairo1_run_the_constructor_with_the_program_cache() {
+    let address = Address(1111.into());
+    let class_hash = ClassHash([1; 32]);
+    let echo_class_hash = ClassHash([2; 32]);
+
+    let mut state = TestStateSetup::default();
+    state
+        .load_contract(echo_class_hash, "starknet_programs/cairo2/echo.cairo")
+        .unwrap();
+    state
+        .load_deprecated_contract_at_address(
+            class_hash,
+            address.clone(),
+            "starknet_programs/test_contract.json",
+        )
+        .unwrap();
+    let mut state = state.finalize();
+
+    let salt = Felt252::from(7);
+    let initial_balance = Felt252::from(100);
+    let execution_config = ExecutionConfig::new(ExecutionBackend::NativeJit);
+    let execution_result = state
+        .execute_natively(
+            &address,
+            class_hash,
+            Felt252::from_bytes_be(&calculate_sn_keccak(b"test_deploy")),
+            &[
+                Felt252::from_bytes_be(&echo_class_hash.0),
+                salt,
+                1.into(),
+                initial_balance,
+                0.into(),
+            ],
+            &execution_config,
+        )
+        .unwrap();
+
+    let expected_address = Address(
+        calculate_contract_address(
+            &salt,
+            &Felt252::from_bytes_be(&echo_class_hash.0),
+            &[initial_balance],
+            address,
+        )
+        .unwrap(),
+    );
+    let call_info = execution_result.call_info.unwrap();
+    assert_eq!(call_info.retdata, vec![expected_address.0]);
+
+    // The constructor of the Cairo 1 contract runs natively and writes its initial balance.
+    assert_eq!(call_info.internal_calls.len(), 1);
+    let constructor_call = &call_info.internal_calls[0];
+    assert_eq!(
+        constructor_call.entry_point_type,
+        Some(EntryPointType::Constructor)
+    );
+    assert_eq!(constructor_call.contract_address, expected_address);
+    assert_eq!(
+        state
+            .state_native
+            .get_storage_at(&(expected_address, calculate_sn_keccak(b"balance")))
+            .unwrap(),
+        initial_balance
+    );
+    assert!(execution_config
+        .program_cache()
+        .unwrap()
+        .contains(&echo_class_hash));
+}
+
 #[derive(Debug, Default)]
 struct TestStateSetup {
     state_reader: InMemoryStateReader,
@@ -876,6 +1348,32 @@ impl TestStateSetup {
         Ok(())
     }
 
+    /// Declares the libfunc `generic_id` in the sierra program of `class_hash`, without using it.
+    pub fn declare_libfunc(&mut self, class_hash: ClassHash, generic_id: &str) {
+        let Some(CompiledClass::Casm {
+            casm,
+            sierra: Some(sierra),
+        }) = self.cache_native.get_contract_class(class_hash)
+        else {
+            panic!("the class has no sierra program");
+        };
+        let (mut program, entry_points) = (*sierra).clone();
+        program.libfunc_declarations.push(LibfuncDeclaration {
+            id: ConcreteLibfuncId::new(u64::MAX),
+            long_id: ConcreteLibfuncLongId {
+                generic_id: GenericLibfuncId::from_string(generic_id),
+                generic_args: Vec::new(),
+            },
+        });
+        self.cache_native.set_contract_class(
+            class_hash,
+            CompiledClass::Casm {
+                casm,
+                sierra: Some(Arc::new((program, entry_points))),
+            },
+        );
+    }
+
     pub fn load_contract_at_address(
         &mut self,
         class_hash: ClassHash,
@@ -894,6 +1392,31 @@ impl TestStateSetup {
         Ok(())
     }
 
+    pub fn load_deprecated_contract_at_address(
+        &mut self,
+        class_hash: ClassHash,
+        contract_address: Address,
+        path: impl AsRef<Path>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let contract_class = Arc::new(DeprecatedContractClass::from_path(path)?);
+
+        self.cache_vm.set_contract_class(
+            class_hash,
+            CompiledClass::Deprecated(contract_class.clone()),
+        );
+        self.cache_native
+            .set_contract_class(class_hash, CompiledClass::Deprecated(contract_class));
+
+        self.state_reader
+            .address_to_class_hash_mut()
+            .insert(contract_address.clone(), class_hash);
+        self.state_reader
+            .address_to_nonce_mut()
+            .insert(contract_address, Felt252::default());
+
+        Ok(())
+    }
+
     pub fn finalize(self) -> TestState {
         let state_reader = Arc::new(self.state_reader);
 
@@ -922,12 +1445,35 @@ impl TestState {
             .insert(k, v);
     }
 
+    /// Executes the entry point with both the VM and Cairo Native, without their execution
+    /// resources, which are estimated by Native and compared separately.
     pub fn execute(
         &mut self,
         caller_address: &Address,
         callee_address: &Address,
         entry_point: (EntryPointType, &Felt252),
         call_data: &[Felt252],
+    ) -> Result<(ExecutionResult, ExecutionResult), Box<dyn std::error::Error>> {
+        let (mut execution_result_vm, mut execution_result_native) =
+            self.execute_with_resources(caller_address, callee_address, entry_point, call_data)?;
+        for execution_result in [&mut execution_result_vm, &mut execution_result_native] {
+            if let Some(callinfo) = execution_result.call_info.as_mut() {
+                callinfo.execution_resources = None;
+                for callinfo in callinfo.internal_calls.iter_mut() {
+                    callinfo.execution_resources = None;
+                }
+            }
+        }
+
+        Ok((execution_result_vm, execution_result_native))
+    }
+
+    pub fn execute_with_resources(
+        &mut self,
+        caller_address: &Address,
+        callee_address: &Address,
+        entry_point: (EntryPointType, &Felt252),
+        call_data: &[Felt252],
     ) -> Result<(ExecutionResult, ExecutionResult), Box<dyn std::error::Error>> {
         let (class_hash_vm, contract_class_vm) =
             Self::get_contract_class_for_address(&self.state_vm, caller_address)
@@ -953,7 +1499,7 @@ impl TestState {
         let mut block_context = BlockContext::default();
         block_context.block_info_mut().block_number = 30;
 
-        let mut execution_result_vm = ExecutionEntryPoint::new(
+        let execution_result_vm = ExecutionEntryPoint::new(
             callee_address.clone(),
             call_data.to_vec(),
             *entry_point.1,
@@ -978,16 +1524,8 @@ impl TestState {
             ),
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &ExecutionConfig::new(ExecutionBackend::Vm),
         )?;
-        // Overwrite the execution result's execution_resources as native doesn't output it
-        if let Some(callinfo) = execution_result_vm.call_info.as_mut() {
-            callinfo.execution_resources = None;
-            for callinfo in callinfo.internal_calls.iter_mut() {
-                callinfo.execution_resources = None;
-            }
-        }
 
         let execution_result_native = ExecutionEntryPoint::new(
             callee_address.clone(),
@@ -1014,13 +1552,53 @@ impl TestState {
             ),
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &ExecutionConfig::new(ExecutionBackend::NativeJit),
         )?;
 
         Ok((execution_result_vm, execution_result_native))
     }
 
+    /// Executes an external entry point of the contract at `address` with the native state and
+    /// the given execution config.
+    pub fn execute_natively(
+        &mut self,
+        address: &Address,
+        class_hash: ClassHash,
+        entry_point_selector: Felt252,
+        call_data: &[Felt252],
+        execution_config: &ExecutionConfig,
+    ) -> Result<ExecutionResult, Box<dyn std::error::Error>> {
+        let block_context = BlockContext::default();
+
+        Ok(ExecutionEntryPoint::new(
+            address.clone(),
+            call_data.to_vec(),
+            entry_point_selector,
+            Address(0.into()),
+            EntryPointType::External,
+            Some(CallType::Delegate),
+            Some(class_hash),
+            1000000,
+        )
+        .execute(
+            &mut self.state_native,
+            &block_context,
+            &mut ExecutionResourcesManager::default(),
+            &mut TransactionExecutionContext::new(
+                Address(Felt252::default()),
+                Felt252::default(),
+                Vec::default(),
+                Default::default(),
+                10.into(),
+                block_context.invoke_tx_max_n_steps(),
+                *TRANSACTION_VERSION,
+            ),
+            false,
+            block_context.invoke_tx_max_n_steps(),
+            execution_config,
+        )?)
+    }
+
     fn get_contract_class_for_address(
         state: &CachedState<InMemoryStateReader, PermanentContractClassCache>,
         address: &Address,
@@ -120,8 +120,7 @@ fn test_erc20_cairo2() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
     let erc20_address = *call_info.call_info.unwrap().retdata.get(0).unwrap();
@@ -175,12 +174,7 @@ fn test_erc20_cairo2() {
     .unwrap();
 
     let account_address_1 = internal_deploy_account
-        .execute(
-            &mut state,
-            &Default::default(),
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &Default::default(), &Default::default())
         .expect("failed to execute internal_deploy_account")
         .validate_info
         .expect("validate_info missing")
@@ -232,12 +226,7 @@ fn test_erc20_cairo2() {
     .unwrap();
 
     let account_address_2 = internal_deploy_account
-        .execute(
-            &mut state,
-            &Default::default(),
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &Default::default(), &Default::default())
         .unwrap()
         .validate_info
         .unwrap()
@@ -254,8 +243,7 @@ fn test_erc20_cairo2() {
         &mut state,
         BlockContext::default(),
         account_address_1.clone(),
-        #[cfg(feature = "cairo-native")]
-        None,
+        &Default::default(),
     )
     .unwrap();
 
@@ -270,8 +258,7 @@ fn test_erc20_cairo2() {
         &mut state,
         BlockContext::default(),
         account_address_1.clone(),
-        #[cfg(feature = "cairo-native")]
-        None,
+        &Default::default(),
     )
     .unwrap();
 
@@ -286,8 +273,7 @@ fn test_erc20_cairo2() {
         &mut state,
         BlockContext::default(),
         account_address_1,
-        #[cfg(feature = "cairo-native")]
-        None,
+        &Default::default(),
     )
     .unwrap();
 
@@ -134,8 +134,7 @@ pub fn execute_entry_point(
         &mut tx_execution_context,
         false,
         call_config.block_context.invoke_tx_max_n_steps(),
-        #[cfg(feature = "cairo-native")]
-        None,
+        &Default::default(),
     )?;
 
     Ok(call_info.unwrap())
@@ -172,12 +171,7 @@ pub fn deploy(
         &CompiledClass::Deprecated(Arc::new(contract_class)),
     )?;
 
-    let tx_execution_info = internal_deploy.apply(
-        state,
-        block_context,
-        #[cfg(feature = "cairo-native")]
-        None,
-    )?;
+    let tx_execution_info = internal_deploy.apply(state, block_context, &Default::default())?;
 
     let call_info = tx_execution_info.call_info.unwrap();
     let contract_address = call_info.contract_address;
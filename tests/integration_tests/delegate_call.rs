@@ -127,8 +127,7 @@ fn delegate_call() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .is_ok());
 }
@@ -121,8 +121,7 @@ fn delegate_l1_handler() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .is_ok());
 }
@@ -76,12 +76,7 @@ fn internal_deploy_account() {
     .unwrap();
 
     let tx_info = internal_deploy_account
-        .execute(
-            &mut state,
-            &Default::default(),
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &Default::default(), &Default::default())
         .unwrap();
 
     let contract_address = calculate_contract_address(
@@ -187,12 +182,7 @@ fn internal_deploy_account_cairo1() {
     .unwrap();
 
     let tx_info = internal_deploy_account
-        .execute(
-            &mut state,
-            &Default::default(),
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &Default::default(), &Default::default())
         .unwrap();
 
     let accessed_keys: ClassHash = ClassHash([
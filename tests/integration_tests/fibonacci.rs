@@ -132,8 +132,7 @@ fn integration_test() {
                 &mut tx_execution_context,
                 false,
                 block_context.invoke_tx_max_n_steps(),
-                #[cfg(feature = "cairo-native")]
-                None,
+                &Default::default(),
             )
             .unwrap()
             .call_info
@@ -232,8 +231,7 @@ fn integration_test_cairo1() {
                 &mut tx_execution_context,
                 false,
                 block_context.invoke_tx_max_n_steps(),
-                #[cfg(feature = "cairo-native")]
-                None,
+                &Default::default(),
             )
             .unwrap()
             .call_info
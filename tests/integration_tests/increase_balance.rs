@@ -141,8 +141,7 @@ fn hello_starknet_increase_balance() {
                 &mut tx_execution_context,
                 false,
                 block_context.invoke_tx_max_n_steps(),
-                #[cfg(feature = "cairo-native")]
-                None,
+                &Default::default(),
             )
             .unwrap()
             .call_info
@@ -84,8 +84,7 @@ fn test_internal_calls() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .expect("Could not execute contract");
 
@@ -3,13 +3,7 @@ use assert_matches::assert_matches;
 use cairo_lang_starknet::contract_class::ContractClass as SierraContractClass;
 use cairo_vm::{
     vm::runners::builtin_runner::{HASH_BUILTIN_NAME, RANGE_CHECK_BUILTIN_NAME},
-    vm::{
-        errors::{
-            cairo_run_errors::CairoRunError, vm_errors::VirtualMachineError,
-            vm_exception::VmException,
-        },
-        runners::cairo_runner::ExecutionResources,
-    },
+    vm::runners::cairo_runner::ExecutionResources,
     Felt252,
 };
 use lazy_static::lazy_static;
@@ -17,6 +11,7 @@ use num_bigint::BigUint;
 use num_traits::Zero;
 use pretty_assertions_sorted::{assert_eq, assert_eq_sorted};
 use starknet_in_rust::execution::execution_entry_point::ExecutionEntryPoint;
+use starknet_in_rust::execution::revert::RevertReason;
 use starknet_in_rust::execution::TransactionExecutionContext;
 use starknet_in_rust::services::api::contract_classes::compiled_class::CompiledClass;
 use starknet_in_rust::services::api::contract_classes::deprecated_contract_class::ContractClass;
@@ -993,12 +988,7 @@ fn test_declare_tx() {
     assert!(state.get_contract_class(&declare_tx.class_hash).is_err());
     // Execute declare_tx
     let result = declare_tx
-        .execute(
-            &mut state,
-            &block_context,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &block_context, &Default::default())
         .unwrap();
     // Check ContractClass is set after the declare_tx
     assert!(state.get_contract_class(&declare_tx.class_hash).is_ok());
@@ -1090,12 +1080,7 @@ fn test_declarev2_tx() {
         .is_err());
     // Execute declare_tx
     let result = declare_tx
-        .execute(
-            &mut state,
-            &block_context,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &block_context, &Default::default())
         .unwrap();
     // Check ContractClass is set after the declare_tx
     assert!(state
@@ -1343,13 +1328,7 @@ fn test_invoke_tx() {
     // Extract invoke transaction fields for testing, as it is consumed when creating an account
     // transaction.
     let result = invoke_tx
-        .execute(
-            state,
-            block_context,
-            0,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(state, block_context, 0, &Default::default())
         .unwrap();
     let expected_execution_info = expected_transaction_execution_info(block_context);
 
@@ -1373,13 +1352,7 @@ fn test_invoke_tx_exceeded_max_fee() {
     // Extract invoke transaction fields for testing, as it is consumed when creating an account
     // transaction.
     let result = invoke_tx
-        .execute(
-            state,
-            block_context,
-            0,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(state, block_context, 0, &Default::default())
         .unwrap();
     let mut expected_result = expected_transaction_execution_info(block_context).to_revert_error(
         format!(
@@ -1468,13 +1441,7 @@ fn test_invoke_tx_state() {
     let invoke_tx = invoke_tx(calldata, u128::MAX);
 
     let result = invoke_tx
-        .execute(
-            state,
-            starknet_general_context,
-            0,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(state, starknet_general_context, 0, &Default::default())
         .unwrap();
 
     let expected_final_state = expected_state_after_tx(result.actual_fee);
@@ -1544,23 +1511,13 @@ fn test_invoke_with_declarev2_tx() {
     // Declare the fibonacci contract
     let declare_tx = declarev2_tx();
     declare_tx
-        .execute(
-            state,
-            block_context,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(state, block_context, &Default::default())
         .unwrap();
 
     // Deploy the fibonacci contract
     let deploy = deploy_fib_syscall();
     deploy
-        .execute(
-            state,
-            block_context,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(state, block_context, &Default::default())
         .unwrap();
 
     let Address(test_contract_address) = TEST_FIB_CONTRACT_ADDRESS.clone();
@@ -1580,8 +1537,7 @@ fn test_invoke_with_declarev2_tx() {
             state,
             block_context,
             expected_gas_consumed,
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -1634,12 +1590,7 @@ fn test_deploy_account() {
     );
 
     let tx_info = deploy_account_tx
-        .execute(
-            &mut state,
-            &block_context,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &block_context, &Default::default())
         .unwrap();
 
     use pretty_assertions_sorted::assert_eq_sorted;
@@ -1916,12 +1867,7 @@ fn test_state_for_declare_tx() {
         .is_zero());
     // Execute declare_tx
     let fee = declare_tx
-        .execute(
-            &mut state,
-            &block_context,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(&mut state, &block_context, &Default::default())
         .unwrap()
         .actual_fee
         .into();
@@ -2103,24 +2049,24 @@ fn test_invoke_tx_wrong_call_data() {
     let invoke_tx = invoke_tx(calldata, u128::MAX);
 
     // Execute transaction
-    let result = invoke_tx.execute(
-        state,
-        starknet_general_context,
-        0,
-        #[cfg(feature = "cairo-native")]
-        None,
-    );
+    let result = invoke_tx.execute(state, starknet_general_context, 0, &Default::default());
 
-    // Assert error
-    assert_matches!(
-        result,
-        Err(TransactionError::CairoRunner(CairoRunError::VmException(
-            VmException {
-                inner_exc: VirtualMachineError::DiffAssertValues(..),
-                ..
-            }
-        )))
+    // The transaction is reverted and charged for the steps executed up to the failure.
+    let tx_info = result.unwrap();
+    assert!(tx_info.call_info.is_none());
+    assert!(tx_info.actual_fee > 0);
+
+    let revert_error = tx_info.revert_error.unwrap();
+    assert_eq!(
+        revert_error.frames[0].contract_address,
+        TEST_ACCOUNT_CONTRACT_ADDRESS.clone()
+    );
+    assert_eq!(
+        revert_error.frames[0].entry_point_selector,
+        *EXECUTE_ENTRY_POINT_SELECTOR
     );
+    assert!(revert_error.frames[0].location.is_some());
+    assert!(revert_error.to_string().contains("ASSERT_EQ"));
 }
 
 #[test]
@@ -2148,16 +2094,27 @@ fn test_invoke_tx_wrong_entrypoint() {
     .unwrap();
 
     // Execute transaction
-    let result = invoke_tx.execute(
-        state,
-        starknet_general_context,
-        0,
-        #[cfg(feature = "cairo-native")]
-        None,
-    );
+    let result = invoke_tx.execute(state, starknet_general_context, 0, &Default::default());
+
+    // The transaction is reverted, the failing entrypoint being the outermost call.
+    let tx_info = result.unwrap();
+    assert!(tx_info.call_info.is_none());
 
-    // Assert error
-    assert_matches!(result, Err(TransactionError::EntryPointNotFound(_)));
+    let revert_error = tx_info.revert_error.unwrap();
+    assert_eq!(revert_error.frames.len(), 1);
+    assert_eq!(
+        revert_error.frames[0].contract_address,
+        TEST_ACCOUNT_CONTRACT_ADDRESS.clone()
+    );
+    assert_eq!(
+        revert_error.reason,
+        RevertReason::Error(
+            TransactionError::EntryPointNotFound(Felt252::from_bytes_be(&calculate_sn_keccak(
+                b"none_function"
+            )))
+            .to_string()
+        )
+    );
 }
 
 #[test]
@@ -2182,12 +2139,7 @@ fn test_deploy_undeclared_account() {
     assert!(state.get_contract_class(&not_deployed_class_hash).is_err());
 
     // Execute transaction
-    let result = deploy_account_tx.execute(
-        &mut state,
-        &block_context,
-        #[cfg(feature = "cairo-native")]
-        None,
-    );
+    let result = deploy_account_tx.execute(&mut state, &block_context, &Default::default());
 
     // Execute transaction
     assert_matches!(
@@ -2203,23 +2155,13 @@ fn test_library_call_with_declare_v2() {
     // Declare the fibonacci contract
     let declare_tx = declarev2_tx();
     declare_tx
-        .execute(
-            state,
-            block_context,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(state, block_context, &Default::default())
         .unwrap();
 
     // Deploy the fibonacci contract
     let deploy = deploy_fib_syscall();
     deploy
-        .execute(
-            state,
-            block_context,
-            #[cfg(feature = "cairo-native")]
-            None,
-        )
+        .execute(state, block_context, &Default::default())
         .unwrap();
 
     //  Create program and entry point types for contract class
@@ -2305,8 +2247,7 @@ fn test_library_call_with_declare_v2() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -138,8 +138,7 @@ fn integration_storage_test() {
                 &mut tx_execution_context,
                 false,
                 block_context.invoke_tx_max_n_steps(),
-                #[cfg(feature = "cairo-native")]
-                None,
+                &Default::default(),
             )
             .unwrap()
             .call_info
@@ -151,8 +151,7 @@ fn test_contract<'a>(
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .expect("Could not execute contract")
         .call_info
@@ -1205,8 +1204,7 @@ fn deploy_cairo1_from_cairo0_with_constructor() {
         &mut tx_execution_context,
         false,
         block_context.invoke_tx_max_n_steps(),
-        #[cfg(feature = "cairo-native")]
-        None,
+        &Default::default(),
     );
 
     assert!(call_info.is_ok());
@@ -1313,8 +1311,7 @@ fn deploy_cairo1_from_cairo0_without_constructor() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap();
 
@@ -1421,8 +1418,7 @@ fn deploy_cairo1_and_invoke() {
         &mut tx_execution_context,
         false,
         block_context.invoke_tx_max_n_steps(),
-        #[cfg(feature = "cairo-native")]
-        None,
+        &Default::default(),
     );
 
     assert!(call_info.is_ok());
@@ -1465,8 +1461,7 @@ fn deploy_cairo1_and_invoke() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap()
         .call_info
@@ -1566,8 +1561,7 @@ fn send_messages_to_l1_different_contract_calls() {
             &mut tx_execution_context,
             false,
             block_context.invoke_tx_max_n_steps(),
-            #[cfg(feature = "cairo-native")]
-            None,
+            &Default::default(),
         )
         .unwrap()
         .call_info
@@ -14,6 +14,7 @@ use starknet_in_rust::definitions::constants::EXECUTE_ENTRY_POINT_SELECTOR;
 use starknet_in_rust::{
     core::contract_address::compute_casm_class_hash,
     definitions::block_context::{BlockContext, StarknetChainId},
+    execution::backend::{ExecutionBackend, ExecutionConfig},
     state::{
         cached_state::CachedState, contract_class_cache::ContractClassCache, state_api::StateReader,
     },
@@ -22,12 +23,6 @@ use starknet_in_rust::{
 use tracing::info;
 use tracing_subscriber::{EnvFilter, FmtSubscriber};
 
-use {
-    cairo_native::cache::{JitProgramCache, ProgramCache},
-    starknet_in_rust::utils::get_native_context,
-    std::{cell::RefCell, rc::Rc},
-};
-
 lazy_static! {
     static ref ACCOUNT_ADDRESS: Felt252 = 4321.into();
     static ref OWNER_ADDRESS: Felt252 = 4321.into();
@@ -45,31 +40,28 @@ fn compare_yas_bench() -> Result<(), Box<dyn std::error::Error>> {
 
     let mut state_vm = utils::default_state()?;
     let mut state_jit = utils::default_state()?;
-    let program_cache_vm = None;
-    let program_cache_jit = Some(Rc::new(RefCell::new(ProgramCache::from(
-        JitProgramCache::new(get_native_context()),
-    ))));
+    let execution_config_vm = ExecutionConfig::new(ExecutionBackend::Vm);
+    let execution_config_jit = ExecutionConfig::new(ExecutionBackend::NativeJit);
     // Execute pre-swap operations
     // Declare ERC20, YASFactory, YASPool and YASRouter contracts.
     info!("Declaring the ERC20 contract.");
-    let erc20_class_hash_jit = declare_erc20(&mut state_jit, program_cache_jit.clone())?;
-    let erc20_class_hash_vm = declare_erc20(&mut state_vm, program_cache_vm.clone())?;
+    let erc20_class_hash_jit = declare_erc20(&mut state_jit, &execution_config_jit)?;
+    let erc20_class_hash_vm = declare_erc20(&mut state_vm, &execution_config_vm)?;
     info!("Declaring the YASFactory contract.");
-    let yas_factory_class_hash_jit =
-        declare_yas_factory(&mut state_jit, program_cache_jit.clone())?;
-    let yas_factory_class_hash_vm = declare_yas_factory(&mut state_vm, program_cache_vm.clone())?;
+    let yas_factory_class_hash_jit = declare_yas_factory(&mut state_jit, &execution_config_jit)?;
+    let yas_factory_class_hash_vm = declare_yas_factory(&mut state_vm, &execution_config_vm)?;
     info!("Declaring the YASRouter contract.");
-    let yas_router_class_hash_jit = declare_yas_router(&mut state_jit, program_cache_jit.clone())?;
-    let yas_router_class_hash_vm = declare_yas_router(&mut state_vm, program_cache_vm.clone())?;
+    let yas_router_class_hash_jit = declare_yas_router(&mut state_jit, &execution_config_jit)?;
+    let yas_router_class_hash_vm = declare_yas_router(&mut state_vm, &execution_config_vm)?;
     info!("Declaring the YASPool contract.");
-    let yas_pool_class_hash_jit = declare_yas_pool(&mut state_jit, program_cache_jit.clone())?;
-    let yas_pool_class_hash_vm = declare_yas_pool(&mut state_vm, program_cache_vm.clone())?;
+    let yas_pool_class_hash_jit = declare_yas_pool(&mut state_jit, &execution_config_jit)?;
+    let yas_pool_class_hash_vm = declare_yas_pool(&mut state_vm, &execution_config_vm)?;
 
     // Deploy two ERC20 contracts.
     info!("Deploying TYAS0 token on ERC20.");
     let yas0_token_address_jit = deploy_erc20(
         &mut state_jit,
-        program_cache_jit.clone(),
+        &execution_config_jit,
         &erc20_class_hash_jit,
         "TYAS0",
         "$YAS0",
@@ -78,7 +70,7 @@ fn compare_yas_bench() -> Result<(), Box<dyn std::error::Error>> {
     )?;
     let yas0_token_address_vm = deploy_erc20(
         &mut state_vm,
-        program_cache_vm.clone(),
+        &execution_config_vm,
         &erc20_class_hash_vm,
         "TYAS0",
         "$YAS0",
@@ -88,7 +80,7 @@ fn compare_yas_bench() -> Result<(), Box<dyn std::error::Error>> {
     info!("Deploying TYAS1 token on ERC20.");
     let yas1_token_address_jit = deploy_erc20(
         &mut state_jit,
-        program_cache_jit.clone(),
+        &execution_config_jit,
         &erc20_class_hash_jit,
         "TYAS1",
         "$YAS1",
@@ -97,7 +89,7 @@ fn compare_yas_bench() -> Result<(), Box<dyn std::error::Error>> {
     )?;
     let yas1_token_address_vm = deploy_erc20(
         &mut state_vm,
-        program_cache_vm.clone(),
+        &execution_config_vm,
         &erc20_class_hash_vm,
         "TYAS1",
         "$YAS1",
@@ -109,14 +101,14 @@ fn compare_yas_bench() -> Result<(), Box<dyn std::error::Error>> {
     info!("Deploying YASFactory contract.");
     let yas_factory_address_jit = deploy_yas_factory(
         &mut state_jit,
-        program_cache_jit.clone(),
+        &execution_config_jit,
         &yas_factory_class_hash_jit,
         *OWNER_ADDRESS,
         yas_pool_class_hash_jit,
     )?;
     let yas_factory_address_vm = deploy_yas_factory(
         &mut state_vm,
-        program_cache_vm.clone(),
+        &execution_config_vm,
         &yas_factory_class_hash_vm,
         *OWNER_ADDRESS,
         yas_pool_class_hash_vm,
@@ -126,12 +118,12 @@ fn compare_yas_bench() -> Result<(), Box<dyn std::error::Error>> {
     info!("Deploying YASRouter contract.");
     let yas_router_address_jit = deploy_yas_router(
         &mut state_jit,
-        program_cache_jit.clone(),
+        &execution_config_jit,
         &yas_router_class_hash_jit,
     )?;
     let yas_router_address_vm = deploy_yas_router(
         &mut state_vm,
-        program_cache_vm.clone(),
+        &execution_config_vm,
         &yas_router_class_hash_vm,
     )?;
 
@@ -139,7 +131,7 @@ fn compare_yas_bench() -> Result<(), Box<dyn std::error::Error>> {
     info!("Deploying YASPool contract.");
     let yas_pool_address_jit = deploy_yas_pool(
         &mut state_jit,
-        program_cache_jit.clone(),
+        &execution_config_jit,
         &yas_pool_class_hash_jit,
         yas_factory_address_jit,
         yas0_token_address_jit,
@@ -149,7 +141,7 @@ fn compare_yas_bench() -> Result<(), Box<dyn std::error::Error>> {
     )?;
     let yas_pool_address_vm = deploy_yas_pool(
         &mut state_vm,
-        program_cache_vm.clone(),
+        &execution_config_vm,
         &yas_pool_class_hash_vm,
         yas_factory_address_vm,
         yas0_token_address_vm,
@@ -162,14 +154,14 @@ fn compare_yas_bench() -> Result<(), Box<dyn std::error::Error>> {
     info!("Initializing pool.");
     initialize_pool(
         &mut state_jit,
-        program_cache_jit.clone(),
+        &execution_config_jit,
         &yas_pool_address_jit,
         (79_228_162_514_264_337_593_543_950_336, 0),
         false,
     )?;
     initialize_pool(
         &mut state_vm,
-        program_cache_vm.clone(),
+        &execution_config_vm,
         &yas_pool_address_vm,
         (79_228_162_514_264_337_593_543_950_336, 0),
         false,
@@ -179,28 +171,28 @@ fn compare_yas_bench() -> Result<(), Box<dyn std::error::Error>> {
     info!("Approving tokens.");
     approve_max(
         &mut state_jit,
-        program_cache_jit.clone(),
+        &execution_config_jit,
         &ACCOUNT_ADDRESS,
         yas0_token_address_jit,
         yas_router_address_jit,
     )?;
     approve_max(
         &mut state_jit,
-        program_cache_jit.clone(),
+        &execution_config_jit,
         &ACCOUNT_ADDRESS,
         yas1_token_address_jit,
         yas_router_address_jit,
     )?;
     approve_max(
         &mut state_vm,
-        program_cache_vm.clone(),
+        &execution_config_vm,
         &ACCOUNT_ADDRESS,
         yas0_token_address_vm,
         yas_router_address_vm,
     )?;
     approve_max(
         &mut state_vm,
-        program_cache_vm.clone(),
+        &execution_config_vm,
         &ACCOUNT_ADDRESS,
         yas1_token_address_vm,
         yas_router_address_vm,
@@ -210,7 +202,7 @@ fn compare_yas_bench() -> Result<(), Box<dyn std::error::Error>> {
     info!("Minting tokens.");
     mint(
         &mut state_jit,
-        program_cache_jit.clone(),
+        &execution_config_jit,
         &ACCOUNT_ADDRESS,
         yas_router_address_jit,
         yas_pool_address_jit,
@@ -221,7 +213,7 @@ fn compare_yas_bench() -> Result<(), Box<dyn std::error::Error>> {
     )?;
     mint(
         &mut state_vm,
-        program_cache_vm.clone(),
+        &execution_config_vm,
         &ACCOUNT_ADDRESS,
         yas_router_address_vm,
         yas_pool_address_vm,
@@ -236,7 +228,7 @@ fn compare_yas_bench() -> Result<(), Box<dyn std::error::Error>> {
     for _ in 0..5 {
         swap(
             &mut state_jit,
-            program_cache_jit.clone(),
+            &execution_config_jit,
             &ACCOUNT_ADDRESS,
             yas_router_address_jit,
             yas_pool_address_jit,
@@ -248,7 +240,7 @@ fn compare_yas_bench() -> Result<(), Box<dyn std::error::Error>> {
 
         swap(
             &mut state_vm,
-            program_cache_vm.clone(),
+            &execution_config_vm,
             &ACCOUNT_ADDRESS,
             yas_router_address_vm,
             yas_pool_address_vm,
@@ -262,26 +254,26 @@ fn compare_yas_bench() -> Result<(), Box<dyn std::error::Error>> {
 
         let tyas0_jit = balance_of(
             &mut state_jit,
-            program_cache_jit.clone(),
+            &execution_config_jit,
             &yas0_token_address_jit,
             *OWNER_ADDRESS,
         )?;
         let tyas1_jit = balance_of(
             &mut state_jit,
-            program_cache_jit.clone(),
+            &execution_config_jit,
             &yas0_token_address_jit,
             *OWNER_ADDRESS,
         )?;
 
         let tyas0_vm = balance_of(
             &mut state_vm,
-            program_cache_vm.clone(),
+            &execution_config_vm,
             &yas0_token_address_vm,
             *OWNER_ADDRESS,
         )?;
         let tyas1_vm = balance_of(
             &mut state_vm,
-            program_cache_vm.clone(),
+            &execution_config_vm,
             &yas0_token_address_vm,
             *OWNER_ADDRESS,
         )?;
@@ -298,7 +290,7 @@ fn compare_yas_bench() -> Result<(), Box<dyn std::error::Error>> {
 
 fn declare_erc20<S, C>(
     state: &mut CachedState<S, C>,
-    program_cache: Option<Rc<RefCell<ProgramCache<ClassHash>>>>,
+    execution_config: &ExecutionConfig,
 ) -> Result<Felt252, Box<dyn std::error::Error>>
 where
     S: StateReader,
@@ -321,7 +313,7 @@ where
         vec![],
         nonce,
     )?
-    .execute(state, &BlockContext::default(), program_cache)?;
+    .execute(state, &BlockContext::default(), execution_config)?;
 
     // Ensure the execution was successful.
     let validate_info = tx_execution_info.validate_info.unwrap();
@@ -334,7 +326,7 @@ where
 
 fn declare_yas_factory<S, C>(
     state: &mut CachedState<S, C>,
-    program_cache: Option<Rc<RefCell<ProgramCache<ClassHash>>>>,
+    execution_config: &ExecutionConfig,
 ) -> Result<Felt252, Box<dyn std::error::Error>>
 where
     S: StateReader,
@@ -357,7 +349,7 @@ where
         vec![],
         nonce,
     )?
-    .execute(state, &BlockContext::default(), program_cache)?;
+    .execute(state, &BlockContext::default(), execution_config)?;
 
     // Ensure the execution was successful.
     let validate_info = tx_execution_info.validate_info.unwrap();
@@ -370,7 +362,7 @@ where
 
 fn declare_yas_router<S, C>(
     state: &mut CachedState<S, C>,
-    program_cache: Option<Rc<RefCell<ProgramCache<ClassHash>>>>,
+    execution_config: &ExecutionConfig,
 ) -> Result<Felt252, Box<dyn std::error::Error>>
 where
     S: StateReader,
@@ -393,7 +385,7 @@ where
         vec![],
         nonce,
     )?
-    .execute(state, &BlockContext::default(), program_cache)?;
+    .execute(state, &BlockContext::default(), execution_config)?;
 
     // Ensure the execution was successful.
     let validate_info = tx_execution_info.validate_info.unwrap();
@@ -406,7 +398,7 @@ where
 
 fn declare_yas_pool<S, C>(
     state: &mut CachedState<S, C>,
-    program_cache: Option<Rc<RefCell<ProgramCache<ClassHash>>>>,
+    execution_config: &ExecutionConfig,
 ) -> Result<Felt252, Box<dyn std::error::Error>>
 where
     S: StateReader,
@@ -429,7 +421,7 @@ where
         vec![],
         nonce,
     )?
-    .execute(state, &BlockContext::default(), program_cache)?;
+    .execute(state, &BlockContext::default(), execution_config)?;
 
     // Ensure the execution was successful.
     let validate_info = tx_execution_info.validate_info.unwrap();
@@ -442,7 +434,7 @@ where
 
 fn deploy_erc20<S, C>(
     state: &mut CachedState<S, C>,
-    program_cache: Option<Rc<RefCell<ProgramCache<ClassHash>>>>,
+    execution_config: &ExecutionConfig,
     erc20_class_hash: &Felt252,
     name: &str,
     symbol: &str,
@@ -479,7 +471,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        program_cache,
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
@@ -493,7 +485,7 @@ where
 
 fn deploy_yas_factory<S, C>(
     state: &mut CachedState<S, C>,
-    program_cache: Option<Rc<RefCell<ProgramCache<ClassHash>>>>,
+    execution_config: &ExecutionConfig,
     yas_factory_class_hash: &Felt252,
     owner_address: Felt252,
     pool_class_hash: Felt252,
@@ -525,7 +517,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        program_cache,
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
@@ -539,7 +531,7 @@ where
 
 fn deploy_yas_router<S, C>(
     state: &mut CachedState<S, C>,
-    program_cache: Option<Rc<RefCell<ProgramCache<ClassHash>>>>,
+    execution_config: &ExecutionConfig,
     yas_router_class_hash: &Felt252,
 ) -> Result<Felt252, Box<dyn std::error::Error>>
 where
@@ -563,7 +555,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        program_cache,
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
@@ -578,7 +570,7 @@ where
 #[allow(clippy::too_many_arguments)]
 fn deploy_yas_pool<S, C>(
     state: &mut CachedState<S, C>,
-    program_cache: Option<Rc<RefCell<ProgramCache<ClassHash>>>>,
+    execution_config: &ExecutionConfig,
     yas_pool_class_hash: &Felt252,
     yas_factory_address: Felt252,
     yas0_token_address: Felt252,
@@ -617,7 +609,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        program_cache,
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
@@ -631,7 +623,7 @@ where
 
 fn initialize_pool<S, C>(
     state: &mut CachedState<S, C>,
-    program_cache: Option<Rc<RefCell<ProgramCache<ClassHash>>>>,
+    execution_config: &ExecutionConfig,
     yas_pool_address: &Felt252,
     price_sqrt: (u128, u128),
     sign: bool,
@@ -667,7 +659,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        program_cache,
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
@@ -681,7 +673,7 @@ where
 
 fn approve_max<S, C>(
     state: &mut CachedState<S, C>,
-    program_cache: Option<Rc<RefCell<ProgramCache<ClassHash>>>>,
+    execution_config: &ExecutionConfig,
     account_address: &Felt252,
     token_address: Felt252,
     wallet_address: Felt252,
@@ -715,7 +707,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        program_cache,
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
@@ -730,7 +722,7 @@ where
 #[allow(clippy::too_many_arguments)]
 fn mint<S, C>(
     state: &mut CachedState<S, C>,
-    program_cache: Option<Rc<RefCell<ProgramCache<ClassHash>>>>,
+    execution_config: &ExecutionConfig,
     account_address: &Felt252,
     yas_router_address: Felt252,
     yas_pool_address: Felt252,
@@ -772,7 +764,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        program_cache,
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
@@ -787,7 +779,7 @@ where
 #[allow(clippy::too_many_arguments)]
 fn swap<S, C>(
     state: &mut CachedState<S, C>,
-    program_cache: Option<Rc<RefCell<ProgramCache<ClassHash>>>>,
+    execution_config: &ExecutionConfig,
     account_address: &Felt252,
     yas_router_address: Felt252,
     yas_pool_address: Felt252,
@@ -831,7 +823,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        program_cache,
+        execution_config,
     )?;
 
     // Ensure the execution was successful.
@@ -845,7 +837,7 @@ where
 
 fn balance_of<S, C>(
     state: &mut CachedState<S, C>,
-    program_cache: Option<Rc<RefCell<ProgramCache<ClassHash>>>>,
+    execution_config: &ExecutionConfig,
     token_address: &Felt252,
     wallet_address: Felt252,
 ) -> Result<Felt252, Box<dyn std::error::Error>>
@@ -878,7 +870,7 @@ where
         state,
         &BlockContext::default(),
         u64::MAX.into(),
-        program_cache,
+        execution_config,
     )?;
 
     // Ensure the execution was successful.